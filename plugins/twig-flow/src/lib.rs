@@ -9,9 +9,15 @@ use anyhow::Result;
 use clap::{CommandFactory, Parser};
 use clap_complete::CompleteEnv;
 pub use cli::Cli;
+use twig_core::plugin::manifest::{PluginManifest, print_manifest_if_requested};
 
 /// Execute the plugin with the provided command-line arguments.
 pub fn run() -> Result<()> {
+  // Answer the manifest handshake from the twig CLI
+  if print_manifest_if_requested(|| PluginManifest::from_command(&Cli::command()))? {
+    return Ok(());
+  }
+
   // Handle shell completion via CompleteEnv (activated by COMPLETE=<shell> env var)
   CompleteEnv::with_factory(Cli::command).complete();
 
@@ -328,6 +328,7 @@ fn handle_stale_jira_association(
 /// creation path re-reads and re-writes `.twig/state.json`), so aborting
 /// here produces a cleaner failure than continuing with stale data.
 fn clear_stale_branch_metadata(repo_path: &Path, branch_name: &str) -> Result<()> {
+  let _lock = RepoState::lock(repo_path)?;
   let mut state = RepoState::load(repo_path)?;
   if state.remove_branch_metadata(branch_name) {
     state.save(repo_path)?;
@@ -621,6 +622,7 @@ fn create_new_branch(
   checkout_branch(repo, branch_name)?;
 
   if let Some(parent) = branch_base.parent_name() {
+    let _lock = RepoState::lock(repo_path)?;
     let mut repo_state = RepoState::load(repo_path)?;
     repo_state.add_dependency(branch_name.to_string(), parent.to_string())?;
     repo_state.save(repo_path)?;
@@ -696,6 +698,7 @@ fn create_branch_with_name(
   checkout_branch(repo, branch_name)?;
 
   if let Some(parent) = branch_base.parent_name() {
+    let _lock = RepoState::lock(repo_path)?;
     let mut repo_state = RepoState::load(repo_path)?;
     repo_state.add_dependency(branch_name.to_string(), parent.to_string())?;
     repo_state.save(repo_path)?;
@@ -774,6 +777,7 @@ fn create_branch_from_jira(
 
     // Record parent dependency in repo state
     if let Some(parent) = branch_base.parent_name() {
+      let _lock = RepoState::lock(repo_path)?;
       let mut repo_state = RepoState::load(repo_path)?;
       repo_state.add_dependency(branch_name.to_string(), parent.to_string())?;
       repo_state.save(repo_path)?;
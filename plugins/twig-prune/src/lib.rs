@@ -2,17 +2,23 @@ mod cli;
 
 use std::collections::HashSet;
 use std::fmt;
+use std::path::Path;
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use dialoguer::MultiSelect;
 use git2::BranchType;
 use owo_colors::OwoColorize;
+use twig_core::config::ConfigDirs;
 use twig_core::git::delete_local_branch;
+use twig_core::hooks::{HookBranch, HookPayload, HookRunner};
+use twig_core::jira_parser::JiraLifecycleEvent;
 use twig_core::output::{print_error, print_info, print_success, print_warning};
 use twig_core::plugin::PluginContext;
+use twig_core::plugin::manifest::{PluginManifest, print_manifest_if_requested};
 use twig_core::state::RepoState;
 use twig_core::{GitHubRepo, twig_theme};
+use twig_jira::TransitionOutcome;
 
 use crate::cli::Cli;
 
@@ -69,6 +75,14 @@ fn pluralize(count: usize, singular: &str, plural: &str) -> String {
 
 /// Execute the plugin with the provided command-line arguments.
 pub fn run() -> Result<()> {
+  // Answer the manifest handshake from the twig CLI
+  if print_manifest_if_requested(|| PluginManifest {
+    completions: false,
+    ..PluginManifest::from_command(&Cli::command())
+  })? {
+    return Ok(());
+  }
+
   let cli = Cli::parse();
   let ctx = PluginContext::discover()?;
 
@@ -141,7 +155,7 @@ pub fn run() -> Result<()> {
 
   // --- GitHub PR check ---
   if !branches_with_prs.is_empty() {
-    match twig_gh::create_github_runtime_and_client(home.home_dir()) {
+    match twig_gh::create_github_runtime_and_client_for_host(home.home_dir(), &github_repo.host) {
       Ok((rt, gh)) => {
         print_info(&format!(
           "Checking {} for {}",
@@ -149,22 +163,30 @@ pub fn run() -> Result<()> {
           github_repo.full_name()
         ));
 
-        for (branch_name, pr_number) in &branches_with_prs {
-          match rt.block_on(gh.get_pull_request(&github_repo.owner, &github_repo.repo, *pr_number)) {
-            Ok(pr) if pr.merged_at.is_some() => {
-              candidates.push(Candidate {
-                branch_name: branch_name.clone(),
-                reason: PruneReason::MergedPr {
-                  number: pr.number,
-                  title: pr.title.clone(),
-                },
-              });
-            }
-            Ok(_) => {} // PR exists but not merged
-            Err(e) => {
-              print_warning(&format!("Could not fetch PR #{pr_number} for '{branch_name}': {e}"));
+        let numbers: Vec<u32> = branches_with_prs.iter().map(|(_, pr)| *pr).collect();
+        match rt.block_on(gh.get_pull_request_summaries(&github_repo.owner, &github_repo.repo, &numbers)) {
+          Ok(summaries) => {
+            for (branch_name, pr_number) in &branches_with_prs {
+              match summaries.get(pr_number) {
+                Some(pr) if pr.is_merged() => {
+                  candidates.push(Candidate {
+                    branch_name: branch_name.clone(),
+                    reason: PruneReason::MergedPr {
+                      number: pr.number,
+                      title: pr.title.clone(),
+                    },
+                  });
+                }
+                Some(_) => {} // PR exists but not merged
+                None => {
+                  print_warning(&format!("Could not fetch PR #{pr_number} for '{branch_name}'"));
+                }
+              }
             }
           }
+          Err(e) => {
+            print_warning(&format!("Could not fetch PRs, skipping PR checks: {e}"));
+          }
         }
       }
       Err(e) => {
@@ -253,6 +275,12 @@ pub fn run() -> Result<()> {
   // --- Dry-run: just list and exit ---
   if cli.dry_run {
     println!();
+    let merged: Vec<&str> = candidates
+      .iter()
+      .filter(|c| matches!(c.reason, PruneReason::MergedPr { .. }))
+      .map(|c| c.branch_name.as_str())
+      .collect();
+    apply_merged_transitions(home.home_dir(), &state, &merged, true);
     print_info("Dry run — no branches were deleted.");
     return Ok(());
   }
@@ -285,6 +313,27 @@ pub fn run() -> Result<()> {
 
   let selected_set: HashSet<usize> = selected_indices.iter().copied().collect();
 
+  // Let pre-prune hooks veto the deletion
+  let selected_branches: Vec<HookBranch> = candidates
+    .iter()
+    .enumerate()
+    .filter(|(i, _)| selected_set.contains(i))
+    .map(|(_, c)| {
+      state.get_branch_metadata(&c.branch_name).map_or_else(
+        || HookBranch {
+          branch: c.branch_name.clone(),
+          ..Default::default()
+        },
+        HookBranch::from,
+      )
+    })
+    .collect();
+  if !selected_branches.is_empty() {
+    HookRunner::for_repo(repo_path)?.run(HookPayload::PrePrune {
+      branches: selected_branches,
+    })?;
+  }
+
   let mut summary = PruneSummary {
     total_candidates: candidates.len(),
     ..Default::default()
@@ -310,6 +359,14 @@ pub fn run() -> Result<()> {
     }
   }
 
+  // Move Jira issues of merged branches along before their metadata is evicted
+  let merged_deleted: Vec<&str> = candidates
+    .iter()
+    .filter(|c| matches!(c.reason, PruneReason::MergedPr { .. }) && summary.deleted.contains(&c.branch_name))
+    .map(|c| c.branch_name.as_str())
+    .collect();
+  apply_merged_transitions(home.home_dir(), &state, &merged_deleted, false);
+
   // Clean up twig state for any deleted branches
   if !summary.deleted.is_empty() {
     let local_branches: HashSet<String> = repo
@@ -320,10 +377,15 @@ pub fn run() -> Result<()> {
       .filter_map(|(b, _)| b.name().ok().flatten().map(|n| n.to_string()))
       .collect();
 
-    let mut state = RepoState::load(repo_path).unwrap_or_default();
-    state.evict_stale_branches(&local_branches);
-    if let Err(e) = state.save(repo_path) {
-      print_error(&format!("Failed to update twig state: {e}"));
+    match RepoState::lock(repo_path) {
+      Ok(_lock) => {
+        let mut state = RepoState::load(repo_path).unwrap_or_default();
+        state.evict_stale_branches(&local_branches);
+        if let Err(e) = state.save(repo_path) {
+          print_error(&format!("Failed to update twig state: {e}"));
+        }
+      }
+      Err(e) => print_error(&format!("Failed to update twig state: {e}")),
     }
   }
 
@@ -333,6 +395,48 @@ pub fn run() -> Result<()> {
   Ok(())
 }
 
+/// Apply the "PR merged" Jira transition rule to the issues linked to
+/// `branches`.
+fn apply_merged_transitions(home: &Path, state: &RepoState, branches: &[&str], dry_run: bool) {
+  let issue_keys: Vec<String> = branches
+    .iter()
+    .filter_map(|branch| state.get_branch_metadata(branch).and_then(|m| m.jira_issue.clone()))
+    .collect();
+  if issue_keys.is_empty() {
+    return;
+  }
+
+  let rules = match ConfigDirs::new().and_then(|dirs| dirs.load_jira_config()) {
+    Ok(config) => config.transitions,
+    Err(e) => {
+      print_warning(&format!("Could not load Jira transition rules: {e}"));
+      return;
+    }
+  };
+  if rules.target_for(JiraLifecycleEvent::PrMerged).is_none() {
+    return;
+  }
+
+  let client = twig_jira::get_jira_host().and_then(|host| twig_jira::create_jira_runtime_and_client(home, &host));
+  let (rt, jira) = match client {
+    Ok(client) => client,
+    Err(e) => {
+      print_warning(&format!("Skipping Jira transitions: {e}"));
+      return;
+    }
+  };
+
+  for issue_key in &issue_keys {
+    match rt.block_on(jira.apply_lifecycle_rule(&rules, JiraLifecycleEvent::PrMerged, issue_key, dry_run)) {
+      Ok(Some(outcome @ TransitionOutcome::Transitioned { .. })) => print_success(&outcome.describe(issue_key)),
+      Ok(Some(outcome @ TransitionOutcome::NotAvailable { .. })) => print_warning(&outcome.describe(issue_key)),
+      Ok(Some(outcome)) => print_info(&outcome.describe(issue_key)),
+      Ok(None) => {}
+      Err(e) => print_warning(&format!("Could not transition {issue_key}: {e}")),
+    }
+  }
+}
+
 /// Display a single candidate with rich formatting and a progress divider.
 fn display_candidate(candidate: &Candidate, current: usize, total: usize) {
   let separator = "─".repeat(22);
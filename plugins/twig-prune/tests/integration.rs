@@ -15,6 +15,17 @@ fn help_output_shows_usage() {
     .stdout(predicate::str::contains("--dry-run"));
 }
 
+#[test]
+fn manifest_handshake_prints_json() {
+  cargo_bin_cmd!("twig-prune")
+    .arg("--twig-manifest")
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("\"name\":\"prune\""))
+    .stdout(predicate::str::contains("\"twig_core\":\"^"))
+    .stdout(predicate::str::contains("\"completions\":false"));
+}
+
 #[test]
 fn skip_prompts_flag_is_documented() {
   cargo_bin_cmd!("twig-prune")
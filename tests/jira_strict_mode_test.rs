@@ -1,4 +1,5 @@
 #![cfg(unix)]
+#![allow(clippy::panic_in_result_fn)]
 
 //! End-to-end regression tests for Jira strict parsing mode.
 //!
@@ -32,7 +33,10 @@ fn setup_config_env() -> Result<(EnvTestGuard, ConfigDirs)> {
 
 /// Save a Jira config with the given mode through the real persistence layer.
 fn save_mode(config_dirs: &ConfigDirs, mode: JiraParsingMode) -> Result<()> {
-  config_dirs.save_jira_config(&JiraParsingConfig { mode })
+  config_dirs.save_jira_config(&JiraParsingConfig {
+    mode,
+    ..JiraParsingConfig::default()
+  })
 }
 
 /// Load config and create a parser — mirrors the `create_jira_parser()` pipeline
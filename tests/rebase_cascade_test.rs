@@ -1,4 +1,5 @@
 #![cfg(unix)]
+#![allow(clippy::panic_in_result_fn, clippy::unwrap_used, clippy::shadow_unrelated)]
 
 use std::fs;
 use std::path::Path;
@@ -267,6 +268,53 @@ fn test_cascade_command() -> Result<()> {
   Ok(())
 }
 
+#[test]
+fn test_cascade_rebases_branches_checked_out_in_worktrees() -> Result<()> {
+  let git_repo = GitRepoTestGuard::new();
+  let repo = &git_repo.repo;
+  let repo_path = git_repo.path();
+
+  create_commit(repo, "file1.txt", "Initial content", "Initial commit")?;
+  ensure_main_branch(repo)?;
+
+  create_branch(repo, "feature", Some("main"))?;
+  checkout_branch(repo, "feature")?;
+  create_commit(repo, "feature.txt", "Feature content", "Feature commit")?;
+
+  checkout_branch(repo, "main")?;
+  create_commit(repo, "main-update.txt", "Updated main content", "Updated main commit")?;
+
+  add_branch_dependency(repo_path, "feature", "main")?;
+  add_root_branch(repo_path, "main", true)?;
+
+  // Check feature out in its own worktree so the main checkout can't switch to it
+  let worktrees_dir = tempfile::TempDir::new()?;
+  let worktree_path = worktrees_dir.path().join("feature");
+  let feature_ref = repo.find_branch("feature", BranchType::Local)?.into_reference();
+  let mut opts = git2::WorktreeAddOptions::new();
+  opts.reference(Some(&feature_ref));
+  repo.worktree("feature", &worktree_path, Some(&opts))?;
+
+  run_cascade_command(repo_path, None, false, false, false, false)?;
+
+  // The branch was rebased in place: its worktree now contains main's update
+  assert!(
+    worktree_path.join("main-update.txt").exists(),
+    "feature's worktree should contain main-update.txt after cascade"
+  );
+  let feature_tree = repo
+    .find_branch("feature", BranchType::Local)?
+    .into_reference()
+    .peel_to_commit()?
+    .tree()?;
+  assert!(feature_tree.get_name("main-update.txt").is_some());
+
+  // The main checkout stays on the branch the cascade started from
+  assert_eq!(repo.head()?.shorthand(), Some("main"));
+
+  Ok(())
+}
+
 #[test]
 fn test_rebase_with_force_flag() -> Result<()> {
   // Create a temporary git repository
@@ -85,7 +85,7 @@ pub(crate) fn handle_adopt_command(args: AdoptArgs) -> Result<()> {
   let repo =
     Git2Repository::open(&repo_path).context(format!("Failed to open git repository at {}", repo_path.display()))?;
 
-  let mut repo_state =
+  let repo_state =
     RepoState::load(&repo_path).with_context(|| format!("Failed to load repo state at {}", repo_path.display()))?;
 
   debug!(
@@ -140,9 +140,13 @@ pub(crate) fn handle_adopt_command(args: AdoptArgs) -> Result<()> {
     return Ok(());
   }
 
+  // Reload under the lock rather than holding it across the prompt, so changes
+  // made by other twig processes in the meantime are kept
   debug!(plan_count = plan.len(), "Applying adoption plan to repository state");
-  apply_plan(&mut repo_state, &plan)?;
-  repo_state.save(&repo_path)?;
+  let _lock = RepoState::lock(&repo_path)?;
+  let mut current_state = RepoState::load(&repo_path)?;
+  apply_plan(&mut current_state, &plan)?;
+  current_state.save(&repo_path)?;
 
   print_success("Adoption complete. Branch relationships updated.");
   Ok(())
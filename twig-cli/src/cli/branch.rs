@@ -234,7 +234,8 @@ pub(crate) fn handle_branch_command(branch: BranchArgs) -> Result<()> {
         detect_repository().context("Not in a git repository")?
       };
 
-      // Load repository state
+      // Load repository state, locked until it is saved
+      let _lock = RepoState::lock(&repo_path)?;
       let mut repo_state = RepoState::load(&repo_path)?;
       let child = resolve_branch_alias(&repo_path, &cmd.child)?;
       let parent = resolve_branch_alias(&repo_path, &cmd.parent)?;
@@ -261,7 +262,8 @@ pub(crate) fn handle_branch_command(branch: BranchArgs) -> Result<()> {
         detect_repository().context("Not in a git repository")?
       };
 
-      // Load repository state
+      // Load repository state, locked until it is saved
+      let _lock = RepoState::lock(&repo_path)?;
       let mut repo_state = RepoState::load(&repo_path)?;
       let child = resolve_branch_alias(&repo_path, &cmd.child)?;
       let parent = resolve_branch_alias(&repo_path, &cmd.parent)?;
@@ -286,7 +288,8 @@ pub(crate) fn handle_branch_command(branch: BranchArgs) -> Result<()> {
           detect_repository().context("Not in a git repository")?
         };
 
-        // Load repository state
+        // Load repository state, locked until it is saved
+        let _lock = RepoState::lock(&repo_path)?;
         let mut repo_state = RepoState::load(&repo_path)?;
         let branch = resolve_branch_alias(&repo_path, &cmd.branch)?;
 
@@ -346,7 +349,8 @@ pub(crate) fn handle_branch_command(branch: BranchArgs) -> Result<()> {
           detect_repository().context("Not in a git repository")?
         };
 
-        // Load repository state
+        // Load repository state, locked until it is saved
+        let _lock = RepoState::lock(&repo_path)?;
         let mut repo_state = RepoState::load(&repo_path)?;
         let branch = resolve_branch_alias(&repo_path, &cmd.branch)?;
 
@@ -9,7 +9,9 @@ use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use clap::Args;
 use git2::Repository as Git2Repository;
-use twig_core::output::{print_error, print_info, print_success, print_warning};
+use twig_core::git::find_checkout_path_for_branch;
+use twig_core::hooks::{CascadeStatus, HookPayload, HookRunner};
+use twig_core::output::{format_repo_path, print_error, print_info, print_success, print_warning};
 use twig_core::{RepoState, detect_repository};
 
 use super::rebase_common::{
@@ -140,6 +142,8 @@ fn rebase_downstream(
     return Ok(());
   }
 
+  let hooks = HookRunner::for_repo(repo_path)?;
+
   // Perform the cascading rebase
   // Track branches that could not be rebased so that their descendants are also skipped.
   let mut failed_branches: HashSet<String> = HashSet::new();
@@ -164,52 +168,79 @@ fn rebase_downstream(
       continue;
     }
 
+    // Branches checked out in another worktree are rebased in place there,
+    // since git refuses to check them out a second time.
+    let work_dir = branch_work_dir(&repo, repo_path, &branch)?;
+    let in_worktree = work_dir != repo_path;
+
     // Rebase this branch onto each of its parents
     for parent in parents {
+      let post_cascade = |status| {
+        hooks.run(HookPayload::PostCascade {
+          branch: branch.clone(),
+          onto: parent.to_string(),
+          work_dir: work_dir.clone(),
+          status,
+        })
+      };
+
+      // A failing pre-cascade hook stops the whole cascade
+      if let Err(e) = hooks.run(HookPayload::PreCascade {
+        branch: branch.clone(),
+        onto: parent.to_string(),
+        work_dir: work_dir.clone(),
+      }) {
+        execute_git_command(repo_path, &["checkout", &current_branch_name])?;
+        return Err(e);
+      }
+
       print_info(&format!("Rebasing {branch} onto {parent}"));
 
-      // First checkout the branch
-      let checkout_result = execute_git_command(repo_path, &["checkout", &branch])?;
-      if !checkout_result.success {
-        let output = checkout_result.output.trim().to_string();
-        if output.contains("is already used by worktree") {
-          print_error(&format!(
-            "Failed to checkout branch {branch}: {output}\n  \
-             Hint: this branch is checked out in another worktree. \
-             Switch to that worktree to rebase it, or use `git worktree remove` \
-             to detach it first."
-          ));
-        } else {
+      if in_worktree {
+        print_info(&format!(
+          "{branch} is checked out in a worktree; rebasing in place at {}",
+          format_repo_path(&work_dir.display().to_string())
+        ));
+      } else {
+        // First checkout the branch
+        let checkout_result = execute_git_command(repo_path, &["checkout", &branch])?;
+        if !checkout_result.success {
+          let output = checkout_result.output.trim().to_string();
           print_error(&format!("Failed to checkout branch {branch}: {output}"));
+          post_cascade(CascadeStatus::Failed)?;
+          failed_branches.insert(branch.clone());
+          continue 'branches;
         }
-        failed_branches.insert(branch.clone());
-        continue 'branches;
       }
 
       // Execute the rebase
-      let result = rebase_branch(repo_path, parent, autostash)?;
+      let result = rebase_branch(&work_dir, parent, autostash)?;
 
       match result {
         RebaseResult::Success => {
           print_success(&format!("Successfully rebased {branch} onto {parent}",));
+          post_cascade(CascadeStatus::Rebased)?;
         }
         RebaseResult::UpToDate => {
           if force {
             // Force rebase even if up-to-date
             print_info("Branch is up-to-date, but force flag is set. Rebasing anyway...");
-            let force_result = rebase_branch_force(repo_path, parent, autostash)?;
+            let force_result = rebase_branch_force(&work_dir, parent, autostash)?;
             match force_result {
               RebaseResult::Success => {
                 print_success(&format!("Successfully force-rebased {branch} onto {parent}",));
+                post_cascade(CascadeStatus::Rebased)?;
               }
               _ => {
                 print_error(&format!("Failed to force-rebase {branch} onto {parent}",));
+                post_cascade(CascadeStatus::Failed)?;
                 failed_branches.insert(branch.clone());
                 continue 'branches;
               }
             }
           } else {
             print_info(&format!("Branch {branch} is already up-to-date with {parent}",));
+            post_cascade(CascadeStatus::UpToDate)?;
           }
         }
         RebaseResult::Conflict => {
@@ -220,11 +251,12 @@ fn rebase_downstream(
             let resolution = handle_rebase_conflict()?;
 
             match resolution {
-              ConflictResolution::Continue => match attempt_rebase_continue(repo_path)? {
+              ConflictResolution::Continue => match attempt_rebase_continue(&work_dir)? {
                 RebaseContinueOutcome::Completed => {
                   print_success(&format!(
                     "Rebase of {branch} onto {parent} completed after resolving conflicts",
                   ));
+                  post_cascade(CascadeStatus::Rebased)?;
                   break 'conflict_loop;
                 }
                 RebaseContinueOutcome::MoreConflicts => continue 'conflict_loop,
@@ -233,14 +265,16 @@ fn rebase_downstream(
                     "Failed to continue rebase of {branch} onto {parent}. \
                      You may need to resolve conflicts manually."
                   ));
-                  abort_rebase(repo_path)?;
+                  abort_rebase(&work_dir)?;
+                  post_cascade(CascadeStatus::Failed)?;
                   failed_branches.insert(branch.clone());
                   continue 'branches;
                 }
               },
               ConflictResolution::AbortToOriginal => {
-                abort_rebase(repo_path)?;
+                abort_rebase(&work_dir)?;
                 print_info(&format!("Rebase of {branch} onto {parent} aborted",));
+                post_cascade(CascadeStatus::Failed)?;
 
                 // Checkout the original branch
                 let checkout_result = execute_git_command(repo_path, &["checkout", &current_branch_name])?;
@@ -251,16 +285,18 @@ fn rebase_downstream(
                 return Ok(());
               }
               ConflictResolution::AbortStayHere => {
-                abort_rebase(repo_path)?;
+                abort_rebase(&work_dir)?;
                 print_info(&format!(
                   "Rebase of {branch} onto {parent} aborted; staying on {branch} and stopping cascade",
                 ));
+                post_cascade(CascadeStatus::Failed)?;
                 stopped_branch = Some(branch.clone());
                 break 'branches;
               }
-              ConflictResolution::Skip => match attempt_rebase_skip(repo_path)? {
+              ConflictResolution::Skip => match attempt_rebase_skip(&work_dir)? {
                 RebaseContinueOutcome::Completed => {
                   print_info(&format!("Skipped commit during rebase of {branch} onto {parent}",));
+                  post_cascade(CascadeStatus::Rebased)?;
                   break 'conflict_loop;
                 }
                 RebaseContinueOutcome::MoreConflicts => continue 'conflict_loop,
@@ -269,7 +305,8 @@ fn rebase_downstream(
                     "Failed to skip commit during rebase of {branch} onto {parent}. \
                      You may need to resolve conflicts manually."
                   ));
-                  abort_rebase(repo_path)?;
+                  abort_rebase(&work_dir)?;
+                  post_cascade(CascadeStatus::Failed)?;
                   failed_branches.insert(branch.clone());
                   continue 'branches;
                 }
@@ -280,6 +317,7 @@ fn rebase_downstream(
         RebaseResult::Error => {
           print_error(&format!("Failed to rebase {branch} onto {parent}",));
           // Skip this branch's descendants since the rebase did not complete.
+          post_cascade(CascadeStatus::Failed)?;
           failed_branches.insert(branch.clone());
           continue 'branches;
         }
@@ -318,6 +356,25 @@ fn rebase_downstream(
   Ok(())
 }
 
+/// Directory in which `branch` should be rebased: the worktree that already
+/// has it checked out, or the repository the cascade was started from.
+fn branch_work_dir(repo: &Git2Repository, repo_path: &Path, branch: &str) -> Result<PathBuf> {
+  let Some(checkout_path) = find_checkout_path_for_branch(repo, branch)? else {
+    return Ok(repo_path.to_path_buf());
+  };
+
+  let same_dir = match (checkout_path.canonicalize(), repo_path.canonicalize()) {
+    (Ok(a), Ok(b)) => a == b,
+    _ => checkout_path == repo_path,
+  };
+
+  if same_dir {
+    Ok(repo_path.to_path_buf())
+  } else {
+    Ok(checkout_path)
+  }
+}
+
 /// Get all descendants of a branch up to a certain depth
 fn get_all_descendants(repo_state: &RepoState, branch: &str, max_depth: Option<u32>) -> Vec<String> {
   let mut descendants = Vec::new();
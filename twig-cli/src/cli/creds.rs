@@ -4,17 +4,26 @@
 //! credentials for external services like Jira and GitHub.
 
 use std::io::{self, Write};
+use std::path::Path;
 
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
 use directories::BaseDirs;
+use owo_colors::OwoColorize;
 use tokio::runtime::Runtime;
-use twig_core::output::{format_command, format_repo_path, print_error, print_info, print_success, print_warning};
-use twig_gh::{check_github_credentials, create_github_client};
-use twig_jira::{check_jira_credentials, create_jira_client, get_jira_host};
-
-use crate::creds::netrc::{get_netrc_path, write_netrc_entry};
-#[cfg(unix)]
+use twig_core::config::get_config_dirs;
+use twig_core::creds::Credentials;
+use twig_core::creds::chain::{CredentialBackendKind, CredentialChain, GITHUB_TOKEN_USER};
+use twig_core::github::GITHUB_COM_HOST;
+use twig_core::open_url_in_browser;
+use twig_core::output::{format_command, print_error, print_info, print_success, print_warning};
+use twig_gh::oauth::{ENV_GITHUB_CLIENT_ID, resolve_client_id};
+use twig_gh::{
+  DeviceFlow, REQUIRED_SCOPES, create_github_client, find_github_credentials, missing_scopes, store_oauth_token,
+};
+use twig_jira::{create_jira_client, find_jira_credentials, get_jira_host};
+
+use crate::creds::netrc::get_netrc_path;
 use crate::creds::platform::FilePermissions;
 #[cfg(unix)]
 use crate::creds::platform::UnixFilePermissions;
@@ -35,18 +44,39 @@ pub enum CredsSubcommands {
   /// Check if credentials are properly configured
   #[command(
     long_about = "Checks if credentials for Jira and GitHub are properly configured.\n\n\
-                      This command verifies that your .netrc file contains the necessary\n\
-                      credentials for the services that twig integrates with. It also checks\n\
-                      file permissions to ensure your credentials are secure."
+                      This command looks up credentials through the backends configured in\n\
+                      creds.toml (environment variables, keyring, GitHub CLI, credential helper,\n\
+                      .netrc) and reports which backend answered. It also checks .netrc file\n\
+                      permissions to ensure your credentials are secure."
   )]
   Check,
 
   /// Set up credentials interactively
   #[command(long_about = "Interactive wizard to set up credentials for Jira and GitHub.\n\n\
                       This command guides you through the process of setting up credentials\n\
-                      for the services that twig integrates with. It will create or update\n\
-                      your .netrc file with the provided credentials.")]
-  Setup,
+                      for the services that twig integrates with. Credentials are stored in\n\
+                      the first writable backend from creds.toml (keyring, helper or .netrc).\n\n\
+                      When an OAuth client ID is available (--client-id, TWIG_GITHUB_CLIENT_ID\n\
+                      or oauth_client_id in github.toml), GitHub login uses the device flow:\n\
+                      twig shows a code to enter in the browser and stores the issued token.\n\
+                      Expiring tokens are refreshed automatically.")]
+  Setup(SetupArgs),
+}
+
+/// Arguments for the setup command
+#[derive(Args)]
+pub struct SetupArgs {
+  /// GitHub host to log in to, e.g. a GitHub Enterprise Server host
+  #[arg(long, value_name = "HOST", default_value = GITHUB_COM_HOST)]
+  pub github_host: String,
+
+  /// Client ID of the OAuth app used for device-flow login
+  #[arg(long, value_name = "ID")]
+  pub client_id: Option<String>,
+
+  /// Paste a personal access token instead of using device-flow login
+  #[arg(long)]
+  pub token: bool,
 }
 
 /// Handle the creds command
@@ -57,62 +87,38 @@ pub enum CredsSubcommands {
 pub(crate) fn handle_creds_command(creds: CredsArgs) -> Result<()> {
   match creds.subcommand {
     CredsSubcommands::Check => handle_check_command(),
-    CredsSubcommands::Setup => handle_setup_command(),
+    CredsSubcommands::Setup(args) => handle_setup_command(args),
   }
 }
 
 /// Handle the check command
 ///
-/// This function checks if the .netrc file exists, verifies its permissions,
-/// and checks for Jira and GitHub credentials. It also prints an example
-/// .netrc format for user reference.
+/// This function lists the configured credential backends, checks the .netrc
+/// file permissions when .netrc is one of them, and reports which backend
+/// provided the Jira and GitHub credentials.
 fn handle_check_command() -> Result<()> {
   let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
   let home_dir = base_dirs.home_dir();
 
-  let netrc_path = get_netrc_path(home_dir);
-
-  // Check if .netrc file exists
-  if !netrc_path.exists() {
-    print_error("No .netrc file found.");
-    println!(
-      "Create a .netrc file at {} with your credentials.",
-      format_repo_path(&netrc_path.display().to_string())
-    );
-    return Ok(());
-  }
+  let chain = CredentialChain::load(home_dir)?;
+  let backends: Vec<String> = chain.backends().iter().map(ToString::to_string).collect();
+  print_info(&format!("Credential backends (in order): {}", backends.join(", ")));
 
-  // Check file permissions
-  #[cfg(unix)]
-  {
-    let has_secure_permissions = UnixFilePermissions::has_secure_permissions(&netrc_path)?;
-    if !has_secure_permissions {
-      print_warning("Your .netrc file has insecure permissions.");
-      println!(
-        "For security, change permissions to 600: {}",
-        format_command(&format!("chmod 600 {}", netrc_path.display()))
-      );
-    } else {
-      print_success(".netrc file has secure permissions.");
-    }
+  let netrc_path = get_netrc_path(home_dir);
+  if chain.backends().contains(&CredentialBackendKind::Netrc) && netrc_path.exists() {
+    check_netrc_permissions(&netrc_path)?;
   }
 
-  #[cfg(windows)]
-  {
-    use crate::creds::platform::FilePermissions;
-    let _ = WindowsFilePermissions::has_secure_permissions(&netrc_path);
-    print_warning("Secure file permissions are not fully supported on Windows.");
-    print_warning("Your .netrc file may not be properly secured.");
-    println!("For security, consider using Windows Credential Manager instead.");
-  }
+  let mut missing = false;
 
   // Check Jira credentials
   match get_jira_host() {
-    Ok(jira_host) => match check_jira_credentials(home_dir, &jira_host) {
-      Ok(true) => print_success("Jira credentials found."),
-      Ok(false) => {
+    Ok(jira_host) => match find_jira_credentials(home_dir, &jira_host) {
+      Ok(Some(found)) => print_success(&format!("Jira credentials found in {}.", found.source())),
+      Ok(None) => {
+        missing = true;
         print_warning("No Jira credentials found.");
-        println!("Add credentials for machine 'atlassian.net' to your .netrc file.");
+        println!("Set TWIG_JIRA_USER and TWIG_JIRA_TOKEN, or add credentials for machine 'atlassian.net'.");
       }
       Err(e) => print_error(&format!("Error checking Jira credentials: {e}")),
     },
@@ -120,57 +126,89 @@ fn handle_check_command() -> Result<()> {
   }
 
   // Check GitHub credentials
-  match check_github_credentials(home_dir) {
-    Ok(true) => print_success("GitHub credentials found."),
-    Ok(false) => {
+  match find_github_credentials(home_dir, "github.com") {
+    Ok(Some(found)) => print_success(&format!("GitHub credentials found in {}.", found.source())),
+    Ok(None) => {
+      missing = true;
       print_warning("No GitHub credentials found.");
-      println!("Add credentials for machine 'github.com' to your .netrc file.");
+      println!("Set TWIG_GITHUB_TOKEN, or add credentials for machine 'github.com'.");
     }
     Err(e) => print_error(&format!("Error checking GitHub credentials: {e}")),
   }
 
-  // Print .netrc format example
-  print_info("Example .netrc format:");
-  println!("```");
-  println!("machine atlassian.net");
-  println!("  login your-email@example.com");
-  println!("  password your-api-token");
-  println!();
-  println!("machine github.com");
-  println!("  login your-github-username");
-  println!("  password your-personal-access-token");
-  println!("```");
+  if missing {
+    // Print .netrc format example
+    print_info("Example .netrc format:");
+    println!("```");
+    println!("machine atlassian.net");
+    println!("  login your-email@example.com");
+    println!("  password your-api-token");
+    println!();
+    println!("machine github.com");
+    println!("  login your-github-username");
+    println!("  password your-personal-access-token");
+    println!("```");
+    print_info("Other backends (keyring, gh, helper) can be enabled in creds.toml.");
+  }
 
   Ok(())
 }
 
-/// Handle the setup command
-fn handle_setup_command() -> Result<()> {
-  print_info("Welcome to the twig credential setup wizard!");
-  println!("This wizard will help you configure credentials for Jira and GitHub.");
-  println!();
-
+/// Warn when the .netrc file is readable by other users
+fn check_netrc_permissions(netrc_path: &Path) -> Result<()> {
   #[cfg(unix)]
   {
-    println!("• Credentials will be stored in ~/.netrc");
-    println!("• File permissions will be automatically set to 600 for security");
+    let has_secure_permissions = UnixFilePermissions::has_secure_permissions(netrc_path)?;
+    if !has_secure_permissions {
+      print_warning("Your .netrc file has insecure permissions.");
+      println!(
+        "For security, change permissions to 600: {}",
+        format_command(&format!("chmod 600 {}", netrc_path.display()))
+      );
+    } else {
+      print_success(".netrc file has secure permissions.");
+    }
   }
 
   #[cfg(windows)]
   {
-    println!("• Credentials will be stored in Windows Credential Manager");
-    println!("• Will fall back to ~/.netrc if it exists");
+    let _ = WindowsFilePermissions::has_secure_permissions(netrc_path);
+    print_warning("Secure file permissions are not fully supported on Windows.");
+    print_warning("Your .netrc file may not be properly secured.");
+    println!("For security, consider using Windows Credential Manager instead.");
   }
 
+  Ok(())
+}
+
+/// Handle the setup command
+fn handle_setup_command(args: SetupArgs) -> Result<()> {
+  print_info("Welcome to the twig credential setup wizard!");
+  println!("This wizard will help you configure credentials for Jira and GitHub.");
   println!();
 
   let rt = Runtime::new()?;
 
   let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
   let netrc_path = get_netrc_path(base_dirs.home_dir());
+  let chain = CredentialChain::load(base_dirs.home_dir())?;
+
+  let Some(store_backend) = chain.store_backend() else {
+    print_error("None of the credential backends configured in creds.toml can store credentials.");
+    println!("Add 'keyring', 'helper' or 'netrc' to the backends list, or export TWIG_GITHUB_TOKEN/TWIG_JIRA_TOKEN.");
+    return Ok(());
+  };
+
+  println!("• Credentials will be stored in the {}", store_backend.description());
+  if store_backend == CredentialBackendKind::Netrc {
+    #[cfg(unix)]
+    println!("• File permissions will be automatically set to 600 for security");
+  }
+
+  println!();
 
   // Check if .netrc exists and warn about overwriting
-  if netrc_path.exists() {
+  if store_backend == CredentialBackendKind::Netrc && netrc_path.exists() {
     print_warning("A .netrc file already exists.");
     print!("Do you want to add/update credentials? (y/n): ");
     io::stdout().flush()?;
@@ -236,7 +274,7 @@ fn handle_setup_command() -> Result<()> {
         match rt.block_on(client.test_connection()) {
           Ok(true) => {
             print_success("Jira credentials validated successfully!");
-            write_netrc_entry(&netrc_path, "atlassian.net", &jira_email, &jira_token)?;
+            store_credentials(&chain, "atlassian.net", &jira_email, &jira_token)?;
           }
           Ok(false) => {
             print_error("Failed to validate Jira credentials. Please check your credentials and domain.");
@@ -258,12 +296,96 @@ fn handle_setup_command() -> Result<()> {
 
   println!();
   print_info("Setting up GitHub credentials:");
+  let github_host = args.github_host.trim().to_lowercase();
+  let github_config = get_config_dirs()?.load_github_config()?;
+  match resolve_client_id(args.client_id.as_deref(), &github_config, &github_host).filter(|_| !args.token) {
+    Some(client_id) => {
+      let flow = DeviceFlow::for_host(&github_config, &github_host, &client_id);
+      if let Err(e) = rt.block_on(github_device_login(
+        &flow,
+        base_dirs.home_dir(),
+        &github_host,
+        &client_id,
+        &github_config.api_url(&github_host),
+      )) {
+        print_error(&format!("GitHub device login failed: {e:#}"));
+        println!(
+          "You can run {} to paste a personal access token instead.",
+          format_command("twig creds setup --token")
+        );
+      }
+    }
+    None => setup_github_token(&rt, &chain, &github_host, &github_config.api_url(&github_host))?,
+  }
+
+  // Set secure permissions on .netrc
+  if store_backend == CredentialBackendKind::Netrc && netrc_path.exists() {
+    #[cfg(unix)]
+    {
+      UnixFilePermissions::set_secure_permissions(&netrc_path)?;
+      print_success("Set secure permissions on .netrc file (600).");
+    }
+  }
+
+  println!();
+  print_success("Credential setup complete!");
+  print_info("You can now use twig with Jira and GitHub integration.");
+  print_info(&format!(
+    "Run {} to verify your credentials.",
+    format_command("twig creds check")
+  ));
+
+  Ok(())
+}
+
+/// Log in to GitHub with the OAuth device flow and store the issued token
+async fn github_device_login(flow: &DeviceFlow, home: &Path, host: &str, client_id: &str, api_url: &str) -> Result<()> {
+  let code = flow.request_code(REQUIRED_SCOPES).await?;
+  println!(
+    "Open {} and enter the code {}",
+    code.verification_uri,
+    code.user_code.bold()
+  );
+  if open_url_in_browser(&code.verification_uri).is_err() {
+    println!("(Could not open a browser; open the URL manually.)");
+  }
+  print_info("Waiting for authorization...");
+  let token = flow.poll_for_token(&code).await?;
+
+  let mut client = create_github_client(GITHUB_TOKEN_USER, &token.access_token);
+  client.set_base_url(api_url);
+  let user = client
+    .get_current_user()
+    .await
+    .context("Failed to validate the new token")?;
+  let scopes = client.get_token_scopes().await?;
+  if !check_scopes(scopes.as_deref()) {
+    return Ok(());
+  }
+
+  let backend = store_oauth_token(home, host, &user.login, client_id, &token)
+    .with_context(|| format!("Failed to store credentials for {host}"))?;
+  print_success(&format!("Logged in to {host} as {}.", user.login));
+  print_success(&format!(
+    "Stored credentials for {host} in the {}.",
+    backend.description()
+  ));
+  if token.expires_in.is_some() {
+    print_info("The token expires and will be refreshed automatically.");
+  }
+  Ok(())
+}
+
+/// Prompt for a GitHub personal access token, validate it and store it
+fn setup_github_token(rt: &Runtime, chain: &CredentialChain, host: &str, api_url: &str) -> Result<()> {
   println!("You'll need your GitHub username and a Personal Access Token.");
-  println!("To create a PAT, visit: https://github.com/settings/tokens");
-  println!("Required scopes: repo, read:user");
+  println!("To create a PAT, visit: https://{host}/settings/tokens");
+  println!("Required scopes: {}", REQUIRED_SCOPES.join(", "));
+  println!(
+    "Tip: configure an OAuth client ID (--client-id or {ENV_GITHUB_CLIENT_ID}) to log in with the device flow instead."
+  );
   println!();
 
-  // Get GitHub credentials
   print!("Enter your GitHub username: ");
   io::stdout().flush()?;
   let mut github_username = String::new();
@@ -274,65 +396,76 @@ fn handle_setup_command() -> Result<()> {
     print_warning("Username cannot be empty. Skipping GitHub setup.");
     println!("You can run 'twig creds setup' again to configure GitHub later.");
     println!();
-  } else {
-    print!("Enter your GitHub Personal Access Token: ");
-    io::stdout().flush()?;
-    let mut github_token = String::new();
-    io::stdin().read_line(&mut github_token)?;
-    let github_token = github_token.trim().to_string();
+    return Ok(());
+  }
 
-    if github_token.is_empty() {
-      print_warning("Personal Access Token cannot be empty. Skipping GitHub setup.");
-      println!("You can run 'twig creds setup' again to configure GitHub later.");
-      println!();
-    } else {
-      // Validate GitHub credentials
-      print_info("Validating GitHub credentials...");
-      let client = create_github_client(&github_username, &github_token);
-      match rt.block_on(client.test_connection()) {
-        Ok(true) => {
-          print_success("GitHub credentials validated successfully!");
-          write_netrc_entry(&netrc_path, "github.com", &github_username, &github_token)?;
-        }
-        Ok(false) => {
-          print_error("Failed to validate GitHub credentials. Please check your username and token.");
-          print_info("Common issues:");
-          println!("  • Make sure your username is correct");
-          println!("  • Verify your Personal Access Token is valid and not expired");
-          println!("  • Check that the token has required scopes: repo, read:user");
-          print_info("You can manually add credentials to your .netrc file later.");
-        }
-        Err(e) => {
-          print_error(&format!("Error validating GitHub credentials: {e}",));
-          print_info("This might be a network issue or GitHub might be unreachable.");
-          print_info("You can manually add credentials to your .netrc file later.");
-        }
-      }
-    }
+  print!("Enter your GitHub Personal Access Token: ");
+  io::stdout().flush()?;
+  let mut github_token = String::new();
+  io::stdin().read_line(&mut github_token)?;
+  let github_token = github_token.trim().to_string();
+
+  if github_token.is_empty() {
+    print_warning("Personal Access Token cannot be empty. Skipping GitHub setup.");
+    println!("You can run 'twig creds setup' again to configure GitHub later.");
+    println!();
+    return Ok(());
   }
 
-  // Set secure permissions on .netrc
-  if netrc_path.exists() {
-    #[cfg(unix)]
-    {
-      UnixFilePermissions::set_secure_permissions(&netrc_path)?;
-      print_success("Set secure permissions on .netrc file (600).");
+  // Validate GitHub credentials
+  print_info("Validating GitHub credentials...");
+  let mut client = create_github_client(&github_username, &github_token);
+  client.set_base_url(api_url);
+  match rt.block_on(client.get_token_scopes()) {
+    Ok(scopes) => {
+      print_success("GitHub credentials validated successfully!");
+      if check_scopes(scopes.as_deref()) {
+        store_credentials(chain, host, &github_username, &github_token)?;
+      }
     }
-
-    #[cfg(windows)]
-    {
-      print_info("Found existing .netrc file that will be used as a fallback if needed.");
-      print_info("Windows Credential Manager will be used as the primary credential store.");
+    Err(e) => {
+      print_error(&format!("Failed to validate GitHub credentials: {e}"));
+      print_info("Common issues:");
+      println!("  • Make sure your username is correct");
+      println!("  • Verify your Personal Access Token is valid and not expired");
+      println!(
+        "  • Check that the token has required scopes: {}",
+        REQUIRED_SCOPES.join(", ")
+      );
+      print_info("You can manually add credentials to your .netrc file later.");
     }
   }
+  Ok(())
+}
 
-  println!();
-  print_success("Credential setup complete!");
-  print_info("You can now use twig with Jira and GitHub integration.");
-  print_info(&format!(
-    "Run {} to verify your credentials.",
-    format_command("twig creds check")
-  ));
+/// Report whether the token's scopes cover what twig needs. Tokens without
+/// scope information (fine-grained tokens, GitHub App tokens) are accepted.
+fn check_scopes(scopes: Option<&[String]>) -> bool {
+  let Some(scopes) = scopes else {
+    print_info("The token does not report OAuth scopes; make sure it can read repositories and pull requests.");
+    return true;
+  };
+  let missing = missing_scopes(scopes, REQUIRED_SCOPES);
+  if missing.is_empty() {
+    return true;
+  }
+  print_error(&format!("The token is missing required scopes: {}", missing.join(", ")));
+  println!("Required scopes: {}", REQUIRED_SCOPES.join(", "));
+  false
+}
 
+/// Store validated credentials in the chain's writable backend
+fn store_credentials(chain: &CredentialChain, host: &str, username: &str, password: &str) -> Result<()> {
+  let credentials = Credentials {
+    username: username.to_string(),
+    password: password.to_string(),
+  };
+  let backend = chain
+    .store(host, &credentials)
+    .with_context(|| format!("Failed to store credentials for {host}"))?;
+  print_success(&format!(
+    "Stored credentials for {host} in the {}.",
+    backend.description()
+  ));
   Ok(())
 }
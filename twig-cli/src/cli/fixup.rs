@@ -1,7 +1,7 @@
 //! # Fixup Command
 //!
 //! Implements the `fixup` command for creating fixup commits with an
-//! interactive commit selector using skim fuzzy finder.
+//! interactive commit selector (see [`crate::fixup::selector`]).
 
 use anyhow::{Context, Result};
 use clap::Args;
@@ -4,25 +4,35 @@
 //! including pull request management, status checks, and synchronization with
 //! branch metadata for development workflows.
 
+use std::collections::BTreeSet;
+use std::time::Duration;
+
 use anyhow::{Context, Result};
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
 use directories::BaseDirs;
 use git2::Repository as Git2Repository;
 use owo_colors::OwoColorize;
 use tabled::settings::Style;
 use tabled::{Table, Tabled};
 use tokio::runtime::Runtime;
+use twig_core::github::GITHUB_COM_HOST;
+use twig_core::jira_parser::JiraLifecycleEvent;
 use twig_core::output::{
   format_check_status, format_command, format_pr_review_status, print_error, print_info, print_success, print_warning,
 };
 use twig_core::state::BranchMetadata;
 use twig_core::{
-  RepoState, detect_repository, detect_repository_from_path, get_current_branch_github_pr, truncate_string,
+  RepoState, detect_repository, detect_repository_from_path, get_current_branch_github_pr, resolve_origin_github_repo,
+  truncate_string,
 };
+use twig_gh::endpoints::actions::log_tail;
 use twig_gh::{
-  GitHubPr, GitHubRepo, PullRequestStatus, create_github_client_from_netrc, create_github_runtime_and_client,
+  CheckRun, GitHubClient, GitHubPr, GitHubRepo, PullRequestStatus, ReviewThread, create_github_client_for_host,
+  create_github_runtime_and_client_for_host, group_threads_by_file,
 };
 
+use super::jira::run_lifecycle_transitions;
+
 /// Command for GitHub integration
 #[derive(Args)]
 pub struct GitHubArgs {
@@ -56,7 +66,9 @@ pub enum GitHubSubcommands {
   /// View CI/CD checks for a PR
   #[command(long_about = "View CI/CD checks for a GitHub pull request.\n\n\
                       This command displays the status of CI/CD checks for a specific pull request,\n\
-                      including check name, status, conclusion, and links to detailed results.")]
+                      including check name, status, conclusion, and links to detailed results.\n\n\
+                      Use --logs to print the end of failed GitHub Actions job logs, --rerun to re-run\n\
+                      failed jobs, and --watch to wait for checks to finish (exits non-zero on failure).")]
   #[command(alias = "ci")]
   Checks(ChecksCommand),
 
@@ -83,6 +95,47 @@ pub struct ChecksCommand {
   /// Path to a specific repository (defaults to current repository)
   #[arg(long, short = 'r', value_name = "PATH")]
   pub repo: Option<String>,
+
+  /// Show the end of the logs of failed GitHub Actions jobs
+  #[arg(long)]
+  pub logs: bool,
+
+  /// Number of log lines to show per failed job
+  #[arg(long, value_name = "LINES", default_value = "50")]
+  pub tail: usize,
+
+  /// Re-run failed GitHub Actions jobs, or their whole workflows with "all"
+  #[arg(
+    long,
+    value_enum,
+    value_name = "SCOPE",
+    num_args = 0..=1,
+    require_equals = true,
+    default_missing_value = "failed"
+  )]
+  pub rerun: Option<RerunScope>,
+
+  /// Wait until all checks complete; exits with an error if any fail
+  #[arg(long, short = 'w')]
+  pub watch: bool,
+
+  /// Seconds between polls while watching
+  #[arg(long, value_name = "SECONDS", default_value = "15")]
+  pub interval: u64,
+
+  /// Give up watching after this many seconds, exiting with an error.
+  /// Without it, watching stops if no checks have started after 20 polls
+  #[arg(long, value_name = "SECONDS", requires = "watch")]
+  pub timeout: Option<u64>,
+}
+
+/// What to re-run for failed checks
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum RerunScope {
+  /// Only the failed jobs of each workflow run
+  Failed,
+  /// Every job of each workflow run with a failure
+  All,
 }
 
 /// Pull request operations
@@ -117,6 +170,30 @@ pub enum PrSubcommands {
   )]
   #[command(alias = "st")]
   Status,
+
+  /// Show review threads and comments for a PR
+  #[command(
+    long_about = "Show the review threads and conversation comments on a pull request.\n\n\
+                           Inline review threads are grouped by file and marked as resolved or\n\
+                           unresolved. Use --unresolved to only show what is left to address."
+  )]
+  Comments(CommentsCommand),
+}
+
+/// Show review threads and comments for a PR
+#[derive(Args)]
+pub struct CommentsCommand {
+  /// PR number (defaults to current branch's PR)
+  #[arg(index = 1)]
+  pub pr_number: Option<String>,
+
+  /// Path to a specific repository (defaults to current repository)
+  #[arg(long, short = 'r', value_name = "PATH")]
+  pub repo: Option<String>,
+
+  /// Only show unresolved review threads
+  #[arg(long, short = 'u')]
+  pub unresolved: bool,
 }
 
 /// List pull requests for a repository
@@ -180,6 +257,7 @@ pub(crate) fn handle_github_command(github: GitHubArgs) -> Result<()> {
       }
       PrSubcommands::List(list) => handle_pr_list_command(&list),
       PrSubcommands::Status => handle_pr_status_command(),
+      PrSubcommands::Comments(comments) => handle_pr_comments_command(&comments),
     },
   }
 }
@@ -233,8 +311,8 @@ fn handle_github_open_command(cmd: &OpenCommand) -> Result<()> {
   };
 
   // Extract owner and repo from remote URL
-  let (owner, repo_name) = match GitHubRepo::parse(remote_url) {
-    Ok(repo) => (repo.owner, repo.repo),
+  let (host, owner, repo_name) = match GitHubRepo::parse(remote_url) {
+    Ok(repo) => (repo.host, repo.owner, repo.repo),
     Err(e) => {
       print_error(&format!("Failed to extract repository info from URL: {e}"));
       return Ok(());
@@ -272,7 +350,7 @@ fn handle_github_open_command(cmd: &OpenCommand) -> Result<()> {
   };
 
   // Construct GitHub PR URL
-  let url = format!("https://github.com/{owner}/{repo_name}/pull/{pr_number}");
+  let url = format!("https://{host}/{owner}/{repo_name}/pull/{pr_number}");
 
   // Open URL in browser
   open_url_in_browser(&url)
@@ -280,13 +358,20 @@ fn handle_github_open_command(cmd: &OpenCommand) -> Result<()> {
 
 /// Handle the check command
 fn handle_check_command() -> Result<()> {
+  // Check the host of the current repository's origin, if there is one, so
+  // GitHub Enterprise Server credentials can be verified too.
+  let host = detect_repository()
+    .and_then(|path| Git2Repository::open(path).ok())
+    .and_then(|repo| resolve_origin_github_repo(&repo).ok())
+    .map_or_else(|| GITHUB_COM_HOST.to_string(), |repo| repo.host);
+
   let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
-  let (rt, github_client) = create_github_runtime_and_client(base_dirs.home_dir())?;
+  let (rt, github_client) = create_github_runtime_and_client_for_host(base_dirs.home_dir(), &host)?;
 
   // Test connection
   match rt.block_on(github_client.test_connection()) {
     Ok(true) => {
-      print_success("Successfully authenticated with GitHub");
+      print_success(&format!("Successfully authenticated with GitHub ({host})"));
 
       // Get user information
       match rt.block_on(github_client.get_current_user()) {
@@ -316,9 +401,6 @@ fn handle_check_command() -> Result<()> {
 
 /// Handle the checks command
 fn handle_checks_command(cmd: &ChecksCommand) -> Result<()> {
-  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
-  let (rt, github_client) = create_github_runtime_and_client(base_dirs.home_dir())?;
-
   // Get repository path (current or specified)
   let repo_path = if let Some(path) = &cmd.repo {
     detect_repository_from_path(path)
@@ -330,10 +412,11 @@ fn handle_checks_command(cmd: &ChecksCommand) -> Result<()> {
   let repo_path = match repo_path {
     Some(path) => path,
     None => {
-      print_error(
-        "No git repository found. Make sure you're in a git repository or specify a valid repository path with --repo",
+      return checks_failure(
+        cmd.watch,
+        "No git repository found. Make sure you're in a git repository or specify a valid repository path with --repo"
+          .to_string(),
       );
-      return Ok(());
     }
   };
 
@@ -341,8 +424,7 @@ fn handle_checks_command(cmd: &ChecksCommand) -> Result<()> {
   let repo = match Git2Repository::open(&repo_path) {
     Ok(repo) => repo,
     Err(e) => {
-      print_error(&format!("Failed to open git repository: {e}"));
-      return Ok(());
+      return checks_failure(cmd.watch, format!("Failed to open git repository: {e}"));
     }
   };
 
@@ -350,36 +432,35 @@ fn handle_checks_command(cmd: &ChecksCommand) -> Result<()> {
   let remote = match repo.find_remote("origin") {
     Ok(remote) => remote,
     Err(e) => {
-      print_error(&format!("Failed to find remote 'origin': {e}"));
-      return Ok(());
+      return checks_failure(cmd.watch, format!("Failed to find remote 'origin': {e}"));
     }
   };
 
   let remote_url = match remote.url() {
     Some(url) => url,
     None => {
-      print_error("Failed to get remote URL");
-      return Ok(());
+      return checks_failure(cmd.watch, "Failed to get remote URL".to_string());
     }
   };
 
   // Extract owner and repo from remote URL
-  let (owner, repo_name) = match GitHubRepo::parse(remote_url) {
-    Ok(repo) => (repo.owner, repo.repo),
+  let (host, owner, repo_name) = match GitHubRepo::parse(remote_url) {
+    Ok(repo) => (repo.host, repo.owner, repo.repo),
     Err(e) => {
-      print_error(&format!("Failed to extract repository info from URL: {e}"));
-      return Ok(());
+      return checks_failure(cmd.watch, format!("Failed to extract repository info from URL: {e}"));
     }
   };
 
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let (rt, github_client) = create_github_runtime_and_client_for_host(base_dirs.home_dir(), &host)?;
+
   // Determine PR number
   let pr_number = if let Some(pr_num_str) = &cmd.pr_number {
     // PR number provided as argument
     match pr_num_str.parse::<u32>() {
       Ok(num) => num,
       Err(_) => {
-        print_error(&format!("Invalid PR number: {pr_num_str}"));
-        return Ok(());
+        return checks_failure(cmd.watch, format!("Invalid PR number: {pr_num_str}"));
       }
     }
   } else {
@@ -391,29 +472,25 @@ fn handle_checks_command(cmd: &ChecksCommand) -> Result<()> {
         let head = match repo.head() {
           Ok(head) => head,
           Err(e) => {
-            print_error(&format!("Failed to get repository HEAD: {e}"));
-            return Ok(());
+            return checks_failure(cmd.watch, format!("Failed to get repository HEAD: {e}"));
           }
         };
 
         let branch_name = match head.shorthand() {
           Some(name) => name,
           None => {
-            print_error("Failed to get branch name");
-            return Ok(());
+            return checks_failure(cmd.watch, "Failed to get branch name".to_string());
           }
         };
 
-        print_error(&format!("Branch '{branch_name}' has no associated PR"));
         print_info(&format!(
           "Link a PR with {} or specify a PR number",
           format_command("twig github pr link <pr-url>")
         ));
-        return Ok(());
+        return checks_failure(cmd.watch, format!("Branch '{branch_name}' has no associated PR"));
       }
       Err(e) => {
-        print_error(&format!("Failed to get associated PR: {e}"));
-        return Ok(());
+        return checks_failure(cmd.watch, format!("Failed to get associated PR: {e}"));
       }
     }
   };
@@ -424,100 +501,314 @@ fn handle_checks_command(cmd: &ChecksCommand) -> Result<()> {
   let pr = match rt.block_on(github_client.get_pull_request(&owner, &repo_name, pr_number)) {
     Ok(pr) => pr,
     Err(e) => {
-      print_error(&format!("Failed to fetch PR: {e}"));
-      return Ok(());
+      return checks_failure(cmd.watch, format!("Failed to fetch PR: {e}"));
     }
   };
 
   // Fetch check runs for the PR's head commit
   println!("Fetching checks for commit {}...", pr.head.sha);
 
-  match rt.block_on(github_client.get_check_runs(&owner, &repo_name, &pr.head.sha)) {
-    Ok(check_runs) => {
-      if check_runs.is_empty() {
-        println!("No checks found for this PR");
-        return Ok(());
+  let mut check_runs = match rt.block_on(github_client.get_check_runs(&owner, &repo_name, &pr.head.sha)) {
+    Ok(check_runs) => check_runs,
+    Err(e) => {
+      return checks_failure(cmd.watch, format!("Failed to fetch check runs: {e}"));
+    }
+  };
+
+  let mut rerun_started = false;
+  if let Some(scope) = cmd.rerun {
+    rerun_started = rerun_failed_checks(&rt, &github_client, &owner, &repo_name, &check_runs, scope);
+  }
+
+  if cmd.watch {
+    let interval = Duration::from_secs(cmd.interval.max(1));
+    if rerun_started {
+      // Give GitHub a moment to register the new runs before polling.
+      std::thread::sleep(interval);
+    }
+    let timeout = cmd.timeout.map(Duration::from_secs);
+    check_runs = watch_checks(&rt, &github_client, &owner, &repo_name, pr_number, interval, timeout)?;
+  }
+
+  if check_runs.is_empty() {
+    println!("No checks found for this PR");
+    return Ok(());
+  }
+
+  display_check_runs(&check_runs);
+
+  if cmd.logs {
+    show_failed_job_logs(&rt, &github_client, &owner, &repo_name, &check_runs, cmd.tail);
+  }
+
+  if cmd.watch {
+    let failed = check_runs.iter().filter(|run| run.is_failed()).count();
+    if failed > 0 {
+      return Err(anyhow::anyhow!(
+        "{failed} of {} checks failed for PR #{pr_number}",
+        check_runs.len()
+      ));
+    }
+    print_success(&format!("All checks passed for PR #{pr_number}"));
+  }
+
+  Ok(())
+}
+
+/// Report a failure of the checks command. With `--watch` the command serves
+/// as a CI gate, so failures are returned as errors to exit non-zero.
+fn checks_failure(watch: bool, message: String) -> Result<()> {
+  if watch {
+    return Err(anyhow::anyhow!(message));
+  }
+  print_error(&message);
+  Ok(())
+}
+
+/// Display check runs as a table followed by their details URLs
+fn display_check_runs(check_runs: &[CheckRun]) {
+  // Define a struct for check run data with Tabled trait
+  #[derive(Tabled)]
+  struct CheckRunRow {
+    #[tabled(rename = "Check Name")]
+    name: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Conclusion")]
+    conclusion: String,
+    #[tabled(rename = "Started At")]
+    started_at: String,
+  }
+
+  // Convert check runs to table rows with plain text first (no colors)
+  let rows: Vec<CheckRunRow> = check_runs
+    .iter()
+    .map(|check| {
+      // Format date to be more readable
+      let started_date = check.started_at.split('T').next().unwrap_or(&check.started_at);
+
+      CheckRunRow {
+        name: check.name.clone(),
+        status: check.status.clone(),
+        conclusion: check.conclusion.clone().unwrap_or_else(|| "N/A".to_string()),
+        started_at: started_date.to_string(),
       }
+    })
+    .collect();
+
+  // Create table with proper formatting
+  let mut table = Table::new(rows);
+
+  // Apply styling to the table
+  table
+    .with(Style::sharp())
+    .with(tabled::settings::Padding::new(1, 1, 0, 0))
+    .with(
+      tabled::settings::Modify::new(tabled::settings::object::Columns::new(..))
+        .with(tabled::settings::Alignment::center()),
+    );
+
+  // Let's use a simpler approach - create the table first, then format the output
+  let table_string = format!("{table}",);
+
+  // Now apply colors to the formatted table string
+  let colored_table = table_string
+    .replace("completed", &"completed".green().to_string())
+    .replace("in_progress", &"in_progress".yellow().to_string())
+    .replace("queued", &"queued".blue().to_string())
+    .replace("success", &"success".green().to_string())
+    .replace("failure", &"failure".red().to_string())
+    .replace("cancelled", &"cancelled".yellow().to_string())
+    .replace("timed_out", &"timed_out".red().to_string())
+    .replace("action_required", &"action_required".yellow().to_string());
+
+  println!("\n{colored_table}",);
+
+  // Display details URLs
+  println!("\nDetails:");
+  for check in check_runs {
+    if let Some(url) = &check.details_url {
+      println!("  • {}: {url}", check.name,);
+    }
+  }
+  println!();
+}
 
-      // Define a struct for check run data with Tabled trait
-      #[derive(Tabled)]
-      struct CheckRunRow {
-        #[tabled(rename = "Check Name")]
-        name: String,
-        #[tabled(rename = "Status")]
-        status: String,
-        #[tabled(rename = "Conclusion")]
-        conclusion: String,
-        #[tabled(rename = "Started At")]
-        started_at: String,
+/// Consecutive polls without a single check run before `--watch` gives up
+/// when no `--timeout` is set
+const NO_CHECKS_GRACE_POLLS: u32 = 20;
+
+/// Poll a PR's checks until every check run has completed, or fail once
+/// `timeout` has passed
+///
+/// The PR is re-fetched on every poll so that new pushes are picked up.
+/// Without a timeout, watching stops after [`NO_CHECKS_GRACE_POLLS`] polls
+/// that find no check runs at all, e.g. when no CI is configured.
+fn watch_checks(
+  rt: &Runtime,
+  github_client: &GitHubClient,
+  owner: &str,
+  repo_name: &str,
+  pr_number: u32,
+  interval: Duration,
+  timeout: Option<Duration>,
+) -> Result<Vec<CheckRun>> {
+  let deadline = timeout.map(|timeout| std::time::Instant::now() + timeout);
+  let mut last_progress = String::new();
+  let mut polls_without_checks = 0;
+
+  loop {
+    let pr = rt
+      .block_on(github_client.get_pull_request(owner, repo_name, pr_number))
+      .context(format!("Failed to fetch PR #{pr_number}"))?;
+    let check_runs = rt
+      .block_on(github_client.get_check_runs(owner, repo_name, &pr.head.sha))
+      .context("Failed to fetch check runs")?;
+
+    let completed = check_runs.iter().filter(|run| run.is_completed()).count();
+    if !check_runs.is_empty() && completed == check_runs.len() {
+      return Ok(check_runs);
+    }
+
+    if check_runs.is_empty() {
+      polls_without_checks += 1;
+      if timeout.is_none() && polls_without_checks >= NO_CHECKS_GRACE_POLLS {
+        return Err(anyhow::anyhow!(
+          "No checks found for PR #{pr_number} after {polls_without_checks} polls. Is CI configured for this repository? \
+           Use --timeout to keep waiting longer."
+        ));
       }
+    } else {
+      polls_without_checks = 0;
+    }
 
-      // Convert check runs to table rows with plain text first (no colors)
-      let rows: Vec<CheckRunRow> = check_runs
-        .iter()
-        .map(|check| {
-          // Format date to be more readable
-          let started_date = check.started_at.split('T').next().unwrap_or(&check.started_at);
+    let progress = if check_runs.is_empty() {
+      "Waiting for checks to start...".to_string()
+    } else {
+      let failed = check_runs.iter().filter(|run| run.is_failed()).count();
+      format!(
+        "{completed}/{} checks complete ({failed} failed), waiting...",
+        check_runs.len()
+      )
+    };
+    if progress != last_progress {
+      print_info(&progress);
+      last_progress = progress;
+    }
 
-          CheckRunRow {
-            name: check.name.clone(),
-            status: check.status.clone(),
-            conclusion: check.conclusion.clone().unwrap_or_else(|| "N/A".to_string()),
-            started_at: started_date.to_string(),
-          }
-        })
-        .collect();
+    if let Some(deadline) = deadline {
+      let now = std::time::Instant::now();
+      if now >= deadline {
+        return Err(anyhow::anyhow!(
+          "Timed out after {}s waiting for checks on PR #{pr_number}",
+          timeout.unwrap_or_default().as_secs()
+        ));
+      }
+      std::thread::sleep(interval.min(deadline - now));
+    } else {
+      std::thread::sleep(interval);
+    }
+  }
+}
 
-      // Create table with proper formatting
-      let mut table = Table::new(rows);
-
-      // Apply styling to the table
-      table
-        .with(Style::sharp())
-        .with(tabled::settings::Padding::new(1, 1, 0, 0))
-        .with(
-          tabled::settings::Modify::new(tabled::settings::object::Columns::new(..))
-            .with(tabled::settings::Alignment::center()),
-        );
-
-      // Let's use a simpler approach - create the table first, then format the output
-      let table_string = format!("{table}",);
-
-      // Now apply colors to the formatted table string
-      let colored_table = table_string
-        .replace("completed", &"completed".green().to_string())
-        .replace("in_progress", &"in_progress".yellow().to_string())
-        .replace("queued", &"queued".blue().to_string())
-        .replace("success", &"success".green().to_string())
-        .replace("failure", &"failure".red().to_string())
-        .replace("cancelled", &"cancelled".yellow().to_string())
-        .replace("timed_out", &"timed_out".red().to_string())
-        .replace("action_required", &"action_required".yellow().to_string());
-
-      println!("\n{colored_table}",);
-
-      // Display details URLs
-      println!("\nDetails:");
-      for check in &check_runs {
-        if let Some(url) = &check.details_url {
-          println!("  • {}: {url}", check.name,);
-        }
+/// Re-run the workflow runs behind failed GitHub Actions check runs
+///
+/// Returns `true` if at least one re-run was started.
+fn rerun_failed_checks(
+  rt: &Runtime,
+  github_client: &GitHubClient,
+  owner: &str,
+  repo_name: &str,
+  check_runs: &[CheckRun],
+  scope: RerunScope,
+) -> bool {
+  let mut run_ids = BTreeSet::new();
+  for check in check_runs.iter().filter(|run| run.is_failed()) {
+    if !check.is_actions_job() {
+      print_warning(&format!(
+        "Cannot re-run '{}': it is not a GitHub Actions job",
+        check.name
+      ));
+      continue;
+    }
+    match rt.block_on(github_client.get_workflow_job(owner, repo_name, check.id)) {
+      Ok(job) => {
+        run_ids.insert(job.run_id);
       }
-      println!();
+      Err(e) => print_warning(&format!("Could not look up workflow run for '{}': {e}", check.name)),
     }
-    Err(e) => {
-      print_error(&format!("Failed to fetch check runs: {e}"));
+  }
+
+  if run_ids.is_empty() {
+    print_info("No failed GitHub Actions jobs to re-run");
+    return false;
+  }
+
+  let mut started = false;
+  for run_id in run_ids {
+    let result = match scope {
+      RerunScope::Failed => rt.block_on(github_client.rerun_failed_jobs(owner, repo_name, run_id)),
+      RerunScope::All => rt.block_on(github_client.rerun_workflow(owner, repo_name, run_id)),
+    };
+    match result {
+      Ok(()) => {
+        started = true;
+        match scope {
+          RerunScope::Failed => print_success(&format!("Re-running failed jobs of workflow run {run_id}")),
+          RerunScope::All => print_success(&format!("Re-running workflow run {run_id}")),
+        }
+      }
+      Err(e) => print_error(&format!("Failed to re-run workflow run {run_id}: {e}")),
     }
   }
+  started
+}
 
-  Ok(())
+/// Print the end of the log of every failed GitHub Actions job
+fn show_failed_job_logs(
+  rt: &Runtime,
+  github_client: &GitHubClient,
+  owner: &str,
+  repo_name: &str,
+  check_runs: &[CheckRun],
+  tail: usize,
+) {
+  for check in check_runs.iter().filter(|run| run.is_failed()) {
+    if !check.is_actions_job() {
+      print_warning(&format!(
+        "Logs for '{}' are only available on its details page",
+        check.name
+      ));
+      continue;
+    }
+
+    let failed_step = rt
+      .block_on(github_client.get_workflow_job(owner, repo_name, check.id))
+      .ok()
+      .and_then(|job| {
+        job
+          .steps
+          .into_iter()
+          .find(|step| step.conclusion.as_deref() == Some("failure"))
+      });
+    match failed_step {
+      Some(step) => print_info(&format!(
+        "{} failed at step '{}' (last {tail} lines):",
+        check.name, step.name
+      )),
+      None => print_info(&format!("{} (last {tail} lines):", check.name)),
+    }
+
+    match rt.block_on(github_client.get_job_logs(owner, repo_name, check.id)) {
+      Ok(log) => println!("{}", log_tail(&log, tail)),
+      Err(e) => print_warning(&format!("Could not download logs for '{}': {e}", check.name)),
+    }
+    println!();
+  }
 }
 
 /// Handle the PR status command
 fn handle_pr_status_command() -> Result<()> {
-  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
-  let (rt, github_client) = create_github_runtime_and_client(base_dirs.home_dir())?;
-
   // Get the current repository
   let repo_path = match detect_repository() {
     Some(path) => path,
@@ -588,20 +879,27 @@ fn handle_pr_status_command() -> Result<()> {
   };
 
   // Extract owner and repo from remote URL
-  let (owner, repo_name) = match GitHubRepo::parse(remote_url) {
-    Ok(repo) => (repo.owner, repo.repo),
+  let (host, owner, repo_name) = match GitHubRepo::parse(remote_url) {
+    Ok(repo) => (repo.host, repo.owner, repo.repo),
     Err(e) => {
       print_error(&format!("Failed to extract repository info from URL: {e}"));
       return Ok(());
     }
   };
 
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let (rt, github_client) = create_github_runtime_and_client_for_host(base_dirs.home_dir(), &host)?;
+
   // Get PR status
   print_info(&format!("Fetching PR status for #{pr_number}..."));
 
   match rt.block_on(github_client.get_pr_status(&owner, &repo_name, pr_number)) {
     Ok(status) => {
       display_pr_status(&status);
+      match rt.block_on(github_client.get_review_threads(&owner, &repo_name, pr_number)) {
+        Ok(threads) => display_unresolved_threads(&threads, pr_number),
+        Err(e) => print_warning(&format!("Failed to fetch review threads: {e}")),
+      }
     }
     Err(e) => {
       print_error(&format!("Failed to get PR status: {e}"));
@@ -613,9 +911,6 @@ fn handle_pr_status_command() -> Result<()> {
 
 /// Handle the PR list command
 fn handle_pr_list_command(cmd: &ListCommand) -> Result<()> {
-  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
-  let (rt, github_client) = create_github_runtime_and_client(base_dirs.home_dir())?;
-
   // Get repository path (current or specified)
   let repo_path = if let Some(path) = &cmd.repo {
     detect_repository_from_path(path)
@@ -661,14 +956,17 @@ fn handle_pr_list_command(cmd: &ListCommand) -> Result<()> {
   };
 
   // Extract owner and repo from remote URL
-  let (owner, repo_name) = match GitHubRepo::parse(remote_url) {
-    Ok(repo) => (repo.owner, repo.repo),
+  let (host, owner, repo_name) = match GitHubRepo::parse(remote_url) {
+    Ok(repo) => (repo.host, repo.owner, repo.repo),
     Err(e) => {
       print_error(&format!("Failed to extract repository info from URL: {e}"));
       return Ok(());
     }
   };
 
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let (rt, github_client) = create_github_runtime_and_client_for_host(base_dirs.home_dir(), &host)?;
+
   let pagination = twig_gh::endpoints::pulls::PaginationOptions {
     per_page: cmd.limit,
     page: 1,
@@ -771,20 +1069,20 @@ fn handle_pr_link_command(pr_url_or_id: &str) -> Result<()> {
     }
   };
 
-  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
-  let github_client = create_github_client_from_netrc(base_dirs.home_dir())?;
-
   // Extract owner and repo from remote URL
-  let (owner, repo_name) = match GitHubRepo::parse(remote_url) {
-    Ok(repo) => (repo.owner, repo.repo),
+  let (host, owner, repo_name) = match GitHubRepo::parse(remote_url) {
+    Ok(repo) => (repo.host, repo.owner, repo.repo),
     Err(e) => {
       print_error(&format!("Failed to extract repository info from URL: {e}"));
       return Ok(());
     }
   };
 
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let github_client = create_github_client_for_host(base_dirs.home_dir(), &host)?;
+
   // Determine if input is a PR URL or PR ID
-  let pr_number = if pr_url_or_id.contains("github.com") && pr_url_or_id.contains("/pull/") {
+  let pr_number = if pr_url_or_id.contains("/pull/") {
     // Input is a URL
     match GitHubPr::parse(pr_url_or_id) {
       Ok(pr) => pr.number,
@@ -830,7 +1128,14 @@ fn handle_pr_link_command(pr_url_or_id: &str) -> Result<()> {
     }
   };
 
-  // Load the repository state
+  // Load the repository state, locked until it is saved
+  let state_lock = match RepoState::lock(&repo_path) {
+    Ok(lock) => lock,
+    Err(e) => {
+      print_error(&format!("Failed to lock repository state: {e}"));
+      return Ok(());
+    }
+  };
   let mut repo_state = match RepoState::load(&repo_path) {
     Ok(state) => state,
     Err(e) => {
@@ -842,6 +1147,10 @@ fn handle_pr_link_command(pr_url_or_id: &str) -> Result<()> {
   // Check if the branch already has an associated issue
   let now = chrono::Utc::now().to_rfc3339();
 
+  let linked_jira_issue = repo_state
+    .get_branch_metadata(branch_name)
+    .and_then(|metadata| metadata.jira_issue.clone());
+
   if let Some(branch_issue) = repo_state.get_branch_metadata(branch_name) {
     // Update the existing branch issue
     let mut updated_branch_issue = branch_issue.clone();
@@ -869,13 +1178,24 @@ fn handle_pr_link_command(pr_url_or_id: &str) -> Result<()> {
   }
 
   // Save the repository state
-  match repo_state.save(&repo_path) {
-    Ok(_) => Ok(()),
-    Err(e) => {
-      print_error(&format!("Failed to save repository state: {e}"));
-      Ok(())
-    }
+  if let Err(e) = repo_state.save(&repo_path) {
+    print_error(&format!("Failed to save repository state: {e}"));
+    return Ok(());
+  }
+  drop(state_lock);
+
+  let event = if pr.merged_at.is_some() {
+    Some(JiraLifecycleEvent::PrMerged)
+  } else if pr.state == "open" {
+    Some(JiraLifecycleEvent::PrOpened)
+  } else {
+    None
+  };
+  if let (Some(issue_key), Some(event)) = (linked_jira_issue, event) {
+    run_lifecycle_transitions(&[(issue_key, event)], false);
   }
+
+  Ok(())
 }
 
 /// Display PR status information
@@ -948,3 +1268,199 @@ fn display_pr_status(status: &PullRequestStatus) {
 
   println!();
 }
+
+/// Display a summary of unresolved review threads after the PR status
+fn display_unresolved_threads(threads: &[ReviewThread], pr_number: u32) {
+  let unresolved: Vec<&ReviewThread> = threads.iter().filter(|t| !t.is_resolved).collect();
+  if threads.is_empty() {
+    return;
+  }
+
+  if unresolved.is_empty() {
+    print_success(&format!("All {} review threads resolved", threads.len()));
+    println!();
+    return;
+  }
+
+  print_warning(&format!(
+    "{} of {} review threads unresolved:",
+    unresolved.len(),
+    threads.len()
+  ));
+  for (path, file_threads) in group_threads_by_file(unresolved) {
+    for thread in file_threads {
+      let author = thread.comments.first().map_or("unknown", |c| c.author.as_str());
+      println!("  {} ({})", format_thread_location(path, thread.line), author);
+    }
+  }
+  print_info(&format!(
+    "See the full threads with {}",
+    format_command(&format!("twig github pr comments {pr_number} --unresolved"))
+  ));
+  println!();
+}
+
+/// Handle the PR comments command
+fn handle_pr_comments_command(cmd: &CommentsCommand) -> Result<()> {
+  let repo_path = if let Some(path) = &cmd.repo {
+    detect_repository_from_path(path)
+  } else {
+    detect_repository()
+  };
+  let Some(repo_path) = repo_path else {
+    print_error(
+      "No git repository found. Make sure you're in a git repository or specify a valid repository path with --repo",
+    );
+    return Ok(());
+  };
+
+  let repo = match Git2Repository::open(&repo_path) {
+    Ok(repo) => repo,
+    Err(e) => {
+      print_error(&format!("Failed to open git repository: {e}"));
+      return Ok(());
+    }
+  };
+
+  let remote_url = match repo.find_remote("origin") {
+    Ok(remote) => match remote.url() {
+      Some(url) => url.to_string(),
+      None => {
+        print_error("Failed to get remote URL");
+        return Ok(());
+      }
+    },
+    Err(e) => {
+      print_error(&format!("Failed to find remote 'origin': {e}"));
+      return Ok(());
+    }
+  };
+
+  let (host, owner, repo_name) = match GitHubRepo::parse(&remote_url) {
+    Ok(repo) => (repo.host, repo.owner, repo.repo),
+    Err(e) => {
+      print_error(&format!("Failed to extract repository info from URL: {e}"));
+      return Ok(());
+    }
+  };
+
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let (rt, github_client) = create_github_runtime_and_client_for_host(base_dirs.home_dir(), &host)?;
+
+  let pr_number = if let Some(pr_num_str) = &cmd.pr_number {
+    match pr_num_str.parse::<u32>() {
+      Ok(num) => num,
+      Err(_) => {
+        print_error(&format!("Invalid PR number: {pr_num_str}"));
+        return Ok(());
+      }
+    }
+  } else {
+    match get_current_branch_github_pr() {
+      Ok(Some(pr_number)) => pr_number,
+      Ok(None) => {
+        print_error("Current branch has no associated PR");
+        print_info(&format!(
+          "Link a PR with {} or specify a PR number",
+          format_command("twig github pr link <pr-url>")
+        ));
+        return Ok(());
+      }
+      Err(e) => {
+        print_error(&format!("Failed to get associated PR: {e}"));
+        return Ok(());
+      }
+    }
+  };
+
+  print_info(&format!("Fetching comments for PR #{pr_number}..."));
+
+  let threads = match rt.block_on(github_client.get_review_threads(&owner, &repo_name, pr_number)) {
+    Ok(threads) => threads,
+    Err(e) => {
+      print_error(&format!("Failed to fetch review threads: {e}"));
+      return Ok(());
+    }
+  };
+  let issue_comments = match rt.block_on(github_client.list_issue_comments(&owner, &repo_name, pr_number)) {
+    Ok(comments) => comments,
+    Err(e) => {
+      print_error(&format!("Failed to fetch PR comments: {e}"));
+      return Ok(());
+    }
+  };
+
+  let unresolved_count = threads.iter().filter(|t| !t.is_resolved).count();
+  let shown = threads.iter().filter(|t| !cmd.unresolved || !t.is_resolved);
+
+  println!();
+  for (path, file_threads) in group_threads_by_file(shown) {
+    println!("{}", path.bold());
+    for thread in file_threads {
+      let marker = if thread.is_resolved {
+        "resolved".green().to_string()
+      } else {
+        "unresolved".yellow().to_string()
+      };
+      let outdated = if thread.is_outdated { " (outdated)" } else { "" };
+      let line = thread
+        .line
+        .map_or_else(|| "file".to_string(), |line| format!("L{line}"));
+      println!("  {line} [{marker}]{outdated}");
+      for comment in &thread.comments {
+        println!("    {}: {}", comment.author.cyan(), first_line(&comment.body));
+      }
+      if let Some(first) = thread.comments.first() {
+        println!("    {}", first.url.dimmed());
+      }
+    }
+    println!();
+  }
+
+  if !cmd.unresolved && !issue_comments.is_empty() {
+    print_info("Conversation:");
+    for comment in &issue_comments {
+      println!(
+        "  {} {}: {}",
+        comment.created_at.dimmed(),
+        comment.user.login.cyan(),
+        first_line(&comment.body)
+      );
+    }
+    println!();
+  }
+
+  if threads.is_empty() {
+    print_info("No review threads on this PR");
+  } else if unresolved_count == 0 {
+    print_success(&format!("All {} review threads resolved", threads.len()));
+  } else {
+    print_warning(&format!(
+      "{} of {} review threads unresolved",
+      unresolved_count,
+      threads.len()
+    ));
+  }
+
+  Ok(())
+}
+
+/// Format a review thread's position as `path:line`
+fn format_thread_location(path: &str, line: Option<u32>) -> String {
+  match line {
+    Some(line) => format!("{path}:{line}"),
+    None => path.to_string(),
+  }
+}
+
+/// First line of a comment body, truncated for single-line display
+fn first_line(body: &str) -> String {
+  let line = body.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+  let more = body.trim().lines().count() > 1;
+  let line = truncate_string(line, 100);
+  if more && !line.ends_with("...") {
+    format!("{line} ...")
+  } else {
+    line
+  }
+}
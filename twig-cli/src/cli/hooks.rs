@@ -0,0 +1,90 @@
+//! # Hooks Command
+//!
+//! Derive-based implementation of the hooks command for deciding which
+//! repositories may run the hooks they configure in `.twig/hooks.toml`.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use twig_core::config::get_config_dirs;
+use twig_core::output::{print_info, print_success};
+
+use crate::utils::resolve_repository_path;
+
+/// Command for managing repository hooks
+#[derive(Args)]
+pub struct HooksArgs {
+  /// The subcommand to execute
+  #[command(subcommand)]
+  pub subcommand: HooksSubcommands,
+}
+
+/// Subcommands for the hooks command
+#[derive(Subcommand)]
+pub enum HooksSubcommands {
+  /// Allow a repository to run its hooks
+  #[command(
+    long_about = "Trusts a repository so twig runs the hooks in its .twig/hooks.toml.\n\n\
+                     Repository hooks are shell commands committed alongside the code, so twig\n\
+                     ignores them until you have reviewed the file and trusted the repository.\n\
+                     Linked worktrees share the trust of their main repository."
+  )]
+  Trust(TrustCommand),
+
+  /// Stop a repository from running its hooks
+  #[command(long_about = "Removes a repository from the trusted list.\n\n\
+                     Its .twig/hooks.toml is ignored again; hooks from your global\n\
+                     hooks.toml keep running.")]
+  Untrust(TrustCommand),
+
+  /// List trusted repositories
+  #[command(long_about = "Displays the repositories whose .twig/hooks.toml twig runs.")]
+  #[command(alias = "ls")]
+  List,
+}
+
+/// Trust or untrust a repository
+#[derive(Args)]
+pub struct TrustCommand {
+  /// Path to a specific repository (defaults to current repository)
+  #[arg(long, short = 'r', value_name = "PATH")]
+  pub repo: Option<String>,
+}
+
+/// Handle the hooks command
+pub(crate) fn handle_hooks_command(hooks: HooksArgs) -> Result<()> {
+  let config_dirs = get_config_dirs()?;
+
+  match hooks.subcommand {
+    HooksSubcommands::Trust(cmd) => {
+      let repo_path = resolve_repository_path(cmd.repo.as_deref())?;
+      let mut trusted = config_dirs.load_trusted_repos()?;
+      if trusted.add(&repo_path) {
+        config_dirs.save_trusted_repos(&trusted)?;
+        print_success(&format!("Trusted hooks in {}", repo_path.display()));
+      } else {
+        print_info(&format!("{} is already trusted", repo_path.display()));
+      }
+    }
+    HooksSubcommands::Untrust(cmd) => {
+      let repo_path = resolve_repository_path(cmd.repo.as_deref())?;
+      let mut trusted = config_dirs.load_trusted_repos()?;
+      if trusted.remove(&repo_path) {
+        config_dirs.save_trusted_repos(&trusted)?;
+        print_success(&format!("No longer trusting hooks in {}", repo_path.display()));
+      } else {
+        print_info(&format!("{} was not trusted", repo_path.display()));
+      }
+    }
+    HooksSubcommands::List => {
+      let trusted = config_dirs.load_trusted_repos()?;
+      if trusted.repos.is_empty() {
+        print_info("No trusted repositories.");
+      }
+      for repo in &trusted.repos {
+        println!("{}", repo.display());
+      }
+    }
+  }
+
+  Ok(())
+}
@@ -3,18 +3,31 @@
 //! Derive-based implementation of the Jira command for Jira integration,
 //! including issue viewing, transitioning, and branch creation.
 
+use std::io::IsTerminal;
+
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
+use dialoguer::FuzzySelect;
 use directories::BaseDirs;
 use git2::Repository as Git2Repository;
 use owo_colors::OwoColorize;
-use twig_core::jira_parser::JiraTicketParser;
-use twig_core::output::{print_error, print_info, print_success, print_warning};
+use tabled::settings::Style;
+use tabled::{Table, Tabled};
+use twig_core::git::switch::ParentBranchOption;
+use twig_core::hooks::{HookPayload, HookRunner};
+use twig_core::jira_parser::{JiraLifecycleEvent, JiraTicketParser};
+use twig_core::output::{ColorMode, format_command, print_error, print_info, print_success, print_warning};
 use twig_core::{
   RepoState, StateBranchMetadata, create_jira_parser, create_worktree, detect_repository,
-  generate_branch_name_from_issue, get_config_dirs, get_current_branch_jira_issue,
+  generate_branch_name_from_issue, get_config_dirs, get_current_branch_jira_issue, truncate_string, twig_theme,
+};
+use twig_jira::endpoints::issues::build_jql;
+use twig_jira::{
+  IssueRef, JiraClient, JiraUser, NewIssue, RichTextRenderer, TransitionOutcome, create_jira_runtime_and_client,
+  get_jira_host,
 };
-use twig_jira::{create_jira_runtime_and_client, get_jira_host};
+
+use super::switch::{SwitchArgs, create_branch_from_jira_issue, handle_switch_command};
 
 /// Command for Jira integration
 #[derive(Args)]
@@ -51,6 +64,104 @@ pub enum JiraSubcommands {
     with_worktree: bool,
   },
 
+  /// Create a Jira issue
+  #[command(long_about = "Create a new Jira issue.\n\n\
+                         Creates an issue in the given project and prints its key. With --branch, a branch\n\
+                         is created for the new issue right away, exactly like 'twig switch <issue-key>'.\n\n\
+                         Examples:\n  \
+                         twig jira create -p PROJ \"Fix login timeout\"\n  \
+                         twig jira create -p PROJ -t Bug -a me -l backend \"Crash on empty config\" --branch\n  \
+                         twig jira create -p PROJ --epic PROJ-10 \"Add export\" --branch --parent current")]
+  Create {
+    /// Summary of the new issue
+    #[arg(required = true, index = 1)]
+    summary: String,
+
+    /// Project key (e.g., PROJ)
+    #[arg(long, short = 'p')]
+    project: String,
+
+    /// Issue type name
+    #[arg(long = "type", short = 't', default_value = "Task")]
+    issue_type: String,
+
+    /// Issue description
+    #[arg(long, short = 'd')]
+    description: Option<String>,
+
+    /// Assignee account ID, or 'me' for yourself
+    #[arg(long, short = 'a')]
+    assignee: Option<String>,
+
+    /// Label to add (can be repeated)
+    #[arg(long = "label", short = 'l')]
+    labels: Vec<String>,
+
+    /// Key of the parent epic
+    #[arg(long)]
+    epic: Option<String>,
+
+    /// Create and switch to a branch for the new issue
+    #[arg(long, short = 'b')]
+    branch: bool,
+
+    /// Parent branch for the new branch ('current', a branch name, a Jira key or 'none')
+    #[arg(long, requires = "branch")]
+    parent: Option<String>,
+  },
+
+  /// List or search Jira issues
+  #[command(
+    visible_alias = "search",
+    long_about = "List Jira issues matching a query.\n\n\
+                  The query is taken from --jql, from a saved query named in the first argument,\n\
+                  or built from the --project, --status and --assignee filters. Saved queries live\n\
+                  under [queries] in jira.toml; use --save <NAME> to store the current query.\n\n\
+                  With --interactive, pick an issue from a fuzzy finder and switch to its branch,\n\
+                  creating it if needed (like 'twig switch <issue-key>').\n\n\
+                  Examples:\n  \
+                  twig jira list --assignee me --status \"In Progress\"\n  \
+                  twig jira search --jql \"sprint in openSprints() AND assignee = currentUser()\" --save sprint\n  \
+                  twig jira list sprint -i"
+  )]
+  List {
+    /// Name of a saved query from jira.toml
+    #[arg(index = 1, conflicts_with = "jql")]
+    query: Option<String>,
+
+    /// Raw JQL query
+    #[arg(long)]
+    jql: Option<String>,
+
+    /// Filter by project key
+    #[arg(long, short = 'p', conflicts_with_all = ["jql", "query"])]
+    project: Option<String>,
+
+    /// Filter by status name
+    #[arg(long, short = 's', conflicts_with_all = ["jql", "query"])]
+    status: Option<String>,
+
+    /// Filter by assignee ('me' for yourself)
+    #[arg(long, short = 'a', conflicts_with_all = ["jql", "query"])]
+    assignee: Option<String>,
+
+    /// Maximum number of issues to fetch
+    #[arg(long, short = 'n', default_value_t = 50)]
+    limit: usize,
+
+    /// Save the query under this name in jira.toml
+    #[arg(long, value_name = "NAME")]
+    save: Option<String>,
+
+    /// Pick an issue and switch to (or create) its branch
+    #[arg(long, short = 'i')]
+    interactive: bool,
+
+    /// Parent for a newly created branch ('current', a branch name, a Jira key or 'none')
+    #[arg(long, requires = "interactive")]
+    parent: Option<String>,
+  },
+
   /// Link a branch to a Jira issue
   #[command(long_about = "Link an existing Git branch to a Jira issue.\n\n\
                        This command associates a branch with a Jira issue in the repository state,\n\
@@ -90,7 +201,8 @@ pub enum JiraSubcommands {
   /// View a Jira issue
   #[command(long_about = "View details of a Jira issue.\n\n\
                    This command displays information about a specific Jira issue,\n\
-                   including its key, summary, status, and description.")]
+                   including its key, summary, status, parent, description, subtasks,\n\
+                   linked issues and comments. Rich text is rendered for the terminal.")]
   View {
     #[arg(
       index = 1,
@@ -158,6 +270,66 @@ pub(crate) fn handle_jira_command(jira: JiraArgs) -> Result<()> {
         }
       }
     }
+    JiraSubcommands::Create {
+      summary,
+      project,
+      issue_type,
+      description,
+      assignee,
+      labels,
+      epic,
+      branch,
+      parent,
+    } => {
+      let epic = match epic {
+        Some(key) => match jira_parser
+          .as_ref()
+          .and_then(|parser| parse_and_validate_issue_key(parser, &key))
+        {
+          Some(normalized_key) => Some(normalized_key),
+          None => {
+            print_error(&format!("Invalid Jira issue key format: '{key}'"));
+            return Ok(());
+          }
+        },
+        None => None,
+      };
+      let new_issue = NewIssue {
+        project_key: project.to_uppercase(),
+        issue_type,
+        summary,
+        description,
+        assignee,
+        labels,
+        parent: epic,
+      };
+      let branch_parent = branch.then(|| ParentBranchOption::from_cli_value(parent.as_deref()));
+      handle_create_issue_command(new_issue, branch_parent, jira_parser.as_ref())
+    }
+    JiraSubcommands::List {
+      query,
+      jql,
+      project,
+      status,
+      assignee,
+      limit,
+      save,
+      interactive,
+      parent,
+    } => {
+      let jql = match (jql, query) {
+        (Some(jql), _) => jql,
+        (None, Some(name)) => match resolve_saved_query(&name)? {
+          Some(jql) => jql,
+          None => return Ok(()),
+        },
+        (None, None) => build_jql(project.as_deref(), status.as_deref(), assignee.as_deref()),
+      };
+      if let Some(name) = save {
+        save_query(&name, &jql)?;
+      }
+      handle_list_issues_command(&jql, limit, interactive, parent)
+    }
     JiraSubcommands::LinkBranch { issue_key, branch_name } => {
       match issue_key {
         Some(key) => {
@@ -272,6 +444,28 @@ fn handle_show_jira_config() -> Result<()> {
 
   print_info("Current Jira configuration:");
   println!("  Jira Ticket Parsing: {:?}", jira_config.mode);
+  if !jira_config.queries.is_empty() {
+    println!("  Saved queries:");
+    for (name, jql) in &jira_config.queries {
+      println!("    {name}: {jql}");
+    }
+  }
+  let rules = &jira_config.transitions;
+  if !rules.is_empty() {
+    println!(
+      "  Automatic transitions{}:",
+      if rules.dry_run { " (dry run)" } else { "" }
+    );
+    for event in [
+      JiraLifecycleEvent::BranchCreated,
+      JiraLifecycleEvent::PrOpened,
+      JiraLifecycleEvent::PrMerged,
+    ] {
+      if let Some(target) = rules.target_for(event) {
+        println!("    on {event} → {target}");
+      }
+    }
+  }
 
   Ok(())
 }
@@ -299,59 +493,83 @@ fn handle_view_issue_command(issue_key: &str) -> Result<()> {
 
   rt.block_on(async {
     // Fetch the issue
-    match jira_client.get_issue(issue_key).await {
+    match jira_client.get_issue_details(issue_key).await {
       Ok(issue) => {
-        // Create a cleaner, more elegant output for the Jira issue
-        let title = format!(" Jira Issue: {} ", issue.key);
-        let title_len = title.len();
-        let line_width = 78;
-        let left_padding = (line_width - title_len) / 2;
-        let right_padding = line_width - title_len - left_padding;
+        let colors = if std::io::stdout().is_terminal() {
+          ColorMode::Auto
+        } else {
+          ColorMode::No
+        };
+        let renderer = RichTextRenderer::new(colors);
+        let fields = &issue.fields;
 
         // Print a clear header for the issue
-        println!(
-          "\n{}{}{}",
-          "─".repeat(left_padding),
-          title.blue().bold(),
-          "─".repeat(right_padding)
-        );
+        print_section_header(&format!(" Jira Issue: {} ", issue.key));
 
         // Format key fields with clear labels and indentation
-        println!("\n  {} {}", "•".blue(), format!("Key:     {}", issue.key).bold());
+        println!("\n  {} {}", "•".blue(), format!("Key:      {}", issue.key).bold());
+        println!("  {} {}", "•".blue(), format!("Summary:  {}", fields.summary).bold());
         println!(
           "  {} {}",
           "•".blue(),
-          format!("Summary: {}", issue.fields.summary).bold()
-        );
-        println!(
-          "  {} {}",
-          "•".blue(),
-          format!("Status:  {}", issue.fields.status.name).yellow().bold()
+          format!("Status:   {}", fields.status.name).yellow().bold()
         );
+        if let Some(issue_type) = &fields.issuetype {
+          println!("  {} Type:     {}", "•".blue(), issue_type.name);
+        }
+        if let Some(assignee) = &fields.assignee {
+          println!("  {} Assignee: {}", "•".blue(), assignee.display_name);
+        }
+        if let Some(parent) = &fields.parent {
+          println!("  {} Parent:   {}", "•".blue(), format_issue_ref(parent));
+        }
 
         // Add description in its own section if available
-        if let Some(description) = &issue.fields.description {
-          let desc_title = " DESCRIPTION ";
-          let desc_title_len = desc_title.len();
-          let desc_left_padding = (line_width - desc_title_len) / 2;
-          let desc_right_padding = line_width - desc_title_len - desc_left_padding;
-
-          println!(
-            "\n{}{}{}",
-            "─".repeat(desc_left_padding),
-            desc_title.blue().bold(),
-            "─".repeat(desc_right_padding)
-          );
-
-          // Indent the description for better readability
+        if let Some(description) = &fields.description {
+          let rendered = renderer.render(description);
+          if !rendered.trim().is_empty() {
+            print_section_header(" DESCRIPTION ");
+            println!();
+            print_indented(&rendered, "  ");
+            println!();
+          }
+        }
+
+        if !fields.subtasks.is_empty() {
+          print_section_header(" SUBTASKS ");
+          println!();
+          for subtask in &fields.subtasks {
+            println!("  {} {}", "•".blue(), format_issue_ref(subtask));
+          }
           println!();
-          for line in description.lines() {
-            println!("  {line}");
+        }
+
+        let links: Vec<_> = fields.issuelinks.iter().filter_map(|link| link.relation()).collect();
+        if !links.is_empty() {
+          print_section_header(" LINKED ISSUES ");
+          println!();
+          for (relation, linked) in links {
+            println!("  {} {} {}", "•".blue(), relation.dimmed(), format_issue_ref(linked));
+          }
+          println!();
+        }
+
+        if let Some(comments) = fields.comment.as_ref().filter(|comments| !comments.comments.is_empty()) {
+          let total = comments.total.map_or(comments.comments.len(), |total| total as usize);
+          print_section_header(&format!(" COMMENTS ({total}) "));
+          for comment in &comments.comments {
+            let author = comment
+              .author
+              .as_ref()
+              .map_or("Unknown", |author| author.display_name.as_str());
+            let created = comment.created.get(..10).unwrap_or(&comment.created);
+            println!("\n  {} {}", author.bold(), created.dimmed());
+            print_indented(&renderer.render(&comment.body), "    ");
           }
           println!();
         }
 
-        println!("{}\n", "─".repeat(line_width));
+        println!("{}\n", "─".repeat(VIEW_LINE_WIDTH));
         Ok(())
       }
       Err(e) => {
@@ -362,6 +580,49 @@ fn handle_view_issue_command(issue_key: &str) -> Result<()> {
   })
 }
 
+/// Width of the rules drawn by `twig jira view`
+const VIEW_LINE_WIDTH: usize = 78;
+
+/// Print a section title centered in a horizontal rule
+fn print_section_header(title: &str) {
+  let title_len = title.chars().count().min(VIEW_LINE_WIDTH);
+  let left_padding = (VIEW_LINE_WIDTH - title_len) / 2;
+  let right_padding = VIEW_LINE_WIDTH - title_len - left_padding;
+
+  println!(
+    "\n{}{}{}",
+    "─".repeat(left_padding),
+    title.blue().bold(),
+    "─".repeat(right_padding)
+  );
+}
+
+/// Print rendered text with every non-empty line indented
+fn print_indented(text: &str, indent: &str) {
+  for line in text.lines() {
+    if line.is_empty() {
+      println!();
+    } else {
+      println!("{indent}{line}");
+    }
+  }
+}
+
+/// Format an issue reference as `KEY [Status] Summary`
+fn format_issue_ref(issue: &IssueRef) -> String {
+  let Some(fields) = &issue.fields else {
+    return issue.key.clone();
+  };
+  let mut formatted = issue.key.bold().to_string();
+  if let Some(status) = &fields.status {
+    formatted.push_str(&format!(" [{}]", status.name.yellow()));
+  }
+  if let Some(summary) = &fields.summary {
+    formatted.push_str(&format!(" {summary}"));
+  }
+  formatted
+}
+
 /// Handle the transition issue command
 fn handle_transition_issue_command(issue_key: &str, transition: Option<&str>) -> Result<()> {
   let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
@@ -515,7 +776,8 @@ fn handle_create_branch_command(issue_key: &str, with_worktree: bool) -> Result<
       }
     }
 
-    // Load the repository state
+    // Load the repository state, locked until it is saved
+    let _lock = RepoState::lock(&repo_path)?;
     let mut state = RepoState::load(&repo_path)?;
 
     // Add the branch-issue association
@@ -533,10 +795,269 @@ fn handle_create_branch_command(issue_key: &str, with_worktree: bool) -> Result<
       "Associated branch '{branch_name}' with Jira issue {issue_key}"
     ));
 
+    HookRunner::for_repo(&repo_path)?.run(HookPayload::PostBranchCreate {
+      branch: branch_name.clone(),
+      parent: None,
+    })?;
+
+    apply_lifecycle_transition(&jira_client, JiraLifecycleEvent::BranchCreated, issue_key, false).await;
+
     Ok(())
   })
 }
 
+/// Handle the create issue command
+///
+/// When `branch_parent` is set, a branch is created for the new issue using
+/// the same flow as `twig switch <issue-key>`.
+fn handle_create_issue_command(
+  mut new_issue: NewIssue,
+  branch_parent: Option<ParentBranchOption>,
+  jira_parser: Option<&JiraTicketParser>,
+) -> Result<()> {
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let jira_host = get_jira_host()?;
+
+  let (rt, jira_client) = create_jira_runtime_and_client(base_dirs.home_dir(), &jira_host)?;
+
+  if new_issue.assignee.as_deref() == Some("me") {
+    match rt.block_on(jira_client.get_current_user()) {
+      Ok(JiraUser {
+        account_id: Some(account_id),
+        ..
+      }) => new_issue.assignee = Some(account_id),
+      Ok(_) => {
+        print_error("Could not determine your Jira account ID; pass it to --assignee instead");
+        return Ok(());
+      }
+      Err(e) => {
+        print_error(&format!("Failed to look up the current Jira user: {e}"));
+        return Ok(());
+      }
+    }
+  }
+
+  let created = match rt.block_on(jira_client.create_issue(&new_issue)) {
+    Ok(created) => created,
+    Err(e) => {
+      print_error(&format!("Failed to create issue: {e}"));
+      return Ok(());
+    }
+  };
+
+  print_success(&format!("Created {}: {}", created.key.bold(), new_issue.summary));
+  println!("  {jira_host}/browse/{}", created.key);
+
+  let Some(parent_option) = branch_parent else {
+    print_info(&format!(
+      "Start working on it with {}",
+      format_command(&format!("twig switch {}", created.key))
+    ));
+    return Ok(());
+  };
+
+  let Some(repo_path) = detect_repository() else {
+    print_error("Not in a git repository; skipping branch creation");
+    return Ok(());
+  };
+  let repo = Git2Repository::open(&repo_path).context("Failed to open git repository")?;
+
+  create_branch_from_jira_issue(
+    &jira_client,
+    &repo,
+    &repo_path,
+    &created.key,
+    &parent_option,
+    jira_parser,
+  )
+}
+
+/// Look up a saved query by name, reporting the available names when it is
+/// missing
+fn resolve_saved_query(name: &str) -> Result<Option<String>> {
+  let config = get_config_dirs()?.load_jira_config()?;
+  if let Some(jql) = config.queries.get(name) {
+    return Ok(Some(jql.clone()));
+  }
+
+  print_error(&format!("No saved query named '{name}'"));
+  if config.queries.is_empty() {
+    print_info(&format!(
+      "Save one with {}",
+      format_command(&format!("twig jira list --jql \"...\" --save {name}"))
+    ));
+  } else {
+    let names: Vec<&str> = config.queries.keys().map(String::as_str).collect();
+    print_info(&format!("Saved queries: {}", names.join(", ")));
+  }
+  Ok(None)
+}
+
+/// Store `jql` as a named query in jira.toml
+fn save_query(name: &str, jql: &str) -> Result<()> {
+  let config_dirs = get_config_dirs()?;
+  let mut config = config_dirs.load_jira_config()?;
+  config.queries.insert(name.to_string(), jql.to_string());
+  config_dirs.save_jira_config(&config)?;
+  print_success(&format!("Saved query '{name}'"));
+  Ok(())
+}
+
+/// Handle the list command
+fn handle_list_issues_command(jql: &str, limit: usize, interactive: bool, parent: Option<String>) -> Result<()> {
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let jira_host = get_jira_host()?;
+
+  let (rt, jira_client) = create_jira_runtime_and_client(base_dirs.home_dir(), &jira_host)?;
+
+  let issues = match rt.block_on(jira_client.search_issues(jql, limit)) {
+    Ok(issues) => issues,
+    Err(e) => {
+      print_error(&format!("Failed to search issues: {e}"));
+      return Ok(());
+    }
+  };
+
+  if issues.is_empty() {
+    print_info(&format!("No issues match: {jql}"));
+    return Ok(());
+  }
+
+  if !interactive {
+    #[derive(Tabled)]
+    struct IssueRow {
+      #[tabled(rename = "Key")]
+      key: String,
+      #[tabled(rename = "Status")]
+      status: String,
+      #[tabled(rename = "Assignee")]
+      assignee: String,
+      #[tabled(rename = "Summary")]
+      summary: String,
+    }
+
+    let rows = issues.iter().map(|issue| IssueRow {
+      key: issue.key.clone(),
+      status: issue.fields.status.name.clone(),
+      assignee: issue
+        .fields
+        .assignee
+        .as_ref()
+        .map_or_else(|| "Unassigned".to_string(), |user| user.display_name.clone()),
+      summary: truncate_string(&issue.fields.summary, 60),
+    });
+    println!("\n{}\n", Table::new(rows).with(Style::sharp()));
+    print_info(&format!(
+      "{} issue(s). Switch to one with {}",
+      issues.len(),
+      format_command("twig switch <issue-key>")
+    ));
+    return Ok(());
+  }
+
+  let items: Vec<String> = issues
+    .iter()
+    .map(|issue| {
+      format!(
+        "{}  [{}]  {}",
+        issue.key, issue.fields.status.name, issue.fields.summary
+      )
+    })
+    .collect();
+  let selection = FuzzySelect::with_theme(&twig_theme())
+    .with_prompt("Select an issue (type to filter)")
+    .items(&items)
+    .default(0)
+    .interact_opt()
+    .context("Failed to display issue picker")?;
+  let Some(index) = selection else {
+    print_info("No issue selected");
+    return Ok(());
+  };
+
+  // Release the Jira runtime before the switch flow starts its own.
+  drop(rt);
+  handle_switch_command(SwitchArgs {
+    input: Some(issues[index].key.clone()),
+    root: false,
+    no_create: false,
+    parent,
+  })
+}
+
+/// Apply the transition rule configured for `event` to an issue
+///
+/// Lifecycle transitions never fail the surrounding command: problems are
+/// reported as warnings.
+pub(crate) async fn apply_lifecycle_transition(
+  jira_client: &JiraClient,
+  event: JiraLifecycleEvent,
+  issue_key: &str,
+  dry_run: bool,
+) {
+  let rules = match get_config_dirs().and_then(|dirs| dirs.load_jira_config()) {
+    Ok(config) => config.transitions,
+    Err(e) => {
+      print_warning(&format!("Could not load Jira transition rules: {e}"));
+      return;
+    }
+  };
+
+  let outcome = jira_client
+    .apply_lifecycle_rule(&rules, event, issue_key, dry_run)
+    .await;
+  report_lifecycle_outcome(event, issue_key, outcome);
+}
+
+/// Apply transition rules for a batch of `(issue key, event)` pairs
+///
+/// A Jira client is only created when at least one event has a rule.
+pub(crate) fn run_lifecycle_transitions(events: &[(String, JiraLifecycleEvent)], dry_run: bool) {
+  let rules = match get_config_dirs().and_then(|dirs| dirs.load_jira_config()) {
+    Ok(config) => config.transitions,
+    Err(e) => {
+      print_warning(&format!("Could not load Jira transition rules: {e}"));
+      return;
+    }
+  };
+  if !events.iter().any(|(_, event)| rules.target_for(*event).is_some()) {
+    return;
+  }
+
+  let client = BaseDirs::new()
+    .context("Failed to get $HOME directory")
+    .and_then(|base_dirs| {
+      let jira_host = get_jira_host()?;
+      create_jira_runtime_and_client(base_dirs.home_dir(), &jira_host)
+    });
+  let (rt, jira_client) = match client {
+    Ok(client) => client,
+    Err(e) => {
+      print_warning(&format!("Skipping Jira transitions: {e}"));
+      return;
+    }
+  };
+
+  for (issue_key, event) in events {
+    let outcome = rt.block_on(jira_client.apply_lifecycle_rule(&rules, *event, issue_key, dry_run));
+    report_lifecycle_outcome(*event, issue_key, outcome);
+  }
+}
+
+fn report_lifecycle_outcome(event: JiraLifecycleEvent, issue_key: &str, outcome: Result<Option<TransitionOutcome>>) {
+  match outcome {
+    Ok(None) => {}
+    Ok(Some(outcome @ TransitionOutcome::Transitioned { .. })) => {
+      print_success(&format!("{} ({event})", outcome.describe(issue_key)));
+    }
+    Ok(Some(outcome @ TransitionOutcome::NotAvailable { .. })) => {
+      print_warning(&format!("{} ({event})", outcome.describe(issue_key)));
+    }
+    Ok(Some(outcome)) => print_info(&format!("{} ({event})", outcome.describe(issue_key))),
+    Err(e) => print_warning(&format!("Could not apply '{event}' transition to {issue_key}: {e}")),
+  }
+}
+
 /// Handle the link branch command
 fn handle_link_branch_command(issue_key: &str, branch_name: Option<&str>) -> Result<()> {
   let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
@@ -595,7 +1116,8 @@ fn handle_link_branch_command(issue_key: &str, branch_name: Option<&str>) -> Res
       .unwrap()
       .to_rfc3339();
 
-    // Load the repository state
+    // Load the repository state, locked until it is saved
+    let _lock = RepoState::lock(&repo_path)?;
     let mut state = RepoState::load(&repo_path)?;
 
     // Check if the branch is already associated with an issue
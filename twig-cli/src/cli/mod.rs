@@ -16,7 +16,9 @@ mod creds;
 pub mod fixup;
 mod git;
 mod github;
+mod hooks;
 mod jira;
+mod plugin_cmd;
 pub mod rebase;
 mod rebase_common;
 mod self_cmd;
@@ -31,7 +33,7 @@ use anyhow::Result;
 use clap::{ArgAction, Parser, Subcommand};
 use twig_core::output::{ColorMode, cli_styles};
 
-use crate::plugin;
+use crate::{plugin, update_check};
 
 /// Top-level CLI command for the twig tool
 #[derive(Parser)]
@@ -158,6 +160,14 @@ pub enum Commands {
   #[command(alias = "gh")]
   GitHub(github::GitHubArgs),
 
+  /// Trust repositories to run their hooks
+  #[command(
+    long_about = "Manage which repositories may run the hooks in their .twig/hooks.toml.\n\n\
+            Hooks committed to a repository only run after you trust it with\n\
+            'twig hooks trust'. Hooks in your global hooks.toml always run."
+  )]
+  Hooks(hooks::HooksArgs),
+
   /// Set up twig's local configuration files
   #[command(long_about = "Initializes the twig configuration for your environment.\n\n\
             This creates necessary configuration files in your home directory to track\n\
@@ -176,6 +186,15 @@ pub enum Commands {
   #[command(hide = true)]
   Panic,
 
+  /// Install and manage third-party plugins
+  #[command(long_about = "Install, upgrade and remove plugins published as GitHub releases.\n\n\
+            Plugins are looked up by name in a plugin index (a JSON file listing plugin\n\
+            names and their release repositories) or installed directly from a GitHub\n\
+            repository. Installed plugins live in twig's plugin directory, which plugin\n\
+            discovery searches after your PATH.")]
+  #[command(arg_required_else_help = true)]
+  Plugin(plugin_cmd::PluginArgs),
+
   /// Rebase the current branch onto its parent chain
   #[command(long_about = "Rebase the current branch on its parent(s).\n\n\
             This command rebases the current branch on its parent(s) based on\n\
@@ -247,6 +266,29 @@ pub enum Commands {
   External(Vec<OsString>),
 }
 
+/// Build the CLI command with installed plugins merged in as subcommands.
+///
+/// Used for help output and shell completions only; argument parsing uses
+/// [`Cli`] directly so plugin invocations reach the external subcommand
+/// handler.
+pub fn command_with_plugins() -> clap::Command {
+  use clap::CommandFactory;
+  let command = Cli::command();
+  let plugins = plugin::plugin_commands(&command);
+  command.subcommands(plugins)
+}
+
+/// Returns `Some(long)` when the arguments only ask for top-level help
+/// (`twig -h`, `twig --help` or `twig help`), which is answered with plugins
+/// included.
+pub fn top_level_help_requested(args: &[OsString]) -> Option<bool> {
+  match args {
+    [_, flag] if flag == "--help" || flag == "help" => Some(true),
+    [_, flag] if flag == "-h" => Some(false),
+    _ => None,
+  }
+}
+
 pub fn handle_cli(cli: Cli) -> Result<()> {
   // Set global color override based on --colors argument
   match cli.colors {
@@ -263,7 +305,13 @@ pub fn handle_cli(cli: Cli) -> Result<()> {
     twig_core::set_hyperlinks_override(false);
   }
 
-  match cli.command {
+  // Commands under `twig self` manage updates themselves
+  let update_notice = match &cli.command {
+    Some(Commands::SelfCmd(_)) | None => None,
+    Some(_) => update_check::start(),
+  };
+
+  let result = match cli.command {
     Some(command) => match command {
       Commands::Adopt(adopt) => adopt::handle_adopt_command(adopt),
       Commands::Branch(branch) => branch::handle_branch_command(branch),
@@ -274,11 +322,13 @@ pub fn handle_cli(cli: Cli) -> Result<()> {
       Commands::Fixup(fixup) => fixup::handle_fixup_command(fixup),
       Commands::Git(git) => git::handle_git_command(git),
       Commands::GitHub(github) => github::handle_github_command(github),
+      Commands::Hooks(hooks) => hooks::handle_hooks_command(hooks),
       Commands::Init => config::handle_init_command(),
       Commands::Jira(jira) => jira::handle_jira_command(jira),
       Commands::Panic => {
         panic!("This is an intentional test panic to verify panic handler integration");
       }
+      Commands::Plugin(args) => plugin_cmd::handle_plugin_command(args),
       Commands::Rebase(rebase) => rebase::handle_rebase_command(rebase),
       Commands::SelfCmd(self_args) => self_cmd::handle_self_command(self_args),
       Commands::Switch(switch) => switch::handle_switch_command(switch),
@@ -306,9 +356,34 @@ pub fn handle_cli(cli: Cli) -> Result<()> {
       }
     },
     None => {
-      use clap::CommandFactory;
-      Cli::command().print_help()?;
+      command_with_plugins().print_help()?;
       Ok(())
     }
+  };
+
+  if result.is_ok()
+    && let Some(notice) = update_notice
+  {
+    update_check::print_notice(&notice);
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn args(args: &[&str]) -> Vec<OsString> {
+    args.iter().map(OsString::from).collect()
+  }
+
+  #[test]
+  fn detects_top_level_help() {
+    assert_eq!(top_level_help_requested(&args(&["twig", "--help"])), Some(true));
+    assert_eq!(top_level_help_requested(&args(&["twig", "help"])), Some(true));
+    assert_eq!(top_level_help_requested(&args(&["twig", "-h"])), Some(false));
+    assert_eq!(top_level_help_requested(&args(&["twig", "help", "sync"])), None);
+    assert_eq!(top_level_help_requested(&args(&["twig", "sync", "--help"])), None);
   }
 }
@@ -0,0 +1,218 @@
+//! # Plugin Command
+//!
+//! Implements the `twig plugin` command group for installing, upgrading and
+//! removing third-party plugins from a plugin index.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use twig_core::get_config_dirs;
+use twig_core::output::{format_command, print_header, print_info};
+
+use crate::plugin::install::{
+  InstalledPlugins, PluginIndex, index_location, install_plugin, remove_plugin, resolve_install_spec, upgrade_plugins,
+};
+
+/// Arguments for the top-level `twig plugin` command.
+#[derive(Args)]
+pub struct PluginArgs {
+  /// Subcommands under `twig plugin`
+  #[command(subcommand)]
+  pub command: PluginSubcommand,
+}
+
+/// Subcommands available under `twig plugin`.
+#[derive(Subcommand)]
+pub enum PluginSubcommand {
+  /// Install plugins from the plugin index or a GitHub repository
+  #[command(
+    long_about = "Downloads the latest release of each plugin and installs it into twig's\n\
+            plugin directory, where plugin discovery finds it.\n\n\
+            A plugin can be given by its name in the plugin index, or as a GitHub repository\n\
+            URL or owner/repo shorthand. Release archives must follow twig's asset naming\n\
+            (<name>-<os>-<arch>.tar.gz, .zip on Windows) and publish SHA-256 checksums;\n\
+            archives that do not match their checksum are not installed."
+  )]
+  #[command(alias = "add")]
+  Install(InstallArgs),
+
+  /// List installed plugins, or plugins available in the index
+  #[command(alias = "ls")]
+  List(ListArgs),
+
+  /// Upgrade installed plugins to their latest releases
+  #[command(alias = "update")]
+  Upgrade(UpgradeArgs),
+
+  /// Remove installed plugins
+  #[command(alias = "rm", alias = "uninstall")]
+  Remove(RemoveArgs),
+}
+
+/// Arguments for `twig plugin install`.
+#[derive(Args)]
+pub struct InstallArgs {
+  /// Plugin names, GitHub repository URLs, or owner/repo shorthands
+  #[arg(required = true, value_name = "NAME|URL")]
+  pub plugins: Vec<String>,
+
+  /// Plugin index URL or file (defaults to $TWIG_PLUGIN_INDEX or twig's index)
+  #[arg(long, value_name = "LOCATION")]
+  pub index: Option<String>,
+
+  /// Reinstall even if the latest version is already installed
+  #[arg(long)]
+  pub force: bool,
+}
+
+/// Arguments for `twig plugin list`.
+#[derive(Args)]
+pub struct ListArgs {
+  /// List plugins available in the index instead of installed ones
+  #[arg(long)]
+  pub available: bool,
+
+  /// Plugin index URL or file (defaults to $TWIG_PLUGIN_INDEX or twig's index)
+  #[arg(long, value_name = "LOCATION", requires = "available")]
+  pub index: Option<String>,
+}
+
+/// Arguments for `twig plugin upgrade`.
+#[derive(Args)]
+pub struct UpgradeArgs {
+  /// Plugins to upgrade
+  #[arg(required_unless_present = "all", conflicts_with = "all")]
+  pub plugins: Vec<String>,
+
+  /// Upgrade every installed plugin
+  #[arg(long)]
+  pub all: bool,
+
+  /// Reinstall even if the latest version is already installed
+  #[arg(long)]
+  pub force: bool,
+}
+
+/// Arguments for `twig plugin remove`.
+#[derive(Args)]
+pub struct RemoveArgs {
+  /// Plugins to remove
+  #[arg(required = true)]
+  pub plugins: Vec<String>,
+}
+
+/// Execute a `twig plugin` command.
+pub fn handle_plugin_command(args: PluginArgs) -> Result<()> {
+  match args.command {
+    PluginSubcommand::Install(cmd) => handle_install_command(cmd),
+    PluginSubcommand::List(cmd) => handle_list_command(cmd),
+    PluginSubcommand::Upgrade(cmd) => upgrade_plugins(&cmd.plugins, cmd.force),
+    PluginSubcommand::Remove(cmd) => cmd.plugins.iter().try_for_each(|name| remove_plugin(name)),
+  }
+}
+
+fn handle_install_command(args: InstallArgs) -> Result<()> {
+  let location = index_location(args.index.as_deref());
+  let mut index: Option<PluginIndex> = None;
+
+  for spec in &args.plugins {
+    // Load the index at most once, and only for plugins given by name
+    let entry = resolve_install_spec(spec, || match &index {
+      Some(index) => Ok(index.clone()),
+      None => PluginIndex::load(&location).inspect(|loaded| index = Some(loaded.clone())),
+    })?;
+    install_plugin(&entry, args.force)?;
+  }
+
+  Ok(())
+}
+
+fn handle_list_command(args: ListArgs) -> Result<()> {
+  if args.available {
+    let location = index_location(args.index.as_deref());
+    let index = PluginIndex::load(&location)?;
+    if index.plugins.is_empty() {
+      print_info(&format!("The plugin index at {location} lists no plugins."));
+      return Ok(());
+    }
+
+    print_header("Available plugins");
+    for entry in &index.plugins {
+      println!("  {}", format_command(&entry.binary()));
+      if let Some(description) = &entry.description {
+        println!("    Description: {description}");
+      }
+      println!("    Source: https://github.com/{}", entry.repo);
+    }
+    return Ok(());
+  }
+
+  let config_dirs = get_config_dirs()?;
+  let installed = InstalledPlugins::load(&config_dirs.installed_plugins_path())?;
+  if installed.plugins.is_empty() {
+    print_info(&format!(
+      "No plugins are installed. Run {} to see what's available.",
+      format_command("twig plugin list --available")
+    ));
+    return Ok(());
+  }
+
+  print_header("Installed plugins");
+  for plugin in installed.plugins.values() {
+    println!("  {} {}", format_command(&plugin.entry.binary()), plugin.version);
+    if let Some(description) = &plugin.entry.description {
+      println!("    Description: {description}");
+    }
+    println!("    Source: https://github.com/{}", plugin.entry.repo);
+  }
+  println!("  Directory: {}", config_dirs.plugin_bin_dir().display());
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use twig_test_utils::setup_test_env_with_init;
+
+  use super::*;
+
+  #[test]
+  fn removing_a_plugin_that_was_not_installed_fails() {
+    let (_env_guard, _config_dirs) = setup_test_env_with_init().unwrap();
+
+    let err = handle_plugin_command(PluginArgs {
+      command: PluginSubcommand::Remove(RemoveArgs {
+        plugins: vec!["twig-missing".to_string()],
+      }),
+    })
+    .unwrap_err();
+
+    assert_eq!(
+      err.to_string(),
+      "Plugin 'missing' was not installed with 'twig plugin install'"
+    );
+  }
+
+  #[test]
+  fn upgrading_with_nothing_installed_succeeds_without_network() {
+    let (_env_guard, _config_dirs) = setup_test_env_with_init().unwrap();
+
+    handle_plugin_command(PluginArgs {
+      command: PluginSubcommand::Upgrade(UpgradeArgs {
+        plugins: Vec::new(),
+        all: true,
+        force: false,
+      }),
+    })
+    .unwrap();
+
+    let err = handle_plugin_command(PluginArgs {
+      command: PluginSubcommand::Upgrade(UpgradeArgs {
+        plugins: vec!["flow".to_string()],
+        all: false,
+        force: false,
+      }),
+    })
+    .unwrap_err();
+    assert!(err.to_string().contains("'flow' was not installed"));
+  }
+}
@@ -3,13 +3,18 @@
 //! Implements the `twig self` command group for managing Twig's
 //! own lifecycle behaviours like self-updating.
 
+use std::path::PathBuf;
+
 use anyhow::Result;
 use clap::{Args, Subcommand};
-use twig_core::output::{format_command, print_header, print_info, print_warning};
+use twig_core::output::{format_command, print_header, print_info, print_success, print_warning};
+use twig_core::plugin::manifest::{Compatibility, TWIG_CORE_VERSION};
 
 use super::completion;
-use crate::self_update::{PluginInstallOptions, SelfUpdateOptions, run as run_self_update, run_plugin_install};
-use crate::{diagnostics, plugin};
+use crate::self_update::{
+  PluginInstallOptions, ReleaseSelector, SelfUpdateOptions, run as run_self_update, run_plugin_install, run_rollback,
+};
+use crate::{diagnostics, plugin, update_check};
 
 /// Arguments for the top-level `twig self` command.
 #[derive(Args)]
@@ -25,24 +30,40 @@ pub enum SelfSubcommand {
   /// Update Twig or its plugins to the latest release
   #[command(
     long_about = "Download the latest Twig release from GitHub and replace the current executable.\n\n\
-This command determines the platform-specific binary to download, verifies permissions,\n\
-handles sudo elevation when required, and ensures that the running executable is swapped\n\
-out safely once the update completes.\n\n\
+This command determines the platform-specific binary to download, verifies it against the\n\
+SHA-256 checksum published with the release (and its minisign signature when a public key\n\
+is configured via TWIG_UPDATE_PUBLIC_KEY), handles sudo elevation when required, and ensures\n\
+that the running executable is swapped out safely once the update completes. The previous\n\
+binary is kept so `twig self rollback` can restore it.\n\n\
+Use --to to install a specific version, or --prerelease to include prereleases.\n\
+Use --check to only report whether Twig or its first-party plugins have a newer release.\n\
 Use `twig self update flow`, `twig self update prune`, or `twig self update mcp` to install or\n\
 update individual plugins instead."
   )]
   #[command(alias = "upgrade")]
   Update(SelfUpdateArgs),
 
+  /// Restore the Twig version installed before the last update
+  #[command(
+    long_about = "Reinstalls the Twig binary that the last `twig self update` replaced.\n\n\
+The binary being replaced is saved in turn, so running rollback again returns to the\n\
+newer version."
+  )]
+  Rollback,
+
   /// Run system diagnostics
   #[command(
     long_about = "Runs comprehensive system diagnostics to check twig's configuration and dependencies.\n\n\
             This command checks system information, configuration directories, credentials,\n\
             git configuration, tracked repositories, and network connectivity. Use this\n\
-            command to troubleshoot issues or verify that twig is properly configured."
+            command to troubleshoot issues or verify that twig is properly configured.\n\n\
+            With --bundle, the diagnostics are written to a zip archive for bug reports instead,\n\
+            together with twig and plugin versions, configuration files, a summary of the\n\
+            repository's twig state, registry statistics, the git version and recent debug logs.\n\
+            Credentials and hostnames are scrubbed from the bundle."
   )]
   #[command(alias = "diag")]
-  Diagnose,
+  Diagnose(DiagnoseArgs),
 
   /// Generate shell completions
   #[command(long_about = "Generates shell completion scripts for twig commands.\n\n\
@@ -54,12 +75,23 @@ update individual plugins instead."
   #[command(
     long_about = "Searches your PATH for executables following the twig-<command> naming\n\
 convention and prints the plugins that can be invoked. Use this command to verify that\n\
-Twig can locate your installed plugins."
+Twig can locate your installed plugins.\n\n\
+Plugins that provide a manifest (a twig-<command>.plugin.toml sidecar file, or JSON\n\
+printed in response to --twig-manifest) are shown with their description, version,\n\
+subcommands and the twig-core versions they support."
   )]
   #[command(alias = "list-plugins")]
   Plugins,
 }
 
+/// Arguments for `twig self diagnose`.
+#[derive(Args, Debug, Clone)]
+pub struct DiagnoseArgs {
+  /// Write a diagnostic bundle to this zip file
+  #[arg(long, value_name = "PATH")]
+  pub bundle: Option<PathBuf>,
+}
+
 /// Arguments for `twig self update`.
 #[derive(Args, Debug, Clone)]
 pub struct SelfUpdateArgs {
@@ -67,6 +99,18 @@ pub struct SelfUpdateArgs {
   #[arg(long)]
   pub force: bool,
 
+  /// Install this version instead of the latest release
+  #[arg(long = "to", value_name = "VERSION", conflicts_with = "prerelease")]
+  pub target_version: Option<String>,
+
+  /// Include prereleases when looking for the newest release
+  #[arg(long)]
+  pub prerelease: bool,
+
+  /// Check for newer releases without installing anything
+  #[arg(long, conflicts_with_all = ["force", "target_version", "prerelease"])]
+  pub check: bool,
+
   /// What to update
   #[command(subcommand)]
   pub target: Option<UpdateTarget>,
@@ -104,16 +148,43 @@ install or update the MCP server binary."
 pub fn handle_self_command(args: SelfArgs) -> Result<()> {
   match args.command {
     SelfSubcommand::Update(cmd) => handle_update_command(cmd),
-    SelfSubcommand::Diagnose => diagnostics::run_diagnostics(),
+    SelfSubcommand::Rollback => run_rollback(),
+    SelfSubcommand::Diagnose(cmd) => handle_diagnose_command(cmd),
     SelfSubcommand::Completion(cmd) => completion::handle_completion_command(cmd),
     SelfSubcommand::Plugins => list_plugins(),
   }
 }
 
+fn handle_diagnose_command(args: DiagnoseArgs) -> Result<()> {
+  let Some(path) = args.bundle else {
+    return diagnostics::run_diagnostics();
+  };
+
+  print_info("Collecting diagnostics…");
+  diagnostics::write_bundle(&path)?;
+  print_success(&format!("Wrote diagnostic bundle to {}", path.display()));
+  print_info("Credentials and hostnames have been scrubbed, but review the bundle before sharing it.");
+  Ok(())
+}
+
 fn handle_update_command(args: SelfUpdateArgs) -> Result<()> {
-  let plugin_opts = PluginInstallOptions { force: args.force };
+  if args.check {
+    return update_check::run_check();
+  }
+
+  let release = ReleaseSelector {
+    version: args.target_version,
+    prerelease: args.prerelease,
+  };
+  let plugin_opts = PluginInstallOptions {
+    force: args.force,
+    release: release.clone(),
+  };
   match args.target {
-    None => run_self_update(SelfUpdateOptions { force: args.force }),
+    None => run_self_update(SelfUpdateOptions {
+      force: args.force,
+      release,
+    }),
     Some(UpdateTarget::Flow) => run_plugin_install("twig-flow", plugin_opts),
     Some(UpdateTarget::Prune) => run_plugin_install("twig-prune", plugin_opts),
     Some(UpdateTarget::Mcp) => run_plugin_install("twig-mcp", plugin_opts),
@@ -145,6 +216,45 @@ fn list_plugins() -> Result<()> {
       println!("    Size: {}", format_file_size(size_in_bytes));
     }
 
+    if let Some(manifest) = &plugin.manifest {
+      if let Some(description) = &manifest.description {
+        println!("    Description: {description}");
+      }
+      if let Some(version) = &manifest.version {
+        println!("    Version: {version}");
+      }
+      if let Some(required) = &manifest.twig_core {
+        println!("    Requires twig-core: {required}");
+      }
+      if !manifest.subcommands.is_empty() {
+        let names: Vec<&str> = manifest.subcommands.iter().map(|sub| sub.name.as_str()).collect();
+        println!("    Subcommands: {}", names.join(", "));
+      }
+      println!(
+        "    Completions: {}",
+        if manifest.completions {
+          "supported"
+        } else {
+          "not supported"
+        }
+      );
+      match manifest.compatibility() {
+        Compatibility::Compatible => {}
+        Compatibility::Incompatible { required } => print_warning(&format!(
+          "twig-{} requires twig-core {required}, but this twig uses {TWIG_CORE_VERSION}.",
+          plugin.name
+        )),
+        Compatibility::Invalid { required, error } => print_warning(&format!(
+          "twig-{} declares an invalid twig-core requirement '{required}': {error}",
+          plugin.name
+        )),
+      }
+    } else if let Some(error) = &plugin.manifest_error {
+      print_warning(&format!("twig-{} has an invalid manifest: {error}", plugin.name));
+    } else {
+      println!("    Manifest: none");
+    }
+
     if plugin.paths.len() > 1 {
       println!("    Also found at:");
       for alternate in plugin.paths.iter().skip(1) {
@@ -14,13 +14,16 @@ use twig_core::git::switch::{
   BranchBaseResolution, ParentBranchOption, PullRequestCheckoutRequest, PullRequestHeadInfo, SwitchInput,
   checkout_pr_branch, detect_switch_input, resolve_branch_base, store_jira_association, try_checkout_remote_branch,
 };
-use twig_core::jira_parser::JiraTicketParser;
+use twig_core::github::GITHUB_COM_HOST;
+use twig_core::hooks::{HookPayload, HookRunner};
+use twig_core::jira_parser::{JiraLifecycleEvent, JiraTicketParser};
 use twig_core::output::{print_error, print_info, print_success, print_warning};
 use twig_core::state::RepoState;
-use twig_core::{checkout_branch, detect_repository, generate_branch_name_from_issue};
-use twig_gh::{GitHubClient, GitHubRepo, create_github_client_from_netrc};
+use twig_core::{checkout_branch, detect_repository, generate_branch_name_from_issue, resolve_origin_github_repo};
+use twig_gh::{GitHubClient, GitHubRepo, create_github_client_for_host};
 use twig_jira::{JiraClient, create_jira_client_from_netrc, get_jira_host};
 
+use super::jira::apply_lifecycle_transition;
 use crate::complete::switch_target_completer;
 
 /// Command for intelligently switching to branches based on various inputs
@@ -148,7 +151,10 @@ pub(crate) fn handle_switch_command(switch: SwitchArgs) -> Result<()> {
     }
     SwitchInput::GitHubPrId(pr_number) | SwitchInput::GitHubPrUrl(pr_number) => {
       let base_dirs = BaseDirs::new().context("Failed to get $HOME")?;
-      let gh = create_github_client_from_netrc(base_dirs.home_dir()).context("Failed to create GitHub client")?;
+      // PRs are looked up on the origin remote, which may live on a GitHub
+      // Enterprise Server host.
+      let host = resolve_origin_github_repo(ctx.repo).map_or_else(|_| GITHUB_COM_HOST.to_string(), |r| r.host);
+      let gh = create_github_client_for_host(base_dirs.home_dir(), &host).context("Failed to create GitHub client")?;
 
       handle_github_pr_switch(&gh, &ctx, pr_number)
     }
@@ -238,9 +244,12 @@ fn handle_branch_switch(
 
   // Branch doesn't exist
   if create_if_missing {
+    let hooks = SwitchHooks::before(repo, repo_path, branch_name)?;
+
     if try_checkout_remote_branch(repo, branch_name)? {
       print_success(&format!("Checked out {branch_name} from origin.",));
-      return Ok(());
+      hooks.branch_created(None)?;
+      return hooks.after();
     }
 
     print_info(&format!("Branch '{branch_name}' doesn't exist. Creating it...",));
@@ -248,7 +257,7 @@ fn handle_branch_switch(
     // Resolve parent branch
     let branch_base = resolve_branch_base(repo, repo_path, parent_option, jira_parser)?;
 
-    create_and_switch_to_branch(repo, repo_path, branch_name, &branch_base)
+    create_branch_and_checkout(repo, repo_path, branch_name, &branch_base, &hooks)
   } else {
     print_warning(&format!(
       "Branch '{branch_name}' doesn't exist. Use --create to create it.",
@@ -295,11 +304,56 @@ fn handle_root_switch(repo: &Git2Repository, repo_path: &std::path::Path, repo_s
   switch_to_branch(repo, repo_path, &dependency_root)
 }
 
+/// Lifecycle hooks fired around a branch switch
+struct SwitchHooks {
+  runner: HookRunner,
+  from: Option<String>,
+  to: String,
+}
+
+impl SwitchHooks {
+  /// Load the repository's hooks and run the pre-switch hooks, which may
+  /// abort the switch
+  fn before(repo: &Git2Repository, repo_path: &Path, to: &str) -> Result<Self> {
+    let hooks = Self {
+      runner: HookRunner::for_repo(repo_path)?,
+      from: repo
+        .head()
+        .ok()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(str::to_string)),
+      to: to.to_string(),
+    };
+    hooks.runner.run(HookPayload::PreSwitch {
+      from: hooks.from.clone(),
+      to: hooks.to.clone(),
+    })?;
+    Ok(hooks)
+  }
+
+  /// Run the post-branch-create hooks for the switch target
+  fn branch_created(&self, parent: Option<&str>) -> Result<()> {
+    self.runner.run(HookPayload::PostBranchCreate {
+      branch: self.to.clone(),
+      parent: parent.map(str::to_string),
+    })
+  }
+
+  /// Run the post-switch hooks
+  fn after(&self) -> Result<()> {
+    self.runner.run(HookPayload::PostSwitch {
+      from: self.from.clone(),
+      to: self.to.clone(),
+    })
+  }
+}
+
 /// Switch to an existing branch
-fn switch_to_branch(repo: &Git2Repository, _repo_path: &std::path::Path, branch_name: &str) -> Result<()> {
+fn switch_to_branch(repo: &Git2Repository, repo_path: &std::path::Path, branch_name: &str) -> Result<()> {
+  let hooks = SwitchHooks::before(repo, repo_path, branch_name)?;
   checkout_branch(repo, branch_name)?;
   print_success(&format!("Switched to branch '{branch_name}'",));
-  Ok(())
+  hooks.after()
 }
 
 /// Create a new branch and switch to it
@@ -308,6 +362,19 @@ fn create_and_switch_to_branch(
   repo_path: &std::path::Path,
   branch_name: &str,
   branch_base: &BranchBaseResolution,
+) -> Result<()> {
+  let hooks = SwitchHooks::before(repo, repo_path, branch_name)?;
+  create_branch_and_checkout(repo, repo_path, branch_name, branch_base, &hooks)
+}
+
+/// Create a new branch, check it out and record its parent, once the
+/// pre-switch hooks have run
+fn create_branch_and_checkout(
+  repo: &Git2Repository,
+  repo_path: &std::path::Path,
+  branch_name: &str,
+  branch_base: &BranchBaseResolution,
+  hooks: &SwitchHooks,
 ) -> Result<()> {
   let base_commit = repo
     .find_commit(branch_base.commit())
@@ -319,17 +386,20 @@ fn create_and_switch_to_branch(
 
   print_success(&format!("Created branch '{branch_name}'",));
 
-  switch_to_branch(repo, repo_path, branch_name)?;
+  checkout_branch(repo, branch_name)?;
+  print_success(&format!("Switched to branch '{branch_name}'",));
 
   if let Some(parent) = branch_base.parent_name() {
     add_branch_dependency(repo_path, branch_name, parent)?;
   }
 
-  Ok(())
+  hooks.branch_created(branch_base.parent_name())?;
+  hooks.after()
 }
 
 /// Add a branch dependency
 fn add_branch_dependency(repo_path: &std::path::Path, child: &str, parent: &str) -> Result<()> {
+  let _lock = RepoState::lock(repo_path)?;
   let mut repo_state = RepoState::load(repo_path)?;
 
   match repo_state.add_dependency(child.to_string(), parent.to_string()) {
@@ -346,7 +416,7 @@ fn add_branch_dependency(repo_path: &std::path::Path, child: &str, parent: &str)
 }
 
 /// Create a branch from a Jira issue
-fn create_branch_from_jira_issue(
+pub(crate) fn create_branch_from_jira_issue(
   jira_client: &JiraClient,
   repo: &Git2Repository,
   repo_path: &std::path::Path,
@@ -382,6 +452,8 @@ fn create_branch_from_jira_issue(
     print_success(&format!(
       "Created and switched to branch '{branch_name}' for Jira issue {issue_key}",
     ));
+
+    apply_lifecycle_transition(jira_client, JiraLifecycleEvent::BranchCreated, issue_key, false).await;
     Ok(())
   })
 }
@@ -450,6 +522,7 @@ fn create_branch_from_github_pr(
       },
     };
 
+    let hooks = SwitchHooks::before(repo, repo_path, &request.head.branch)?;
     let outcome = checkout_pr_branch(repo, repo_path, &request)?;
 
     if outcome.fork_remote_created
@@ -467,7 +540,9 @@ fn create_branch_from_github_pr(
     ));
     print_info(&format!("PR Title: {}", pr.title));
     print_info(&format!("PR URL: {}", pr.html_url));
-    Ok(())
+
+    hooks.branch_created(request.parent.as_deref())?;
+    hooks.after()
   })
 }
 
@@ -557,7 +632,7 @@ mod tests {
       "test remote branch",
     )?;
 
-    assert!(try_checkout_remote_branch(&repo, "feature/existing")?);
+    assert!(try_checkout_remote_branch(repo, "feature/existing")?);
 
     let repo = git2::Repository::open(repo_guard.path())?;
     let local_branch = repo.find_branch("feature/existing", BranchType::Local)?;
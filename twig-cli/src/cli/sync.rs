@@ -13,11 +13,15 @@ use git2::{BranchType, Repository as Git2Repository};
 use indicatif::{ProgressBar, ProgressStyle};
 use regex::Regex;
 use tokio::runtime::Runtime;
-use tokio::task::JoinSet;
 use tracing::warn;
+use twig_core::github::GITHUB_COM_HOST;
+use twig_core::hooks::{HookBranch, HookPayload, HookRunner};
+use twig_core::jira_parser::JiraLifecycleEvent;
 use twig_core::output::{print_info, print_success, print_warning};
 use twig_core::state::{BranchMetadata, RepoState};
-use twig_gh::{GitHubClient, GitHubRepo, create_github_client_from_netrc};
+use twig_gh::{GitHubClient, GitHubRepo, PullRequestSummary, create_github_client_for_host, primary_pull_request};
+
+use super::jira::run_lifecycle_transitions;
 
 static JIRA_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
   vec![
@@ -87,7 +91,8 @@ fn sync_branches(
     return Ok(());
   }
 
-  // Load current repository state
+  // Load current repository state, locked until the changes are saved
+  let state_lock = RepoState::lock(repo_path)?;
   let mut repo_state = RepoState::load(repo_path)?;
 
   // Evict metadata for branches that no longer exist locally
@@ -104,12 +109,21 @@ fn sync_branches(
   let mut updated_associations = Vec::new();
   let mut conflicting_associations = Vec::new();
   let mut unlinked_branches = Vec::new();
+  let mut lifecycle_events = Vec::new();
+  let mut merged_branches = Vec::new();
+  let mut newly_merged = Vec::new();
 
   // Create runtime for async operations
   let rt = Runtime::new().context("Failed to create async runtime")?;
+  let github_repo = if !no_github {
+    resolve_repo_info_from_origin(repo_path)
+  } else {
+    None
+  };
   let github_client = if !no_github {
     let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
-    Some(create_github_client_from_netrc(base_dirs.home_dir())?)
+    let host = github_repo.as_ref().map_or(GITHUB_COM_HOST, |r| r.host.as_str());
+    Some(create_github_client_for_host(base_dirs.home_dir(), host)?)
   } else {
     None
   };
@@ -124,11 +138,7 @@ fn sync_branches(
   );
   pb.set_message("Scanning branches for Jira issues and GitHub PRs...");
 
-  let repo_info = if github_client.is_some() {
-    resolve_repo_info_from_origin(repo_path)
-  } else {
-    None
-  };
+  let repo_info = github_repo.map(|r| (r.owner, r.repo));
 
   let jira_by_branch = detect_jira_issues_for_branches(&branch_names, no_jira);
   let github_pr_results =
@@ -144,11 +154,40 @@ fn sync_branches(
     // Detect patterns in branch name
     let detected_jira = jira_by_branch.get(branch_name).cloned().flatten();
 
-    let detected_pr = github_pr_results
+    let detected_summary = github_pr_results
       .as_ref()
       .and_then(|results| results.get(branch_name))
-      .copied()
-      .flatten();
+      .and_then(Option::as_ref);
+    let detected_pr = detected_summary.map(|pr| pr.number);
+    // Merges already recorded by an earlier sync have been acted on
+    let merged_now = detected_summary
+      .filter(|summary| summary.is_merged() && !repo_state.is_recorded_merged(branch_name, summary.number));
+    if let Some(summary) = merged_now {
+      newly_merged.push((branch_name.clone(), summary.number));
+      merged_branches.push(HookBranch {
+        branch: branch_name.clone(),
+        jira_issue: detected_jira
+          .clone()
+          .or_else(|| existing_association.and_then(|a| a.jira_issue.clone())),
+        github_pr: Some(summary.number),
+      });
+    }
+
+    if !no_jira
+      && let Some(summary) = detected_summary
+      && let Some(issue_key) = detected_jira
+        .clone()
+        .or_else(|| existing_association.and_then(|a| a.jira_issue.clone()))
+    {
+      let newly_linked = existing_association.and_then(|a| a.github_pr) != Some(summary.number);
+      if summary.is_merged() {
+        if merged_now.is_some() {
+          lifecycle_events.push((issue_key, JiraLifecycleEvent::PrMerged));
+        }
+      } else if summary.is_open() && newly_linked {
+        lifecycle_events.push((issue_key, JiraLifecycleEvent::PrOpened));
+      }
+    }
 
     match (detected_jira, detected_pr, existing_association) {
       // No patterns detected
@@ -203,18 +242,34 @@ fn sync_branches(
 
   // Apply changes if not dry run
   if !dry_run {
+    let payload = HookPayload::PostSync {
+      linked: detected_associations.iter().map(HookBranch::from).collect(),
+      updated: updated_associations
+        .iter()
+        .map(|(_, new)| HookBranch::from(new))
+        .collect(),
+      merged: merged_branches,
+    };
+
     apply_sync_changes(
       &mut repo_state,
       repo_path,
       detected_associations,
       updated_associations,
+      newly_merged,
       !eviction_stats.is_empty(),
     )?;
+
+    // Hook commands may run twig themselves
+    drop(state_lock);
+    HookRunner::for_repo(repo_path)?.run(payload)?;
   } else if !eviction_stats.is_empty() {
     // Eviction is background cleanup — save even in dry-run mode
     repo_state.save(repo_path)?;
   }
 
+  run_lifecycle_transitions(&lifecycle_events, dry_run);
+
   Ok(())
 }
 
@@ -265,7 +320,7 @@ fn detect_github_prs_for_branches(
   repo_info: Option<&(String, String)>,
   runtime: &Runtime,
   progress: &ProgressBar,
-) -> Option<HashMap<String, Option<u32>>> {
+) -> Option<HashMap<String, Option<PullRequestSummary>>> {
   let (github_client, repo_info) = match (github_client, repo_info) {
     (Some(client), Some(info)) => (client, info),
     (Some(_), None) => {
@@ -275,39 +330,22 @@ fn detect_github_prs_for_branches(
     _ => return None,
   };
 
-  progress.set_message("Fetching GitHub PRs for branches in parallel...");
-
-  let gh_client = github_client.clone();
-  let owner = repo_info.0.clone();
-  let repo_name = repo_info.1.clone();
-  let branch_names = branch_names.to_vec();
+  progress.set_message("Fetching GitHub PRs for branches...");
 
-  let results = runtime.block_on(async move {
-    let mut join_set = JoinSet::new();
-
-    for branch_name in branch_names {
-      let gh = gh_client.clone();
-      let owner = owner.clone();
-      let repo_name = repo_name.clone();
-
-      join_set.spawn(async move {
-        let pr = detect_github_pr_from_branch(&gh, &branch_name, &owner, &repo_name).await;
+  let (owner, repo_name) = repo_info;
+  let results = match runtime.block_on(github_client.find_pull_requests_by_head_refs(owner, repo_name, branch_names)) {
+    Ok(prs_by_branch) => prs_by_branch
+      .into_iter()
+      .map(|(branch_name, prs)| {
+        let pr = primary_pull_request(&prs).cloned();
         (branch_name, pr)
-      });
-    }
-
-    let mut results = HashMap::new();
-    while let Some(result) = join_set.join_next().await {
-      match result {
-        Ok((branch_name, pr)) => {
-          results.insert(branch_name, pr);
-        }
-        Err(error) => warn!("GitHub PR detection task failed: {error}"),
-      }
+      })
+      .collect(),
+    Err(error) => {
+      warn!("Skipping GitHub PR detection: {error}");
+      return None;
     }
-
-    results
-  });
+  };
 
   progress.set_message("Scanning branches for Jira issues and GitHub PRs...");
 
@@ -333,44 +371,20 @@ fn detect_jira_issue_from_branch(branch_name: &str) -> Option<String> {
   None
 }
 
-/// Resolve GitHub repository owner and name from origin remote URL
+/// Resolve the GitHub repository from the origin remote URL
 ///
 /// This function looks at the URL structure of the `origin` git remote to
-/// extract the repository owner and name, which are then used for API calls.
+/// extract the repository host, owner and name, which are then used for API
+/// calls.
 ///
 /// NOTE: This assumes that the `origin` remote points to a GitHub repository
 /// and that it is their primary / representative remote.
-fn resolve_repo_info_from_origin(repo_path: &std::path::Path) -> Option<(String, String)> {
+fn resolve_repo_info_from_origin(repo_path: &std::path::Path) -> Option<GitHubRepo> {
   let repo = Git2Repository::open(repo_path).ok()?;
   let remote = repo.find_remote("origin").ok()?;
   let remote_url = remote.url()?;
 
-  GitHubRepo::parse(remote_url).ok().map(|r| (r.owner, r.repo))
-}
-
-/// Detect GitHub PR number from branch using GitHub API
-async fn detect_github_pr_from_branch(
-  github_client: &GitHubClient,
-  branch_name: &str,
-  owner: &str,
-  repo_name: &str,
-) -> Option<u32> {
-  // Search for PRs with this branch as head
-  match github_client
-    .find_pull_requests_by_head_branch(owner, repo_name, branch_name, Some("all"))
-    .await
-  {
-    Ok(prs) => {
-      // Return the first open PR, or the most recent PR if none are open
-      let open_pr = prs.iter().find(|pr| pr.state == "open");
-      if let Some(pr) = open_pr {
-        Some(pr.number)
-      } else {
-        prs.first().map(|pr| pr.number)
-      }
-    }
-    Err(_) => None, // Silently fall back if API call fails
-  }
+  GitHubRepo::parse(remote_url).ok()
 }
 
 /// Determine if an existing branch association should be updated and whether
@@ -515,10 +529,17 @@ fn apply_sync_changes(
   repo_path: &std::path::Path,
   detected: Vec<BranchMetadata>,
   updated: Vec<(BranchMetadata, BranchMetadata)>,
+  merged: Vec<(String, u32)>,
   eviction_occurred: bool,
 ) -> Result<()> {
   let mut changes_made = eviction_occurred;
 
+  // Remember merges so their lifecycle rules and hooks fire only once
+  for (branch, pr_number) in merged {
+    repo_state.record_merged(&branch, pr_number);
+    changes_made = true;
+  }
+
   // Add new associations
   for association in detected {
     repo_state.add_branch_issue(association);
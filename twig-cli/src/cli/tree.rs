@@ -3,15 +3,18 @@
 //! Derive-based implementation of the tree command for visualizing branch
 //! dependency trees.
 
+use std::collections::HashMap;
 use std::io;
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use clap::Args;
+use directories::BaseDirs;
 use git2::Repository as Git2Repository;
-use tree_renderer::TreeRenderer;
+use tree_renderer::{PrStatus, TreeRenderer};
 use twig_core::output::{format_command, print_info, print_warning};
-use twig_core::{detect_repository, tree_renderer};
+use twig_core::{detect_repository, resolve_origin_github_repo, tree_renderer};
+use twig_gh::{PullRequestSummary, create_github_runtime_and_client_for_host, primary_pull_request};
 
 use crate::user_defined_dependency_resolver::UserDefinedDependencyResolver;
 
@@ -29,6 +32,10 @@ pub struct TreeArgs {
   /// Disable colored output
   #[arg(long = "no-color")]
   pub no_color: bool,
+
+  /// Fetch live PR state, checks and review decision from GitHub
+  #[arg(long = "pr-status")]
+  pub pr_status: bool,
 }
 
 /// Handle the tree command
@@ -83,6 +90,13 @@ pub(crate) fn handle_tree_command(tree: TreeArgs) -> Result<()> {
 
   // Create and configure the tree renderer
   let mut renderer = TreeRenderer::new(&branch_nodes, &roots, tree.max_depth, tree.no_color);
+  if tree.pr_status {
+    let branch_names: Vec<String> = branch_nodes.keys().cloned().collect();
+    match fetch_pr_statuses(&repo, &branch_names) {
+      Ok(statuses) => renderer = renderer.with_pr_statuses(statuses),
+      Err(e) => print_warning(&format!("Could not fetch PR status from GitHub: {e}")),
+    }
+  }
   let mut stdout = io::stdout();
   renderer.render(&mut stdout, &roots, Some("\n"))?;
 
@@ -97,6 +111,53 @@ pub(crate) fn handle_tree_command(tree: TreeArgs) -> Result<()> {
   Ok(())
 }
 
+/// Fetch the PR for each branch from GitHub in a single batched query
+fn fetch_pr_statuses(repo: &Git2Repository, branch_names: &[String]) -> Result<HashMap<String, PrStatus>> {
+  let github_repo = resolve_origin_github_repo(repo)?;
+
+  let base_dirs = BaseDirs::new().context("Failed to get $HOME directory")?;
+  let (rt, github_client) = create_github_runtime_and_client_for_host(base_dirs.home_dir(), &github_repo.host)?;
+  let prs_by_branch =
+    rt.block_on(github_client.find_pull_requests_by_head_refs(&github_repo.owner, &github_repo.repo, branch_names))?;
+
+  Ok(
+    prs_by_branch
+      .into_iter()
+      .filter_map(|(branch, prs)| primary_pull_request(&prs).map(|pr| (branch, pr_status_from_summary(pr))))
+      .collect(),
+  )
+}
+
+/// Convert a GitHub PR summary into the tree's PR column state
+fn pr_status_from_summary(pr: &PullRequestSummary) -> PrStatus {
+  let state = if pr.is_merged() {
+    "merged"
+  } else if !pr.is_open() {
+    "closed"
+  } else if pr.is_draft {
+    "draft"
+  } else {
+    "open"
+  };
+  let checks = pr.check_state.as_deref().map(|state| match state {
+    "SUCCESS" => "success",
+    "FAILURE" | "ERROR" => "failure",
+    _ => "pending",
+  });
+  let review = pr.review_decision.as_deref().and_then(|decision| match decision {
+    "APPROVED" => Some("approved"),
+    "CHANGES_REQUESTED" => Some("changes requested"),
+    _ => None,
+  });
+
+  PrStatus {
+    number: pr.number,
+    state: state.to_string(),
+    checks: checks.map(str::to_string),
+    review: review.map(str::to_string),
+  }
+}
+
 fn display_summary(branch_nodes: &std::collections::HashMap<String, tree_renderer::BranchNode>) {
   let branches_with_issues = branch_nodes.values().filter(|node| node.metadata.is_some()).count();
 
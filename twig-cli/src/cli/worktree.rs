@@ -3,14 +3,19 @@
 //! Derive-based implementation of the worktree command for managing Git
 //! worktrees for efficient multi-branch development.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
 use git2::Repository as Git2Repository;
+use twig_core::git::{find_checkout_path_for_branch, find_worktree_for_branch};
 use twig_core::output::{format_command, format_timestamp, print_header};
-use twig_core::state::create_worktree;
-use twig_core::{RepoState, detect_repository_from_path, format_repo_path, print_success, print_warning};
+use twig_core::state::{
+  RemoveWorktreeOptions, create_worktree_with_config, migrate_worktrees, remove_worktree_for_branch,
+};
+use twig_core::{
+  RepoState, detect_repository_from_path, format_repo_path, get_config_dirs, print_info, print_success, print_warning,
+};
 
 /// Command for worktree management
 #[derive(Args)]
@@ -33,12 +38,38 @@ pub enum WorktreeSubcommands {
   )]
   Clean(CleanCommand),
 
+  /// Change directory into a branch's worktree (requires shell integration)
+  #[command(
+    long_about = "Changes the current shell's directory to the worktree for a branch.\n\n\
+                     A program cannot change its parent shell's directory, so this command only\n\
+                     works once the shell function from 'twig worktree shell-init' is loaded:\n\n\
+                     \x20 bash/zsh: eval \"$(twig worktree shell-init bash)\"\n\
+                     \x20 fish:     twig worktree shell-init fish | source"
+  )]
+  Cd(PathCommand),
+
+  /// Show or change the worktree layout
+  #[command(long_about = "Shows or changes where new worktrees are created.\n\n\
+                     The path template supports {repo} (the repository directory name) and {branch}\n\
+                     (the branch name with '/' and other unsafe characters percent-encoded, so\n\
+                     'feature/foo' and 'feature-foo' never collide). Relative templates are resolved\n\
+                     against the repository's parent directory; '~/' expands to your home directory.\n\n\
+                     Settings live in worktree.toml in twig's config directory. A repository's\n\
+                     .twig/worktree.toml overrides them for that repository. Post-create hooks are\n\
+                     configured there under [hooks]:\n\n\
+                     \x20 [hooks]\n\
+                     \x20 copy = [\".env\"]\n\
+                     \x20 post_create = [\"direnv allow\", \"npm install\"]\n\n\
+                     post_create commands from a repository's .twig/worktree.toml only run once the\n\
+                     repository is trusted with 'twig hooks trust'.")]
+  Config(ConfigCommand),
+
   /// Create a new worktree for a branch
   #[command(long_about = "Creates a new Git worktree for a specific branch.\n\n\
                      This allows you to work on multiple branches simultaneously without switching\n\
                      branches in your main repository. If the branch doesn't exist, it will be\n\
-                     created. The worktree will be created in a directory named after the branch\n\
-                     within the repository's parent directory.")]
+                     created. The worktree location follows the configured path template (see\n\
+                     'twig worktree config'), and any configured post-create hooks are run.")]
   #[command(alias = "new")]
   Create(CreateCommand),
 
@@ -48,6 +79,53 @@ pub enum WorktreeSubcommands {
                      to help you track your active development environments.")]
   #[command(alias = "ls")]
   List(ListCommand),
+
+  /// Move existing worktrees to the configured layout
+  #[command(
+    long_about = "Moves existing worktrees to the locations given by the current path template.\n\n\
+                     Use this after changing the layout with 'twig worktree config', or to move\n\
+                     worktrees created by older versions of twig to the collision-free naming\n\
+                     scheme. Uncommitted work moves along with the worktree."
+  )]
+  Migrate(MigrateCommand),
+
+  /// Print the path of a branch's worktree
+  #[command(
+    long_about = "Prints the directory of the worktree that has a branch checked out.\n\n\
+                     Only the path is written to stdout, which makes this command suitable for\n\
+                     scripting, e.g. cd \"$(twig worktree path feature/foo)\". If the branch is\n\
+                     checked out in the main repository, the repository path is printed instead."
+  )]
+  Path(PathCommand),
+
+  /// Remove the worktree for a branch
+  #[command(long_about = "Removes the worktree that has a branch checked out.\n\n\
+                     The worktree directory is deleted and its record is removed from twig's\n\
+                     repository state. Worktrees with uncommitted or untracked changes are left\n\
+                     alone unless --force is given. Use --delete-branch to delete the local\n\
+                     branch as well; branches stacked on it are moved onto its parent.")]
+  #[command(alias = "rm")]
+  Remove(RemoveCommand),
+
+  /// Print the shell function that enables 'twig worktree cd'
+  #[command(
+    long_about = "Prints a shell function that wraps twig so 'twig worktree cd <branch>'\n\
+                     (or 'twig wt cd <branch>') changes the current shell's directory.\n\n\
+                     Add one of the following to your shell's startup file:\n\n\
+                     \x20 bash:  eval \"$(twig worktree shell-init bash)\"\n\
+                     \x20 zsh:   eval \"$(twig worktree shell-init zsh)\"\n\
+                     \x20 fish:  twig worktree shell-init fish | source"
+  )]
+  ShellInit(ShellInitCommand),
+
+  /// Create worktrees for every branch in the current stack
+  #[command(long_about = "Materializes every branch of a stack as its own worktree.\n\n\
+                     The stack is the current branch, its ancestors and its descendants as defined\n\
+                     with 'twig branch depend'; sibling stacks and root branches are left out. A\n\
+                     worktree is created for each of them that is not already checked out\n\
+                     somewhere, so the whole stack can be reviewed or demoed side by side. 'twig cascade' rebases such branches in place inside their\n\
+                     worktrees.")]
+  Stack(StackCommand),
 }
 
 /// Create a new worktree for a branch
@@ -57,6 +135,10 @@ pub struct CreateCommand {
   #[arg(required = true)]
   pub branch: String,
 
+  /// Skip the configured post-create hooks
+  #[arg(long)]
+  pub no_hooks: bool,
+
   /// Path to a specific repository
   #[arg(long, short = 'r', value_name = "PATH")]
   pub repo: Option<String>,
@@ -78,6 +160,97 @@ pub struct CleanCommand {
   pub repo: Option<String>,
 }
 
+/// Show or change the worktree layout
+#[derive(Args)]
+pub struct ConfigCommand {
+  /// Set the global worktree path template (e.g. '~/wt/{repo}/{branch}')
+  #[arg(long, value_name = "TEMPLATE")]
+  pub path_template: Option<String>,
+
+  /// Path to a specific repository
+  #[arg(long, short = 'r', value_name = "PATH")]
+  pub repo: Option<String>,
+}
+
+/// Move existing worktrees to the configured layout
+#[derive(Args)]
+pub struct MigrateCommand {
+  /// Show what would be moved without moving anything
+  #[arg(long)]
+  pub dry_run: bool,
+
+  /// Path to a specific repository
+  #[arg(long, short = 'r', value_name = "PATH")]
+  pub repo: Option<String>,
+}
+
+/// Create worktrees for every branch in a stack
+#[derive(Args)]
+pub struct StackCommand {
+  /// Branch whose stack to materialize (defaults to the current branch)
+  #[arg(long, short = 'b', value_name = "BRANCH")]
+  pub branch: Option<String>,
+
+  /// Skip the configured post-create hooks
+  #[arg(long)]
+  pub no_hooks: bool,
+
+  /// Path to a specific repository
+  #[arg(long, short = 'r', value_name = "PATH")]
+  pub repo: Option<String>,
+}
+
+/// Locate the worktree for a branch
+#[derive(Args)]
+pub struct PathCommand {
+  /// Branch name
+  #[arg(required = true)]
+  pub branch: String,
+
+  /// Path to a specific repository
+  #[arg(long, short = 'r', value_name = "PATH")]
+  pub repo: Option<String>,
+}
+
+/// Remove the worktree for a branch
+#[derive(Args)]
+pub struct RemoveCommand {
+  /// Branch name
+  #[arg(required = true)]
+  pub branch: String,
+
+  /// Remove the worktree even if it has uncommitted changes
+  #[arg(long, short = 'f')]
+  pub force: bool,
+
+  /// Also delete the local branch
+  #[arg(long, short = 'd')]
+  pub delete_branch: bool,
+
+  /// Path to a specific repository
+  #[arg(long, short = 'r', value_name = "PATH")]
+  pub repo: Option<String>,
+}
+
+/// Print the shell integration function
+#[derive(Args)]
+pub struct ShellInitCommand {
+  /// Shell to generate the function for
+  #[arg(value_enum)]
+  pub shell: InitShell,
+}
+
+/// Shells supported by `twig worktree shell-init`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitShell {
+  /// Bourne Again `SHell` (bash)
+  Bash,
+  /// Friendly Interactive `SHell` (fish)
+  Fish,
+  /// Z `SHell` (zsh)
+  Zsh,
+}
+
 pub(crate) fn handle_worktree_command(worktree: WorktreeArgs) -> Result<()> {
   match worktree.subcommand {
     WorktreeSubcommands::Clean(clean) => {
@@ -85,17 +258,273 @@ pub(crate) fn handle_worktree_command(worktree: WorktreeArgs) -> Result<()> {
         .ok_or_else(|| anyhow::anyhow!("Could not detect repository path"))?;
       clean_worktrees(repo_path)
     }
+    WorktreeSubcommands::Cd(_) => Err(anyhow::anyhow!(
+      "'twig worktree cd' needs shell integration to change your directory.\n\
+       Load it with one of:\n  \
+       eval \"$(twig worktree shell-init bash)\"\n  \
+       eval \"$(twig worktree shell-init zsh)\"\n  \
+       twig worktree shell-init fish | source"
+    )),
     WorktreeSubcommands::Create(create) => {
       let repo_path = detect_repository_from_path(create.repo.as_deref().unwrap_or("."))
         .ok_or_else(|| anyhow::anyhow!("Could not detect repository path"))?;
-      create_worktree(repo_path, &create.branch)?;
+      let mut worktree_config = get_config_dirs()?.load_worktree_config(&repo_path)?;
+      if create.no_hooks {
+        worktree_config.hooks = Default::default();
+      }
+      create_worktree_with_config(repo_path, &create.branch, &worktree_config)?;
       Ok(())
     }
+    WorktreeSubcommands::Config(config) => handle_config_command(config),
+    WorktreeSubcommands::Migrate(migrate) => {
+      let repo_path = detect_repository_from_path(migrate.repo.as_deref().unwrap_or("."))
+        .ok_or_else(|| anyhow::anyhow!("Could not detect repository path"))?;
+      handle_migrate_command(&repo_path, migrate.dry_run)
+    }
     WorktreeSubcommands::List(list) => {
       let repo_path = detect_repository_from_path(list.repo.as_deref().unwrap_or("."))
         .ok_or_else(|| anyhow::anyhow!("Could not detect repository path"))?;
       list_worktrees(repo_path)
     }
+    WorktreeSubcommands::Path(path) => {
+      let repo_path = detect_repository_from_path(path.repo.as_deref().unwrap_or("."))
+        .ok_or_else(|| anyhow::anyhow!("Could not detect repository path"))?;
+      let worktree_path = resolve_worktree_path(&repo_path, &path.branch)?;
+      println!("{}", worktree_path.display());
+      Ok(())
+    }
+    WorktreeSubcommands::Remove(remove) => {
+      let repo_path = detect_repository_from_path(remove.repo.as_deref().unwrap_or("."))
+        .ok_or_else(|| anyhow::anyhow!("Could not detect repository path"))?;
+      let options = RemoveWorktreeOptions {
+        force: remove.force,
+        delete_branch: remove.delete_branch,
+      };
+      let removed_path = remove_worktree_for_branch(&repo_path, &remove.branch, options)?;
+      print_success(&format!(
+        "Removed worktree for branch '{}' at {}",
+        remove.branch,
+        format_repo_path(&removed_path.display().to_string())
+      ));
+      if remove.delete_branch {
+        print_success(&format!("Deleted branch '{}'", remove.branch));
+      }
+      Ok(())
+    }
+    WorktreeSubcommands::Stack(stack) => {
+      let repo_path = detect_repository_from_path(stack.repo.as_deref().unwrap_or("."))
+        .ok_or_else(|| anyhow::anyhow!("Could not detect repository path"))?;
+      create_stack_worktrees(&repo_path, stack.branch.as_deref(), stack.no_hooks)
+    }
+    WorktreeSubcommands::ShellInit(init) => {
+      print!("{}", shell_init_script(init.shell));
+      Ok(())
+    }
+  }
+}
+
+/// Show the effective worktree configuration, optionally updating the global
+/// path template first.
+fn handle_config_command(config: ConfigCommand) -> Result<()> {
+  let config_dirs = get_config_dirs()?;
+  let repo_path = detect_repository_from_path(config.repo.as_deref().unwrap_or("."));
+
+  if let Some(template) = config.path_template {
+    let mut global = config_dirs.load_global_worktree_config()?;
+    global.path_template = template;
+    // Validate before saving so a bad template never reaches disk
+    global.worktree_path(Path::new("repo"), "branch")?;
+    config_dirs.save_worktree_config(&global)?;
+    print_success(&format!("Worktree path template set to: {}", global.path_template));
+    println!(
+      "Run {} to move existing worktrees.",
+      format_command("twig worktree migrate")
+    );
+  }
+
+  let worktree_config = match &repo_path {
+    Some(repo_path) => config_dirs.load_worktree_config(repo_path)?,
+    None => config_dirs.load_global_worktree_config()?,
+  };
+
+  print_info("Current worktree configuration:");
+  println!("  Path template: {}", worktree_config.path_template);
+  if let Some(repo_path) = &repo_path {
+    println!(
+      "  Example: {}",
+      format_repo_path(
+        &worktree_config
+          .worktree_path(repo_path, "feature/example")?
+          .display()
+          .to_string()
+      )
+    );
+  }
+  if !worktree_config.hooks.copy.is_empty() {
+    println!("  Copy into new worktrees: {}", worktree_config.hooks.copy.join(", "));
+  }
+  for command in &worktree_config.hooks.post_create {
+    println!("  Post-create: {command}");
+  }
+
+  Ok(())
+}
+
+/// Move worktrees to the configured layout
+fn handle_migrate_command(repo_path: &Path, dry_run: bool) -> Result<()> {
+  let worktree_config = get_config_dirs()?.load_worktree_config(repo_path)?;
+  let migrations = migrate_worktrees(repo_path, &worktree_config, dry_run)?;
+
+  if migrations.is_empty() {
+    println!("All worktrees already follow the configured layout");
+    return Ok(());
+  }
+
+  for migration in &migrations {
+    println!(
+      "  {}: {} -> {}",
+      migration.branch,
+      format_repo_path(&migration.from.display().to_string()),
+      format_repo_path(&migration.to.display().to_string())
+    );
+  }
+
+  if dry_run {
+    print_info(&format!("{} worktree(s) would be moved", migrations.len()));
+  } else {
+    print_success(&format!("Moved {} worktree(s)", migrations.len()));
+  }
+
+  Ok(())
+}
+
+/// Create a worktree for every branch in the stack containing `branch`
+fn create_stack_worktrees(repo_path: &Path, branch: Option<&str>, no_hooks: bool) -> Result<()> {
+  let repo =
+    Git2Repository::open(repo_path).context(format!("Failed to open git repository at {}", repo_path.display()))?;
+
+  let branch = match branch {
+    Some(branch) => branch.to_string(),
+    None => {
+      let head = repo.head().context("Failed to get HEAD reference")?;
+      if !head.is_branch() {
+        return Err(anyhow::anyhow!("HEAD is not a branch. Use --branch to pick a stack."));
+      }
+      head.shorthand().unwrap_or("HEAD").to_string()
+    }
+  };
+
+  let state = RepoState::load(repo_path)?;
+  let stack = state.get_stack_branches(&branch);
+  if state.get_dependency_parents(&branch).is_empty() && state.get_dependency_children(&branch).is_empty() {
+    print_warning(&format!(
+      "Branch '{branch}' has no dependencies. Define a stack with {}.",
+      format_command("twig branch depend <parent>")
+    ));
+  }
+
+  let mut worktree_config = get_config_dirs()?.load_worktree_config(repo_path)?;
+  if no_hooks {
+    worktree_config.hooks = Default::default();
+  }
+
+  let mut created = 0;
+  let mut failed = 0;
+  for stack_branch in &stack {
+    if let Some(path) = find_checkout_path_for_branch(&repo, stack_branch)? {
+      println!(
+        "  {stack_branch}: already checked out at {}",
+        format_repo_path(&path.display().to_string())
+      );
+      continue;
+    }
+
+    if repo.find_branch(stack_branch, git2::BranchType::Local).is_err() {
+      print_warning(&format!("Skipping '{stack_branch}': no local branch with that name"));
+      continue;
+    }
+
+    match create_worktree_with_config(repo_path, stack_branch, &worktree_config) {
+      Ok(_) => created += 1,
+      Err(e) => {
+        print_warning(&format!("Failed to create worktree for '{stack_branch}': {e}"));
+        failed += 1;
+      }
+    }
+  }
+
+  if failed > 0 {
+    print_warning(&format!("Created {created} worktree(s); {failed} could not be created"));
+  } else {
+    print_success(&format!(
+      "Stack of '{branch}' has {} branch(es); created {created} new worktree(s)",
+      stack.len()
+    ));
+  }
+
+  Ok(())
+}
+
+/// Resolve the directory where `branch` is checked out.
+///
+/// Linked worktrees are checked first; if none has the branch, the main
+/// repository is used when its HEAD is on that branch.
+fn resolve_worktree_path(repo_path: &Path, branch: &str) -> Result<PathBuf> {
+  let repo =
+    Git2Repository::open(repo_path).context(format!("Failed to open git repository at {}", repo_path.display()))?;
+
+  if let Some(entry) = find_worktree_for_branch(&repo, branch)? {
+    if !entry.path.exists() {
+      return Err(anyhow::anyhow!(
+        "Worktree for branch '{branch}' no longer exists at {}. Run 'twig worktree clean' to prune it.",
+        entry.path.display()
+      ));
+    }
+    return Ok(entry.path);
+  }
+
+  let main_branch = repo.head().ok().and_then(|head| head.shorthand().map(str::to_string));
+  if main_branch.as_deref() == Some(branch)
+    && let Some(workdir) = repo.workdir()
+  {
+    return Ok(workdir.to_path_buf());
+  }
+
+  Err(anyhow::anyhow!(
+    "No worktree found for branch '{branch}'. Create one with 'twig worktree create {branch}'."
+  ))
+}
+
+/// Shell function that intercepts `twig wt cd` / `twig worktree cd` and
+/// forwards everything else to the real binary.
+fn shell_init_script(shell: InitShell) -> &'static str {
+  match shell {
+    InitShell::Bash | InitShell::Zsh => {
+      r#"# twig shell integration: enables `twig worktree cd <branch>`
+twig() {
+  if [ "$#" -ge 2 ] && { [ "$1" = "wt" ] || [ "$1" = "worktree" ]; } && [ "$2" = "cd" ]; then
+    shift 2
+    local __twig_wt_path
+    __twig_wt_path="$(command twig worktree path "$@")" || return
+    builtin cd -- "$__twig_wt_path"
+  else
+    command twig "$@"
+  fi
+}
+"#
+    }
+    InitShell::Fish => {
+      r#"# twig shell integration: enables `twig worktree cd <branch>`
+function twig --description 'twig with worktree cd support'
+    if test (count $argv) -ge 2; and contains -- $argv[1] wt worktree; and test "$argv[2]" = cd
+        set -l __twig_wt_path (command twig worktree path $argv[3..-1]); or return
+        builtin cd $__twig_wt_path
+    else
+        command twig $argv
+    end
+end
+"#
+    }
   }
 }
 
@@ -168,7 +597,8 @@ fn clean_worktrees<P: AsRef<Path>>(repo_path: P) -> Result<()> {
     return Ok(());
   }
 
-  // Load the repository state
+  // Load the repository state, locked until it is saved
+  let _lock = RepoState::lock(repo_path)?;
   let mut state = RepoState::load(repo_path)?;
   let mut cleaned_count = 0;
 
@@ -204,3 +634,17 @@ fn clean_worktrees<P: AsRef<Path>>(repo_path: P) -> Result<()> {
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn shell_init_scripts_route_cd_through_worktree_path() {
+    for shell in [InitShell::Bash, InitShell::Zsh, InitShell::Fish] {
+      let script = shell_init_script(shell);
+      assert!(script.contains("command twig worktree path"), "{shell:?}");
+      assert!(script.contains("builtin cd"), "{shell:?}");
+    }
+  }
+}
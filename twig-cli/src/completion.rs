@@ -6,10 +6,10 @@
 use std::io;
 
 use anyhow::Result;
-use clap::{CommandFactory, ValueEnum};
+use clap::ValueEnum;
 use clap_complete::generate;
 
-use crate::cli::Cli;
+use crate::cli::command_with_plugins;
 
 /// Shell with auto-generated completion script available.
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,9 +25,10 @@ pub enum Shell {
   Zsh,
 }
 
-/// Generate shell completions for the specified shell
+/// Generate shell completions for the specified shell, including the
+/// subcommands of installed plugins
 pub fn generate_completions(shell: clap_complete::Shell) -> Result<()> {
-  let mut cmd = Cli::command();
+  let mut cmd = command_with_plugins();
   let app_name = cmd.get_name().to_string();
 
   generate(shell, &mut cmd, app_name, &mut io::stdout());
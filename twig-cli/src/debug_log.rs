@@ -0,0 +1,107 @@
+//! # Debug Log
+//!
+//! Keeps a log of twig's own debug output in the cache directory, whatever the
+//! verbosity of the command, so `twig self diagnose --bundle` can include what
+//! happened in recent runs. The log is rotated once it reaches
+//! [`MAX_LOG_SIZE`], keeping one previous file.
+
+use std::fs::{self, File, OpenOptions};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use twig_core::ConfigDirs;
+
+/// Size at which the log is rotated
+pub const MAX_LOG_SIZE: u64 = 1024 * 1024;
+
+const LOG_FILE: &str = "debug.log";
+const ROTATED_LOG_FILE: &str = "debug.log.1";
+
+/// Directory holding the debug log.
+pub fn log_dir(config_dirs: &ConfigDirs) -> Option<PathBuf> {
+  config_dirs.cache_dir().map(|dir| dir.join("logs"))
+}
+
+/// Opens the debug log for appending, rotating it first if it has grown too
+/// large.
+pub fn open(config_dirs: &ConfigDirs) -> Result<File> {
+  let dir = log_dir(config_dirs).context("No cache directory is configured")?;
+  fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+  let path = dir.join(LOG_FILE);
+  if fs::metadata(&path).is_ok_and(|metadata| metadata.len() >= MAX_LOG_SIZE) {
+    fs::rename(&path, dir.join(ROTATED_LOG_FILE)).with_context(|| format!("Failed to rotate {}", path.display()))?;
+  }
+
+  OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)
+    .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Events recorded in the debug log: debug and above from twig's own crates.
+pub fn filter() -> Targets {
+  Targets::new().with_target("twig", Level::DEBUG)
+}
+
+/// The last `max_lines` lines of the debug log, oldest first.
+pub fn recent_lines(config_dirs: &ConfigDirs, max_lines: usize) -> Result<Vec<String>> {
+  let Some(dir) = log_dir(config_dirs) else {
+    return Ok(Vec::new());
+  };
+
+  let mut lines = Vec::new();
+  for name in [ROTATED_LOG_FILE, LOG_FILE] {
+    let path = dir.join(name);
+    if path.exists() {
+      let content = fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+      lines.extend(String::from_utf8_lossy(&content).lines().map(str::to_string));
+    }
+  }
+
+  let skip = lines.len().saturating_sub(max_lines);
+  Ok(lines.split_off(skip))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Write;
+
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn config_dirs(dir: &TempDir) -> ConfigDirs {
+    ConfigDirs {
+      config_dir: dir.path().join("config"),
+      data_dir: dir.path().join("data"),
+      cache_dir: Some(dir.path().join("cache")),
+    }
+  }
+
+  #[test]
+  fn rotates_and_reads_recent_lines() {
+    let dir = TempDir::new().unwrap();
+    let config_dirs = config_dirs(&dir);
+    assert!(recent_lines(&config_dirs, 10).unwrap().is_empty());
+
+    let mut log = open(&config_dirs).unwrap();
+    let line = "x".repeat(1023);
+    for _ in 0..1024 {
+      writeln!(log, "{line}").unwrap();
+    }
+    drop(log);
+
+    let mut log = open(&config_dirs).unwrap();
+    writeln!(log, "after rotation").unwrap();
+    drop(log);
+
+    let log_dir = log_dir(&config_dirs).unwrap();
+    assert!(log_dir.join(ROTATED_LOG_FILE).exists());
+    let lines = recent_lines(&config_dirs, 2).unwrap();
+    assert_eq!(lines, vec![line, "after rotation".to_string()]);
+  }
+}
@@ -1,22 +1,29 @@
 //! # System Diagnostics
 //!
 //! Provides comprehensive system diagnostics and health checks for twig,
-//! including configuration validation, credential checking, and Git repository
-//! status.
+//! including configuration validation, credential checking, Git repository
+//! status, and update availability.
 
 use std::process::Command;
 use std::{env, fs};
 
 use anyhow::Result;
 use directories::BaseDirs;
+use twig_core::creds::chain::CredentialChain;
 use twig_core::get_config_dirs;
 use twig_core::output::{format_repo_path, print_error, print_header, print_success};
-use twig_gh::check_github_credentials;
-use twig_jira::{check_jira_credentials, get_jira_host};
+use twig_gh::find_github_credentials;
+use twig_jira::{find_jira_credentials, get_jira_host};
+
+mod bundle;
+mod redact;
+
+pub use bundle::write_bundle;
 
 use crate::consts;
 use crate::creds::netrc::get_netrc_path;
 use crate::git::list_repositories;
+use crate::update_check::{CheckStatus, NO_UPDATE_CHECK_ENV, UpdateCheck};
 
 /// Run comprehensive system diagnostics
 pub fn run_diagnostics() -> Result<()> {
@@ -43,6 +50,10 @@ pub fn run_diagnostics() -> Result<()> {
   check_tracked_repositories()?;
   println!();
 
+  // Check for updates
+  check_updates()?;
+  println!();
+
   // Check dependencies
   check_dependencies()?;
   println!();
@@ -167,26 +178,6 @@ fn check_credentials() -> Result<()> {
     {
       println!("  .netrc permissions: Unable to check on this platform");
     }
-
-    // Check specific credentials
-    match get_jira_host() {
-      Ok(jira_host) => {
-        println!("  Jira host: {jira_host}");
-        // Check Jira credentials
-        match check_jira_credentials(base_dirs.home_dir(), &jira_host) {
-          Ok(true) => println!("  Jira credentials: Found"),
-          Ok(false) => println!("  Jira credentials: Not found"),
-          Err(e) => print_error(&format!("  Jira credentials: Error - {e}")),
-        }
-      }
-      Err(e) => print_error(&format!("  Jira: Error - {e}")),
-    }
-
-    match check_github_credentials(base_dirs.home_dir()) {
-      Ok(true) => println!("  GitHub credentials: Found"),
-      Ok(false) => println!("  GitHub credentials: Not found"),
-      Err(e) => print_error(&format!("  GitHub credentials: Error - {e}")),
-    }
   } else {
     println!(
       "  .netrc file: {} (not found)",
@@ -194,6 +185,34 @@ fn check_credentials() -> Result<()> {
     );
   }
 
+  match CredentialChain::load(base_dirs.home_dir()) {
+    Ok(chain) => {
+      let backends: Vec<String> = chain.backends().iter().map(ToString::to_string).collect();
+      println!("  Credential backends: {}", backends.join(", "));
+    }
+    Err(e) => print_error(&format!("  Credential backends: Error - {e}")),
+  }
+
+  // Check specific credentials
+  match get_jira_host() {
+    Ok(jira_host) => {
+      println!("  Jira host: {jira_host}");
+      // Check Jira credentials
+      match find_jira_credentials(base_dirs.home_dir(), &jira_host) {
+        Ok(Some(found)) => println!("  Jira credentials: Found ({})", found.source()),
+        Ok(None) => println!("  Jira credentials: Not found"),
+        Err(e) => print_error(&format!("  Jira credentials: Error - {e}")),
+      }
+    }
+    Err(e) => print_error(&format!("  Jira: Error - {e}")),
+  }
+
+  match find_github_credentials(base_dirs.home_dir(), "github.com") {
+    Ok(Some(found)) => println!("  GitHub credentials: Found ({})", found.source()),
+    Ok(None) => println!("  GitHub credentials: Not found"),
+    Err(e) => print_error(&format!("  GitHub credentials: Error - {e}")),
+  }
+
   Ok(())
 }
 
@@ -284,6 +303,48 @@ fn check_tracked_repositories() -> Result<()> {
   Ok(())
 }
 
+/// Report the update check setting and the result of the last check
+fn check_updates() -> Result<()> {
+  println!("Updates:");
+
+  let config_dirs = get_config_dirs()?;
+  println!("  Installed version: {}", env!("CARGO_PKG_VERSION"));
+  match CheckStatus::load(&config_dirs)? {
+    CheckStatus::Enabled => println!("  Update check: Enabled"),
+    CheckStatus::NotEnabled => println!(
+      "  Update check: Not enabled (set check = true in {})",
+      format_repo_path(&config_dirs.updates_config_path().display().to_string())
+    ),
+    CheckStatus::DisabledByEnv => println!("  Update check: Disabled by {NO_UPDATE_CHECK_ENV}"),
+  }
+
+  let cached = match UpdateCheck::cache_path(&config_dirs) {
+    Some(path) => UpdateCheck::load(&path)?,
+    None => None,
+  };
+  let Some(check) = cached else {
+    println!("  Last check: Never");
+    return Ok(());
+  };
+
+  println!("  Last check: {}", check.checked_at.format("%Y-%m-%d %H:%M UTC"));
+  if let Some(error) = &check.error {
+    println!("  Last check failed: {error}");
+  }
+  if let Some(latest) = &check.latest_version {
+    println!("  Latest release: {latest}");
+    let outdated = check.outdated(env!("CARGO_PKG_VERSION"));
+    if outdated.is_empty() {
+      println!("  Status: Up to date");
+    }
+    for (name, version) in outdated {
+      println!("  Outdated: {name} {version}");
+    }
+  }
+
+  Ok(())
+}
+
 /// Check dependencies
 fn check_dependencies() -> Result<()> {
   println!("Dependencies:");
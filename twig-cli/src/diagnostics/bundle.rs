@@ -0,0 +1,430 @@
+//! Exporting a diagnostic bundle: a zip archive with what is needed to
+//! investigate a bug report, scrubbed of credentials and hostnames.
+//!
+//! The bundle holds:
+//!
+//! - `summary.json`: twig, plugin and git versions, registry statistics, a summary of the current repository's
+//!   `.twig/state.json` (counts only, no branch names) and the last update check
+//! - `diagnostics.txt`: the output of `twig self diagnose`
+//! - `config/`: twig's configuration files
+//! - `logs/debug.log`: the end of twig's debug log
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use directories::BaseDirs;
+use regex::Regex;
+use serde::Serialize;
+use twig_core::github::GITHUB_COM_HOST;
+use twig_core::state::{Registry, RepoState};
+use twig_core::{ConfigDirs, detect_repository, get_config_dirs};
+use twig_gh::find_github_credentials;
+use twig_jira::{find_jira_credentials, get_jira_host};
+use zip::write::SimpleFileOptions;
+
+use super::redact::Redactor;
+use crate::plugin::install::InstalledPlugins;
+use crate::plugin::list_cached_plugins;
+use crate::update_check::UpdateCheck;
+use crate::{consts, debug_log};
+
+/// Lines of the debug log included in the bundle
+const DEBUG_LOG_LINES: usize = 2000;
+
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").expect("valid regex"));
+
+/// A text file in the bundle
+struct BundleFile {
+  name: String,
+  content: String,
+}
+
+impl BundleFile {
+  fn new(name: impl Into<String>, content: impl Into<String>) -> Self {
+    Self {
+      name: name.into(),
+      content: content.into(),
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct Summary {
+  created_at: DateTime<Utc>,
+  twig_version: &'static str,
+  os: &'static str,
+  arch: &'static str,
+  git_version: Option<String>,
+  plugins: serde_json::Value,
+  registry: serde_json::Value,
+  repository_state: serde_json::Value,
+  update_check: Option<UpdateCheck>,
+}
+
+#[derive(Debug, Serialize)]
+struct PluginSummary {
+  name: String,
+  version: Option<String>,
+  /// Whether `twig plugin install` installed the plugin
+  installed_from_index: bool,
+  /// Number of copies found on the plugin search path
+  locations: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct RegistrySummary {
+  repositories: usize,
+  /// Tracked repositories whose directory no longer exists
+  missing: usize,
+  /// Tracked repositories with a `.twig/state.json`
+  with_state: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct StateSummary {
+  version: u32,
+  updated_at: DateTime<Utc>,
+  file_size_bytes: u64,
+  branches: usize,
+  jira_links: usize,
+  pr_links: usize,
+  dependencies: usize,
+  root_branches: usize,
+  default_root: bool,
+  worktrees: usize,
+}
+
+/// Collects a diagnostic bundle and writes it to `path` as a zip archive.
+pub fn write_bundle(path: &Path) -> Result<()> {
+  let config_dirs = get_config_dirs()?;
+  let redactor = build_redactor(&config_dirs);
+
+  let summary = Summary {
+    created_at: Utc::now(),
+    twig_version: env!("CARGO_PKG_VERSION"),
+    os: std::env::consts::OS,
+    arch: std::env::consts::ARCH,
+    git_version: git_version(),
+    plugins: section(summarize_plugins(&config_dirs)),
+    registry: section(summarize_registry(&config_dirs)),
+    repository_state: match detect_repository() {
+      Some(repo_path) => section(summarize_state(&config_dirs.repo_state_path(&repo_path))),
+      None => serde_json::Value::Null,
+    },
+    update_check: UpdateCheck::cache_path(&config_dirs).and_then(|path| UpdateCheck::load(&path).ok().flatten()),
+  };
+
+  let mut files = vec![
+    BundleFile::new(
+      "summary.json",
+      serde_json::to_string_pretty(&summary).context("Failed to serialize summary")?,
+    ),
+    BundleFile::new("diagnostics.txt", capture_diagnostics()),
+  ];
+  files.extend(config_files(&config_dirs)?);
+  files.push(BundleFile::new(
+    "logs/debug.log",
+    debug_log::recent_lines(&config_dirs, DEBUG_LOG_LINES)?.join("\n"),
+  ));
+
+  write_zip(path, &files, &redactor)
+}
+
+/// Serializes a section of the summary, recording the error if it could not
+/// be collected.
+fn section<T: Serialize>(result: Result<T>) -> serde_json::Value {
+  result
+    .and_then(|value| serde_json::to_value(value).context("Failed to serialize"))
+    .unwrap_or_else(|e| serde_json::json!({ "error": format!("{e:#}") }))
+}
+
+fn write_zip(path: &Path, files: &[BundleFile], redactor: &Redactor) -> Result<()> {
+  let file = File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+  let mut zip = zip::ZipWriter::new(file);
+  let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+  for file in files {
+    zip
+      .start_file(file.name.as_str(), options)
+      .with_context(|| format!("Failed to add {} to the bundle", file.name))?;
+    zip
+      .write_all(redactor.redact(&file.content).as_bytes())
+      .with_context(|| format!("Failed to write {} to the bundle", file.name))?;
+  }
+
+  zip.finish().context("Failed to finish the bundle")?;
+  Ok(())
+}
+
+/// Registers the credentials, hosts and personal details known to twig.
+fn build_redactor(config_dirs: &ConfigDirs) -> Redactor {
+  let mut redactor = Redactor::default();
+  let base_dirs = BaseDirs::new();
+  let github_config = config_dirs.load_github_config().unwrap_or_default();
+
+  if let Some(base_dirs) = &base_dirs {
+    let home = base_dirs.home_dir();
+    redactor.add_path(&home.display().to_string(), "~");
+
+    let netrc_path = crate::creds::netrc::get_netrc_path(home);
+    if let Ok(content) = fs::read_to_string(netrc_path) {
+      for entry in twig_core::netrc::Netrc::parse(&content).entries() {
+        entry.machine.iter().for_each(|machine| redactor.add_host(machine));
+        entry.login.iter().for_each(|login| redactor.add_secret(login));
+        entry.password.iter().for_each(|password| redactor.add_secret(password));
+        entry.account.iter().for_each(|account| redactor.add_secret(account));
+      }
+    }
+
+    // Credentials from other backends, such as the keychain or environment
+    let jira = get_jira_host()
+      .ok()
+      .inspect(|host| redactor.add_host(host))
+      .and_then(|host| find_jira_credentials(home, &host).ok().flatten());
+    let github_hosts = std::iter::once(GITHUB_COM_HOST).chain(github_config.hosts.keys().map(String::as_str));
+    let github = github_hosts.filter_map(|host| find_github_credentials(home, host).ok().flatten());
+    for found in jira.into_iter().chain(github) {
+      redactor.add_secret(&found.credentials.username);
+      redactor.add_secret(&found.credentials.password);
+    }
+  }
+
+  for (host, settings) in &github_config.hosts {
+    redactor.add_host(host);
+    for url in [&settings.api_url, &settings.oauth_url].into_iter().flatten() {
+      redactor.add_host(url);
+    }
+  }
+
+  for key in ["user.name", "user.email"] {
+    if let Some(value) = git_output(&["config", "--global", key]) {
+      redactor.add_secret(&value);
+    }
+  }
+
+  if let Some(hostname) = Command::new("hostname")
+    .output()
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+  {
+    redactor.add_host(&hostname);
+  }
+
+  redactor
+}
+
+fn git_output(args: &[&str]) -> Option<String> {
+  let output = Command::new(consts::GIT_EXECUTABLE).args(args).output().ok()?;
+  let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+fn git_version() -> Option<String> {
+  git_output(&["--version"])
+}
+
+/// Runs `twig self diagnose` and returns its output without colors.
+fn capture_diagnostics() -> String {
+  let output = std::env::current_exe().and_then(|exe| {
+    Command::new(exe)
+      .args(["--colors", "no", "--no-links", "self", "diagnose"])
+      .output()
+  });
+
+  match output {
+    Ok(output) => {
+      let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+      if !output.stderr.is_empty() {
+        text.push_str("\n--- stderr ---\n");
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+      }
+      ANSI_ESCAPE.replace_all(&text, "").into_owned()
+    }
+    Err(e) => format!("Failed to run diagnostics: {e}"),
+  }
+}
+
+/// Twig's top-level configuration files.
+fn config_files(config_dirs: &ConfigDirs) -> Result<Vec<BundleFile>> {
+  let config_dir = config_dirs.config_dir();
+  if !config_dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut files = Vec::new();
+  for entry in fs::read_dir(config_dir).with_context(|| format!("Failed to read {}", config_dir.display()))? {
+    let path = entry?.path();
+    if !path.is_file() || path.extension().is_none_or(|ext| ext != "toml") {
+      continue;
+    }
+    let Some(name) = path.file_name().map(|name| name.to_string_lossy().into_owned()) else {
+      continue;
+    };
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    files.push(BundleFile::new(format!("config/{name}"), content));
+  }
+
+  files.sort_by(|a, b| a.name.cmp(&b.name));
+  Ok(files)
+}
+
+fn summarize_plugins(config_dirs: &ConfigDirs) -> Result<Vec<PluginSummary>> {
+  let installed = InstalledPlugins::load(&config_dirs.installed_plugins_path())?;
+
+  // Cached manifests only: writing a bundle should not run plugin binaries
+  Ok(
+    list_cached_plugins()?
+      .into_iter()
+      .map(|plugin| {
+        let record = installed.plugins.get(&plugin.name);
+        PluginSummary {
+          version: plugin
+            .manifest
+            .and_then(|manifest| manifest.version)
+            .or_else(|| record.map(|record| record.version.clone())),
+          installed_from_index: record.is_some(),
+          locations: plugin.paths.len(),
+          name: plugin.name,
+        }
+      })
+      .collect(),
+  )
+}
+
+fn summarize_registry(config_dirs: &ConfigDirs) -> Result<RegistrySummary> {
+  let registry = Registry::load(config_dirs)?;
+  let repositories = registry.list();
+
+  Ok(RegistrySummary {
+    repositories: repositories.len(),
+    missing: repositories
+      .iter()
+      .filter(|repo| !Path::new(&repo.path).exists())
+      .count(),
+    with_state: repositories
+      .iter()
+      .filter(|repo| config_dirs.repo_state_path(&repo.path).exists())
+      .count(),
+  })
+}
+
+/// Counts of what the state file at `state_path` records, or `None` when the
+/// repository has no state file.
+fn summarize_state(state_path: &Path) -> Result<Option<StateSummary>> {
+  if !state_path.exists() {
+    return Ok(None);
+  }
+
+  let content = fs::read_to_string(state_path).with_context(|| format!("Failed to read {}", state_path.display()))?;
+  let state: RepoState =
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", state_path.display()))?;
+
+  Ok(Some(StateSummary {
+    version: state.version,
+    updated_at: state.updated_at,
+    file_size_bytes: content.len() as u64,
+    branches: state.branches.len(),
+    jira_links: state.branches.values().filter(|b| b.jira_issue.is_some()).count(),
+    pr_links: state.branches.values().filter(|b| b.github_pr.is_some()).count(),
+    dependencies: state.dependencies.len(),
+    root_branches: state.root_branches.len(),
+    default_root: state.root_branches.iter().any(|root| root.is_default),
+    worktrees: state.worktrees.len(),
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::io::Read;
+
+  use tempfile::TempDir;
+  use twig_core::state::BranchMetadata;
+
+  use super::*;
+
+  #[test]
+  fn summarizes_state_without_branch_names() {
+    let dir = TempDir::new().unwrap();
+    let state_path = dir.path().join("state.json");
+    assert_eq!(summarize_state(&state_path).unwrap(), None);
+
+    let mut state = RepoState::default();
+    state.add_branch_issue(BranchMetadata {
+      branch: "feature/secret-project".to_string(),
+      jira_issue: Some("PROJ-1".to_string()),
+      github_pr: Some(12),
+      created_at: "2024-01-01T00:00:00Z".to_string(),
+    });
+    state.add_root("main".to_string(), true).unwrap();
+    state
+      .add_dependency("feature/secret-project".to_string(), "main".to_string())
+      .unwrap();
+    fs::write(&state_path, serde_json::to_string(&state).unwrap()).unwrap();
+
+    let summary = summarize_state(&state_path).unwrap().unwrap();
+    assert_eq!((summary.branches, summary.jira_links, summary.pr_links), (1, 1, 1));
+    assert_eq!((summary.dependencies, summary.root_branches), (1, 1));
+    assert!(summary.default_root);
+    assert!(!serde_json::to_string(&summary).unwrap().contains("secret-project"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn redacts_credentials_for_configured_github_hosts() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (_env_guard, _config_dirs) = twig_test_utils::setup_test_env_with_init().unwrap();
+    let _netrc_guard = twig_test_utils::NetrcGuard::new("");
+    let config_dirs = get_config_dirs().unwrap();
+    config_dirs.init().unwrap();
+
+    let helper = config_dirs.config_dir().join("helper.sh");
+    fs::write(
+      &helper,
+      "#!/bin/sh\ngrep -q '^host=ghe.corp.local$' && printf 'username=ghe-user\\npassword=ghe-secret-token\\n'\nexit 0\n",
+    )
+    .unwrap();
+    fs::set_permissions(&helper, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(
+      config_dirs.creds_config_path(),
+      format!("backends = [\"helper\"]\nhelper = \"{}\"\n", helper.display()),
+    )
+    .unwrap();
+    fs::write(config_dirs.github_config_path(), "[hosts.\"ghe.corp.local\"]\n").unwrap();
+
+    let redactor = build_redactor(&config_dirs);
+    assert_eq!(
+      redactor.redact("ghe.corp.local token ghe-secret-token"),
+      "<host-1> token <redacted>"
+    );
+  }
+
+  #[test]
+  fn writes_redacted_files() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("bundle.zip");
+    let mut redactor = Redactor::default();
+    redactor.add_host("jira.corp.example");
+
+    let files = [
+      BundleFile::new("diagnostics.txt", "Jira host: jira.corp.example"),
+      BundleFile::new("config/creds.toml", "token = \"abc123\"\n"),
+    ];
+    write_zip(&path, &files, &redactor).unwrap();
+
+    let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+    let mut read = |name: &str| {
+      let mut content = String::new();
+      archive.by_name(name).unwrap().read_to_string(&mut content).unwrap();
+      content
+    };
+    assert_eq!(read("diagnostics.txt"), "Jira host: <host-1>");
+    assert_eq!(read("config/creds.toml"), "token = \"<redacted>\"\n");
+  }
+}
@@ -561,7 +561,7 @@ mod tests {
         "Twig Test User",
         "test@example.com",
       )
-      .expect(&format!("Failed to create commit {i}"));
+      .unwrap_or_else(|_| panic!("Failed to create commit {i}"));
     }
 
     // Create FixupArgs with a limit of 3
@@ -14,6 +14,7 @@ use owo_colors::OwoColorize;
 use serde::Serialize;
 use tokio::{task, time};
 use twig_core::git::delete_local_branch;
+use twig_core::hooks::{HookBranch, HookPayload, HookRunner};
 use twig_core::output::{
   format_command, format_repo_name, format_repo_path, format_timestamp, print_error, print_header, print_success,
   print_warning,
@@ -551,6 +552,7 @@ fn interactive_prune_branches<P: AsRef<Path>>(
     total_stale: stale_branches.len(),
     ..Default::default()
   };
+  let hooks = HookRunner::for_repo(repo_path.as_ref())?;
 
   for (index, branch_info) in stale_branches.into_iter().enumerate() {
     // Enhance branch info with novel commits and external data
@@ -561,6 +563,15 @@ fn interactive_prune_branches<P: AsRef<Path>>(
 
     // Prompt user for deletion
     if prompt_for_deletion(&enhanced_info.name)? {
+      let metadata = repo_state.get_branch_metadata(&enhanced_info.name);
+      hooks.run(HookPayload::PrePrune {
+        branches: vec![HookBranch {
+          branch: enhanced_info.name.clone(),
+          jira_issue: metadata.and_then(|m| m.jira_issue.clone()),
+          github_pr: metadata.and_then(|m| m.github_pr),
+        }],
+      })?;
+
       match delete_local_branch(repo, &enhanced_info.name) {
         Ok(()) => {
           summary.deleted.push(enhanced_info.name.clone());
@@ -9,11 +9,13 @@ pub mod cli;
 pub mod complete;
 pub mod completion;
 pub mod consts;
+pub mod debug_log;
 pub mod diagnostics;
 pub mod fixup;
 pub mod git;
 pub mod plugin;
 pub mod self_update;
+pub mod update_check;
 pub mod user_defined_dependency_resolver;
 pub mod utils;
 
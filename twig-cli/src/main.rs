@@ -3,14 +3,18 @@
 //! The main entry point for the twig command-line tool, a Git-based developer
 //! productivity tool for managing branch dependencies and workflows.
 
+use std::sync::Mutex;
+
 use anyhow::Result;
-use clap::{CommandFactory, Parser};
+use clap::Parser;
 use clap_complete::CompleteEnv;
 use human_panic::{Metadata, setup_panic};
 use tracing::debug;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{EnvFilter, fmt};
 use twig_cli::cli::{self, handle_cli};
+use twig_cli::debug_log;
+use twig_core::get_config_dirs;
 
 fn main() -> Result<()> {
   setup_panic!(
@@ -21,10 +25,22 @@ fn main() -> Result<()> {
   );
 
   // Handle shell completion via CompleteEnv (activated by COMPLETE=<shell> env var)
-  CompleteEnv::with_factory(cli::Cli::command).complete();
+  CompleteEnv::with_factory(cli::command_with_plugins).complete();
+
+  // Top-level help lists installed plugins alongside the built-in commands
+  let args: Vec<_> = std::env::args_os().collect();
+  if let Some(long) = cli::top_level_help_requested(&args) {
+    let mut command = cli::command_with_plugins();
+    if long {
+      command.print_long_help()?;
+    } else {
+      command.print_help()?;
+    }
+    return Ok(());
+  }
 
   // Parse CLI arguments using the derive-based implementation
-  let cmd = cli::Cli::parse();
+  let cmd = cli::Cli::parse_from(args);
 
   // Set up tracing based on verbosity level
   let verbose_count = cmd.verbose;
@@ -35,10 +51,20 @@ fn main() -> Result<()> {
     _ => tracing::Level::TRACE, // -vvv or more: trace and everything else
   };
 
+  // Debug output is also appended to a log file for diagnostic bundles
+  let debug_log = get_config_dirs()
+    .ok()
+    .and_then(|config_dirs| debug_log::open(&config_dirs).ok());
+
   // Initialize the tracing subscriber with the specified level
   tracing_subscriber::registry()
-    .with(fmt::layer())
-    .with(EnvFilter::from_default_env().add_directive(level.into()))
+    .with(fmt::layer().with_filter(EnvFilter::from_default_env().add_directive(level.into())))
+    .with(debug_log.map(|file| {
+      fmt::layer()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_filter(debug_log::filter())
+    }))
     .init();
 
   debug!("Tracing initialized with level: {}", level);
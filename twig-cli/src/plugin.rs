@@ -2,19 +2,37 @@
 //!
 //! Implements the plugin discovery system that allows twig to execute external
 //! plugins following the kubectl/Docker-inspired plugin model.
+//!
+//! Plugins may describe themselves with a manifest (see
+//! [`twig_core::plugin::manifest`]), which twig uses to list them with a
+//! description, warn about incompatible `twig-core` versions, and merge their
+//! subcommands into `twig --help` and shell completions.
+//!
+//! While a plugin runs, twig serves the host API (see [`host`]) so the plugin
+//! can read the branch graph, mutate repository state and make authenticated
+//! API requests through twig instead of reimplementing them.
+//!
+//! Plugins are discovered on `PATH` and in the twig-managed plugin directory
+//! that `twig plugin install` installs into (see [`install`]).
 
-mod platform;
+mod host;
+pub mod install;
+mod manifest;
 
 use std::collections::BTreeMap;
+use std::env;
 use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::{env, fs};
 
 use anyhow::{Context, Result};
-use platform::{candidate_filenames, is_executable};
+use host::PluginHost;
+use manifest::ManifestLoader;
 use tracing::{debug, instrument};
-use twig_core::output::ColorMode;
+use twig_core::output::{ColorMode, print_warning};
+use twig_core::plugin::discovery::{is_executable, plugin_search_path, resolve_plugin_path};
+use twig_core::plugin::host::{HOST_ADDR_ENV, HOST_TOKEN_ENV};
+use twig_core::plugin::manifest::{Compatibility, PluginManifest, TWIG_CORE_VERSION};
 
 /// Execute a plugin with the given name and arguments
 #[instrument(level = "debug", skip(verbosity, colors, no_links))]
@@ -27,7 +45,7 @@ pub fn execute_plugin(
 ) -> Result<()> {
   let plugin_binary = format!("twig-{plugin_name}");
 
-  let plugin_path = resolve_plugin_path(&plugin_binary)?.ok_or_else(|| {
+  let plugin_path = resolve_plugin_path(&plugin_binary).ok_or_else(|| {
     anyhow::anyhow!(
       "Unknown command '{plugin_name}'. No plugin 'twig-{plugin_name}' found in PATH.\n\n\
              To install plugins, place executable files named 'twig-<command>' in your PATH."
@@ -36,6 +54,14 @@ pub fn execute_plugin(
 
   debug!("Executing plugin '{}' at {}", plugin_binary, plugin_path.display());
 
+  let mut loader = ManifestLoader::from_config_dirs();
+  match loader.load(&plugin_path) {
+    Ok(Some(manifest)) => warn_if_incompatible(&plugin_binary, &manifest),
+    Ok(None) => {}
+    Err(e) => debug!("Could not load manifest for '{plugin_binary}': {e:#}"),
+  }
+  loader.save();
+
   // Set up environment variables
   let config_dirs = twig_core::get_config_dirs()?;
   let current_repo = twig_core::detect_repository();
@@ -54,14 +80,25 @@ pub fn execute_plugin(
     .stdout(Stdio::inherit())
     .stderr(Stdio::inherit());
 
-  if let Some(repo) = current_repo {
+  if let Some(repo) = &current_repo {
     cmd.env("TWIG_CURRENT_REPO", repo.display().to_string());
   }
 
-  if let Some(branch) = current_branch {
+  if let Some(branch) = &current_branch {
     cmd.env("TWIG_CURRENT_BRANCH", branch);
   }
 
+  let host = match PluginHost::start(config_dirs.clone(), current_repo, current_branch) {
+    Ok(host) => {
+      cmd.env(HOST_ADDR_ENV, host.addr()).env(HOST_TOKEN_ENV, host.token());
+      Some(host)
+    }
+    Err(e) => {
+      debug!("Could not start the plugin host API: {e:#}");
+      None
+    }
+  };
+
   let status = cmd
     .status()
     .with_context(|| format!("Failed to execute plugin '{plugin_binary}'"))?;
@@ -70,13 +107,29 @@ pub fn execute_plugin(
     status.code().unwrap_or(-1)
   );
 
+  // `process::exit` skips destructors, so clean up the host socket first
+  drop(host);
   std::process::exit(status.code().unwrap_or(1));
 }
 
 /// Determine if a plugin binary is available in the current PATH
 pub fn plugin_is_available(plugin_name: &str) -> Result<bool> {
   let plugin_binary = format!("twig-{plugin_name}");
-  Ok(resolve_plugin_path(&plugin_binary)?.is_some())
+  Ok(resolve_plugin_path(&plugin_binary).is_some())
+}
+
+/// Warn when a plugin's manifest rules out the running `twig-core` version
+fn warn_if_incompatible(plugin_binary: &str, manifest: &PluginManifest) {
+  match manifest.compatibility() {
+    Compatibility::Compatible => {}
+    Compatibility::Incompatible { required } => print_warning(&format!(
+      "Plugin '{plugin_binary}' requires twig-core {required}, but this twig uses {TWIG_CORE_VERSION}. It may not work \
+       correctly; consider updating it."
+    )),
+    Compatibility::Invalid { required, error } => print_warning(&format!(
+      "Plugin '{plugin_binary}' declares an invalid twig-core requirement '{required}': {error}"
+    )),
+  }
 }
 
 fn color_mode_env(mode: ColorMode) -> &'static str {
@@ -96,18 +149,103 @@ pub struct PluginInfo {
   pub paths: Vec<PathBuf>,
   /// File size in bytes for the primary plugin location, if available.
   pub size_in_bytes: Option<u64>,
+  /// Manifest of the primary plugin location, if the plugin provides one.
+  pub manifest: Option<PluginManifest>,
+  /// Why the manifest could not be loaded, e.g. an invalid sidecar file.
+  pub manifest_error: Option<String>,
 }
 
-/// List available plugins in PATH with basic metadata.
+/// List available plugins in PATH with their metadata and manifests,
+/// running plugins whose manifest is not cached yet to query it.
 pub fn list_available_plugins() -> Result<Vec<PluginInfo>> {
-  let path_var = env::var("PATH").unwrap_or_default();
-  list_available_plugins_from_path(&path_var)
+  let mut plugins = list_available_plugins_from_path(plugin_search_path())?;
+
+  let mut loader = ManifestLoader::from_config_dirs();
+  for plugin in &mut plugins {
+    let Some(primary) = plugin.paths.first() else {
+      continue;
+    };
+    match loader.load(primary) {
+      Ok(manifest) => plugin.manifest = manifest,
+      Err(e) => plugin.manifest_error = Some(format!("{e:#}")),
+    }
+  }
+  loader.save();
+
+  Ok(plugins)
 }
 
-fn list_available_plugins_from_path(path_var: &str) -> Result<Vec<PluginInfo>> {
+/// List available plugins like [`list_available_plugins`], but only with
+/// manifests from sidecar files or the cache. No plugin binary is executed.
+pub fn list_cached_plugins() -> Result<Vec<PluginInfo>> {
+  let mut plugins = list_available_plugins_from_path(plugin_search_path())?;
+
+  let loader = ManifestLoader::from_config_dirs();
+  for plugin in &mut plugins {
+    let Some(primary) = plugin.paths.first() else {
+      continue;
+    };
+    match loader.load_cached(primary) {
+      Ok(manifest) => plugin.manifest = manifest,
+      Err(e) => plugin.manifest_error = Some(format!("{e:#}")),
+    }
+  }
+
+  Ok(plugins)
+}
+
+/// Query and cache the manifest of the plugin binary at `plugin_path`, e.g.
+/// after installing it.
+pub(crate) fn refresh_manifest(plugin_path: &Path) {
+  let mut loader = ManifestLoader::from_config_dirs();
+  if let Err(e) = loader.load(plugin_path) {
+    debug!("Could not load manifest for {}: {e:#}", plugin_path.display());
+  }
+  loader.save();
+}
+
+/// Build clap commands for installed plugins so they appear in `twig --help`
+/// and shell completions. Plugins shadowed by a built-in command are skipped.
+///
+/// Only cached or sidecar manifests are used, so help and completions never
+/// run plugin binaries; `twig self plugins` refreshes the cache.
+pub fn plugin_commands(builtin: &clap::Command) -> Vec<clap::Command> {
+  let plugins = match list_cached_plugins() {
+    Ok(plugins) => plugins,
+    Err(e) => {
+      debug!("Could not list plugins: {e:#}");
+      return Vec::new();
+    }
+  };
+
+  plugins
+    .into_iter()
+    .filter(|plugin| builtin.find_subcommand(&plugin.name).is_none())
+    .map(|plugin| {
+      let about = plugin
+        .manifest
+        .as_ref()
+        .and_then(|manifest| manifest.description.clone())
+        .unwrap_or_else(|| format!("Run the twig-{} plugin", plugin.name));
+      let subcommands = plugin
+        .manifest
+        .iter()
+        .flat_map(|manifest| &manifest.subcommands)
+        .map(|subcommand| {
+          clap::Command::new(subcommand.name.clone()).about(subcommand.about.clone().unwrap_or_default())
+        });
+      clap::Command::new(plugin.name)
+        .about(format!("{about} [plugin]"))
+        .subcommands(subcommands)
+        .allow_external_subcommands(true)
+    })
+    .collect()
+}
+
+fn list_available_plugins_from_path(path_var: impl AsRef<OsStr>) -> Result<Vec<PluginInfo>> {
   let mut plugins: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
 
-  for path in env::split_paths(path_var) {
+  for path in env::split_paths(&path_var) {
     if !path.exists() {
       continue;
     }
@@ -155,46 +293,14 @@ fn list_available_plugins_from_path(path_var: &str) -> Result<Vec<PluginInfo>> {
       name,
       paths,
       size_in_bytes,
+      manifest: None,
+      manifest_error: None,
     });
   }
 
   Ok(plugin_info)
 }
 
-/// Resolve a plugin binary path in PATH order, returning a canonical path when
-/// found.
-fn resolve_plugin_path(plugin_name: &str) -> Result<Option<PathBuf>> {
-  let path_var = env::var_os("PATH");
-  let Some(path_var) = path_var.as_deref() else {
-    return Ok(None);
-  };
-
-  resolve_plugin_path_from_paths(plugin_name, path_var)
-}
-
-fn resolve_plugin_path_from_paths(plugin_name: &str, path_var: &OsStr) -> Result<Option<PathBuf>> {
-  let candidate_names = candidate_filenames(plugin_name);
-
-  for directory in env::split_paths(path_var) {
-    if directory.as_os_str().is_empty() || !directory.exists() {
-      continue;
-    }
-
-    for candidate in &candidate_names {
-      let candidate_path = directory.join(candidate);
-      if is_executable(&candidate_path) {
-        return Ok(Some(canonicalize_or_original(candidate_path)));
-      }
-    }
-  }
-
-  Ok(None)
-}
-
-fn canonicalize_or_original(path: PathBuf) -> PathBuf {
-  fs::canonicalize(&path).unwrap_or(path)
-}
-
 #[cfg(test)]
 mod tests {
   use std::fs;
@@ -251,45 +357,6 @@ mod tests {
     );
   }
 
-  #[test]
-  fn resolve_plugin_path_skips_non_files_and_respects_path_order() {
-    let first_dir = TempDir::new().expect("failed to create temp dir");
-    let second_dir = TempDir::new().expect("failed to create temp dir");
-
-    let non_file = first_dir.path().join("twig-example");
-    fs::create_dir_all(&non_file).expect("failed to create placeholder directory");
-
-    let real_plugin = second_dir.path().join("twig-example");
-    fs::write(&real_plugin, b"#!/bin/sh\necho real\n").unwrap();
-    make_executable(&real_plugin);
-
-    let custom_path = std::env::join_paths([first_dir.path(), second_dir.path()]).unwrap();
-
-    let resolved = resolve_plugin_path_from_paths("twig-example", &custom_path).expect("lookup should succeed");
-
-    assert_eq!(resolved, Some(fs::canonicalize(&real_plugin).unwrap()));
-  }
-
-  #[cfg(unix)]
-  #[test]
-  fn resolve_plugin_path_canonicalizes_symlinks() {
-    use std::os::unix::fs as unix_fs;
-
-    let target_dir = TempDir::new().expect("failed to create temp dir");
-    let binary = target_dir.path().join("twig-example");
-    fs::write(&binary, b"#!/bin/sh\necho linked\n").unwrap();
-    make_executable(&binary);
-
-    let symlink_dir = target_dir.path().join("symlink");
-    unix_fs::symlink(target_dir.path(), &symlink_dir).expect("failed to create symlink");
-
-    let custom_path = std::env::join_paths([symlink_dir]).unwrap();
-
-    let resolved = resolve_plugin_path_from_paths("twig-example", &custom_path).expect("lookup should succeed");
-
-    assert_eq!(resolved, Some(fs::canonicalize(&binary).unwrap()));
-  }
-
   #[cfg_attr(windows, allow(unused))]
   fn make_executable(path: &Path) {
     #[cfg(unix)]
@@ -0,0 +1,407 @@
+//! Host side of the plugin host API.
+//!
+//! [`PluginHost`] serves the JSON-RPC protocol defined in
+//! [`twig_core::plugin::host`] while a plugin runs. Each connection is
+//! handled on its own thread; state mutations are serialized through a single
+//! lock so concurrent plugin requests cannot clobber each other's writes.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+use git2::BranchType;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::debug;
+use twig_core::github::GITHUB_COM_HOST;
+use twig_core::plugin::host::{
+  ApiRequest, ApiResponse, BranchGraph, CONTEXT_GET, GITHUB_REQUEST, GRAPH_GET, HOST_PROTOCOL_VERSION, HostContext,
+  INITIALIZE, InitializeParams, InitializeResult, JIRA_REQUEST, METHODS, RpcError, RpcRequest, RpcResponse,
+  STATE_APPLY, STATE_GET, StateApplyParams, error_codes,
+};
+use twig_core::state::RepoState;
+use twig_core::{ConfigDirs, resolve_origin_github_repo};
+
+/// A running host API server. The listener is shut down with the process;
+/// dropping the host removes its socket.
+pub(super) struct PluginHost {
+  addr: String,
+  token: String,
+  #[cfg_attr(not(unix), allow(dead_code))]
+  socket_dir: Option<PathBuf>,
+}
+
+impl PluginHost {
+  /// Start serving the host API for a plugin invoked in `repo` on `branch`
+  pub(super) fn start(config_dirs: ConfigDirs, repo: Option<PathBuf>, branch: Option<String>) -> Result<Self> {
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let handler = Arc::new(HostHandler {
+      token: token.clone(),
+      config_dirs,
+      repo,
+      branch,
+    });
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+      use std::os::unix::net::UnixListener;
+
+      let socket_dir = std::env::temp_dir().join(format!("twig-host-{}", uuid::Uuid::new_v4().simple()));
+      std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&socket_dir)
+        .with_context(|| format!("Failed to create {}", socket_dir.display()))?;
+      let socket_path = socket_dir.join("host.sock");
+      let listener =
+        UnixListener::bind(&socket_path).with_context(|| format!("Failed to listen on {}", socket_path.display()))?;
+      std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))?;
+
+      thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+          let handler = Arc::clone(&handler);
+          thread::spawn(move || {
+            if let Ok(reader) = stream.try_clone() {
+              handler.serve(BufReader::new(reader), stream);
+            }
+          });
+        }
+      });
+
+      Ok(Self {
+        addr: format!("unix:{}", socket_path.display()),
+        token,
+        socket_dir: Some(socket_dir),
+      })
+    }
+
+    #[cfg(not(unix))]
+    {
+      let listener = std::net::TcpListener::bind("127.0.0.1:0").context("Failed to listen on loopback")?;
+      let addr = listener.local_addr()?;
+
+      thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+          let handler = Arc::clone(&handler);
+          thread::spawn(move || {
+            if let Ok(reader) = stream.try_clone() {
+              handler.serve(BufReader::new(reader), stream);
+            }
+          });
+        }
+      });
+
+      Ok(Self {
+        addr: format!("tcp:{addr}"),
+        token,
+        socket_dir: None,
+      })
+    }
+  }
+
+  /// Address to pass in `TWIG_HOST_ADDR`
+  pub(super) fn addr(&self) -> &str {
+    &self.addr
+  }
+
+  /// Token to pass in `TWIG_HOST_TOKEN`
+  pub(super) fn token(&self) -> &str {
+    &self.token
+  }
+}
+
+impl Drop for PluginHost {
+  fn drop(&mut self) {
+    if let Some(dir) = &self.socket_dir {
+      let _ = std::fs::remove_dir_all(dir);
+    }
+  }
+}
+
+/// Answers host API requests
+struct HostHandler {
+  token: String,
+  config_dirs: ConfigDirs,
+  repo: Option<PathBuf>,
+  branch: Option<String>,
+}
+
+impl HostHandler {
+  /// Serve one connection until the plugin closes it
+  fn serve(&self, reader: impl BufRead, mut writer: impl Write) {
+    let mut initialized = false;
+
+    for line in reader.lines() {
+      let Ok(line) = line else {
+        break;
+      };
+      if line.trim().is_empty() {
+        continue;
+      }
+
+      let response = match serde_json::from_str::<RpcRequest>(&line) {
+        Ok(request) => self.respond(request, &mut initialized),
+        Err(e) => RpcResponse::failure(Value::Null, error_codes::PARSE_ERROR, format!("Invalid request: {e}")),
+      };
+
+      let Ok(mut json) = serde_json::to_string(&response) else {
+        break;
+      };
+      json.push('\n');
+      if writer.write_all(json.as_bytes()).and_then(|()| writer.flush()).is_err() {
+        break;
+      }
+    }
+  }
+
+  fn respond(&self, request: RpcRequest, initialized: &mut bool) -> RpcResponse {
+    if request.jsonrpc != "2.0" {
+      return RpcResponse::failure(request.id, error_codes::INVALID_REQUEST, "Expected jsonrpc \"2.0\"");
+    }
+
+    debug!("Plugin host request: {}", request.method);
+    let result = if request.method == INITIALIZE {
+      self.initialize(request.params).inspect(|_| *initialized = true)
+    } else if !*initialized {
+      Err(rpc_error(
+        error_codes::NOT_INITIALIZED,
+        format!("Call '{INITIALIZE}' before '{}'", request.method),
+      ))
+    } else {
+      self.dispatch(&request.method, request.params)
+    };
+
+    match result {
+      Ok(result) => RpcResponse::success(request.id, result),
+      Err(error) => RpcResponse::failure(request.id, error.code, error.message),
+    }
+  }
+
+  fn initialize(&self, params: Value) -> Result<Value, RpcError> {
+    let params: InitializeParams = parse_params(params)?;
+    if params.token != self.token {
+      return Err(rpc_error(error_codes::NOT_INITIALIZED, "Invalid host token"));
+    }
+    if params.protocol_version != HOST_PROTOCOL_VERSION {
+      return Err(rpc_error(
+        error_codes::UNSUPPORTED_VERSION,
+        format!(
+          "Protocol version {} is not supported; this twig speaks version {HOST_PROTOCOL_VERSION}",
+          params.protocol_version
+        ),
+      ));
+    }
+
+    to_result(InitializeResult {
+      protocol_version: HOST_PROTOCOL_VERSION,
+      twig_version: env!("CARGO_PKG_VERSION").to_string(),
+      methods: METHODS.iter().map(|m| m.to_string()).collect(),
+    })
+  }
+
+  fn dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+    match method {
+      CONTEXT_GET => to_result(HostContext {
+        repo: self.repo.clone(),
+        branch: self.branch.clone(),
+        config_dir: self.config_dirs.config_dir().clone(),
+        data_dir: self.config_dirs.data_dir().clone(),
+        twig_version: env!("CARGO_PKG_VERSION").to_string(),
+      }),
+      GRAPH_GET => {
+        let repo_path = self.repo_path()?;
+        let repo = git2::Repository::open(repo_path).map_err(internal_error)?;
+        let local_branches: Vec<String> = repo
+          .branches(Some(BranchType::Local))
+          .map_err(internal_error)?
+          .flatten()
+          .filter_map(|(branch, _)| branch.name().ok().flatten().map(str::to_string))
+          .collect();
+        let state = RepoState::load(repo_path).map_err(internal_error)?;
+        to_result(BranchGraph::from_state(&local_branches, &state))
+      }
+      STATE_GET => to_result(RepoState::load(self.repo_path()?).map_err(internal_error)?),
+      STATE_APPLY => {
+        let params: StateApplyParams = parse_params(params)?;
+        let repo_path = self.repo_path()?;
+        let _lock = RepoState::lock(repo_path).map_err(internal_error)?;
+
+        let mut state = RepoState::load(repo_path).map_err(internal_error)?;
+        for mutation in &params.mutations {
+          mutation
+            .apply(&mut state)
+            .map_err(|e| rpc_error(error_codes::OPERATION_FAILED, format!("{e:#}")))?;
+        }
+        state.save(repo_path).map_err(internal_error)?;
+        to_result(state)
+      }
+      GITHUB_REQUEST => {
+        let request: ApiRequest = parse_params(params)?;
+        let host = match &request.host {
+          Some(host) => host.clone(),
+          None => self.origin_github_host(),
+        };
+        let github_config = self.config_dirs.load_github_config().map_err(internal_error)?;
+        if !github_config.is_github_host(&host) {
+          return Err(rpc_error(
+            error_codes::INVALID_PARAMS,
+            format!("'{host}' is not github.com or a GitHub host configured in github.toml"),
+          ));
+        }
+        let home = home_dir()?;
+        let client = twig_gh::create_github_client_for_host(&home, &host).map_err(internal_error)?;
+        let (status, body) = block_on(client.send_raw(&request.method, &request.path, request.body.as_ref()))?;
+        to_result(api_response(status, body))
+      }
+      JIRA_REQUEST => {
+        let request: ApiRequest = parse_params(params)?;
+        let home = home_dir()?;
+        let jira_host = twig_jira::get_jira_host().map_err(internal_error)?;
+        let client = twig_jira::create_jira_client_from_netrc(&home, &jira_host).map_err(internal_error)?;
+        let (status, body) = block_on(client.send_raw(&request.method, &request.path, request.body.as_ref()))?;
+        to_result(api_response(status, body))
+      }
+      _ => Err(rpc_error(
+        error_codes::METHOD_NOT_FOUND,
+        format!("Unknown method '{method}'"),
+      )),
+    }
+  }
+
+  fn repo_path(&self) -> Result<&Path, RpcError> {
+    self.repo.as_deref().ok_or_else(|| {
+      rpc_error(
+        error_codes::OPERATION_FAILED,
+        "The plugin was not started inside a git repository",
+      )
+    })
+  }
+
+  /// GitHub host of the `origin` remote, or github.com
+  fn origin_github_host(&self) -> String {
+    self
+      .repo
+      .as_deref()
+      .and_then(|path| git2::Repository::open(path).ok())
+      .and_then(|repo| resolve_origin_github_repo(&repo).ok())
+      .map_or_else(|| GITHUB_COM_HOST.to_string(), |repo| repo.host)
+  }
+}
+
+fn api_response(status: u16, body: String) -> ApiResponse {
+  let body = serde_json::from_str(&body).unwrap_or(Value::String(body));
+  ApiResponse { status, body }
+}
+
+fn block_on<T>(future: impl std::future::Future<Output = Result<T>>) -> Result<T, RpcError> {
+  tokio::runtime::Builder::new_current_thread()
+    .enable_all()
+    .build()
+    .map_err(internal_error)?
+    .block_on(future)
+    .map_err(|e| rpc_error(error_codes::OPERATION_FAILED, format!("{e:#}")))
+}
+
+fn home_dir() -> Result<PathBuf, RpcError> {
+  BaseDirs::new()
+    .map(|dirs| dirs.home_dir().to_path_buf())
+    .ok_or_else(|| rpc_error(error_codes::INTERNAL_ERROR, "Failed to determine home directory"))
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+  serde_json::from_value(params).map_err(|e| rpc_error(error_codes::INVALID_PARAMS, format!("Invalid params: {e}")))
+}
+
+fn to_result(value: impl Serialize) -> Result<Value, RpcError> {
+  serde_json::to_value(value).map_err(|e| rpc_error(error_codes::INTERNAL_ERROR, e.to_string()))
+}
+
+fn internal_error(error: impl std::fmt::Display) -> RpcError {
+  rpc_error(error_codes::INTERNAL_ERROR, format!("{error:#}"))
+}
+
+fn rpc_error(code: i64, message: impl Into<String>) -> RpcError {
+  RpcError {
+    code,
+    message: message.into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use twig_core::plugin::host::{HostClient, StateMutation};
+  use twig_test_utils::{GitRepoTestGuard, create_commit};
+
+  use super::*;
+
+  #[test]
+  fn host_serves_graph_and_state_mutations() {
+    let repo_guard = GitRepoTestGuard::new();
+    create_commit(&repo_guard.repo, "file.txt", "content", "initial").unwrap();
+    let repo_path = repo_guard.path().to_path_buf();
+
+    let config_dirs = ConfigDirs {
+      config_dir: repo_path.join("config"),
+      data_dir: repo_path.join("data"),
+      cache_dir: None,
+    };
+    let host = PluginHost::start(config_dirs, Some(repo_path.clone()), Some("main".to_string())).unwrap();
+
+    assert!(HostClient::connect(host.addr(), "wrong-token").is_err());
+
+    let mut client = HostClient::connect(host.addr(), host.token()).unwrap();
+    assert_eq!(client.info().protocol_version, HOST_PROTOCOL_VERSION);
+    assert!(client.supports(STATE_APPLY));
+    assert_eq!(client.context().unwrap().branch.as_deref(), Some("main"));
+
+    let state = client
+      .apply_state(vec![StateMutation::AddDependency {
+        child: "feature".to_string(),
+        parent: "main".to_string(),
+      }])
+      .unwrap();
+    assert_eq!(state.get_dependency_parents("feature"), vec!["main"]);
+    assert_eq!(
+      RepoState::load(&repo_path).unwrap().get_dependency_parents("feature"),
+      vec!["main"]
+    );
+
+    // A failing mutation leaves the saved state untouched
+    let failing = client.apply_state(vec![
+      StateMutation::RemoveDependency {
+        child: "feature".to_string(),
+        parent: "main".to_string(),
+      },
+      StateMutation::RemoveDependency {
+        child: "feature".to_string(),
+        parent: "main".to_string(),
+      },
+    ]);
+    assert!(failing.is_err());
+    assert_eq!(client.state().unwrap().get_dependency_parents("feature"), vec!["main"]);
+
+    let graph = client.branch_graph().unwrap();
+    let main = graph.branches.iter().find(|b| b.name == "main").unwrap();
+    assert_eq!(main.children, ["feature"]);
+
+    let unknown: Result<Value> = client.call("nope", Value::Null);
+    assert!(unknown.is_err());
+
+    // GitHub credentials are only sent to github.com and configured hosts
+    let foreign: Result<ApiResponse> = client.call(
+      GITHUB_REQUEST,
+      ApiRequest {
+        method: "GET".to_string(),
+        path: "/user".to_string(),
+        body: None,
+        host: Some("collector.example.com".to_string()),
+      },
+    );
+    let err = format!("{:#}", foreign.unwrap_err());
+    assert!(err.contains("'collector.example.com' is not github.com"), "{err}");
+  }
+}
@@ -0,0 +1,441 @@
+//! Installing third-party plugins with `twig plugin install`.
+//!
+//! Plugins are looked up in a plugin index, a JSON file listing each plugin's
+//! name and the GitHub repository whose releases publish it:
+//!
+//! ```json
+//! {
+//!   "plugins": [
+//!     {
+//!       "name": "standup",
+//!       "description": "Post stand-up notes from your branch stack",
+//!       "repo": "acme/twig-standup",
+//!       "asset": "twig-standup",
+//!       "checksums": "SHA256SUMS"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Release assets follow Twig's own naming convention
+//! (`<asset>-<os>-<arch>[-<version>].tar.gz`, `.zip` on Windows) and must be
+//! accompanied by a SHA-256 checksum. Installed binaries go into the
+//! twig-managed plugin directory and are recorded so they can be listed,
+//! upgraded and removed later.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use twig_core::github::{GITHUB_COM_HOST, GitHubRepo};
+use twig_core::output::{print_info, print_success, print_warning};
+use twig_core::plugin::discovery::resolve_plugin_path;
+use twig_core::{ConfigDirs, get_config_dirs};
+
+use crate::self_update::{
+  InstallOutcome, ReleaseSource, Verification, build_http_client, install_release_asset, target_config,
+};
+
+/// Index used when neither `--index` nor [`INDEX_ENV`] is set
+pub const DEFAULT_INDEX_URL: &str = "https://raw.githubusercontent.com/eddieland/twig/main/plugins/index.json";
+
+/// Environment variable overriding the plugin index location
+pub const INDEX_ENV: &str = "TWIG_PLUGIN_INDEX";
+
+/// A plugin index listing installable plugins
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginIndex {
+  #[serde(default)]
+  pub plugins: Vec<IndexEntry>,
+}
+
+/// Where and how to download a plugin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+  /// Plugin name, invoked as `twig <name>`
+  pub name: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  /// GitHub repository publishing the releases, as `owner/name`
+  pub repo: String,
+  /// Product name used in release asset filenames (defaults to the binary)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub asset: Option<String>,
+  /// Combined checksum asset such as `SHA256SUMS` (defaults to a
+  /// `<asset>.sha256` file next to each archive)
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub checksums: Option<String>,
+}
+
+impl IndexEntry {
+  /// Name of the installed executable, `twig-<name>`
+  pub fn binary(&self) -> String {
+    format!("twig-{}", self.name)
+  }
+}
+
+impl PluginIndex {
+  /// Load the index from a URL or a local file
+  pub fn load(location: &str) -> Result<Self> {
+    let content = if location.starts_with("https://") || location.starts_with("http://") {
+      build_http_client()?
+        .get(location)
+        .send()
+        .with_context(|| format!("Failed to download plugin index {location}"))?
+        .error_for_status()
+        .with_context(|| format!("Failed to download plugin index {location}"))?
+        .text()
+        .with_context(|| format!("Failed to read plugin index {location}"))?
+    } else {
+      fs::read_to_string(location).with_context(|| format!("Failed to read plugin index {location}"))?
+    };
+
+    Self::parse(&content).with_context(|| format!("Invalid plugin index {location}"))
+  }
+
+  /// Parse and validate index JSON
+  pub fn parse(content: &str) -> Result<Self> {
+    let index: Self = serde_json::from_str(content)?;
+    for entry in &index.plugins {
+      validate_name(&entry.name)?;
+    }
+    Ok(index)
+  }
+
+  /// Find a plugin by name, with or without the `twig-` prefix
+  pub fn find(&self, name: &str) -> Option<&IndexEntry> {
+    let name = name.strip_prefix("twig-").unwrap_or(name);
+    self.plugins.iter().find(|entry| entry.name == name)
+  }
+}
+
+/// The index location from `--index`, [`INDEX_ENV`], or the default
+pub fn index_location(override_location: Option<&str>) -> String {
+  override_location
+    .map(str::to_string)
+    .or_else(|| std::env::var(INDEX_ENV).ok().filter(|value| !value.is_empty()))
+    .unwrap_or_else(|| DEFAULT_INDEX_URL.to_string())
+}
+
+/// Plugins installed by `twig plugin install`, keyed by name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstalledPlugins {
+  #[serde(default)]
+  pub plugins: BTreeMap<String, InstalledPlugin>,
+}
+
+/// A plugin installed by `twig plugin install`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+  #[serde(flatten)]
+  pub entry: IndexEntry,
+  /// Release version, without a leading `v`
+  pub version: String,
+  pub installed_at: String,
+}
+
+impl InstalledPlugins {
+  /// Load the record at `path`, or an empty record if it does not exist
+  pub fn load(path: &Path) -> Result<Self> {
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+  }
+
+  /// Save the record to `path`
+  pub fn save(&self, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let content = serde_json::to_string_pretty(self).context("Failed to serialize installed plugins")?;
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+  }
+}
+
+/// Resolve an install argument to an index entry.
+///
+/// GitHub repository URLs and `owner/repo` shorthands are installed directly
+/// from that repository's releases; anything else is looked up by name in the
+/// index returned by `load_index`.
+pub fn resolve_install_spec(spec: &str, load_index: impl FnOnce() -> Result<PluginIndex>) -> Result<IndexEntry> {
+  if let Some(repo) = parse_repo_spec(spec)? {
+    let name = repo.repo.strip_prefix("twig-").unwrap_or(&repo.repo).to_string();
+    validate_name(&name)?;
+    return Ok(IndexEntry {
+      name,
+      description: None,
+      repo: repo.full_name(),
+      asset: None,
+      checksums: None,
+    });
+  }
+
+  let index = load_index()?;
+  index
+    .find(spec)
+    .cloned()
+    .ok_or_else(|| anyhow!("Plugin '{spec}' is not in the plugin index"))
+}
+
+/// Parse `spec` as a GitHub repository, returning `None` for plain names
+fn parse_repo_spec(spec: &str) -> Result<Option<GitHubRepo>> {
+  let repo = if spec.contains("://") || spec.starts_with("git@") {
+    GitHubRepo::parse(spec)?
+  } else if let Some((owner, repo)) = spec.split_once('/') {
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+      bail!("Expected a plugin name, a GitHub URL, or owner/repo, got '{spec}'");
+    }
+    GitHubRepo {
+      host: GITHUB_COM_HOST.to_string(),
+      owner: owner.to_string(),
+      repo: repo.trim_end_matches(".git").to_string(),
+    }
+  } else {
+    return Ok(None);
+  };
+
+  if !repo.is_github_com() {
+    bail!("Plugins can only be installed from {GITHUB_COM_HOST} releases");
+  }
+  Ok(Some(repo))
+}
+
+/// Plugin names become file names, so only allow a conservative character set
+fn validate_name(name: &str) -> Result<()> {
+  let valid = !name.is_empty()
+    && !name.starts_with('-')
+    && name
+      .chars()
+      .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_');
+  if valid {
+    Ok(())
+  } else {
+    Err(anyhow!(
+      "Invalid plugin name '{name}': use lowercase letters, digits, '-' and '_'"
+    ))
+  }
+}
+
+/// Download the latest release of `entry` into the twig-managed plugin
+/// directory. Skips the download when that version is already installed,
+/// unless `force` is set.
+pub fn install_plugin(entry: &IndexEntry, force: bool) -> Result<()> {
+  let config_dirs = get_config_dirs()?;
+  let source = ReleaseSource::from_env()?;
+  install_with(&source, &config_dirs, entry, force)
+}
+
+/// Upgrade the named installed plugins, or all of them when `names` is empty
+pub fn upgrade_plugins(names: &[String], force: bool) -> Result<()> {
+  let config_dirs = get_config_dirs()?;
+  let installed = InstalledPlugins::load(&config_dirs.installed_plugins_path())?;
+
+  let entries: Vec<IndexEntry> = if names.is_empty() {
+    installed.plugins.values().map(|plugin| plugin.entry.clone()).collect()
+  } else {
+    names
+      .iter()
+      .map(|name| {
+        let name = name.strip_prefix("twig-").unwrap_or(name);
+        installed
+          .plugins
+          .get(name)
+          .map(|plugin| plugin.entry.clone())
+          .ok_or_else(|| anyhow!("Plugin '{name}' was not installed with 'twig plugin install'"))
+      })
+      .collect::<Result<_>>()?
+  };
+
+  if entries.is_empty() {
+    print_info("No plugins are installed with 'twig plugin install'.");
+    return Ok(());
+  }
+
+  let source = ReleaseSource::from_env()?;
+  let mut failed = 0;
+  for entry in &entries {
+    if let Err(e) = install_with(&source, &config_dirs, entry, force) {
+      print_warning(&format!("Failed to upgrade twig-{}: {e:#}", entry.name));
+      failed += 1;
+    }
+  }
+
+  if failed > 0 {
+    bail!("{failed} of {} plugin(s) failed to upgrade", entries.len());
+  }
+  Ok(())
+}
+
+/// Remove an installed plugin's binary and record
+pub fn remove_plugin(name: &str) -> Result<()> {
+  let name = name.strip_prefix("twig-").unwrap_or(name);
+  let config_dirs = get_config_dirs()?;
+  let records_path = config_dirs.installed_plugins_path();
+  let mut installed = InstalledPlugins::load(&records_path)?;
+
+  let Some(plugin) = installed.plugins.remove(name) else {
+    bail!("Plugin '{name}' was not installed with 'twig plugin install'");
+  };
+
+  let binary_path = config_dirs
+    .plugin_bin_dir()
+    .join(target_config(&plugin.entry.binary())?.binary_name());
+  if binary_path.exists() {
+    fs::remove_file(&binary_path).with_context(|| format!("Failed to remove {}", binary_path.display()))?;
+  }
+  installed.save(&records_path)?;
+
+  print_success(&format!("Removed twig-{name} {}.", plugin.version));
+  Ok(())
+}
+
+fn install_with(source: &ReleaseSource, config_dirs: &ConfigDirs, entry: &IndexEntry, force: bool) -> Result<()> {
+  let records_path = config_dirs.installed_plugins_path();
+  let mut installed = InstalledPlugins::load(&records_path)?;
+  let binary = entry.binary();
+
+  let release = source.latest(&entry.repo)?;
+  let version = release.clean_tag();
+
+  if !force
+    && installed
+      .plugins
+      .get(&entry.name)
+      .is_some_and(|plugin| plugin.version == version)
+  {
+    print_success(&format!("{binary} {version} is already installed."));
+    return Ok(());
+  }
+
+  let mut target = target_config(&binary)?;
+  if let Some(asset_name) = &entry.asset {
+    target = target.with_asset_name(asset_name);
+  }
+  let asset = release
+    .find_matching_asset(&target)
+    .ok_or_else(|| anyhow!("{} {version} has no release asset for this platform", entry.repo))?;
+
+  let bin_dir = config_dirs.plugin_bin_dir();
+  fs::create_dir_all(&bin_dir).with_context(|| format!("Failed to create {}", bin_dir.display()))?;
+  let install_path = bin_dir.join(target.binary_name());
+
+  print_info(&format!("Downloading {binary} {version} ({})…", asset.name));
+  let verification = Verification {
+    checksum_asset: entry.checksums.as_deref(),
+    public_key: None,
+  };
+  let outcome = install_release_asset(source, &release, asset, &target, verification, &install_path)?;
+
+  installed.plugins.insert(
+    entry.name.clone(),
+    InstalledPlugin {
+      entry: entry.clone(),
+      version: version.clone(),
+      installed_at: chrono::Utc::now().to_rfc3339(),
+    },
+  );
+  installed.save(&records_path)?;
+
+  match outcome {
+    InstallOutcome::Immediate => {
+      super::refresh_manifest(&install_path);
+      print_success(&format!(
+        "{binary} {version} is installed at {}.",
+        install_path.display()
+      ));
+    }
+    #[cfg(windows)]
+    InstallOutcome::Deferred { .. } => {
+      print_success(&format!(
+        "{binary} {version} is staged and will complete installation once Twig exits."
+      ));
+    }
+  }
+
+  if let Some(resolved) = resolve_plugin_path(&binary)
+    && fs::canonicalize(&install_path).is_ok_and(|path| path != resolved)
+  {
+    print_warning(&format!(
+      "{} on your PATH takes precedence over the installed plugin.",
+      resolved.display()
+    ));
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  const INDEX: &str = r#"{
+    "plugins": [
+      { "name": "flow", "repo": "eddieland/twig", "asset": "twig-flow" },
+      { "name": "standup", "description": "Stand-up notes", "repo": "acme/twig-standup", "checksums": "SHA256SUMS" }
+    ]
+  }"#;
+
+  #[test]
+  fn finds_index_entries_with_or_without_prefix() {
+    let index = PluginIndex::parse(INDEX).unwrap();
+
+    let standup = index.find("twig-standup").unwrap();
+    assert_eq!(standup.repo, "acme/twig-standup");
+    assert_eq!(standup.checksums.as_deref(), Some("SHA256SUMS"));
+    assert_eq!(index.find("flow").unwrap().binary(), "twig-flow");
+    assert!(index.find("missing").is_none());
+  }
+
+  #[test]
+  fn rejects_index_names_that_are_not_file_safe() {
+    let err = PluginIndex::parse(r#"{ "plugins": [{ "name": "../evil", "repo": "a/b" }] }"#).unwrap_err();
+    assert!(err.to_string().contains("Invalid plugin name"));
+  }
+
+  #[test]
+  fn resolves_repository_specs_without_the_index() {
+    let no_index = || -> Result<PluginIndex> { panic!("index should not be loaded") };
+
+    let from_url = resolve_install_spec("https://github.com/acme/twig-standup", no_index).unwrap();
+    assert_eq!(from_url.name, "standup");
+    assert_eq!(from_url.repo, "acme/twig-standup");
+
+    let from_shorthand = resolve_install_spec("acme/notes", no_index).unwrap();
+    assert_eq!(from_shorthand.name, "notes");
+    assert_eq!(from_shorthand.repo, "acme/notes");
+
+    let from_index = resolve_install_spec("standup", || PluginIndex::parse(INDEX)).unwrap();
+    assert_eq!(from_index.description.as_deref(), Some("Stand-up notes"));
+
+    assert!(resolve_install_spec("https://git.example.com/acme/twig-standup", no_index).is_err());
+    assert!(resolve_install_spec("unknown", || PluginIndex::parse(INDEX)).is_err());
+  }
+
+  #[test]
+  fn installed_plugins_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("installed-plugins.json");
+    assert!(InstalledPlugins::load(&path).unwrap().plugins.is_empty());
+
+    let index = PluginIndex::parse(INDEX).unwrap();
+    let mut installed = InstalledPlugins::default();
+    installed.plugins.insert(
+      "standup".to_string(),
+      InstalledPlugin {
+        entry: index.find("standup").unwrap().clone(),
+        version: "1.2.0".to_string(),
+        installed_at: "2026-01-01T00:00:00+00:00".to_string(),
+      },
+    );
+    installed.save(&path).unwrap();
+
+    let loaded = InstalledPlugins::load(&path).unwrap();
+    assert_eq!(loaded.plugins, installed.plugins);
+  }
+}
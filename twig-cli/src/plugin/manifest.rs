@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use twig_core::plugin::manifest::{MANIFEST_FLAG, PluginManifest, sidecar_path};
+
+/// File in the twig cache directory holding manifests queried from plugins
+const MANIFEST_CACHE_FILE: &str = "plugin-manifests.json";
+
+/// How long a plugin may take to answer `--twig-manifest`
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Manifests queried from plugin binaries, keyed by binary path. Entries are
+/// reused while the binary's size and modification time are unchanged;
+/// plugins that do not answer the handshake are cached as `None` so they are
+/// not run again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ManifestCache {
+  #[serde(default)]
+  entries: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+  size: u64,
+  modified: u64,
+  manifest: Option<PluginManifest>,
+}
+
+/// Loads plugin manifests from sidecar files or the `--twig-manifest`
+/// handshake, caching handshake results across invocations.
+///
+/// Plugins are only run for the handshake by [`ManifestLoader::load`], which
+/// is reserved for explicit actions such as `twig self plugins`, running a
+/// plugin or installing one.
+pub(super) struct ManifestLoader {
+  cache_path: Option<PathBuf>,
+  cache: ManifestCache,
+  dirty: bool,
+}
+
+impl ManifestLoader {
+  /// Create a loader backed by the cache file in `cache_dir`, if any
+  pub(super) fn new(cache_dir: Option<&Path>) -> Self {
+    let cache_path = cache_dir.map(|dir| dir.join(MANIFEST_CACHE_FILE));
+    let cache = cache_path
+      .as_deref()
+      .and_then(|path| fs::read_to_string(path).ok())
+      .and_then(|content| serde_json::from_str(&content).ok())
+      .unwrap_or_default();
+    Self {
+      cache_path,
+      cache,
+      dirty: false,
+    }
+  }
+
+  /// Create a loader backed by twig's cache directory
+  pub(super) fn from_config_dirs() -> Self {
+    let config_dirs = twig_core::get_config_dirs().ok();
+    Self::new(
+      config_dirs
+        .as_ref()
+        .and_then(|dirs| dirs.cache_dir())
+        .map(PathBuf::as_path),
+    )
+  }
+
+  /// Load the manifest for the plugin binary at `plugin_path`, running it with
+  /// `--twig-manifest` when the cache has no entry for it.
+  ///
+  /// A sidecar file wins over the handshake. Returns `Ok(None)` for plugins
+  /// that provide no manifest.
+  pub(super) fn load(&mut self, plugin_path: &Path) -> Result<Option<PluginManifest>> {
+    if let Some(manifest) = read_sidecar(plugin_path)? {
+      return Ok(Some(manifest));
+    }
+
+    let (key, stamp) = cache_key(plugin_path)?;
+    if let Some(manifest) = self.cached(&key, stamp) {
+      return Ok(manifest);
+    }
+
+    let manifest = query_manifest(plugin_path);
+    self.cache.entries.insert(
+      key,
+      CacheEntry {
+        size: stamp.0,
+        modified: stamp.1,
+        manifest: manifest.clone(),
+      },
+    );
+    self.dirty = true;
+    Ok(manifest)
+  }
+
+  /// Load the manifest for the plugin binary at `plugin_path` from its sidecar
+  /// file or the cache, without running the plugin.
+  ///
+  /// Used for help output and shell completions, which must not execute
+  /// binaries found on `PATH`. Returns `Ok(None)` when no manifest is known.
+  pub(super) fn load_cached(&self, plugin_path: &Path) -> Result<Option<PluginManifest>> {
+    if let Some(manifest) = read_sidecar(plugin_path)? {
+      return Ok(Some(manifest));
+    }
+
+    let (key, stamp) = cache_key(plugin_path)?;
+    Ok(self.cached(&key, stamp).flatten())
+  }
+
+  /// The cached handshake result for `key`, if the binary is unchanged
+  fn cached(&self, key: &str, (size, modified): (u64, u64)) -> Option<Option<PluginManifest>> {
+    self
+      .cache
+      .entries
+      .get(key)
+      .filter(|entry| entry.size == size && entry.modified == modified)
+      .map(|entry| entry.manifest.clone())
+  }
+
+  /// Persist newly queried manifests
+  pub(super) fn save(&self) {
+    if !self.dirty {
+      return;
+    }
+    let Some(cache_path) = &self.cache_path else {
+      return;
+    };
+    let result = serde_json::to_string(&self.cache)
+      .context("Failed to serialize plugin manifest cache")
+      .and_then(|content| {
+        if let Some(parent) = cache_path.parent() {
+          fs::create_dir_all(parent)?;
+        }
+        fs::write(cache_path, content).context("Failed to write plugin manifest cache")
+      });
+    if let Err(e) = result {
+      debug!("Could not save plugin manifest cache: {e:#}");
+    }
+  }
+}
+
+/// Parse the sidecar manifest next to `plugin_path`, if there is one
+fn read_sidecar(plugin_path: &Path) -> Result<Option<PluginManifest>> {
+  let sidecar = sidecar_path(plugin_path);
+  if !sidecar.is_file() {
+    return Ok(None);
+  }
+  let content = fs::read_to_string(&sidecar).with_context(|| format!("Failed to read {}", sidecar.display()))?;
+  PluginManifest::from_toml(&content)
+    .with_context(|| format!("Invalid plugin manifest {}", sidecar.display()))
+    .map(Some)
+}
+
+/// The cache key for `plugin_path` and the size and modification time that
+/// invalidate its entry
+fn cache_key(plugin_path: &Path) -> Result<(String, (u64, u64))> {
+  let metadata = fs::metadata(plugin_path).with_context(|| format!("Failed to stat {}", plugin_path.display()))?;
+  let modified = metadata
+    .modified()
+    .ok()
+    .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+    .map_or(0, |duration| duration.as_secs());
+  Ok((plugin_path.display().to_string(), (metadata.len(), modified)))
+}
+
+/// Run `plugin_path --twig-manifest` and parse its output. Plugins that exit
+/// with an error, time out or print something other than a manifest are
+/// treated as not supporting the handshake.
+fn query_manifest(plugin_path: &Path) -> Option<PluginManifest> {
+  debug!("Querying manifest from {}", plugin_path.display());
+  let mut child = Command::new(plugin_path)
+    .arg(MANIFEST_FLAG)
+    .env("TWIG_VERSION", env!("CARGO_PKG_VERSION"))
+    .env("TWIG_MANIFEST_QUERY", "1")
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null())
+    .spawn()
+    .ok()?;
+
+  let deadline = Instant::now() + QUERY_TIMEOUT;
+  let status = loop {
+    match child.try_wait() {
+      Ok(Some(status)) => break status,
+      Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+      _ => {
+        debug!("Plugin {} did not answer the manifest handshake", plugin_path.display());
+        let _ = child.kill();
+        let _ = child.wait();
+        return None;
+      }
+    }
+  };
+  if !status.success() {
+    return None;
+  }
+
+  let mut stdout = String::new();
+  child.stdout.take()?.read_to_string(&mut stdout).ok()?;
+  match PluginManifest::from_json(&stdout) {
+    Ok(manifest) => Some(manifest),
+    Err(e) => {
+      debug!("Plugin {} printed an invalid manifest: {e:#}", plugin_path.display());
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[cfg(unix)]
+  fn write_script(path: &Path, body: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::write(path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn handshake_results_are_cached() {
+    let bin_dir = TempDir::new().unwrap();
+    let cache_dir = TempDir::new().unwrap();
+    let plugin = bin_dir.path().join("twig-example");
+    let marker = bin_dir.path().join("queried");
+    write_script(
+      &plugin,
+      &format!(
+        "touch '{}'\n[ \"$1\" = \"--twig-manifest\" ] && echo '{{\"name\":\"example\",\"description\":\"Example\"}}'",
+        marker.display()
+      ),
+    );
+
+    let mut loader = ManifestLoader::new(Some(cache_dir.path()));
+    let manifest = loader.load(&plugin).unwrap().unwrap();
+    assert_eq!(manifest.description.as_deref(), Some("Example"));
+    loader.save();
+
+    fs::remove_file(&marker).unwrap();
+    let mut loader = ManifestLoader::new(Some(cache_dir.path()));
+    assert_eq!(loader.load(&plugin).unwrap(), Some(manifest));
+    assert!(!marker.exists(), "cached manifest should not run the plugin again");
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn cached_lookup_never_runs_the_plugin() {
+    let bin_dir = TempDir::new().unwrap();
+    let cache_dir = TempDir::new().unwrap();
+    let plugin = bin_dir.path().join("twig-example");
+    let marker = bin_dir.path().join("queried");
+    write_script(
+      &plugin,
+      &format!(
+        "touch '{}'\necho '{{\"name\":\"example\",\"description\":\"Example\"}}'",
+        marker.display()
+      ),
+    );
+
+    let loader = ManifestLoader::new(Some(cache_dir.path()));
+    assert_eq!(loader.load_cached(&plugin).unwrap(), None);
+    assert!(!marker.exists(), "cached lookup should not run the plugin");
+
+    let mut loader = ManifestLoader::new(Some(cache_dir.path()));
+    let manifest = loader.load(&plugin).unwrap();
+    loader.save();
+    fs::remove_file(&marker).unwrap();
+
+    let loader = ManifestLoader::new(Some(cache_dir.path()));
+    assert_eq!(loader.load_cached(&plugin).unwrap(), manifest);
+    assert!(!marker.exists());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn plugins_without_handshake_have_no_manifest() {
+    let bin_dir = TempDir::new().unwrap();
+    let plugin = bin_dir.path().join("twig-legacy");
+    write_script(&plugin, "echo 'usage: twig-legacy'\nexit 2");
+
+    let mut loader = ManifestLoader::new(None);
+    assert_eq!(loader.load(&plugin).unwrap(), None);
+  }
+
+  #[test]
+  fn sidecar_manifest_wins() {
+    let bin_dir = TempDir::new().unwrap();
+    let plugin = bin_dir.path().join("twig-example");
+    fs::write(&plugin, b"not executed").unwrap();
+    fs::write(
+      sidecar_path(&plugin),
+      "name = \"example\"\ntwig_core = \">=0.1\"\n\n[[subcommands]]\nname = \"run\"\n",
+    )
+    .unwrap();
+
+    let mut loader = ManifestLoader::new(None);
+    let manifest = loader.load(&plugin).unwrap().unwrap();
+    assert_eq!(manifest.twig_core.as_deref(), Some(">=0.1"));
+    assert_eq!(manifest.subcommands[0].name, "run");
+  }
+}
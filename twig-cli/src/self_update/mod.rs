@@ -1,9 +1,19 @@
 //! Self-update helpers for the `twig self update` command.
 //!
-//! This module downloads the latest Twig release from GitHub, extracts the
+//! This module downloads a Twig release from GitHub, extracts the
 //! platform-appropriate archive, and replaces the currently running binary in a
 //! safe and platform-aware manner. Platform-specific installation steps live in
 //! dedicated helpers to keep the main workflow cross-platform.
+//!
+//! Archives are verified before anything is installed (see [`verify`]), and
+//! the replaced binary is kept so `twig self rollback` can restore it (see
+//! [`rollback`]). The same release-asset logic backs `twig plugin install`,
+//! which installs third-party plugins from any GitHub repository following
+//! Twig's asset naming convention.
+
+mod release;
+mod rollback;
+mod verify;
 
 use std::ffi::OsStr;
 use std::fs::{self, File};
@@ -12,45 +22,73 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, anyhow};
 use flate2::read::GzDecoder;
+pub use release::ReleaseSelector;
+pub(crate) use release::{GithubAsset, GithubRelease, ReleaseSource};
 use reqwest::blocking::Client;
-use serde::Deserialize;
+pub(crate) use rollback::Installation;
+pub use rollback::run_rollback;
 use tar::Archive;
 use twig_core::output::{print_info, print_success, print_warning};
 use uuid::Uuid;
+pub(crate) use verify::{Verification, release_public_key};
 use zip::ZipArchive;
 
+/// GitHub repository that publishes Twig and its first-party plugins.
+pub(crate) const TWIG_RELEASE_REPO: &str = "eddieland/twig";
+
 /// Options controlling how the `twig self update` command behaves.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SelfUpdateOptions {
-  /// Install the latest release even if the current version matches.
+  /// Install the selected release even if the current version matches.
   pub force: bool,
+  /// Which release to install.
+  pub release: ReleaseSelector,
 }
 
 /// Options controlling how `twig self update flow` behaves.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PluginInstallOptions {
-  /// Reinstall even if the installed plugin already matches the latest release.
+  /// Reinstall even if the installed plugin already matches the selected release.
   pub force: bool,
+  /// Which release to install.
+  pub release: ReleaseSelector,
 }
 
-/// Download and install the latest Twig release for the current platform.
+/// Download and install a Twig release for the current platform.
 ///
-/// When `force` is false, the function exits early if the running version
-/// already matches the newest GitHub release tag. Otherwise it downloads the
-/// platform-appropriate archive, extracts the binary into a temporary staging
-/// area, and delegates to platform helpers to atomically replace the current
-/// executable.
+/// Installs the newest stable release unless `options.release` pins a version
+/// or opts into prereleases. When `force` is false, the function exits early
+/// if the running version already matches the selected release. Otherwise it
+/// downloads and verifies the platform-appropriate archive, saves the current
+/// executable for `twig self rollback`, and delegates to platform helpers to
+/// atomically replace it.
 pub fn run(options: SelfUpdateOptions) -> Result<()> {
-  let current_version = env!("CARGO_PKG_VERSION").to_string();
+  let source = ReleaseSource::from_env()?;
+  let installation = Installation::current()?;
+  let public_key = release_public_key();
+  update(&source, &installation, &options, public_key.as_deref())
+}
+
+/// Replace `installation` with the release chosen by `options`.
+fn update(
+  source: &ReleaseSource,
+  installation: &Installation,
+  options: &SelfUpdateOptions,
+  public_key: Option<&str>,
+) -> Result<()> {
+  let current_version = &installation.version;
   print_info(&format!("Checking for updates (current version {current_version})…"));
 
-  let client = build_http_client()?;
-  let release = fetch_latest_release(&client)?;
+  let release = source.select(TWIG_RELEASE_REPO, &options.release)?;
   let target = target_config("twig")?;
-  let latest_version = release.clean_tag();
+  let version = release.clean_tag();
 
-  if !options.force && latest_version == current_version {
-    print_success("You're already running the latest version of Twig.");
+  if !options.force && &version == current_version {
+    if options.release.version.is_some() {
+      print_success(&format!("Twig {version} is already installed."));
+    } else {
+      print_success("You're already running the latest version of Twig.");
+    }
     return Ok(());
   }
 
@@ -58,22 +96,28 @@ pub fn run(options: SelfUpdateOptions) -> Result<()> {
     .find_matching_asset(&target)
     .ok_or_else(|| anyhow!("No release asset available for this platform"))?;
 
-  print_info(&format!("Downloading Twig {latest_version} ({})…", asset.name));
+  let channel = if release.prerelease { " prerelease" } else { "" };
+  print_info(&format!("Downloading Twig{channel} {version} ({})…", asset.name));
 
+  let verification = Verification {
+    checksum_asset: None,
+    public_key,
+  };
   let staging_root = create_staging_directory()?;
-  let archive_path = download_asset(&client, asset, &staging_root)?;
-  let binary_path = extract_archive(&archive_path, &staging_root, &target)?;
-
-  print_info("Installing update…");
-  let outcome = install_new_binary(&binary_path)?;
+  let result =
+    download_verified_binary(source, &release, asset, &target, verification, &staging_root).and_then(|binary_path| {
+      installation.save_for_rollback()?;
+      print_info("Installing update…");
+      platform::install_new_binary(&binary_path, &installation.exe)
+    });
 
   if let Err(err) = fs::remove_dir_all(&staging_root) {
     print_warning(&format!("Failed to clean temporary files: {err}"));
   }
 
-  match outcome {
+  match result? {
     InstallOutcome::Immediate => {
-      print_success(&format!("Twig has been updated to version {latest_version}."));
+      print_success(&format!("Twig has been updated to version {version}."));
     }
     #[cfg(windows)]
     InstallOutcome::Deferred { elevated } => {
@@ -83,22 +127,23 @@ pub fn run(options: SelfUpdateOptions) -> Result<()> {
         print_info("A background PowerShell helper will finish applying the update once Twig exits.");
       }
       print_success(&format!(
-        "Twig {latest_version} is staged and will complete installation shortly."
+        "Twig {version} is staged and will complete installation shortly."
       ));
     }
   }
+  print_info(&format!("Run `twig self rollback` to return to {current_version}."));
 
   Ok(())
 }
 
-/// Download and install the latest release of the given plugin.
+/// Download and install a release of the given plugin.
 ///
 /// The plugin binary is placed alongside the running Twig executable so it can
 /// be discovered via standard PATH lookups.
 pub fn run_plugin_install(binary_name: &str, options: PluginInstallOptions) -> Result<()> {
   let display_name = binary_name.replace('-', " ");
-  let client = build_http_client()?;
-  let release = fetch_latest_release(&client)?;
+  let source = ReleaseSource::from_env()?;
+  let release = source.select(TWIG_RELEASE_REPO, &options.release)?;
   let target = target_config(binary_name)?;
   let latest_version = release.clean_tag();
   let install_path = plugin_install_path(&target)?;
@@ -120,16 +165,12 @@ pub fn run_plugin_install(binary_name: &str, options: PluginInstallOptions) -> R
     asset.name
   ));
 
-  let staging_root = create_staging_directory()?;
-  let archive_path = download_asset(&client, asset, &staging_root)?;
-  let binary_path = extract_archive(&archive_path, &staging_root, &target)?;
-
-  print_info(&format!("Installing {display_name} plugin…"));
-  let outcome = install_plugin_binary(&binary_path, &install_path)?;
-
-  if let Err(err) = fs::remove_dir_all(&staging_root) {
-    print_warning(&format!("Failed to clean temporary files: {err}"));
-  }
+  let public_key = release_public_key();
+  let verification = Verification {
+    checksum_asset: None,
+    public_key: public_key.as_deref(),
+  };
+  let outcome = install_release_asset(&source, &release, asset, &target, verification, &install_path)?;
 
   if !path_contains_dir(install_path.parent()) {
     print_warning(&format!(
@@ -161,8 +202,47 @@ pub fn run_plugin_install(binary_name: &str, options: PluginInstallOptions) -> R
   Ok(())
 }
 
+/// Downloads and verifies `asset`, then installs the extracted binary at
+/// `install_path`.
+pub(crate) fn install_release_asset(
+  source: &ReleaseSource,
+  release: &GithubRelease,
+  asset: &GithubAsset,
+  target: &TargetConfig,
+  verification: Verification<'_>,
+  install_path: &Path,
+) -> Result<InstallOutcome> {
+  let staging_root = create_staging_directory()?;
+  let result =
+    download_verified_binary(source, release, asset, target, verification, &staging_root).and_then(|binary_path| {
+      print_info(&format!("Installing {}…", target.product_name()));
+      install_plugin_binary(&binary_path, install_path)
+    });
+
+  if let Err(err) = fs::remove_dir_all(&staging_root) {
+    print_warning(&format!("Failed to clean temporary files: {err}"));
+  }
+
+  result
+}
+
+/// Downloads `asset` into `staging_root`, verifies it, and extracts the
+/// target binary. Returns the path to the extracted binary.
+fn download_verified_binary(
+  source: &ReleaseSource,
+  release: &GithubRelease,
+  asset: &GithubAsset,
+  target: &TargetConfig,
+  verification: Verification<'_>,
+  staging_root: &Path,
+) -> Result<PathBuf> {
+  let archive_path = download_asset(source.client(), asset, staging_root)?;
+  verify::verify_archive(source.client(), release, asset, &archive_path, verification)?;
+  extract_archive(&archive_path, staging_root, target)
+}
+
 /// Constructs an HTTP client configured with a descriptive User-Agent header.
-fn build_http_client() -> Result<Client> {
+pub(crate) fn build_http_client() -> Result<Client> {
   Client::builder()
     .user_agent(format!(
       "twig/{version} (self-update)",
@@ -172,54 +252,12 @@ fn build_http_client() -> Result<Client> {
     .context("Failed to construct HTTP client")
 }
 
-/// A GitHub release returned by the Releases API.
-#[derive(Debug, Deserialize)]
-struct GithubRelease {
-  /// The release tag, typically in the form `vX.Y.Z`.
-  tag_name: String,
-  /// Downloadable assets attached to the release.
-  assets: Vec<GithubAsset>,
-}
-
-impl GithubRelease {
-  /// Returns the version string without a leading `v` prefix.
-  fn clean_tag(&self) -> String {
-    self.tag_name.trim_start_matches('v').to_string()
-  }
-
-  /// Finds the first asset whose name matches the given [`TargetConfig`].
-  fn find_matching_asset<'a>(&'a self, target: &TargetConfig) -> Option<&'a GithubAsset> {
-    self.assets.iter().find(|asset| target.matches(asset))
-  }
-}
-
-/// A downloadable asset attached to a GitHub release.
-#[derive(Debug, Deserialize)]
-struct GithubAsset {
-  /// Filename of the asset (e.g., `twig-linux-x86_64-v0.5.0.tar.gz`).
-  name: String,
-  /// Direct download URL for the asset.
-  browser_download_url: String,
-}
-
-/// Fetches the latest Twig release metadata from the GitHub Releases API.
-fn fetch_latest_release(client: &Client) -> Result<GithubRelease> {
-  client
-    .get("https://api.github.com/repos/eddieland/twig/releases/latest")
-    .send()
-    .context("Failed to query GitHub Releases")?
-    .error_for_status()
-    .context("GitHub Releases request was not successful")?
-    .json::<GithubRelease>()
-    .context("Failed to deserialize GitHub Releases response")
-}
-
 /// Platform-specific configuration for selecting and extracting release assets.
 ///
 /// This struct encapsulates the conventions used to name release archives so
 /// that the correct asset can be selected for the current OS and architecture.
 #[derive(Debug)]
-struct TargetConfig {
+pub(crate) struct TargetConfig {
   /// Substrings that identify a matching operating system (e.g., `["linux"]`).
   os_markers: Vec<&'static str>,
   /// Substrings that identify a matching CPU architecture (e.g., `["x86_64",
@@ -229,12 +267,28 @@ struct TargetConfig {
   archive_extension: &'static str,
   /// Name of the binary inside the archive (includes `.exe` suffix on Windows).
   binary_name: String,
+  /// Product name used in asset filenames when it differs from the binary.
+  asset_name: Option<String>,
 }
 
 impl TargetConfig {
   /// Returns the product name without any platform-specific suffix.
   fn product_name(&self) -> &str {
-    self.binary_name.strip_suffix(".exe").unwrap_or(&self.binary_name)
+    match &self.asset_name {
+      Some(asset_name) => asset_name,
+      None => self.binary_name.strip_suffix(".exe").unwrap_or(&self.binary_name),
+    }
+  }
+
+  /// Returns the binary filename, including `.exe` on Windows.
+  pub(crate) fn binary_name(&self) -> &str {
+    &self.binary_name
+  }
+
+  /// Match assets named after `asset_name` instead of the binary.
+  pub(crate) fn with_asset_name(mut self, asset_name: &str) -> Self {
+    self.asset_name = Some(asset_name.to_string());
+    self
   }
 
   /// Returns `true` if the asset filename matches this target configuration.
@@ -285,7 +339,7 @@ impl TargetConfig {
 /// platform.
 ///
 /// Returns an error if the current operating system is not supported.
-fn target_config(binary_name: &str) -> Result<TargetConfig> {
+pub(crate) fn target_config(binary_name: &str) -> Result<TargetConfig> {
   let arch_markers = match std::env::consts::ARCH {
     "x86_64" => vec!["x86_64", "amd64"],
     "aarch64" => vec!["aarch64", "arm64"],
@@ -298,12 +352,14 @@ fn target_config(binary_name: &str) -> Result<TargetConfig> {
       arch_markers,
       archive_extension: ".tar.gz",
       binary_name: binary_name.to_string(),
+      asset_name: None,
     }),
     "macos" => Ok(TargetConfig {
       os_markers: vec!["macos", "darwin"],
       arch_markers,
       archive_extension: ".tar.gz",
       binary_name: binary_name.to_string(),
+      asset_name: None,
     }),
     "windows" => Ok(TargetConfig {
       os_markers: vec!["windows"],
@@ -314,6 +370,7 @@ fn target_config(binary_name: &str) -> Result<TargetConfig> {
       } else {
         format!("{binary_name}.exe")
       },
+      asset_name: None,
     }),
     other => Err(anyhow!("Unsupported operating system: {other}")),
   }
@@ -422,7 +479,7 @@ fn extract_zip(archive_path: &Path, staging_root: &Path, binary_name: &str) -> R
 /// On Unix, installation is always immediate via atomic rename. On Windows, if
 /// the running executable is locked, installation is deferred to a background
 /// process that waits for Twig to exit before completing the replacement.
-enum InstallOutcome {
+pub(crate) enum InstallOutcome {
   /// The new binary was installed immediately.
   #[cfg_attr(windows, allow(dead_code))]
   Immediate,
@@ -434,13 +491,6 @@ enum InstallOutcome {
   },
 }
 
-/// Installs a new binary to replace the currently running Twig executable.
-fn install_new_binary(binary_path: &Path) -> Result<InstallOutcome> {
-  let current_exe = std::env::current_exe().context("Failed to locate current executable")?;
-
-  platform::install_new_binary(binary_path, &current_exe)
-}
-
 /// Installs a plugin binary to the specified path.
 fn install_plugin_binary(binary_path: &Path, install_path: &Path) -> Result<InstallOutcome> {
   platform::install_new_binary(binary_path, install_path)
@@ -462,7 +512,7 @@ fn plugin_install_path(target: &TargetConfig) -> Result<PathBuf> {
 ///
 /// Returns `Ok(None)` if the plugin does not exist or cannot report its
 /// version.
-fn read_installed_plugin_version(path: &Path) -> Result<Option<String>> {
+pub(crate) fn read_installed_plugin_version(path: &Path) -> Result<Option<String>> {
   if !path.exists() {
     return Ok(None);
   }
@@ -545,6 +595,7 @@ mod tests {
       arch_markers: vec!["x86_64", "amd64"],
       archive_extension: ".tar.gz",
       binary_name: binary_name.to_string(),
+      asset_name: None,
     }
   }
 
@@ -554,6 +605,7 @@ mod tests {
       arch_markers: vec!["aarch64", "arm64"],
       archive_extension: ".tar.gz",
       binary_name: binary_name.to_string(),
+      asset_name: None,
     }
   }
 
@@ -563,6 +615,7 @@ mod tests {
       arch_markers: vec!["x86_64", "amd64"],
       archive_extension: ".zip",
       binary_name: binary_name.to_string(),
+      asset_name: None,
     }
   }
 
@@ -577,6 +630,8 @@ mod tests {
     GithubRelease {
       tag_name: tag_name.to_string(),
       assets,
+      prerelease: false,
+      draft: false,
     }
   }
 
@@ -1021,4 +1076,161 @@ mod tests {
       Some("twig-linux-x86_64-v0.5.7.tar.gz"),
     );
   }
+
+  // End-to-end update and rollback against a mock release server
+  #[cfg(unix)]
+  mod mock_release {
+    use std::fs;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::super::{Installation, ReleaseSource, SelfUpdateOptions, update};
+
+    const NEW_BINARY: &[u8] = b"#!/bin/sh\necho 1.2.0\n";
+    const OLD_BINARY: &[u8] = b"#!/bin/sh\necho 1.0.0\n";
+
+    fn archive() -> Vec<u8> {
+      let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+      let mut header = tar::Header::new_gnu();
+      header.set_size(NEW_BINARY.len() as u64);
+      header.set_mode(0o755);
+      header.set_cksum();
+      builder.append_data(&mut header, "twig", NEW_BINARY).unwrap();
+      builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// Serve release v1.2.0 with an archive for this platform and the given
+    /// checksum file contents.
+    fn serve_release(runtime: &tokio::runtime::Runtime, checksum: impl FnOnce(&[u8]) -> String) -> MockServer {
+      let archive = archive();
+      let checksum = checksum(&archive);
+      let asset_name = format!("twig-{}-{}-v1.2.0.tar.gz", std::env::consts::OS, std::env::consts::ARCH);
+
+      runtime.block_on(async {
+        let server = MockServer::start().await;
+        let uri = server.uri();
+        Mock::given(method("GET"))
+          .and(path("/repos/eddieland/twig/releases/latest"))
+          .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "tag_name": "v1.2.0",
+            "assets": [
+              { "name": asset_name, "browser_download_url": format!("{uri}/download/archive") },
+              { "name": format!("{asset_name}.sha256"), "browser_download_url": format!("{uri}/download/sha256") },
+            ],
+          })))
+          .mount(&server)
+          .await;
+        Mock::given(method("GET"))
+          .and(path("/download/archive"))
+          .respond_with(ResponseTemplate::new(200).set_body_bytes(archive))
+          .mount(&server)
+          .await;
+        Mock::given(method("GET"))
+          .and(path("/download/sha256"))
+          .respond_with(ResponseTemplate::new(200).set_body_string(checksum))
+          .mount(&server)
+          .await;
+        server
+      })
+    }
+
+    fn installation(dir: &TempDir) -> Installation {
+      let exe = dir.path().join("bin").join("twig");
+      fs::create_dir_all(exe.parent().unwrap()).unwrap();
+      fs::write(&exe, OLD_BINARY).unwrap();
+      Installation {
+        exe,
+        version: "1.0.0".to_string(),
+        rollback_dir: dir.path().join("rollback"),
+      }
+    }
+
+    #[test]
+    fn updates_verified_release_and_rolls_back() {
+      let runtime = tokio::runtime::Runtime::new().unwrap();
+      let server = serve_release(&runtime, |archive| format!("{:x}", Sha256::digest(archive)));
+      let source = ReleaseSource::new(reqwest::blocking::Client::new(), &server.uri());
+      let dir = TempDir::new().unwrap();
+      let installation = installation(&dir);
+
+      update(&source, &installation, &SelfUpdateOptions::default(), None).unwrap();
+      assert_eq!(fs::read(&installation.exe).unwrap(), NEW_BINARY);
+      assert_eq!(
+        fs::read(installation.rollback_dir.join("twig-1.0.0")).unwrap(),
+        OLD_BINARY
+      );
+
+      // The updated binary now reports the new version
+      let updated = Installation {
+        version: "1.2.0".to_string(),
+        ..installation.clone()
+      };
+      let (restored, _) = updated.roll_back().unwrap();
+      assert_eq!(restored, "1.0.0");
+      assert_eq!(fs::read(&installation.exe).unwrap(), OLD_BINARY);
+
+      // Rolling back again returns to the newer version
+      let (restored, _) = installation.roll_back().unwrap();
+      assert_eq!(restored, "1.2.0");
+      assert_eq!(fs::read(&installation.exe).unwrap(), NEW_BINARY);
+    }
+
+    #[test]
+    fn refuses_release_with_mismatched_checksum() {
+      let runtime = tokio::runtime::Runtime::new().unwrap();
+      let server = serve_release(&runtime, |_| "0".repeat(64));
+      let source = ReleaseSource::new(reqwest::blocking::Client::new(), &server.uri());
+      let dir = TempDir::new().unwrap();
+      let installation = installation(&dir);
+
+      let err = update(&source, &installation, &SelfUpdateOptions::default(), None).unwrap_err();
+      assert!(format!("{err:#}").contains("Checksum mismatch"));
+      assert_eq!(fs::read(&installation.exe).unwrap(), OLD_BINARY);
+      assert!(!installation.rollback_dir.exists());
+      assert!(installation.roll_back().is_err());
+    }
+
+    #[test]
+    fn leaves_current_version_alone() {
+      let runtime = tokio::runtime::Runtime::new().unwrap();
+      let server = serve_release(&runtime, |archive| format!("{:x}", Sha256::digest(archive)));
+      let source = ReleaseSource::new(reqwest::blocking::Client::new(), &server.uri());
+      let dir = TempDir::new().unwrap();
+      let installation = Installation {
+        version: "1.2.0".to_string(),
+        ..installation(&dir)
+      };
+
+      update(&source, &installation, &SelfUpdateOptions::default(), None).unwrap();
+      assert_eq!(fs::read(&installation.exe).unwrap(), OLD_BINARY);
+      assert!(!installation.rollback_dir.exists());
+
+      let forced = SelfUpdateOptions {
+        force: true,
+        ..SelfUpdateOptions::default()
+      };
+      update(&source, &installation, &forced, None).unwrap();
+      assert_eq!(fs::read(&installation.exe).unwrap(), NEW_BINARY);
+    }
+
+    #[test]
+    fn requires_signature_when_public_key_is_configured() {
+      let runtime = tokio::runtime::Runtime::new().unwrap();
+      let server = serve_release(&runtime, |archive| format!("{:x}", Sha256::digest(archive)));
+      let source = ReleaseSource::new(reqwest::blocking::Client::new(), &server.uri());
+      let dir = TempDir::new().unwrap();
+      let installation = installation(&dir);
+
+      let public_key = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+      let err = update(&source, &installation, &SelfUpdateOptions::default(), Some(public_key)).unwrap_err();
+      assert!(err.to_string().contains("has no signature"));
+      assert_eq!(fs::read(&installation.exe).unwrap(), OLD_BINARY);
+    }
+  }
 }
@@ -0,0 +1,225 @@
+//! Locating releases through the GitHub Releases API.
+//!
+//! [`ReleaseSource`] talks to `api.github.com` by default. Setting
+//! `TWIG_RELEASES_API_URL` points it at any server with the same API shape,
+//! such as a mirror or a local mock release server.
+
+use anyhow::{Context, Result, anyhow};
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use super::{TargetConfig, build_http_client};
+
+/// Environment variable overriding the GitHub API URL used for releases.
+pub(crate) const RELEASES_API_ENV: &str = "TWIG_RELEASES_API_URL";
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Which release `twig self update` should install.
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseSelector {
+  /// Install this version instead of the newest release.
+  pub version: Option<String>,
+  /// Consider prereleases when looking for the newest release.
+  pub prerelease: bool,
+}
+
+/// A GitHub release returned by the Releases API.
+#[derive(Debug, Deserialize)]
+pub(crate) struct GithubRelease {
+  /// The release tag, typically in the form `vX.Y.Z`.
+  pub(crate) tag_name: String,
+  /// Downloadable assets attached to the release.
+  pub(crate) assets: Vec<GithubAsset>,
+  /// Whether the release is marked as a prerelease.
+  #[serde(default)]
+  pub(crate) prerelease: bool,
+  /// Whether the release is an unpublished draft.
+  #[serde(default)]
+  pub(crate) draft: bool,
+}
+
+impl GithubRelease {
+  /// Returns the version string without a leading `v` prefix.
+  pub(crate) fn clean_tag(&self) -> String {
+    self.tag_name.trim_start_matches('v').to_string()
+  }
+
+  /// Finds the first asset whose name matches the given [`TargetConfig`].
+  pub(crate) fn find_matching_asset<'a>(&'a self, target: &TargetConfig) -> Option<&'a GithubAsset> {
+    self.assets.iter().find(|asset| target.matches(asset))
+  }
+
+  /// Finds the asset with exactly this filename.
+  pub(crate) fn find_asset(&self, name: &str) -> Option<&GithubAsset> {
+    self.assets.iter().find(|asset| asset.name == name)
+  }
+}
+
+/// A downloadable asset attached to a GitHub release.
+#[derive(Debug, Deserialize)]
+pub(crate) struct GithubAsset {
+  /// Filename of the asset (e.g., `twig-linux-x86_64-v0.5.0.tar.gz`).
+  pub(crate) name: String,
+  /// Direct download URL for the asset.
+  pub(crate) browser_download_url: String,
+}
+
+/// Fetches release metadata and assets from the GitHub Releases API.
+pub(crate) struct ReleaseSource {
+  client: Client,
+  api_url: String,
+}
+
+impl ReleaseSource {
+  /// Creates a source for the API at `api_url` (e.g. `https://api.github.com`).
+  pub(crate) fn new(client: Client, api_url: &str) -> Self {
+    Self {
+      client,
+      api_url: api_url.trim_end_matches('/').to_string(),
+    }
+  }
+
+  /// Creates a source for GitHub, or the API named by `TWIG_RELEASES_API_URL`.
+  pub(crate) fn from_env() -> Result<Self> {
+    let api_url = std::env::var(RELEASES_API_ENV)
+      .ok()
+      .filter(|url| !url.is_empty())
+      .unwrap_or_else(|| GITHUB_API_URL.to_string());
+    Ok(Self::new(build_http_client()?, &api_url))
+  }
+
+  /// The HTTP client used for API requests and downloads.
+  pub(crate) fn client(&self) -> &Client {
+    &self.client
+  }
+
+  /// Fetches the latest stable release of `repo` (`owner/name`).
+  pub(crate) fn latest(&self, repo: &str) -> Result<GithubRelease> {
+    self.get_json(&format!("{}/repos/{repo}/releases/latest", self.api_url))
+  }
+
+  /// Fetches the release of `repo` chosen by `selector`.
+  pub(crate) fn select(&self, repo: &str, selector: &ReleaseSelector) -> Result<GithubRelease> {
+    match &selector.version {
+      Some(version) => self.tagged(repo, version),
+      None if selector.prerelease => self.newest(repo),
+      None => self.latest(repo),
+    }
+  }
+
+  /// Fetches the release tagged `v<version>` or `<version>`.
+  fn tagged(&self, repo: &str, version: &str) -> Result<GithubRelease> {
+    let version = version.trim_start_matches('v');
+    for tag in [format!("v{version}"), version.to_string()] {
+      let response = self
+        .client
+        .get(format!("{}/repos/{repo}/releases/tags/{tag}", self.api_url))
+        .send()
+        .context("Failed to query GitHub Releases")?;
+      if response.status() == StatusCode::NOT_FOUND {
+        continue;
+      }
+      return response
+        .error_for_status()
+        .context("GitHub Releases request was not successful")?
+        .json()
+        .context("Failed to deserialize GitHub Releases response");
+    }
+
+    Err(anyhow!("Release {version} was not found in {repo}"))
+  }
+
+  /// Fetches the newest published release, including prereleases.
+  fn newest(&self, repo: &str) -> Result<GithubRelease> {
+    let releases: Vec<GithubRelease> = self.get_json(&format!("{}/repos/{repo}/releases?per_page=30", self.api_url))?;
+    releases
+      .into_iter()
+      .find(|release| !release.draft)
+      .ok_or_else(|| anyhow!("{repo} has no published releases"))
+  }
+
+  fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+    self
+      .client
+      .get(url)
+      .send()
+      .context("Failed to query GitHub Releases")?
+      .error_for_status()
+      .context("GitHub Releases request was not successful")?
+      .json()
+      .context("Failed to deserialize GitHub Releases response")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use wiremock::matchers::{method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+
+  fn release_json(tag: &str, prerelease: bool, draft: bool) -> serde_json::Value {
+    json!({ "tag_name": tag, "assets": [], "prerelease": prerelease, "draft": draft })
+  }
+
+  #[test]
+  fn selects_pinned_and_prerelease_versions() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start());
+    runtime.block_on(async {
+      Mock::given(method("GET"))
+        .and(path("/repos/acme/tool/releases/tags/v1.2.0"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+      Mock::given(method("GET"))
+        .and(path("/repos/acme/tool/releases/tags/1.2.0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(release_json("1.2.0", false, false)))
+        .mount(&server)
+        .await;
+      Mock::given(method("GET"))
+        .and(path("/repos/acme/tool/releases"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+          release_json("v2.0.0-rc.2", true, true),
+          release_json("v2.0.0-rc.1", true, false),
+          release_json("v1.2.0", false, false),
+        ])))
+        .mount(&server)
+        .await;
+      Mock::given(method("GET"))
+        .and(path("/repos/acme/tool/releases/latest"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(release_json("v1.2.0", false, false)))
+        .mount(&server)
+        .await;
+    });
+
+    let source = ReleaseSource::new(Client::new(), &format!("{}/", server.uri()));
+
+    let pinned = ReleaseSelector {
+      version: Some("v1.2.0".to_string()),
+      prerelease: false,
+    };
+    assert_eq!(source.select("acme/tool", &pinned).unwrap().clean_tag(), "1.2.0");
+
+    let prerelease = ReleaseSelector {
+      version: None,
+      prerelease: true,
+    };
+    let newest = source.select("acme/tool", &prerelease).unwrap();
+    assert_eq!(newest.clean_tag(), "2.0.0-rc.1");
+    assert!(newest.prerelease);
+
+    let stable = source.select("acme/tool", &ReleaseSelector::default()).unwrap();
+    assert_eq!(stable.clean_tag(), "1.2.0");
+
+    let missing = ReleaseSelector {
+      version: Some("9.9.9".to_string()),
+      prerelease: false,
+    };
+    assert!(source.select("acme/tool", &missing).is_err());
+  }
+}
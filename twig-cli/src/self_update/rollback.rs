@@ -0,0 +1,232 @@
+//! Keeping the previous Twig binary so `twig self rollback` can restore it.
+//!
+//! Before an update replaces the running executable, a copy is saved in the
+//! `rollback` directory under Twig's data directory. Only the most recent copy
+//! is kept. Rolling back saves the binary it replaces in the same way, so a
+//! rollback can itself be undone.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use twig_core::get_config_dirs;
+use twig_core::output::{print_info, print_success};
+
+use super::{InstallOutcome, create_staging_directory, platform};
+
+/// File describing the saved binary
+const RECORD_FILE: &str = "previous.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RollbackRecord {
+  version: String,
+  binary: PathBuf,
+  saved_at: String,
+}
+
+/// An installed Twig executable and where its previous version is kept.
+#[derive(Debug, Clone)]
+pub(crate) struct Installation {
+  /// Path of the executable to replace.
+  pub(crate) exe: PathBuf,
+  /// Version of that executable.
+  pub(crate) version: String,
+  /// Directory holding the saved previous version.
+  pub(crate) rollback_dir: PathBuf,
+}
+
+impl Installation {
+  /// The running Twig executable.
+  pub(crate) fn current() -> Result<Self> {
+    Ok(Self {
+      exe: std::env::current_exe().context("Failed to locate current executable")?,
+      version: env!("CARGO_PKG_VERSION").to_string(),
+      rollback_dir: get_config_dirs()?.data_dir().join("rollback"),
+    })
+  }
+
+  /// Copies the executable into the rollback directory, replacing any
+  /// previously saved version.
+  pub(crate) fn save_for_rollback(&self) -> Result<()> {
+    fs::create_dir_all(&self.rollback_dir)
+      .with_context(|| format!("Failed to create {}", self.rollback_dir.display()))?;
+
+    let binary = self
+      .rollback_dir
+      .join(format!("twig-{}{}", self.version, std::env::consts::EXE_SUFFIX));
+    fs::copy(&self.exe, &binary).with_context(|| format!("Failed to save {} for rollback", self.exe.display()))?;
+
+    for entry in fs::read_dir(&self.rollback_dir)?.flatten() {
+      let path = entry.path();
+      if path != binary && path.file_name().is_some_and(|name| name != RECORD_FILE) {
+        let _ = fs::remove_file(path);
+      }
+    }
+
+    let record = RollbackRecord {
+      version: self.version.clone(),
+      binary,
+      saved_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let content = serde_json::to_string_pretty(&record).context("Failed to serialize rollback record")?;
+    fs::write(self.rollback_dir.join(RECORD_FILE), content).context("Failed to write rollback record")
+  }
+
+  /// Replaces the executable with the saved previous version, saving the
+  /// replaced binary in its place. Returns the restored version.
+  pub(crate) fn roll_back(&self) -> Result<(String, InstallOutcome)> {
+    let record = self
+      .load_record()?
+      .ok_or_else(|| anyhow!("No previous version of Twig has been saved; rollback is available after an update"))?;
+    if !record.binary.is_file() {
+      bail!("The saved Twig binary {} is missing", record.binary.display());
+    }
+
+    // Stage a copy first: saving the current binary removes the old copy, and
+    // installing consumes the staged file.
+    let staging_root = create_staging_directory()?;
+    let staged = staging_root.join(record.binary.file_name().unwrap_or_default());
+    fs::copy(&record.binary, &staged).with_context(|| format!("Failed to stage {}", record.binary.display()))?;
+    platform::finalize_extracted_binary(&staged)?;
+
+    let result = self
+      .save_for_rollback()
+      .and_then(|()| platform::install_new_binary(&staged, &self.exe));
+    let _ = fs::remove_dir_all(&staging_root);
+
+    Ok((record.version, result?))
+  }
+
+  fn load_record(&self) -> Result<Option<RollbackRecord>> {
+    let path = self.rollback_dir.join(RECORD_FILE);
+    if !path.exists() {
+      return Ok(None);
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+      .map(Some)
+      .with_context(|| format!("Failed to parse {}", path.display()))
+  }
+}
+
+/// Restore the Twig version that was installed before the last update.
+pub fn run_rollback() -> Result<()> {
+  let installation = Installation::current()?;
+  let (version, outcome) = installation.roll_back()?;
+
+  match outcome {
+    InstallOutcome::Immediate => {
+      print_success(&format!("Twig has been rolled back to version {version}."));
+    }
+    #[cfg(windows)]
+    InstallOutcome::Deferred { .. } => {
+      print_success(&format!(
+        "Twig {version} is staged and will complete installation shortly."
+      ));
+    }
+  }
+  print_info(&format!(
+    "Run `twig self rollback` again to return to {}.",
+    installation.version
+  ));
+
+  Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  const OLD_BINARY: &[u8] = b"#!/bin/sh\necho 1.0.0\n";
+  const NEW_BINARY: &[u8] = b"#!/bin/sh\necho 1.1.0\n";
+
+  fn installation(dir: &TempDir, version: &str) -> Installation {
+    let exe = dir.path().join("bin").join("twig");
+    fs::create_dir_all(exe.parent().unwrap()).unwrap();
+    Installation {
+      exe,
+      version: version.to_string(),
+      rollback_dir: dir.path().join("rollback"),
+    }
+  }
+
+  fn saved_files(installation: &Installation) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(&installation.rollback_dir)
+      .unwrap()
+      .flatten()
+      .map(|entry| entry.file_name().to_string_lossy().into_owned())
+      .collect();
+    names.sort();
+    names
+  }
+
+  #[test]
+  fn roll_back_requires_a_saved_version() {
+    let dir = TempDir::new().unwrap();
+    let installation = installation(&dir, "1.0.0");
+    fs::write(&installation.exe, OLD_BINARY).unwrap();
+
+    let Err(err) = installation.roll_back() else {
+      panic!("rollback should fail");
+    };
+    assert!(err.to_string().contains("No previous version"));
+    assert_eq!(fs::read(&installation.exe).unwrap(), OLD_BINARY);
+  }
+
+  #[test]
+  fn save_keeps_only_the_latest_copy() {
+    let dir = TempDir::new().unwrap();
+    let old = installation(&dir, "1.0.0");
+    fs::write(&old.exe, OLD_BINARY).unwrap();
+    old.save_for_rollback().unwrap();
+    assert_eq!(saved_files(&old), ["previous.json", "twig-1.0.0"]);
+
+    let new = installation(&dir, "1.1.0");
+    fs::write(&new.exe, NEW_BINARY).unwrap();
+    new.save_for_rollback().unwrap();
+    assert_eq!(saved_files(&new), ["previous.json", "twig-1.1.0"]);
+
+    let record = new.load_record().unwrap().unwrap();
+    assert_eq!(record.version, "1.1.0");
+    assert_eq!(fs::read(record.binary).unwrap(), NEW_BINARY);
+  }
+
+  #[test]
+  fn roll_back_restores_and_can_be_undone() {
+    let dir = TempDir::new().unwrap();
+    let old = installation(&dir, "1.0.0");
+    fs::write(&old.exe, OLD_BINARY).unwrap();
+    old.save_for_rollback().unwrap();
+
+    // Simulate an update to 1.1.0
+    fs::write(&old.exe, NEW_BINARY).unwrap();
+    let new = installation(&dir, "1.1.0");
+
+    let (restored, _) = new.roll_back().unwrap();
+    assert_eq!(restored, "1.0.0");
+    assert_eq!(fs::read(&new.exe).unwrap(), OLD_BINARY);
+    assert_eq!(saved_files(&new), ["previous.json", "twig-1.1.0"]);
+
+    let (restored, _) = old.roll_back().unwrap();
+    assert_eq!(restored, "1.1.0");
+    assert_eq!(fs::read(&old.exe).unwrap(), NEW_BINARY);
+  }
+
+  #[test]
+  fn roll_back_fails_when_the_saved_binary_is_missing() {
+    let dir = TempDir::new().unwrap();
+    let installation = installation(&dir, "1.0.0");
+    fs::write(&installation.exe, OLD_BINARY).unwrap();
+    installation.save_for_rollback().unwrap();
+    fs::remove_file(installation.rollback_dir.join("twig-1.0.0")).unwrap();
+
+    let Err(err) = installation.roll_back() else {
+      panic!("rollback should fail");
+    };
+    assert!(err.to_string().contains("is missing"));
+    assert_eq!(fs::read(&installation.exe).unwrap(), OLD_BINARY);
+  }
+}
@@ -0,0 +1,220 @@
+//! Verifying downloaded release archives before they are installed.
+//!
+//! Every archive must match the SHA-256 checksum published with its release.
+//! When a minisign public key is configured, the archive must also carry a
+//! valid `<archive>.minisig` signature made with that key.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use minisign_verify::{PublicKey, Signature};
+use reqwest::blocking::Client;
+use sha2::{Digest, Sha256};
+
+use super::{GithubAsset, GithubRelease};
+
+/// Environment variable holding the minisign public key Twig releases are
+/// checked against.
+pub(crate) const UPDATE_PUBLIC_KEY_ENV: &str = "TWIG_UPDATE_PUBLIC_KEY";
+
+/// Public key baked in at build time, used when the environment sets none.
+const BUILT_IN_PUBLIC_KEY: Option<&str> = option_env!("TWIG_UPDATE_PUBLIC_KEY");
+
+/// Returns the minisign public key Twig releases must be signed with, if any.
+pub(crate) fn release_public_key() -> Option<String> {
+  std::env::var(UPDATE_PUBLIC_KEY_ENV)
+    .ok()
+    .filter(|key| !key.trim().is_empty())
+    .or_else(|| BUILT_IN_PUBLIC_KEY.map(str::to_string))
+}
+
+/// How a downloaded archive is verified.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Verification<'a> {
+  /// Combined `SHA256SUMS`-style asset; defaults to `<archive>.sha256`.
+  pub(crate) checksum_asset: Option<&'a str>,
+  /// minisign public key; when set, `<archive>.minisig` must verify with it.
+  pub(crate) public_key: Option<&'a str>,
+}
+
+/// Verifies the downloaded `archive_path` of `asset` against the checksum and
+/// signature published with `release`.
+pub(crate) fn verify_archive(
+  client: &Client,
+  release: &GithubRelease,
+  asset: &GithubAsset,
+  archive_path: &Path,
+  verification: Verification<'_>,
+) -> Result<()> {
+  let checksum_name = verification
+    .checksum_asset
+    .map_or_else(|| format!("{}.sha256", asset.name), str::to_string);
+  let checksum_file = release.find_asset(&checksum_name).ok_or_else(|| {
+    anyhow!(
+      "Release {} publishes no checksum ({checksum_name}) for {}; refusing to install an unverified binary",
+      release.tag_name,
+      asset.name
+    )
+  })?;
+  let expected = parse_checksum(&download_text(client, checksum_file)?, &asset.name)
+    .ok_or_else(|| anyhow!("{checksum_name} contains no SHA-256 checksum for {}", asset.name))?;
+  verify_checksum(archive_path, &expected)?;
+
+  if let Some(public_key) = verification.public_key {
+    let signature_name = format!("{}.minisig", asset.name);
+    let signature_file = release
+      .find_asset(&signature_name)
+      .ok_or_else(|| anyhow!("Release {} has no signature for {}", release.tag_name, asset.name))?;
+    verify_signature(archive_path, &download_text(client, signature_file)?, public_key)
+      .with_context(|| format!("Signature verification failed for {}", asset.name))?;
+  }
+
+  Ok(())
+}
+
+fn download_text(client: &Client, asset: &GithubAsset) -> Result<String> {
+  client
+    .get(&asset.browser_download_url)
+    .send()
+    .with_context(|| format!("Failed to download {}", asset.name))?
+    .error_for_status()
+    .with_context(|| format!("GitHub returned an error downloading {}", asset.name))?
+    .text()
+    .with_context(|| format!("Failed to read {}", asset.name))
+}
+
+/// Finds the checksum for `asset_name` in a checksum file.
+///
+/// Accepts both single-asset files (`<hex>` or `<hex>  <name>`) and combined
+/// `sha256sum` output listing several assets.
+fn parse_checksum(content: &str, asset_name: &str) -> Option<String> {
+  content.lines().find_map(|line| {
+    let mut tokens = line.split_whitespace();
+    let hash = tokens.next()?;
+    let name_matches = tokens
+      .next()
+      .is_none_or(|name| name.trim_start_matches('*') == asset_name);
+    let is_sha256 = hash.len() == 64 && hash.chars().all(|ch| ch.is_ascii_hexdigit());
+    (name_matches && is_sha256).then(|| hash.to_lowercase())
+  })
+}
+
+/// Checks that the SHA-256 digest of the file at `path` matches `expected`.
+fn verify_checksum(path: &Path, expected: &str) -> Result<()> {
+  let mut hasher = Sha256::new();
+  read_chunks(path, |chunk| hasher.update(chunk))?;
+
+  let actual = format!("{:x}", hasher.finalize());
+  if actual != expected {
+    bail!(
+      "Checksum mismatch for {}: expected {expected}, got {actual}",
+      path.file_name().and_then(|name| name.to_str()).unwrap_or_default()
+    );
+  }
+  Ok(())
+}
+
+/// Checks the minisign `signature` of the file at `path`. `public_key` may be
+/// the bare base64 key or the contents of a `.pub` file.
+fn verify_signature(path: &Path, signature: &str, public_key: &str) -> Result<()> {
+  let public_key = PublicKey::from_base64(public_key.trim())
+    .or_else(|_| PublicKey::decode(public_key.trim()))
+    .context("Invalid minisign public key")?;
+  let signature = Signature::decode(signature.trim()).context("Invalid minisign signature")?;
+
+  let mut verifier = public_key.verify_stream(&signature)?;
+  read_chunks(path, |chunk| verifier.update(chunk))?;
+  verifier.finalize()?;
+  Ok(())
+}
+
+fn read_chunks(path: &Path, mut consume: impl FnMut(&[u8])) -> Result<()> {
+  let mut file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+  let mut buffer = [0u8; 8192];
+  loop {
+    let read = file
+      .read(&mut buffer)
+      .with_context(|| format!("Failed to read {}", path.display()))?;
+    if read == 0 {
+      return Ok(());
+    }
+    consume(&buffer[..read]);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+  // Test vector from the minisign-verify crate: a prehashed signature of "test"
+  const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+  const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+  fn write_file(dir: &TempDir, content: &str) -> std::path::PathBuf {
+    let path = dir.path().join("archive.tar.gz");
+    std::fs::write(&path, content).unwrap();
+    path
+  }
+
+  #[test]
+  fn parses_single_asset_checksum_file() {
+    let content = format!("{HELLO_SHA256}  twig-flow-linux-x86_64.tar.gz\n");
+    assert_eq!(
+      parse_checksum(&content, "twig-flow-linux-x86_64.tar.gz").as_deref(),
+      Some(HELLO_SHA256)
+    );
+    assert_eq!(
+      parse_checksum(&HELLO_SHA256.to_uppercase(), "anything.tar.gz").as_deref(),
+      Some(HELLO_SHA256)
+    );
+  }
+
+  #[test]
+  fn parses_combined_checksum_file() {
+    let other = "0".repeat(64);
+    let content =
+      format!("{other}  twig-standup-macos-arm64.tar.gz\n{HELLO_SHA256} *twig-standup-linux-x86_64.tar.gz\n");
+    assert_eq!(
+      parse_checksum(&content, "twig-standup-linux-x86_64.tar.gz").as_deref(),
+      Some(HELLO_SHA256)
+    );
+    assert_eq!(parse_checksum(&content, "twig-standup-windows-x86_64.zip"), None);
+  }
+
+  #[test]
+  fn rejects_malformed_checksum() {
+    assert_eq!(parse_checksum("not-a-hash  twig.tar.gz", "twig.tar.gz"), None);
+  }
+
+  #[test]
+  fn verifies_file_checksum() {
+    let dir = TempDir::new().unwrap();
+    let path = write_file(&dir, "hello");
+
+    assert!(verify_checksum(&path, HELLO_SHA256).is_ok());
+    let err = verify_checksum(&path, &"0".repeat(64)).unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch"));
+  }
+
+  #[test]
+  fn verifies_minisign_signature() {
+    let dir = TempDir::new().unwrap();
+
+    let signed = write_file(&dir, "test");
+    verify_signature(&signed, SIGNATURE, PUBLIC_KEY).unwrap();
+    let key_file = format!("untrusted comment: minisign public key E7620F1842B4E81F\n{PUBLIC_KEY}\n");
+    verify_signature(&signed, SIGNATURE, &key_file).unwrap();
+
+    let tampered = write_file(&dir, "Test");
+    assert!(verify_signature(&tampered, SIGNATURE, PUBLIC_KEY).is_err());
+  }
+}
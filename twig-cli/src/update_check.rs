@@ -0,0 +1,358 @@
+//! # Update Notifications
+//!
+//! An opt-in, once-a-day check for newer releases of twig and its first-party
+//! plugins.
+//!
+//! With `check = true` in `updates.toml`, commands read the result of the last
+//! check from twig's cache directory and print a one-line notice when they
+//! finish. When that result is a day old, a detached `twig self update --check`
+//! refreshes it in the background, so the command being run never waits on the
+//! network. Setting `TWIG_NO_UPDATE_CHECK=1` turns the check off.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, TimeDelta, Utc};
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use twig_core::output::{format_command, get_emoji_or_default, print_info, print_success};
+use twig_core::plugin::discovery::resolve_plugin_path;
+use twig_core::{ConfigDirs, get_config_dirs};
+
+use crate::self_update::{ReleaseSource, TWIG_RELEASE_REPO, read_installed_plugin_version};
+
+/// Environment variable that turns the update check off when set to `1`
+pub const NO_UPDATE_CHECK_ENV: &str = "TWIG_NO_UPDATE_CHECK";
+
+/// Plugins released alongside twig, checked against the same release
+const FIRST_PARTY_PLUGINS: [&str; 3] = ["twig-flow", "twig-prune", "twig-mcp"];
+
+const CACHE_FILE: &str = "update-check.json";
+
+/// How long a check result is used before it is refreshed
+const CHECK_INTERVAL: TimeDelta = TimeDelta::hours(24);
+
+/// Whether the update check runs, and if not, why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+  /// `check = true` is set in `updates.toml`
+  Enabled,
+  /// The check has not been turned on in `updates.toml`
+  NotEnabled,
+  /// [`NO_UPDATE_CHECK_ENV`] turns the check off
+  DisabledByEnv,
+}
+
+impl CheckStatus {
+  /// Reads the status from the environment and `updates.toml`.
+  pub fn load(config_dirs: &ConfigDirs) -> Result<Self> {
+    if std::env::var(NO_UPDATE_CHECK_ENV).is_ok_and(|v| v == "1") {
+      return Ok(Self::DisabledByEnv);
+    }
+
+    if config_dirs.load_updates_config()?.check {
+      Ok(Self::Enabled)
+    } else {
+      Ok(Self::NotEnabled)
+    }
+  }
+}
+
+/// The result of the last update check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCheck {
+  /// When the check last ran
+  pub checked_at: DateTime<Utc>,
+  /// The latest twig release, if the check succeeded
+  #[serde(default)]
+  pub latest_version: Option<String>,
+  /// Installed first-party plugins and their versions
+  #[serde(default)]
+  pub plugins: BTreeMap<String, String>,
+  /// Why the check failed, if it did
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+}
+
+impl UpdateCheck {
+  /// Path of the cached result in twig's cache directory.
+  pub fn cache_path(config_dirs: &ConfigDirs) -> Option<PathBuf> {
+    config_dirs.cache_dir().map(|dir| dir.join(CACHE_FILE))
+  }
+
+  /// Loads a cached result, or `None` when no check has run yet.
+  pub fn load(path: &Path) -> Result<Option<Self>> {
+    if !path.exists() {
+      return Ok(None);
+    }
+
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content)
+      .map(Some)
+      .with_context(|| format!("Failed to parse {}", path.display()))
+  }
+
+  /// Writes the result to `path`.
+  pub fn save(&self, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let content = serde_json::to_string_pretty(self).context("Failed to serialize update check")?;
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+  }
+
+  /// Whether the result is old enough to be refreshed.
+  pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+    now - self.checked_at >= CHECK_INTERVAL
+  }
+
+  /// Components older than the latest release, as `(name, installed version)`
+  /// pairs.
+  pub fn outdated<'a>(&'a self, twig_version: &'a str) -> Vec<(&'a str, &'a str)> {
+    let Some(latest) = self.latest_version.as_deref() else {
+      return Vec::new();
+    };
+
+    std::iter::once(("twig", twig_version))
+      .chain(
+        self
+          .plugins
+          .iter()
+          .map(|(name, version)| (name.as_str(), version.as_str())),
+      )
+      .filter(|(_, installed)| is_newer(latest, installed))
+      .collect()
+  }
+
+  /// A one-line notice naming what can be updated, if anything.
+  pub fn notice(&self, twig_version: &str) -> Option<String> {
+    let latest = self.latest_version.as_deref()?;
+    let outdated = self.outdated(twig_version);
+    if outdated.is_empty() {
+      return None;
+    }
+
+    let installed = outdated
+      .iter()
+      .map(|(name, version)| format!("{name} {version}"))
+      .collect::<Vec<_>>()
+      .join(", ");
+    let commands = outdated
+      .iter()
+      .map(|(name, _)| match name.strip_prefix("twig-") {
+        Some(plugin) => format_command(&format!("twig self update {plugin}")),
+        None => format_command("twig self update"),
+      })
+      .collect::<Vec<_>>()
+      .join(", ");
+
+    Some(format!(
+      "Twig {latest} is available (installed: {installed}). Update with {commands}."
+    ))
+  }
+}
+
+/// Prepares the update notice for a command that is about to run.
+///
+/// Returns the notice from the last check, if any, and starts a background
+/// refresh when that check is out of date. Nothing here waits on the network
+/// or fails the command.
+pub fn start() -> Option<String> {
+  if !std::io::stderr().is_terminal() {
+    return None;
+  }
+
+  let config_dirs = get_config_dirs().ok()?;
+  if CheckStatus::load(&config_dirs).ok()? != CheckStatus::Enabled {
+    return None;
+  }
+
+  let path = UpdateCheck::cache_path(&config_dirs)?;
+  let cached = UpdateCheck::load(&path).ok().flatten();
+  let now = Utc::now();
+  if cached.as_ref().is_none_or(|check| check.is_stale(now)) {
+    // Record the attempt first so a failing refresh is retried tomorrow, not
+    // on every command
+    let mut attempt = cached.clone().unwrap_or_else(|| UpdateCheck {
+      checked_at: now,
+      latest_version: None,
+      plugins: BTreeMap::new(),
+      error: None,
+    });
+    attempt.checked_at = now;
+    if let Err(e) = attempt.save(&path).and_then(|()| spawn_refresh()) {
+      debug!("Failed to start update check: {e:#}");
+    }
+  }
+
+  cached?.notice(env!("CARGO_PKG_VERSION"))
+}
+
+/// Prints a notice returned by [`start`] to stderr.
+pub fn print_notice(notice: &str) {
+  let info = get_emoji_or_default("information", "ℹ");
+  eprintln!("{} {notice}", info.blue().bold());
+}
+
+fn spawn_refresh() -> Result<()> {
+  let exe = std::env::current_exe().context("Failed to locate current executable")?;
+  Command::new(exe)
+    .args(["self", "update", "--check"])
+    .env(NO_UPDATE_CHECK_ENV, "1")
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+    .context("Failed to start background update check")?;
+  Ok(())
+}
+
+/// Checks for newer releases now, caching and printing the result.
+pub fn run_check() -> Result<()> {
+  let config_dirs = get_config_dirs()?;
+  let check = check_releases(&ReleaseSource::from_env()?, installed_plugin_versions());
+  if let Some(path) = UpdateCheck::cache_path(&config_dirs) {
+    check.save(&path)?;
+  }
+
+  if let Some(error) = &check.error {
+    return Err(anyhow!("Failed to check for updates: {error}"));
+  }
+
+  match check.notice(env!("CARGO_PKG_VERSION")) {
+    Some(notice) => print_info(&notice),
+    None => print_success(&format!("Twig {} is up to date.", env!("CARGO_PKG_VERSION"))),
+  }
+  Ok(())
+}
+
+/// Looks up the latest twig release for the given installed plugins.
+fn check_releases(source: &ReleaseSource, plugins: BTreeMap<String, String>) -> UpdateCheck {
+  let (latest_version, error) = match source.latest(TWIG_RELEASE_REPO) {
+    Ok(release) => (Some(release.clean_tag()), None),
+    Err(e) => (None, Some(format!("{e:#}"))),
+  };
+
+  UpdateCheck {
+    checked_at: Utc::now(),
+    latest_version,
+    plugins,
+    error,
+  }
+}
+
+/// Versions of the first-party plugins found on the plugin search path.
+fn installed_plugin_versions() -> BTreeMap<String, String> {
+  FIRST_PARTY_PLUGINS
+    .iter()
+    .filter_map(|name| {
+      let path = resolve_plugin_path(name)?;
+      let version = read_installed_plugin_version(&path).ok()??;
+      Some((name.to_string(), version))
+    })
+    .collect()
+}
+
+/// Whether `latest` is newer than `installed`. Versions that are not semver
+/// are only compared for equality.
+fn is_newer(latest: &str, installed: &str) -> bool {
+  match (semver::Version::parse(latest), semver::Version::parse(installed)) {
+    (Ok(latest), Ok(installed)) => latest > installed,
+    _ => latest != installed,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use reqwest::blocking::Client;
+  use serde_json::json;
+  use tempfile::TempDir;
+  use wiremock::matchers::{method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+
+  fn check(latest: Option<&str>, plugins: &[(&str, &str)]) -> UpdateCheck {
+    UpdateCheck {
+      checked_at: Utc::now(),
+      latest_version: latest.map(str::to_string),
+      plugins: plugins
+        .iter()
+        .map(|(name, version)| (name.to_string(), version.to_string()))
+        .collect(),
+      error: None,
+    }
+  }
+
+  #[test]
+  fn notice_names_outdated_components() {
+    let result = check(Some("0.8.0"), &[("twig-flow", "0.7.0"), ("twig-prune", "0.8.0")]);
+
+    assert_eq!(
+      result.outdated("0.7.1"),
+      vec![("twig", "0.7.1"), ("twig-flow", "0.7.0")]
+    );
+    let notice = result.notice("0.7.1").unwrap();
+    assert!(notice.contains("Twig 0.8.0 is available (installed: twig 0.7.1, twig-flow 0.7.0)"));
+    assert!(notice.contains("twig self update flow"));
+    assert_eq!(result.outdated("0.8.0"), vec![("twig-flow", "0.7.0")]);
+
+    let current = check(Some("0.8.0"), &[("twig-flow", "0.8.0")]);
+    assert_eq!(current.notice("0.8.0"), None);
+    assert_eq!(check(None, &[]).notice("0.7.1"), None);
+  }
+
+  #[test]
+  fn compares_versions() {
+    assert!(is_newer("0.10.0", "0.9.3"));
+    assert!(!is_newer("0.9.3", "0.10.0"));
+    assert!(!is_newer("1.0.0", "1.0.0"));
+    assert!(is_newer("1.0.0", "1.0.0-rc.1"));
+    assert!(is_newer("nightly-2", "nightly-1"));
+  }
+
+  #[test]
+  fn caches_result_for_a_day() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("cache").join(CACHE_FILE);
+    assert_eq!(UpdateCheck::load(&path).unwrap(), None);
+
+    let result = check(Some("0.8.0"), &[("twig-flow", "0.7.0")]);
+    result.save(&path).unwrap();
+    let loaded = UpdateCheck::load(&path).unwrap().unwrap();
+    assert_eq!(loaded, result);
+
+    assert!(!loaded.is_stale(loaded.checked_at + TimeDelta::hours(23)));
+    assert!(loaded.is_stale(loaded.checked_at + TimeDelta::hours(24)));
+  }
+
+  #[test]
+  fn records_latest_release_or_failure() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start());
+    runtime.block_on(
+      Mock::given(method("GET"))
+        .and(path("/repos/eddieland/twig/releases/latest"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "tag_name": "v0.8.0", "assets": [] })))
+        .mount(&server),
+    );
+
+    let plugins = BTreeMap::from([("twig-flow".to_string(), "0.7.0".to_string())]);
+    let source = ReleaseSource::new(Client::new(), &server.uri());
+    let result = check_releases(&source, plugins.clone());
+    assert_eq!(result.latest_version.as_deref(), Some("0.8.0"));
+    assert_eq!(result.plugins, plugins);
+    assert_eq!(result.error, None);
+
+    let missing = ReleaseSource::new(Client::new(), &format!("{}/missing", server.uri()));
+    let result = check_releases(&missing, BTreeMap::new());
+    assert_eq!(result.latest_version, None);
+    assert!(result.error.is_some());
+  }
+}
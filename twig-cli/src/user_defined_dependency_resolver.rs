@@ -20,16 +20,22 @@
 //!
 //! # Example
 //!
-//! ```
+//! ```no_run
+//! use twig_cli::user_defined_dependency_resolver::UserDefinedDependencyResolver;
+//! use twig_core::RepoState;
+//!
+//! # fn main() -> anyhow::Result<()> {
 //! let resolver = UserDefinedDependencyResolver;
-//! let repo_state = ...; // Obtain repo state
-//! let repo = ...; // Obtain Git2 repository instance
+//! let repo = git2::Repository::open(".")?;
+//! let repo_state = RepoState::load(".")?;
 //!
 //! // Resolve user dependencies and build branch nodes
 //! let branch_nodes = resolver.resolve_user_dependencies(&repo, &repo_state)?;
 //!
 //! // Build tree structure from user-defined dependencies and roots
 //! let (roots, orphaned) = resolver.build_tree_from_user_dependencies(&branch_nodes, &repo_state);
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! # Notes
@@ -8,8 +8,15 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
 
+use crate::creds::chain::CredentialsConfig;
+use crate::git::worktree::WorktreeConfig;
+use crate::github::GitHubConfig;
+use crate::hooks::HooksConfig;
 use crate::jira_parser::JiraParsingConfig;
+use crate::output::print_warning;
+use crate::trust::TrustedRepos;
 
 /// Represents the configuration directories for the twig application
 #[derive(Debug, Clone)]
@@ -72,6 +79,17 @@ impl ConfigDirs {
     self.data_dir.join("registry.json")
   }
 
+  /// Get the twig-managed directory that `twig plugin install` installs
+  /// plugins into. Plugin discovery searches it after `PATH`.
+  pub fn plugin_bin_dir(&self) -> PathBuf {
+    self.data_dir.join("bin")
+  }
+
+  /// Get the path to the record of plugins installed by `twig plugin install`
+  pub fn installed_plugins_path(&self) -> PathBuf {
+    self.data_dir.join("installed-plugins.json")
+  }
+
   /// Get the path to the repository-local state directory
   pub fn repo_state_dir<P: AsRef<Path>>(&self, repo_path: P) -> PathBuf {
     repo_path.as_ref().join(".twig")
@@ -120,6 +138,226 @@ impl ConfigDirs {
 
     Ok(())
   }
+
+  /// Get the path to the GitHub host configuration file
+  pub fn github_config_path(&self) -> PathBuf {
+    self.config_dir.join("github.toml")
+  }
+
+  /// Load GitHub host configuration from file or return default
+  pub fn load_github_config(&self) -> Result<GitHubConfig> {
+    let config_path = self.github_config_path();
+
+    if config_path.exists() {
+      let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read GitHub config from {}", config_path.display()))?;
+
+      toml::from_str(&content).with_context(|| format!("Failed to parse GitHub config from {}", config_path.display()))
+    } else {
+      Ok(GitHubConfig::default())
+    }
+  }
+
+  /// Get the path to the credential backend configuration file
+  pub fn creds_config_path(&self) -> PathBuf {
+    self.config_dir.join("creds.toml")
+  }
+
+  /// Load credential backend configuration from file or return default
+  pub fn load_creds_config(&self) -> Result<CredentialsConfig> {
+    let config_path = self.creds_config_path();
+
+    if config_path.exists() {
+      let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read credentials config from {}", config_path.display()))?;
+
+      toml::from_str(&content)
+        .with_context(|| format!("Failed to parse credentials config from {}", config_path.display()))
+    } else {
+      Ok(CredentialsConfig::default())
+    }
+  }
+
+  /// Get the path to the worktree configuration file
+  pub fn worktree_config_path(&self) -> PathBuf {
+    self.config_dir.join("worktree.toml")
+  }
+
+  /// Get the path to a repository's worktree configuration override
+  pub fn repo_worktree_config_path<P: AsRef<Path>>(&self, repo_path: P) -> PathBuf {
+    self.repo_state_dir(repo_path).join("worktree.toml")
+  }
+
+  /// Load the worktree configuration for a repository.
+  ///
+  /// A repository's `.twig/worktree.toml` takes precedence over the global
+  /// `worktree.toml`; when neither exists the defaults are returned. Its
+  /// `post_create` commands are dropped with a warning unless the repository
+  /// is trusted (see [`crate::trust`]).
+  pub fn load_worktree_config<P: AsRef<Path>>(&self, repo_path: P) -> Result<WorktreeConfig> {
+    let repo_path = repo_path.as_ref();
+    let repo_config_path = self.repo_worktree_config_path(repo_path);
+    if repo_config_path.exists() {
+      let mut config = Self::read_worktree_config(&repo_config_path)?;
+      if !config.hooks.post_create.is_empty() && !self.load_trusted_repos()?.contains(repo_path) {
+        print_warning(&format!(
+          "Ignoring post_create commands in {} because this repository is not trusted. Run 'twig hooks trust' to allow them.",
+          repo_config_path.display()
+        ));
+        config.hooks.post_create.clear();
+      }
+      return Ok(config);
+    }
+
+    self.load_global_worktree_config()
+  }
+
+  /// Load the global worktree configuration from file or return default
+  pub fn load_global_worktree_config(&self) -> Result<WorktreeConfig> {
+    let config_path = self.worktree_config_path();
+
+    if config_path.exists() {
+      Self::read_worktree_config(&config_path)
+    } else {
+      Ok(WorktreeConfig::default())
+    }
+  }
+
+  fn read_worktree_config(config_path: &Path) -> Result<WorktreeConfig> {
+    let content = fs::read_to_string(config_path)
+      .with_context(|| format!("Failed to read worktree config from {}", config_path.display()))?;
+
+    toml::from_str(&content).with_context(|| format!("Failed to parse worktree config from {}", config_path.display()))
+  }
+
+  /// Save the global worktree configuration to file
+  pub fn save_worktree_config(&self, config: &WorktreeConfig) -> Result<()> {
+    let config_path = self.worktree_config_path();
+
+    if let Some(parent) = config_path.parent() {
+      fs::create_dir_all(parent).with_context(|| format!("Failed to create config directory {}", parent.display()))?;
+    }
+
+    let content = toml::to_string_pretty(config).context("Failed to serialize worktree config to TOML")?;
+
+    fs::write(&config_path, content)
+      .with_context(|| format!("Failed to write worktree config to {}", config_path.display()))?;
+
+    Ok(())
+  }
+
+  /// Get the path to the update check configuration file
+  pub fn updates_config_path(&self) -> PathBuf {
+    self.config_dir.join("updates.toml")
+  }
+
+  /// Load update check configuration from file or return default
+  pub fn load_updates_config(&self) -> Result<UpdatesConfig> {
+    let config_path = self.updates_config_path();
+
+    if config_path.exists() {
+      let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read updates config from {}", config_path.display()))?;
+
+      toml::from_str(&content).with_context(|| format!("Failed to parse updates config from {}", config_path.display()))
+    } else {
+      Ok(UpdatesConfig::default())
+    }
+  }
+
+  /// Get the path to the global hooks configuration file
+  pub fn hooks_config_path(&self) -> PathBuf {
+    self.config_dir.join("hooks.toml")
+  }
+
+  /// Get the path to a repository's hooks configuration file
+  pub fn repo_hooks_config_path<P: AsRef<Path>>(&self, repo_path: P) -> PathBuf {
+    self.repo_state_dir(repo_path).join("hooks.toml")
+  }
+
+  /// Load the hooks configured for a repository.
+  ///
+  /// Unlike the worktree configuration, the repository's `.twig/hooks.toml`
+  /// does not replace the global `hooks.toml`: handlers from both run, global
+  /// ones first. The repository's file is only used once the repository is
+  /// trusted (see [`crate::trust`]); when it is untrusted or cannot be parsed
+  /// a warning is printed and only the global hooks run.
+  pub fn load_hooks_config<P: AsRef<Path>>(&self, repo_path: P) -> Result<HooksConfig> {
+    let mut config = HooksConfig::default();
+
+    let global_path = self.hooks_config_path();
+    if global_path.exists() {
+      config.extend(Self::read_hooks_config(&global_path)?);
+    }
+
+    let repo_path = repo_path.as_ref();
+    let repo_config_path = self.repo_hooks_config_path(repo_path);
+    if repo_config_path.exists() {
+      if !self.load_trusted_repos()?.contains(repo_path) {
+        print_warning(&format!(
+          "Ignoring hooks in {} because this repository is not trusted. Run 'twig hooks trust' to allow them.",
+          repo_config_path.display()
+        ));
+      } else {
+        match Self::read_hooks_config(&repo_config_path) {
+          Ok(repo_config) => config.extend(repo_config),
+          Err(e) => print_warning(&format!("Ignoring repository hooks: {e:#}")),
+        }
+      }
+    }
+
+    Ok(config)
+  }
+
+  fn read_hooks_config(config_path: &Path) -> Result<HooksConfig> {
+    let content = fs::read_to_string(config_path)
+      .with_context(|| format!("Failed to read hooks config from {}", config_path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse hooks config from {}", config_path.display()))
+  }
+
+  /// Get the path to the list of trusted repositories
+  pub fn trusted_repos_path(&self) -> PathBuf {
+    self.config_dir.join("trusted-repos.toml")
+  }
+
+  /// Load the list of trusted repositories from file or return an empty one
+  pub fn load_trusted_repos(&self) -> Result<TrustedRepos> {
+    let config_path = self.trusted_repos_path();
+
+    if config_path.exists() {
+      let content = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read trusted repositories from {}", config_path.display()))?;
+
+      toml::from_str(&content)
+        .with_context(|| format!("Failed to parse trusted repositories from {}", config_path.display()))
+    } else {
+      Ok(TrustedRepos::default())
+    }
+  }
+
+  /// Save the list of trusted repositories to file
+  pub fn save_trusted_repos(&self, trusted: &TrustedRepos) -> Result<()> {
+    let config_path = self.trusted_repos_path();
+
+    if let Some(parent) = config_path.parent() {
+      fs::create_dir_all(parent).with_context(|| format!("Failed to create config directory {}", parent.display()))?;
+    }
+
+    let content = toml::to_string_pretty(trusted).context("Failed to serialize trusted repositories to TOML")?;
+
+    fs::write(&config_path, content)
+      .with_context(|| format!("Failed to write trusted repositories to {}", config_path.display()))?;
+
+    Ok(())
+  }
+}
+
+/// Settings for twig's background update check, read from `updates.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatesConfig {
+  /// Check once a day for newer releases of twig and its first-party plugins
+  #[serde(default)]
+  pub check: bool,
 }
 
 /// Get the configuration directories
@@ -165,6 +403,129 @@ mod tests {
     assert_eq!(state_path, repo_path.join(".twig/state.json"));
   }
 
+  #[test]
+  fn test_repo_worktree_config_overrides_global() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dirs = ConfigDirs {
+      config_dir: temp_dir.path().join("config"),
+      data_dir: temp_dir.path().join("data"),
+      cache_dir: None,
+    };
+    let repo_path = temp_dir.path().join("repo");
+
+    assert_eq!(
+      config_dirs.load_worktree_config(&repo_path).unwrap(),
+      WorktreeConfig::default()
+    );
+
+    let global = WorktreeConfig {
+      path_template: "~/wt/{repo}/{branch}".to_string(),
+      ..WorktreeConfig::default()
+    };
+    config_dirs.save_worktree_config(&global).unwrap();
+    assert_eq!(config_dirs.load_worktree_config(&repo_path).unwrap(), global);
+
+    fs::create_dir_all(repo_path.join(".twig")).unwrap();
+    fs::write(
+      config_dirs.repo_worktree_config_path(&repo_path),
+      "path_template = \"{repo}-wt/{branch}\"\n\n[hooks]\ncopy = [\".env\"]\n",
+    )
+    .unwrap();
+    let repo_config = config_dirs.load_worktree_config(&repo_path).unwrap();
+    assert_eq!(repo_config.path_template, "{repo}-wt/{branch}");
+    assert_eq!(repo_config.hooks.copy, vec![".env".to_string()]);
+    assert!(repo_config.hooks.post_create.is_empty());
+  }
+
+  #[test]
+  fn test_repo_worktree_commands_require_trust() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dirs = ConfigDirs {
+      config_dir: temp_dir.path().join("config"),
+      data_dir: temp_dir.path().join("data"),
+      cache_dir: None,
+    };
+    let repo_path = temp_dir.path().join("repo");
+    fs::create_dir_all(repo_path.join(".twig")).unwrap();
+    fs::write(
+      config_dirs.repo_worktree_config_path(&repo_path),
+      "[hooks]\ncopy = [\".env\"]\npost_create = [\"npm install\"]\n",
+    )
+    .unwrap();
+
+    let untrusted = config_dirs.load_worktree_config(&repo_path).unwrap();
+    assert_eq!(untrusted.hooks.copy, vec![".env".to_string()]);
+    assert!(untrusted.hooks.post_create.is_empty());
+
+    let mut trusted = TrustedRepos::default();
+    trusted.add(&repo_path);
+    config_dirs.save_trusted_repos(&trusted).unwrap();
+
+    let trusted_config = config_dirs.load_worktree_config(&repo_path).unwrap();
+    assert_eq!(trusted_config.hooks.post_create, vec!["npm install".to_string()]);
+  }
+
+  #[test]
+  fn test_hooks_config_merges_global_and_repo() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dirs = ConfigDirs {
+      config_dir: temp_dir.path().join("config"),
+      data_dir: temp_dir.path().join("data"),
+      cache_dir: None,
+    };
+    let repo_path = temp_dir.path().join("repo");
+
+    assert!(config_dirs.load_hooks_config(&repo_path).unwrap().is_empty());
+
+    fs::create_dir_all(config_dirs.config_dir()).unwrap();
+    fs::write(config_dirs.hooks_config_path(), "[[post_sync]]\nplugin = \"chat\"\n").unwrap();
+    fs::create_dir_all(repo_path.join(".twig")).unwrap();
+    fs::write(
+      config_dirs.repo_hooks_config_path(&repo_path),
+      "[[post_switch]]\ncommand = \"npm ci\"\n",
+    )
+    .unwrap();
+
+    // Repository hooks only run once the repository is trusted
+    let config = config_dirs.load_hooks_config(&repo_path).unwrap();
+    assert_eq!(config.post_sync.len(), 1);
+    assert!(config.post_switch.is_empty());
+
+    let mut trusted = config_dirs.load_trusted_repos().unwrap();
+    trusted.add(&repo_path);
+    config_dirs.save_trusted_repos(&trusted).unwrap();
+
+    let config = config_dirs.load_hooks_config(&repo_path).unwrap();
+    assert_eq!(config.post_sync.len(), 1);
+    assert_eq!(config.post_switch.len(), 1);
+  }
+
+  #[test]
+  fn test_malformed_repo_hooks_are_skipped() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dirs = ConfigDirs {
+      config_dir: temp_dir.path().join("config"),
+      data_dir: temp_dir.path().join("data"),
+      cache_dir: None,
+    };
+    let repo_path = temp_dir.path().join("repo");
+    fs::create_dir_all(repo_path.join(".twig")).unwrap();
+    fs::write(
+      config_dirs.repo_hooks_config_path(&repo_path),
+      "[[post_switch]]\ncommand = \"npm ci\"\n\n[unknown]\n",
+    )
+    .unwrap();
+    let mut trusted = TrustedRepos::default();
+    trusted.add(&repo_path);
+    config_dirs.save_trusted_repos(&trusted).unwrap();
+
+    assert!(config_dirs.load_hooks_config(&repo_path).unwrap().is_empty());
+
+    // A malformed global file is the user's own and still fails loudly
+    fs::write(config_dirs.hooks_config_path(), "[unknown]\n").unwrap();
+    assert!(config_dirs.load_hooks_config(&repo_path).is_err());
+  }
+
   #[test]
   fn test_init_creates_directories() {
     let config_dirs = ConfigDirs::new().unwrap();
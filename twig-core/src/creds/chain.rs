@@ -0,0 +1,767 @@
+//! # Credential Backend Chain
+//!
+//! Looks up credentials from an ordered list of backends configured in
+//! `creds.toml`. The first backend that has credentials for a host wins, so
+//! tokens can come from the environment, the system keyring, the GitHub CLI or
+//! an external credential helper instead of sitting in plaintext in `.netrc`.
+//!
+//! ```toml
+//! # ~/.config/twig/creds.toml
+//! backends = ["env", "helper", "keyring", "netrc"]
+//! helper = "pass-twig-helper"
+//! ```
+
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::creds::Credentials;
+use crate::creds::netrc::{get_netrc_path, parse_netrc_default, parse_netrc_file, write_netrc_entry};
+#[cfg(windows)]
+use crate::creds::platform::{CredentialProvider, get_credential_provider};
+
+/// Environment variable holding a github.com token. It is not sent to GitHub
+/// Enterprise hosts.
+pub const ENV_GITHUB_TOKEN: &str = "TWIG_GITHUB_TOKEN";
+/// Environment variable holding the GitHub user name sent with the token
+pub const ENV_GITHUB_USER: &str = "TWIG_GITHUB_USER";
+/// Environment variable holding a Jira API token
+pub const ENV_JIRA_TOKEN: &str = "TWIG_JIRA_TOKEN";
+/// Environment variable holding the Jira account email sent with the token
+pub const ENV_JIRA_USER: &str = "TWIG_JIRA_USER";
+
+/// User name sent with GitHub tokens when none is configured. GitHub ignores
+/// the user name for token authentication.
+pub const GITHUB_TOKEN_USER: &str = "x-access-token";
+
+/// Attribute identifying twig entries in the Secret Service keyring
+const KEYRING_SERVICE: &str = "twig";
+
+/// Service a credential lookup is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialService {
+  GitHub,
+  /// OAuth refresh token issued alongside an expiring GitHub token. Only
+  /// backends that twig writes to are consulted.
+  GitHubRefreshToken,
+  Jira,
+}
+
+impl fmt::Display for CredentialService {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::GitHub => write!(f, "GitHub"),
+      Self::GitHubRefreshToken => write!(f, "GitHub refresh token"),
+      Self::Jira => write!(f, "Jira"),
+    }
+  }
+}
+
+/// A credential backend that can be listed in `creds.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialBackendKind {
+  /// `TWIG_GITHUB_TOKEN` (github.com only) / `TWIG_JIRA_TOKEN` environment
+  /// variables
+  Env,
+  /// The external command configured as `helper`
+  Helper,
+  /// The freedesktop Secret Service on Unix, Windows Credential Manager on
+  /// Windows
+  Keyring,
+  /// The token stored by the GitHub CLI (`gh auth token`)
+  Gh,
+  /// The `~/.netrc` file
+  Netrc,
+}
+
+impl CredentialBackendKind {
+  /// Name shown to users, e.g. by `twig creds check`
+  pub fn description(&self) -> &'static str {
+    match self {
+      Self::Env => "environment variables",
+      Self::Helper => "credential helper",
+      #[cfg(windows)]
+      Self::Keyring => "Windows Credential Manager",
+      #[cfg(not(windows))]
+      Self::Keyring => "Secret Service keyring",
+      Self::Gh => "GitHub CLI",
+      Self::Netrc => ".netrc file",
+    }
+  }
+}
+
+impl fmt::Display for CredentialBackendKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let name = match self {
+      Self::Env => "env",
+      Self::Helper => "helper",
+      Self::Keyring => "keyring",
+      Self::Gh => "gh",
+      Self::Netrc => "netrc",
+    };
+    write!(f, "{name}")
+  }
+}
+
+/// Credential backend configuration stored in `creds.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CredentialsConfig {
+  /// Backends to query, in order
+  pub backends: Vec<CredentialBackendKind>,
+  /// Command implementing the git-credential helper protocol. It is run
+  /// through the shell with `get` or `store` appended.
+  pub helper: Option<String>,
+}
+
+impl Default for CredentialsConfig {
+  fn default() -> Self {
+    Self {
+      #[cfg(windows)]
+      backends: vec![
+        CredentialBackendKind::Env,
+        CredentialBackendKind::Keyring,
+        CredentialBackendKind::Netrc,
+      ],
+      #[cfg(not(windows))]
+      backends: vec![CredentialBackendKind::Env, CredentialBackendKind::Netrc],
+      helper: None,
+    }
+  }
+}
+
+/// Credentials together with the backend that provided them
+#[derive(Debug, Clone)]
+pub struct CredentialMatch {
+  pub credentials: Credentials,
+  pub backend: CredentialBackendKind,
+  /// Backend-specific detail, e.g. the environment variable or helper used
+  pub detail: Option<String>,
+}
+
+impl CredentialMatch {
+  /// Describe where the credentials came from
+  pub fn source(&self) -> String {
+    match &self.detail {
+      Some(detail) => format!("{} ({detail})", self.backend.description()),
+      None => self.backend.description().to_string(),
+    }
+  }
+}
+
+/// Ordered chain of credential backends
+pub struct CredentialChain {
+  home: PathBuf,
+  config: CredentialsConfig,
+}
+
+impl CredentialChain {
+  /// Create a chain from an explicit configuration
+  pub fn new(home: &Path, config: CredentialsConfig) -> Self {
+    Self {
+      home: home.to_path_buf(),
+      config,
+    }
+  }
+
+  /// Create a chain from `creds.toml`, falling back to the default backends
+  /// when the file does not exist
+  pub fn load(home: &Path) -> Result<Self> {
+    let config = match crate::config::get_config_dirs() {
+      Ok(config_dirs) => config_dirs.load_creds_config()?,
+      Err(e) => {
+        debug!("Could not resolve config directories, using default credential backends: {e}");
+        CredentialsConfig::default()
+      }
+    };
+    Ok(Self::new(home, config))
+  }
+
+  /// The configured backends, in lookup order
+  pub fn backends(&self) -> &[CredentialBackendKind] {
+    &self.config.backends
+  }
+
+  /// Look up credentials for `host`, returning the first backend that has
+  /// them. Backend failures are logged and the next backend is tried.
+  pub fn find(&self, service: CredentialService, host: &str) -> Result<Option<CredentialMatch>> {
+    let mut last_error = None;
+    for backend in &self.config.backends {
+      match self.lookup(*backend, service, host) {
+        Ok(Some(found)) => {
+          debug!(backend = %backend, host, "Found credentials");
+          return Ok(Some(found));
+        }
+        Ok(None) => {}
+        Err(e) => {
+          warn!(backend = %backend, host, "Credential backend failed: {e:#}");
+          last_error = Some(e);
+        }
+      }
+    }
+
+    // Surface a backend failure only when no other backend had credentials
+    match last_error {
+      Some(e) => Err(e),
+      None => Ok(None),
+    }
+  }
+
+  /// Credentials from the `.netrc` `default` entry, when the netrc backend is
+  /// configured
+  ///
+  /// [`find`](Self::find) never returns the catch-all entry, so it is not sent
+  /// to hosts it was not meant for. Callers use this only after every lookup
+  /// for a specific host has failed.
+  pub fn find_default(&self) -> Result<Option<CredentialMatch>> {
+    if !self.config.backends.contains(&CredentialBackendKind::Netrc) {
+      return Ok(None);
+    }
+    let netrc_path = get_netrc_path(&self.home);
+    if !netrc_path.exists() {
+      return Ok(None);
+    }
+    Ok(parse_netrc_default(&netrc_path)?.map(|credentials| CredentialMatch {
+      credentials,
+      backend: CredentialBackendKind::Netrc,
+      detail: Some("default entry".to_string()),
+    }))
+  }
+
+  /// The first configured backend that can store credentials
+  pub fn store_backend(&self) -> Option<CredentialBackendKind> {
+    self.config.backends.iter().copied().find(|backend| {
+      matches!(
+        backend,
+        CredentialBackendKind::Keyring | CredentialBackendKind::Helper | CredentialBackendKind::Netrc
+      )
+    })
+  }
+
+  /// Store credentials in the first configured backend that supports writing
+  pub fn store(&self, host: &str, credentials: &Credentials) -> Result<CredentialBackendKind> {
+    let backend = self
+      .store_backend()
+      .context("None of the configured credential backends can store credentials")?;
+    match backend {
+      CredentialBackendKind::Keyring => store_keyring(&self.home, host, credentials)?,
+      CredentialBackendKind::Helper => {
+        run_helper(self.helper()?, "store", host, Some(credentials))?;
+      }
+      CredentialBackendKind::Netrc => write_netrc_entry(
+        &get_netrc_path(&self.home),
+        host,
+        &credentials.username,
+        &credentials.password,
+      )?,
+      CredentialBackendKind::Env | CredentialBackendKind::Gh => bail!("The {backend} backend cannot store credentials"),
+    }
+    Ok(backend)
+  }
+
+  fn helper(&self) -> Result<&str> {
+    self
+      .config
+      .helper
+      .as_deref()
+      .filter(|helper| !helper.trim().is_empty())
+      .context("The 'helper' backend is enabled but no helper command is configured in creds.toml")
+  }
+
+  fn lookup(
+    &self,
+    backend: CredentialBackendKind,
+    service: CredentialService,
+    host: &str,
+  ) -> Result<Option<CredentialMatch>> {
+    let found = |credentials, detail: Option<String>| {
+      Some(CredentialMatch {
+        credentials,
+        backend,
+        detail,
+      })
+    };
+
+    Ok(match backend {
+      CredentialBackendKind::Env => credentials_from_env(service, host, |key| std::env::var(key).ok())
+        .and_then(|(credentials, variable)| found(credentials, Some(variable.to_string()))),
+      CredentialBackendKind::Helper => {
+        let helper = self.helper()?;
+        run_helper(helper, "get", host, None)?.and_then(|credentials| found(credentials, Some(helper.to_string())))
+      }
+      CredentialBackendKind::Keyring => {
+        lookup_keyring(&self.home, host)?.and_then(|credentials| found(credentials, None))
+      }
+      CredentialBackendKind::Gh => match service {
+        CredentialService::GitHub => lookup_gh(host)?.and_then(|credentials| found(credentials, None)),
+        CredentialService::GitHubRefreshToken | CredentialService::Jira => None,
+      },
+      CredentialBackendKind::Netrc => {
+        let netrc_path = get_netrc_path(&self.home);
+        if netrc_path.exists() {
+          parse_netrc_file(&netrc_path, host)?.and_then(|credentials| found(credentials, None))
+        } else {
+          None
+        }
+      }
+    })
+  }
+}
+
+/// Read credentials for `service` on `host` from environment variables using
+/// `get_var`
+///
+/// Returns the credentials and the name of the token variable. The GitHub
+/// token is only used for github.com, so it is never sent to a GitHub
+/// Enterprise host.
+pub fn credentials_from_env(
+  service: CredentialService,
+  host: &str,
+  get_var: impl Fn(&str) -> Option<String>,
+) -> Option<(Credentials, &'static str)> {
+  let present = |key: &str| get_var(key).filter(|value| !value.is_empty());
+  match service {
+    CredentialService::GitHub => {
+      if !is_github_dot_com(host) {
+        return None;
+      }
+      let password = present(ENV_GITHUB_TOKEN)?;
+      let username = present(ENV_GITHUB_USER).unwrap_or_else(|| GITHUB_TOKEN_USER.to_string());
+      Some((Credentials { username, password }, ENV_GITHUB_TOKEN))
+    }
+    CredentialService::Jira => {
+      let password = present(ENV_JIRA_TOKEN)?;
+      // Jira authenticates with the account email, so the token alone is not
+      // enough
+      let Some(username) = present(ENV_JIRA_USER) else {
+        warn!("{ENV_JIRA_TOKEN} is set but {ENV_JIRA_USER} is not; ignoring it");
+        return None;
+      };
+      Some((Credentials { username, password }, ENV_JIRA_TOKEN))
+    }
+    CredentialService::GitHubRefreshToken => None,
+  }
+}
+
+/// Whether `host` (optionally with a scheme) is github.com or its API host
+fn is_github_dot_com(host: &str) -> bool {
+  let host = host.split_once("://").map_or(host, |(_, rest)| rest);
+  let host = host.split(['/', ':']).next().unwrap_or_default();
+  host.eq_ignore_ascii_case("github.com") || host.eq_ignore_ascii_case("api.github.com")
+}
+
+/// Parse the `key=value` lines printed by a git-credential helper
+pub fn parse_helper_output(output: &str) -> Option<Credentials> {
+  let mut username = None;
+  let mut password = None;
+  for line in output.lines() {
+    match line.split_once('=') {
+      Some(("username", value)) => username = Some(value.to_string()),
+      Some(("password", value)) => password = Some(value.to_string()),
+      _ => {}
+    }
+  }
+  match (username, password) {
+    (Some(username), Some(password)) if !password.is_empty() => Some(Credentials { username, password }),
+    _ => None,
+  }
+}
+
+/// Run a git-credential style helper with `action` (`get` or `store`)
+fn run_helper(
+  helper: &str,
+  action: &str,
+  host: &str,
+  credentials: Option<&Credentials>,
+) -> Result<Option<Credentials>> {
+  let command_line = format!("{helper} {action}");
+  #[cfg(windows)]
+  let mut command = {
+    let mut command = Command::new("cmd");
+    command.args(["/C", &command_line]);
+    command
+  };
+  #[cfg(not(windows))]
+  let mut command = {
+    let mut command = Command::new("sh");
+    command.args(["-c", &command_line]);
+    command
+  };
+
+  let mut child = command
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::inherit())
+    .spawn()
+    .with_context(|| format!("Failed to run credential helper '{helper}'"))?;
+
+  let mut request = format!("protocol=https\nhost={host}\n");
+  if let Some(credentials) = credentials {
+    request.push_str(&format!(
+      "username={}\npassword={}\n",
+      credentials.username, credentials.password
+    ));
+  }
+  request.push('\n');
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin
+      .write_all(request.as_bytes())
+      .context("Failed to write to credential helper")?;
+  }
+
+  let output = child
+    .wait_with_output()
+    .with_context(|| format!("Failed to run credential helper '{helper}'"))?;
+  if !output.status.success() {
+    bail!("Credential helper '{helper} {action}' exited with {}", output.status);
+  }
+
+  Ok(parse_helper_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Look up credentials in the Secret Service via `secret-tool`
+#[cfg(not(windows))]
+fn lookup_keyring(_home: &Path, host: &str) -> Result<Option<Credentials>> {
+  let output = match Command::new("secret-tool")
+    .args([
+      "search",
+      "--all",
+      "--unlock",
+      "service",
+      KEYRING_SERVICE,
+      "machine",
+      host,
+    ])
+    .stdin(Stdio::null())
+    .stderr(Stdio::null())
+    .output()
+  {
+    Ok(output) => output,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+      bail!("secret-tool was not found; install libsecret-tools to use the keyring backend")
+    }
+    Err(e) => return Err(e).context("Failed to run secret-tool"),
+  };
+
+  // secret-tool exits with an error when nothing matches
+  if !output.status.success() {
+    return Ok(None);
+  }
+  Ok(parse_secret_tool_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Look up credentials in Windows Credential Manager
+#[cfg(windows)]
+fn lookup_keyring(_home: &Path, host: &str) -> Result<Option<Credentials>> {
+  crate::creds::platform::read_credential_manager(host)
+}
+
+/// Store credentials in the Secret Service via `secret-tool`
+#[cfg(not(windows))]
+fn store_keyring(_home: &Path, host: &str, credentials: &Credentials) -> Result<()> {
+  let mut child = Command::new("secret-tool")
+    .args([
+      "store",
+      &format!("--label=twig credentials for {host}"),
+      "service",
+      KEYRING_SERVICE,
+      "machine",
+      host,
+      "login",
+      &credentials.username,
+    ])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .spawn()
+    .context("Failed to run secret-tool; install libsecret-tools to use the keyring backend")?;
+
+  if let Some(mut stdin) = child.stdin.take() {
+    stdin
+      .write_all(credentials.password.as_bytes())
+      .context("Failed to write to secret-tool")?;
+  }
+  let status = child.wait().context("Failed to run secret-tool")?;
+  if !status.success() {
+    bail!("secret-tool store exited with {status}");
+  }
+  Ok(())
+}
+
+/// Store credentials in Windows Credential Manager
+#[cfg(windows)]
+fn store_keyring(home: &Path, host: &str, credentials: &Credentials) -> Result<()> {
+  get_credential_provider(home).store_credentials(host, credentials)
+}
+
+/// Parse `secret-tool search` output, which lists `attribute.<name> = value`
+/// and `secret = value` lines for each matching item
+pub fn parse_secret_tool_output(output: &str) -> Option<Credentials> {
+  let mut username = None;
+  let mut password = None;
+  for line in output.lines() {
+    match line.split_once(" = ") {
+      Some(("attribute.login", value)) => username = Some(value.to_string()),
+      Some(("secret", value)) => password = Some(value.to_string()),
+      _ => {}
+    }
+    if username.is_some() && password.is_some() {
+      break;
+    }
+  }
+  Some(Credentials {
+    username: username?,
+    password: password.filter(|password| !password.is_empty())?,
+  })
+}
+
+/// Reuse the token stored by the GitHub CLI for `host`
+fn lookup_gh(host: &str) -> Result<Option<Credentials>> {
+  let output = match Command::new("gh")
+    .args(["auth", "token", "--hostname", host])
+    .stdin(Stdio::null())
+    .stderr(Stdio::null())
+    .output()
+  {
+    Ok(output) => output,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+      debug!("gh is not installed");
+      return Ok(None);
+    }
+    Err(e) => return Err(e).context("Failed to run gh auth token"),
+  };
+
+  // gh exits with an error when not logged in to the host
+  let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  if !output.status.success() || token.is_empty() {
+    return Ok(None);
+  }
+
+  let username = gh_hosts_path()
+    .and_then(|path| std::fs::read_to_string(path).ok())
+    .and_then(|hosts| parse_gh_hosts_user(&hosts, host))
+    .unwrap_or_else(|| GITHUB_TOKEN_USER.to_string());
+
+  Ok(Some(Credentials {
+    username,
+    password: token,
+  }))
+}
+
+/// Location of the GitHub CLI's `hosts.yml`
+fn gh_hosts_path() -> Option<PathBuf> {
+  if let Some(dir) = std::env::var_os("GH_CONFIG_DIR") {
+    return Some(PathBuf::from(dir).join("hosts.yml"));
+  }
+  directories::BaseDirs::new().map(|dirs| {
+    #[cfg(windows)]
+    let config_dir = dirs.config_dir().join("GitHub CLI");
+    #[cfg(not(windows))]
+    let config_dir = dirs.home_dir().join(".config").join("gh");
+    config_dir.join("hosts.yml")
+  })
+}
+
+/// Find the `user:` recorded for `host` in the GitHub CLI's `hosts.yml`
+pub fn parse_gh_hosts_user(hosts: &str, host: &str) -> Option<String> {
+  let mut in_host = false;
+  for line in hosts.lines() {
+    if !line.starts_with(' ') && !line.starts_with('\t') {
+      in_host = line.trim_end().trim_end_matches(':') == host;
+      continue;
+    }
+    // Only look at keys directly below the host, not nested per-user entries
+    let indent = line.len() - line.trim_start().len();
+    if in_host
+      && indent <= 4
+      && let Some(user) = line.trim().strip_prefix("user:")
+    {
+      return Some(user.trim().trim_matches('"').to_string()).filter(|user| !user.is_empty());
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    move |key| {
+      vars
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value.to_string())
+    }
+  }
+
+  #[test]
+  fn test_credentials_from_env() {
+    let (github, variable) = credentials_from_env(
+      CredentialService::GitHub,
+      "github.com",
+      env(&[(ENV_GITHUB_TOKEN, "ghp_123")]),
+    )
+    .unwrap();
+    assert_eq!(variable, ENV_GITHUB_TOKEN);
+    assert_eq!(github.username, GITHUB_TOKEN_USER);
+    assert_eq!(github.password, "ghp_123");
+
+    // Jira needs the account email alongside the token
+    assert!(
+      credentials_from_env(
+        CredentialService::Jira,
+        "acme.atlassian.net",
+        env(&[(ENV_JIRA_TOKEN, "secret")])
+      )
+      .is_none()
+    );
+    let (jira, _) = credentials_from_env(
+      CredentialService::Jira,
+      "acme.atlassian.net",
+      env(&[(ENV_JIRA_TOKEN, "secret"), (ENV_JIRA_USER, "me@example.com")]),
+    )
+    .unwrap();
+    assert_eq!(jira.username, "me@example.com");
+  }
+
+  #[test]
+  fn test_github_env_token_is_scoped_to_github_dot_com() {
+    let vars = env(&[(ENV_GITHUB_TOKEN, "ghp_123")]);
+    assert!(credentials_from_env(CredentialService::GitHub, "https://api.github.com", &vars).is_some());
+    assert!(credentials_from_env(CredentialService::GitHub, "GitHub.com", &vars).is_some());
+    assert!(credentials_from_env(CredentialService::GitHub, "ghe.corp.local", &vars).is_none());
+    assert!(credentials_from_env(CredentialService::GitHub, "github.com.evil.example", &vars).is_none());
+  }
+
+  #[test]
+  fn test_chain_env_backend_skips_enterprise_hosts() -> Result<()> {
+    let home = TempDir::new()?;
+    let chain = CredentialChain::new(
+      home.path(),
+      CredentialsConfig {
+        backends: vec![CredentialBackendKind::Env],
+        helper: None,
+      },
+    );
+    // Whatever the process environment holds, a GHES lookup never reaches it
+    assert!(chain.find(CredentialService::GitHub, "ghe.corp.local")?.is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_helper_output() {
+    let creds = parse_helper_output("protocol=https\nhost=github.com\nusername=octocat\npassword=token\n").unwrap();
+    assert_eq!(creds.username, "octocat");
+    assert_eq!(creds.password, "token");
+    assert!(parse_helper_output("quit=1\n").is_none());
+  }
+
+  #[test]
+  fn test_parse_secret_tool_output() {
+    let output = "[/org/freedesktop/secrets/collection/login/1]\nlabel = twig credentials for github.com\nsecret = \
+                  ghp_abc\ncreated = 2024-01-01 00:00:00\nattribute.service = twig\nattribute.login = octocat\n";
+    let creds = parse_secret_tool_output(output).unwrap();
+    assert_eq!(creds.username, "octocat");
+    assert_eq!(creds.password, "ghp_abc");
+  }
+
+  #[test]
+  fn test_parse_gh_hosts_user() {
+    let hosts = "github.com:\n    git_protocol: ssh\n    users:\n        octocat:\n    user: octocat\nghe.example.com:\n    \
+                 user: corp-user\n";
+    assert_eq!(parse_gh_hosts_user(hosts, "github.com").as_deref(), Some("octocat"));
+    assert_eq!(
+      parse_gh_hosts_user(hosts, "ghe.example.com").as_deref(),
+      Some("corp-user")
+    );
+    assert_eq!(parse_gh_hosts_user(hosts, "other.example.com"), None);
+  }
+
+  #[test]
+  fn test_chain_reports_answering_backend() -> Result<()> {
+    let home = TempDir::new()?;
+    std::fs::write(
+      get_netrc_path(home.path()),
+      "machine example.atlassian.net\n  login me@example.com\n  password token\n",
+    )?;
+    let chain = CredentialChain::new(
+      home.path(),
+      CredentialsConfig {
+        backends: vec![CredentialBackendKind::Netrc],
+        helper: None,
+      },
+    );
+
+    let found = chain.find(CredentialService::Jira, "example.atlassian.net")?.unwrap();
+    assert_eq!(found.backend, CredentialBackendKind::Netrc);
+    assert_eq!(found.credentials.username, "me@example.com");
+    assert!(chain.find(CredentialService::Jira, "other.atlassian.net")?.is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn test_chain_only_returns_netrc_default_on_request() -> Result<()> {
+    let home = TempDir::new()?;
+    std::fs::write(
+      get_netrc_path(home.path()),
+      "machine example.atlassian.net login me password token
+default login anonymous password guest
+",
+    )?;
+    let netrc_only = |backends| CredentialChain::new(home.path(), CredentialsConfig { backends, helper: None });
+
+    let chain = netrc_only(vec![CredentialBackendKind::Netrc]);
+    assert!(chain.find(CredentialService::GitHub, "github.com")?.is_none());
+    assert!(chain.find(CredentialService::Jira, "other.atlassian.net")?.is_none());
+    let default = chain.find_default()?.unwrap();
+    assert_eq!(default.credentials.username, "anonymous");
+    assert_eq!(default.source(), ".netrc file (default entry)");
+
+    // Without the netrc backend the file is not consulted at all
+    assert!(netrc_only(vec![CredentialBackendKind::Env]).find_default()?.is_none());
+    Ok(())
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_chain_uses_helper_command() -> Result<()> {
+    let home = TempDir::new()?;
+    let chain = CredentialChain::new(
+      home.path(),
+      CredentialsConfig {
+        backends: vec![CredentialBackendKind::Helper, CredentialBackendKind::Netrc],
+        helper: Some("cat >/dev/null; printf 'username=bot\\npassword=s3cret\\n'; true".to_string()),
+      },
+    );
+
+    let found = chain.find(CredentialService::GitHub, "github.com")?.unwrap();
+    assert_eq!(found.backend, CredentialBackendKind::Helper);
+    assert_eq!(found.credentials.username, "bot");
+    assert_eq!(found.credentials.password, "s3cret");
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_config() -> Result<()> {
+    let config: CredentialsConfig = toml::from_str("backends = [\"env\", \"gh\", \"keyring\"]\nhelper = \"op\"\n")?;
+    assert_eq!(
+      config.backends,
+      vec![
+        CredentialBackendKind::Env,
+        CredentialBackendKind::Gh,
+        CredentialBackendKind::Keyring
+      ]
+    );
+    assert_eq!(config.helper.as_deref(), Some("op"));
+    assert_eq!(toml::from_str::<CredentialsConfig>("")?, CredentialsConfig::default());
+    Ok(())
+  }
+}
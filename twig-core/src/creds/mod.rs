@@ -5,8 +5,9 @@
 //!
 //! This module provides cross-platform credential management with
 //! platform-specific implementations for Unix (.netrc) and Windows (Windows
-//! Credential Manager).
+//! Credential Manager), and a configurable chain of backends on top of them.
 
+pub mod chain;
 pub mod netrc;
 
 // Platform-specific implementations
@@ -1,5 +1,16 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+//! # .netrc Files
+//!
+//! A lossless parser and editor for `.netrc` files. The file is split into
+//! tokens that keep every byte of the original text, so updating one entry
+//! leaves unrelated entries, comments and formatting exactly as they were.
+//!
+//! The grammar follows the ftp/curl conventions:
+//! - `machine <name>` starts an entry and `default` starts the catch-all entry
+//! - `login`, `password` and `account` take a value that may be double quoted, with `\` escaping the next character
+//! - `macdef <name>` is followed by a macro body that runs until an empty line
+//! - `#` starts a comment that runs to the end of the line
+
+use std::fmt;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
@@ -13,123 +24,411 @@ pub fn get_netrc_path(home: &Path) -> PathBuf {
 }
 
 /// Parse a .netrc file for credentials for a specific machine
+///
+/// Only an entry naming the machine matches; the `default` entry is read
+/// separately with [`parse_netrc_default`].
 pub fn parse_netrc_file(path: &Path, target_machine: &str) -> Result<Option<Credentials>> {
-  let file = File::open(path).context("Failed to open .netrc file")?;
-  let reader = BufReader::new(file);
-
-  let mut current_machine = String::new();
-  let mut username = String::new();
-  let mut password = String::new();
-
-  for line in reader.lines() {
-    let line = line.context("Failed to read line from .netrc")?;
-    let parts: Vec<&str> = line.split_whitespace().collect();
-
-    for i in 0..parts.len() {
-      match parts[i] {
-        "machine" if i + 1 < parts.len() => {
-          // If we found credentials for the previous machine, check if it's our target
-          if !current_machine.is_empty() && !username.is_empty() && !password.is_empty() {
-            if current_machine == target_machine {
-              return Ok(Some(Credentials { username, password }));
-            }
-            // Reset for the new machine
-            username = String::new();
-            password = String::new();
+  let content = std::fs::read_to_string(path).context("Failed to read .netrc file")?;
+  Ok(Netrc::parse(&content).credentials(target_machine))
+}
+
+/// Parse a .netrc file for the credentials of its `default` entry
+pub fn parse_netrc_default(path: &Path) -> Result<Option<Credentials>> {
+  let content = std::fs::read_to_string(path).context("Failed to read .netrc file")?;
+  Ok(Netrc::parse(&content).default_credentials())
+}
+
+/// Write or update a .netrc entry for a specific machine
+///
+/// Only the login and password values of the machine's entry change; every
+/// other byte of the file is preserved.
+pub fn write_netrc_entry(path: &Path, machine: &str, username: &str, password: &str) -> Result<()> {
+  let mut netrc = if path.exists() {
+    Netrc::parse(&std::fs::read_to_string(path).context("Failed to read existing .netrc file")?)
+  } else {
+    Netrc::default()
+  };
+
+  netrc.set_credentials(machine, username, password);
+  std::fs::write(path, netrc.to_string()).context("Failed to write .netrc file")?;
+
+  // Set secure permissions on the file
+  #[cfg(unix)]
+  {
+    platform::UnixFilePermissions::set_secure_permissions(path)?;
+  }
+
+  #[cfg(windows)]
+  {
+    // note: this is a no-op on Windows, but we call it for consistency
+    platform::WindowsFilePermissions::set_secure_permissions(path)?;
+  }
+
+  Ok(())
+}
+
+/// Kind of a lexical token in a .netrc file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+  /// Spaces, tabs and newlines
+  Whitespace,
+  /// A `#` comment, up to but not including the newline
+  Comment,
+  /// A bare or double-quoted word
+  Word,
+  /// The body of a `macdef`, including the terminating empty line
+  Macro,
+}
+
+/// A token and its exact source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+  kind: TokenKind,
+  text: String,
+}
+
+impl Token {
+  fn new(kind: TokenKind, text: &str) -> Self {
+    Self {
+      kind,
+      text: text.to_string(),
+    }
+  }
+
+  /// The word's value with quotes and escapes removed
+  fn value(&self) -> String {
+    unquote(&self.text)
+  }
+}
+
+/// A `machine` or `default` entry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetrcEntry {
+  /// Machine name, or `None` for the `default` entry
+  pub machine: Option<String>,
+  pub login: Option<String>,
+  pub password: Option<String>,
+  pub account: Option<String>,
+  /// Token index of the `machine`/`default` keyword
+  start: usize,
+  /// Token index of the machine name, or of the keyword when there is none
+  name_token: usize,
+  login_token: Option<usize>,
+  password_token: Option<usize>,
+}
+
+impl NetrcEntry {
+  /// Whether this is the catch-all `default` entry
+  pub fn is_default(&self) -> bool {
+    self.machine.is_none()
+  }
+
+  /// Login and password, when both are present and non-empty
+  pub fn credentials(&self) -> Option<Credentials> {
+    match (&self.login, &self.password) {
+      (Some(username), Some(password)) if !username.is_empty() && !password.is_empty() => Some(Credentials {
+        username: username.clone(),
+        password: password.clone(),
+      }),
+      _ => None,
+    }
+  }
+}
+
+/// A parsed .netrc file that renders back to its exact source text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Netrc {
+  tokens: Vec<Token>,
+  entries: Vec<NetrcEntry>,
+}
+
+impl Netrc {
+  /// Parse .netrc content. Parsing never fails: unknown keywords are
+  /// skipped and an unterminated quote runs to the end of the file.
+  pub fn parse(content: &str) -> Self {
+    let tokens = tokenize(content);
+    let entries = parse_entries(&tokens);
+    Self { tokens, entries }
+  }
+
+  /// All `machine` and `default` entries in file order
+  pub fn entries(&self) -> &[NetrcEntry] {
+    &self.entries
+  }
+
+  /// The first entry for `machine`. The `default` entry never matches.
+  pub fn find(&self, machine: &str) -> Option<&NetrcEntry> {
+    self
+      .entries
+      .iter()
+      .find(|entry| entry.machine.as_deref() == Some(machine))
+  }
+
+  /// Credentials for `machine`. The `default` entry never matches.
+  pub fn credentials(&self, machine: &str) -> Option<Credentials> {
+    self.find(machine).and_then(NetrcEntry::credentials)
+  }
+
+  /// The catch-all `default` entry, if the file has one
+  pub fn default_entry(&self) -> Option<&NetrcEntry> {
+    self.entries.iter().find(|entry| entry.is_default())
+  }
+
+  /// Credentials of the `default` entry
+  pub fn default_credentials(&self) -> Option<Credentials> {
+    self.default_entry().and_then(NetrcEntry::credentials)
+  }
+
+  /// Set the login and password for `machine`
+  ///
+  /// Existing values are replaced in place. A new entry is added before the
+  /// `default` entry, which must stay last, or at the end of the file.
+  pub fn set_credentials(&mut self, machine: &str, login: &str, password: &str) {
+    let existing = self
+      .entries
+      .iter()
+      .find(|entry| entry.machine.as_deref() == Some(machine))
+      .cloned();
+
+    match existing {
+      Some(entry) => {
+        let mut insertions: Vec<(usize, String)> = Vec::new();
+        match entry.login_token {
+          Some(index) => self.tokens[index].text = quote(login),
+          None => insertions.push((entry.name_token + 1, format!(" login {}", quote(login)))),
+        }
+        match entry.password_token {
+          Some(index) => self.tokens[index].text = quote(password),
+          None => {
+            let after = entry.login_token.unwrap_or(entry.name_token);
+            insertions.push((after + 1, format!(" password {}", quote(password))));
           }
-          current_machine = parts[i + 1].to_string();
         }
-        "login" if i + 1 < parts.len() => {
-          username = parts[i + 1].to_string();
+        // Insert from the back so earlier token indices stay valid; a login
+        // and password inserted at the same spot keep that order
+        for (index, text) in insertions.into_iter().rev() {
+          let index = index.min(self.tokens.len());
+          self.tokens.insert(index, Token::new(TokenKind::Word, &text));
         }
-        "password" if i + 1 < parts.len() => {
-          password = parts[i + 1].to_string();
+      }
+      None => {
+        let block = format!(
+          "machine {}\n  login {}\n  password {}\n",
+          quote(machine),
+          quote(login),
+          quote(password)
+        );
+        match self.default_entry() {
+          Some(default) => {
+            let index = default.start;
+            let at_line_start = index == 0 || self.tokens[index - 1].text.ends_with('\n');
+            let block = if at_line_start { block } else { format!("\n{block}") };
+            self.tokens.insert(index, Token::new(TokenKind::Word, &block));
+          }
+          None => {
+            let rendered = self.to_string();
+            // A macro body without its terminating empty line would swallow
+            // the new entry
+            let separator = match self.tokens.last() {
+              Some(token) if token.kind == TokenKind::Macro && !token.text.ends_with("\n\n") => {
+                if token.text.ends_with('\n') { "\n" } else { "\n\n" }
+              }
+              // Likewise for a quote that is never closed
+              Some(token) if token.kind == TokenKind::Word && is_unterminated_quote(&token.text) => "\"\n",
+              _ if !rendered.is_empty() && !rendered.ends_with('\n') => "\n",
+              _ => "",
+            };
+            self
+              .tokens
+              .push(Token::new(TokenKind::Word, &format!("{separator}{block}")));
+          }
         }
-        _ => {}
       }
     }
-  }
 
-  // Check the last machine in the file
-  if current_machine == target_machine && !username.is_empty() && !password.is_empty() {
-    return Ok(Some(Credentials { username, password }));
+    // Re-parse so token boundaries and entry indices match the new text
+    *self = Self::parse(&self.to_string());
   }
+}
 
-  Ok(None)
+impl fmt::Display for Netrc {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for token in &self.tokens {
+      f.write_str(&token.text)?;
+    }
+    Ok(())
+  }
 }
 
-/// Write or update a .netrc entry for a specific machine
-pub fn write_netrc_entry(path: &Path, machine: &str, username: &str, password: &str) -> Result<()> {
-  // Read existing content if file exists
-  let mut existing_content = String::new();
-  let mut machine_exists = false;
+/// Split .netrc content into tokens whose texts concatenate to the input
+fn tokenize(content: &str) -> Vec<Token> {
+  let mut tokens = Vec::new();
+  let mut rest = content;
+  // Words seen since the last `macdef` keyword: 1 after the keyword, 2 after
+  // the macro name
+  let mut macdef_words = 0;
+  // Whether the next word is the value of a keyword, so `password macdef`
+  // does not start a macro
+  let mut expect_value = false;
+
+  while !rest.is_empty() {
+    if macdef_words == 2 {
+      // The body runs until an empty line (two consecutive newlines)
+      let end = rest.find("\n\n").map_or(rest.len(), |index| index + 2);
+      tokens.push(Token::new(TokenKind::Macro, &rest[..end]));
+      rest = &rest[end..];
+      macdef_words = 0;
+      continue;
+    }
 
-  if path.exists() {
-    existing_content = std::fs::read_to_string(path).context("Failed to read existing .netrc file")?;
+    let first = rest.chars().next().unwrap_or_default();
+    let (kind, len) = if first.is_whitespace() {
+      let len = rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len());
+      (TokenKind::Whitespace, len)
+    } else if first == '#' && !expect_value {
+      // A keyword's value may start with `#`, e.g. `password #secret`
+      (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+    } else if first == '"' {
+      (TokenKind::Word, closing_quote_len(rest).unwrap_or(rest.len()))
+    } else {
+      let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+      (TokenKind::Word, len)
+    };
 
-    // Check if machine already exists
-    machine_exists = existing_content.contains(&format!("machine {machine}"));
+    let token = Token::new(kind, &rest[..len]);
+    if kind == TokenKind::Word {
+      if macdef_words == 1 {
+        macdef_words = 2;
+      } else if expect_value {
+        expect_value = false;
+      } else if token.text == "macdef" {
+        macdef_words = 1;
+      } else {
+        expect_value = matches!(token.text.as_str(), "machine" | "login" | "password" | "account");
+      }
+    }
+    tokens.push(token);
+    rest = &rest[len..];
   }
 
-  if machine_exists {
-    // Update existing entry
-    let lines: Vec<&str> = existing_content.lines().collect();
-    let mut new_content = String::new();
-    let mut skip_until_next_machine = false;
-
-    for line in lines {
-      let trimmed = line.trim();
-
-      if trimmed.starts_with("machine ") {
-        if trimmed == format!("machine {machine}",) {
-          skip_until_next_machine = true;
-          // Add the updated machine entry
-          new_content.push_str(&format!("machine {machine}\n",));
-          new_content.push_str(&format!("  login {username}\n",));
-          new_content.push_str(&format!("  password {password}\n",));
-        } else {
-          skip_until_next_machine = false;
-          new_content.push_str(line);
-          new_content.push('\n');
+  tokens
+}
+
+/// Byte length of the quoted string at the start of `text`, including quotes,
+/// or `None` when the quote is never closed
+fn closing_quote_len(text: &str) -> Option<usize> {
+  let mut escaped = false;
+  for (index, c) in text.char_indices().skip(1) {
+    match c {
+      _ if escaped => escaped = false,
+      '\\' => escaped = true,
+      '"' => return Some(index + 1),
+      _ => {}
+    }
+  }
+  None
+}
+
+/// Whether `text` opens a double quote that is never closed
+fn is_unterminated_quote(text: &str) -> bool {
+  text.starts_with('"') && closing_quote_len(text).is_none()
+}
+
+/// Build entries from the word tokens
+fn parse_entries(tokens: &[Token]) -> Vec<NetrcEntry> {
+  let words: Vec<usize> = tokens
+    .iter()
+    .enumerate()
+    .filter(|(_, token)| token.kind == TokenKind::Word)
+    .map(|(index, _)| index)
+    .collect();
+
+  let mut entries: Vec<NetrcEntry> = Vec::new();
+  let mut position = 0;
+  while position < words.len() {
+    let index = words[position];
+    let value = words.get(position + 1).copied();
+    match tokens[index].text.as_str() {
+      "machine" => {
+        entries.push(NetrcEntry {
+          machine: Some(value.map(|value| tokens[value].value()).unwrap_or_default()),
+          start: index,
+          name_token: value.unwrap_or(index),
+          ..NetrcEntry::default()
+        });
+        position += 2;
+      }
+      "default" => {
+        entries.push(NetrcEntry {
+          start: index,
+          name_token: index,
+          ..NetrcEntry::default()
+        });
+        position += 1;
+      }
+      keyword @ ("login" | "password" | "account") => {
+        if let (Some(entry), Some(value)) = (entries.last_mut(), value) {
+          let text = Some(tokens[value].value());
+          match keyword {
+            "login" => {
+              entry.login = text;
+              entry.login_token = Some(value);
+            }
+            "password" => {
+              entry.password = text;
+              entry.password_token = Some(value);
+            }
+            _ => entry.account = text,
+          }
         }
-      } else if !skip_until_next_machine {
-        new_content.push_str(line);
-        new_content.push('\n');
+        position += 2;
       }
+      // The macro name; the body is a separate token
+      "macdef" => position += 2,
+      _ => position += 1,
     }
+  }
 
-    std::fs::write(path, new_content).context("Failed to write updated .netrc file")?;
-  } else {
-    // Append new entry
-    let mut file = std::fs::OpenOptions::new()
-      .create(true)
-      .append(true)
-      .open(path)
-      .context("Failed to open .netrc file for writing")?;
-
-    // Add a newline if file exists and doesn't end with one
-    if path.metadata()?.len() > 0 && !existing_content.ends_with('\n') {
-      writeln!(file)?;
-    }
+  entries
+}
 
-    writeln!(file, "machine {machine}",)?;
-    writeln!(file, "  login {username}",)?;
-    writeln!(file, "  password {password}",)?;
+/// Remove surrounding double quotes and backslash escapes
+fn unquote(text: &str) -> String {
+  let Some(inner) = text.strip_prefix('"') else {
+    return text.to_string();
+  };
+  let inner = inner.strip_suffix('"').unwrap_or(inner);
+  let mut value = String::with_capacity(inner.len());
+  let mut chars = inner.chars();
+  while let Some(c) = chars.next() {
+    if c == '\\' {
+      if let Some(escaped) = chars.next() {
+        value.push(escaped);
+      }
+    } else {
+      value.push(c);
+    }
   }
+  value
+}
 
-  // Set secure permissions on the file
-  #[cfg(unix)]
-  {
-    platform::UnixFilePermissions::set_secure_permissions(path)?;
+/// Quote a value when it would not survive as a bare word
+fn quote(value: &str) -> String {
+  let needs_quotes =
+    value.is_empty() || value.starts_with(['"', '#']) || value.chars().any(|c| c.is_whitespace() || c == '\\');
+  if !needs_quotes {
+    return value.to_string();
   }
-
-  #[cfg(windows)]
-  {
-    // note: this is a no-op on Windows, but we call it for consistency
-    platform::WindowsFilePermissions::set_secure_permissions(path)?;
+  let mut quoted = String::with_capacity(value.len() + 2);
+  quoted.push('"');
+  for c in value.chars() {
+    if matches!(c, '"' | '\\') {
+      quoted.push('\\');
+    }
+    quoted.push(c);
   }
-
-  Ok(())
+  quoted.push('"');
+  quoted
 }
 
 /// Normalizes a Jira host URL by removing protocol prefixes and trailing
@@ -148,6 +447,8 @@ pub fn write_netrc_entry(path: &Path, machine: &str, username: &str, password: &
 /// # Examples
 ///
 /// ```
+/// use twig_core::netrc::normalize_host;
+///
 /// let host1 = normalize_host("https://company.atlassian.net/");
 /// assert_eq!(host1, "company.atlassian.net");
 ///
@@ -503,6 +804,200 @@ machine github.com
     assert_eq!(result, "localhost:8080");
   }
 
+  #[test]
+  fn test_netrc_round_trips_tricky_content() {
+    let content = "# personal tokens\r\nmachine github.com login octocat password \"p@ss word\" # inline\n\n\tmacdef \
+                   init\nmachine evil.com login x password y\n\nmachine \"my host\" account ops login \"a\\\"b\" \
+                   password c\ndefault login anonymous password guest";
+
+    let netrc = Netrc::parse(content);
+
+    assert_eq!(netrc.to_string(), content);
+    let hosts: Vec<_> = netrc.entries().iter().map(|entry| entry.machine.clone()).collect();
+    assert_eq!(
+      hosts,
+      vec![Some("github.com".to_string()), Some("my host".to_string()), None]
+    );
+    let github = netrc.credentials("github.com").unwrap();
+    assert_eq!(github.password, "p@ss word");
+    let quoted = netrc.find("my host").unwrap();
+    assert_eq!(quoted.login.as_deref(), Some("a\"b"));
+    assert_eq!(quoted.account.as_deref(), Some("ops"));
+    // The macro body is not parsed as entries, and unknown hosts do not match
+    // the default entry
+    assert!(netrc.credentials("evil.com").is_none());
+    assert_eq!(netrc.default_credentials().unwrap().username, "anonymous");
+  }
+
+  #[test]
+  fn test_parse_netrc_ignores_comments() {
+    let netrc =
+      Netrc::parse("# machine github.com login old password old\nmachine github.com login new password new\n");
+    assert_eq!(netrc.credentials("github.com").unwrap().username, "new");
+  }
+
+  #[test]
+  fn test_parse_netrc_hash_prefixed_values() {
+    let content = "machine github.com login #me password #s3cret # comment\nmachine example.com login a password b\n";
+    let netrc = Netrc::parse(content);
+
+    assert_eq!(netrc.to_string(), content);
+    let github = netrc.credentials("github.com").unwrap();
+    assert_eq!(github.username, "#me");
+    assert_eq!(github.password, "#s3cret");
+    assert_eq!(netrc.credentials("example.com").unwrap().username, "a");
+  }
+
+  #[test]
+  fn test_set_credentials_preserves_formatting() {
+    let content =
+      "# work\nmachine github.com\n\tlogin   old # keep me\n\tpassword old\n\nmachine example.com login a password b\n";
+    let mut netrc = Netrc::parse(content);
+
+    netrc.set_credentials("github.com", "new", "s3cret token");
+
+    assert_eq!(
+      netrc.to_string(),
+      "# work\nmachine github.com\n\tlogin   new # keep me\n\tpassword \"s3cret token\"\n\nmachine example.com login a \
+       password b\n"
+    );
+  }
+
+  #[test]
+  fn test_set_credentials_does_not_match_similar_hosts() {
+    let content =
+      "machine github.com.evil.example login evil password evil\nmachine api.github.com login api password api\n";
+    let mut netrc = Netrc::parse(content);
+
+    netrc.set_credentials("github.com", "octocat", "token");
+
+    assert!(netrc.to_string().starts_with(content));
+    assert_eq!(netrc.credentials("github.com").unwrap().username, "octocat");
+    assert_eq!(netrc.credentials("api.github.com").unwrap().username, "api");
+    assert_eq!(netrc.credentials("github.com.evil.example").unwrap().username, "evil");
+  }
+
+  #[test]
+  fn test_set_credentials_adds_missing_fields_and_keeps_default_last() {
+    let mut netrc = Netrc::parse("machine example.com login user\ndefault login anonymous password guest\n");
+
+    netrc.set_credentials("example.com", "user", "pass");
+    netrc.set_credentials("github.com", "octocat", "token");
+
+    assert_eq!(
+      netrc.to_string(),
+      "machine example.com login user password pass\nmachine github.com\n  login octocat\n  password token\ndefault \
+       login anonymous password guest\n"
+    );
+    assert!(netrc.entries().last().unwrap().is_default());
+  }
+
+  #[test]
+  fn test_set_credentials_after_unterminated_macro() {
+    let mut netrc = Netrc::parse("macdef init\ncd /pub");
+
+    netrc.set_credentials("github.com", "octocat", "token");
+
+    assert_eq!(netrc.credentials("github.com").unwrap().username, "octocat");
+    assert!(netrc.to_string().starts_with("macdef init\ncd /pub\n\n"));
+  }
+
+  mod properties {
+    use proptest::prelude::*;
+
+    use super::super::{Netrc, quote};
+
+    /// Host names that are prefixes, suffixes or substrings of each other
+    const HOSTS: &[&str] = &[
+      "github.com",
+      "api.github.com",
+      "github.com.evil",
+      "atlassian.net",
+      "acme.atlassian.net",
+    ];
+
+    fn host() -> impl Strategy<Value = String> {
+      proptest::sample::select(HOSTS).prop_map(str::to_string)
+    }
+
+    fn value() -> impl Strategy<Value = String> {
+      prop_oneof![
+        "[a-zA-Z0-9@._-]{1,10}",
+        "[a-z #\"\\\\]{0,8}".prop_map(|raw| quote(&raw)),
+        "#[a-zA-Z0-9@._-]{0,8}",
+        Just("macdef".to_string()),
+        Just("default".to_string()),
+      ]
+    }
+
+    fn separator() -> impl Strategy<Value = String> {
+      proptest::sample::select(&[" ", "\t", "\n", "\n\n", "  \n\t", "\r\n"][..]).prop_map(str::to_string)
+    }
+
+    fn fragment() -> impl Strategy<Value = String> {
+      prop_oneof![
+        4 => (host(), separator(), value(), separator(), value()).prop_map(|(host, sep1, login, sep2, password)| {
+          format!("machine {host}{sep1}login {login}{sep2}password {password}\n")
+        }),
+        1 => (host(), value()).prop_map(|(host, account)| format!("machine {host} account {account}\n")),
+        1 => "[ -~]{0,20}".prop_map(|text| format!("# {text}\n")),
+        1 => ("[a-z]{1,6}", "[ -~]{0,20}").prop_map(|(name, body)| format!("macdef {name}\n{body}\n\n")),
+        1 => value().prop_map(|login| format!("default login {login} password x\n")),
+        1 => separator(),
+      ]
+    }
+
+    fn netrc_file() -> impl Strategy<Value = String> {
+      proptest::collection::vec(fragment(), 0..8).prop_map(|fragments| fragments.concat())
+    }
+
+    proptest! {
+      #[test]
+      fn parse_round_trips_any_input(content in "\\PC*") {
+        prop_assert_eq!(Netrc::parse(&content).to_string(), content);
+      }
+
+      #[test]
+      fn parse_round_trips_generated_files(content in netrc_file()) {
+        prop_assert_eq!(Netrc::parse(&content).to_string(), content);
+      }
+
+      #[test]
+      fn hash_prefixed_passwords_are_values(host in host(), password in "#[a-zA-Z0-9@._#-]{0,10}") {
+        let netrc = Netrc::parse(&format!("machine {host} login me password {password}\n"));
+        let credentials = netrc.credentials(&host).expect("credentials");
+        prop_assert_eq!(credentials.password, password);
+      }
+
+      #[test]
+      fn set_credentials_only_touches_target(
+        content in netrc_file(),
+        target in host(),
+        login in "\\PC{1,12}",
+        password in "\\PC{1,12}",
+      ) {
+        let before = Netrc::parse(&content);
+        let mut after = before.clone();
+        after.set_credentials(&target, &login, &password);
+
+        let updated = after.find(&target).expect("target entry");
+        prop_assert_eq!(updated.login.as_deref(), Some(login.as_str()));
+        prop_assert_eq!(updated.password.as_deref(), Some(password.as_str()));
+
+        for other in HOSTS.iter().filter(|host| **host != target) {
+          prop_assert_eq!(
+            before.find(other).map(|entry| (&entry.machine, &entry.login, &entry.password)),
+            after.find(other).map(|entry| (&entry.machine, &entry.login, &entry.password))
+          );
+        }
+
+        // Rendering is stable once written
+        let rendered = after.to_string();
+        prop_assert_eq!(Netrc::parse(&rendered).to_string(), rendered);
+      }
+    }
+  }
+
   /// Helper function to create a test .netrc file
   fn create_test_netrc(content: &str) -> (TempDir, PathBuf) {
     let temp_dir = TempDir::new().expect("Failed to create temp directory");
@@ -89,6 +89,12 @@ impl CredentialProvider for WindowsCredentialProvider {
   }
 }
 
+/// Read credentials for `service` from Windows Credential Manager only,
+/// without the `.netrc` fallback
+pub fn read_credential_manager(service: &str) -> Result<Option<Credentials>> {
+  read_windows_credential(&WindowsCredentialProvider::format_target_name(service))
+}
+
 fn read_windows_credential(target_name: &str) -> Result<Option<Credentials>> {
   let target_name_wide = to_wide(target_name);
   let mut credential_ptr: *mut CREDENTIALW = ptr::null_mut();
@@ -10,6 +10,7 @@ pub mod renderer;
 pub mod repository;
 pub mod switch;
 pub mod tree;
+pub mod worktree;
 
 pub use branches::{
   branch_exists, checkout_branch, current_branch, delete_local_branch, get_local_branches, get_upstream_branch,
@@ -38,5 +39,10 @@ pub use tree::{
   annotate_orphaned_branches, attach_orphans_to_default_root, collect_tree_order, default_root_branch,
   determine_render_root, filter_branch_graph, find_orphaned_branches,
 };
+pub use worktree::{
+  DEFAULT_WORKTREE_PATH_TEMPLATE, WorktreeConfig, WorktreeEntry, WorktreeHooks, escape_branch_name,
+  find_checkout_path_for_branch, find_worktree_for_branch, list_worktree_entries, relocate_worktree,
+  run_post_create_hooks, worktree_has_uncommitted_changes,
+};
 
 pub use crate::github::{GitHubPr, GitHubRepo, GitRemoteScheme};
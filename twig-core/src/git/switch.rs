@@ -42,7 +42,7 @@ pub enum SwitchInput {
 /// Detect the switch input type (branch/Jira/PR) from raw user input.
 pub fn detect_switch_input(jira_parser: Option<&JiraTicketParser>, input: &str) -> SwitchInput {
   // Check for GitHub PR URL
-  if input.contains("github.com")
+  if (input.contains("github.com") || input.starts_with("http"))
     && input.contains("/pull/")
     && let Ok(pr) = GitHubPr::parse(input)
   {
@@ -454,6 +454,7 @@ pub fn try_checkout_remote_branch(repo: &Repository, branch_name: &str) -> Resul
 
 /// Store Jira issue association in repository state.
 pub fn store_jira_association(repo_path: &Path, branch_name: &str, issue_key: &str) -> Result<()> {
+  let _lock = RepoState::lock(repo_path)?;
   let mut repo_state = RepoState::load(repo_path)?;
 
   let now = std::time::SystemTime::now()
@@ -477,6 +478,7 @@ pub fn store_jira_association(repo_path: &Path, branch_name: &str, issue_key: &s
 
 /// Store GitHub PR association in repository state.
 pub fn store_github_pr_association(repo_path: &Path, branch_name: &str, pr_number: u32) -> Result<()> {
+  let _lock = RepoState::lock(repo_path)?;
   let mut repo_state = RepoState::load(repo_path)?;
 
   let now = chrono::Utc::now().to_rfc3339();
@@ -498,6 +500,7 @@ pub fn apply_branch_state_mutations(repo_path: &Path, outcome: &BranchSwitchOutc
     return Ok(());
   }
 
+  let _lock = RepoState::lock(repo_path)?;
   let mut repo_state = RepoState::load(repo_path)?;
 
   if let Some(dependency) = &outcome.state_mutations.dependency {
@@ -1178,6 +1181,7 @@ pub fn checkout_pr_branch(
 
   // Record dependency when a parent is specified.
   if let Some(parent) = &request.parent {
+    let _lock = RepoState::lock(repo_path)?;
     let mut repo_state = RepoState::load(repo_path)?;
     if let Err(e) = repo_state.add_dependency(request.head.branch.clone(), parent.clone()) {
       print_warning(&format!("Failed to add dependency: {e}"));
@@ -1220,6 +1224,10 @@ mod tests {
       detect_switch_input(Some(&parser), "https://github.com/owner/repo/pull/123"),
       SwitchInput::GitHubPrUrl(123)
     );
+    assert_eq!(
+      detect_switch_input(Some(&parser), "https://bitbucket.org/owner/repo/pull/123"),
+      SwitchInput::BranchName("https://bitbucket.org/owner/repo/pull/123".to_string())
+    );
     assert_eq!(detect_switch_input(Some(&parser), "PR#42"), SwitchInput::GitHubPrId(42));
     assert_eq!(
       detect_switch_input(Some(&parser), "feature/branch"),
@@ -1284,6 +1292,37 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn stored_associations_wait_for_the_state_lock() -> Result<()> {
+    let guard = GitRepoTestGuard::new();
+    let repo_path = guard.repo.workdir().expect("workdir").to_path_buf();
+
+    let held = RepoState::lock(&repo_path)?;
+    let writer = {
+      let repo_path = repo_path.clone();
+      std::thread::spawn(move || store_jira_association(&repo_path, "feature/work", "PROJ-123"))
+    };
+
+    // Another process changes the state while the writer waits for the lock
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let mut state = RepoState::load(&repo_path)?;
+    state.add_dependency("feature/work".into(), "main".into())?;
+    state.save(&repo_path)?;
+    drop(held);
+    writer.join().expect("writer thread")?;
+
+    let state = RepoState::load(&repo_path)?;
+    assert_eq!(state.get_dependency_parents("feature/work"), vec!["main"]);
+    assert_eq!(
+      state
+        .get_branch_metadata("feature/work")
+        .and_then(|metadata| metadata.jira_issue.as_deref()),
+      Some("PROJ-123")
+    );
+
+    Ok(())
+  }
+
   #[test]
   fn switches_using_jira_key_and_records_state() -> Result<()> {
     let guard = GitRepoTestGuard::new();
@@ -0,0 +1,614 @@
+//! Worktree discovery and layout helpers built on top of git2.
+//!
+//! Git identifies worktrees by an administrative name that does not have to
+//! match the branch checked out inside them. These helpers resolve worktrees
+//! by branch so commands can accept the name users actually think in, and
+//! decide where new worktrees are placed on disk.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use git2::{Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+
+/// Default worktree path template, relative to the repository's parent
+/// directory.
+pub const DEFAULT_WORKTREE_PATH_TEMPLATE: &str = "{repo}-worktrees/{branch}";
+
+/// Configuration for where worktrees are created and what happens afterwards.
+///
+/// Loaded from `worktree.toml` in twig's config directory, or from
+/// `.twig/worktree.toml` inside a repository to override it for that
+/// repository only.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WorktreeConfig {
+  /// Path template for new worktrees.
+  ///
+  /// Supports the `{repo}` (repository directory name) and `{branch}`
+  /// (escaped branch name) placeholders. Relative templates are resolved
+  /// against the repository's parent directory and a leading `~/` expands to
+  /// the home directory.
+  pub path_template: String,
+
+  /// Hooks run after a worktree has been created.
+  pub hooks: WorktreeHooks,
+}
+
+impl Default for WorktreeConfig {
+  fn default() -> Self {
+    Self {
+      path_template: DEFAULT_WORKTREE_PATH_TEMPLATE.to_string(),
+      hooks: WorktreeHooks::default(),
+    }
+  }
+}
+
+/// Post-create hooks for new worktrees.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WorktreeHooks {
+  /// Files or directories, relative to the repository root, copied into the
+  /// new worktree (e.g. `.env`). Missing entries are skipped.
+  pub copy: Vec<String>,
+
+  /// Shell commands run inside the new worktree, in order (e.g.
+  /// `direnv allow`).
+  pub post_create: Vec<String>,
+}
+
+impl WorktreeConfig {
+  /// Resolve the directory a worktree for `branch` should live in.
+  pub fn worktree_path(&self, repo_path: &Path, branch: &str) -> Result<PathBuf> {
+    if !self.path_template.contains("{branch}") {
+      return Err(anyhow::anyhow!(
+        "Worktree path template '{}' must contain the {{branch}} placeholder",
+        self.path_template
+      ));
+    }
+
+    let repo_name = repo_path.file_name().and_then(|n| n.to_str()).unwrap_or("repo");
+    let rendered = self
+      .path_template
+      .replace("{repo}", repo_name)
+      .replace("{branch}", &escape_branch_name(branch));
+
+    let path = if let Some(rest) = rendered.strip_prefix("~/") {
+      let base_dirs = directories::BaseDirs::new().context("Failed to determine home directory")?;
+      base_dirs.home_dir().join(rest)
+    } else {
+      let path = PathBuf::from(&rendered);
+      if path.is_absolute() {
+        path
+      } else {
+        repo_path.parent().unwrap_or(Path::new(".")).join(path)
+      }
+    };
+
+    Ok(path)
+  }
+
+  /// Resolve the directory for `branch` like [`Self::worktree_path`], adding
+  /// a `-N` suffix while it clashes with one of the `taken` worktree paths.
+  ///
+  /// Escaped branch names keep their case, so `Feature/X` and `feature/x`
+  /// would share a directory on case-insensitive filesystems; paths are
+  /// therefore compared ignoring case.
+  pub fn unique_worktree_path(&self, repo_path: &Path, branch: &str, taken: &[PathBuf]) -> Result<PathBuf> {
+    let path = self.worktree_path(repo_path, branch)?;
+    let is_taken = |candidate: &Path| {
+      let candidate = candidate.to_string_lossy().to_lowercase();
+      taken
+        .iter()
+        .any(|path| path.to_string_lossy().to_lowercase() == candidate)
+    };
+    if !is_taken(&path) {
+      return Ok(path);
+    }
+
+    let base_name = path
+      .file_name()
+      .map(|n| n.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let mut suffix = 2;
+    loop {
+      let candidate = path.with_file_name(format!("{base_name}-{suffix}"));
+      if !is_taken(&candidate) {
+        return Ok(candidate);
+      }
+      suffix += 1;
+    }
+  }
+}
+
+/// Escape a branch name into a single path component.
+///
+/// ASCII letters, digits and `-`, `_`, `.`, `+`, `@`, `=`, `,` are kept as-is;
+/// every other byte (including `/` and `%`) is percent-encoded. The mapping is
+/// injective, so `feature/foo` (`feature%2Ffoo`) and `feature-foo` can never
+/// share a directory.
+pub fn escape_branch_name(branch: &str) -> String {
+  let mut escaped = String::with_capacity(branch.len());
+
+  for byte in branch.bytes() {
+    match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'+' | b'@' | b'=' | b',' => {
+        escaped.push(byte as char);
+      }
+      _ => {
+        let _ = write!(escaped, "%{byte:02X}");
+      }
+    }
+  }
+
+  escaped
+}
+
+/// Run the configured post-create hooks for a freshly created worktree.
+///
+/// Files are copied first, then commands run in order inside the worktree
+/// with `TWIG_REPO_PATH`, `TWIG_WORKTREE_PATH` and `TWIG_BRANCH` set. The
+/// first failing step stops the remaining hooks.
+pub fn run_post_create_hooks(
+  hooks: &WorktreeHooks,
+  repo_path: &Path,
+  worktree_path: &Path,
+  branch: &str,
+) -> Result<()> {
+  for entry in &hooks.copy {
+    let source = repo_path.join(entry);
+    if !source.exists() {
+      continue;
+    }
+    copy_recursively(&source, &worktree_path.join(entry))
+      .with_context(|| format!("Failed to copy '{entry}' into the new worktree"))?;
+  }
+
+  for command in &hooks.post_create {
+    let status = shell_command(command)
+      .current_dir(worktree_path)
+      .env("TWIG_REPO_PATH", repo_path)
+      .env("TWIG_WORKTREE_PATH", worktree_path)
+      .env("TWIG_BRANCH", branch)
+      .status()
+      .with_context(|| format!("Failed to run post-create hook '{command}'"))?;
+
+    if !status.success() {
+      return Err(anyhow::anyhow!("Post-create hook '{command}' exited with {status}"));
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(windows)]
+pub(crate) fn shell_command(command: &str) -> std::process::Command {
+  let mut cmd = std::process::Command::new("cmd");
+  cmd.arg("/C").arg(command);
+  cmd
+}
+
+#[cfg(not(windows))]
+pub(crate) fn shell_command(command: &str) -> std::process::Command {
+  let mut cmd = std::process::Command::new("sh");
+  cmd.arg("-c").arg(command);
+  cmd
+}
+
+fn copy_recursively(source: &Path, destination: &Path) -> Result<()> {
+  if source.is_dir() {
+    fs::create_dir_all(destination)?;
+    for entry in fs::read_dir(source)? {
+      let entry = entry?;
+      copy_recursively(&entry.path(), &destination.join(entry.file_name()))?;
+    }
+  } else {
+    if let Some(parent) = destination.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::copy(source, destination)?;
+  }
+
+  Ok(())
+}
+
+/// Move a linked worktree's checkout to `new_path`, keeping git's links in
+/// both directions intact (the equivalent of `git worktree move`).
+pub fn relocate_worktree(repo: &Repository, name: &str, new_path: &Path) -> Result<()> {
+  let worktree = repo
+    .find_worktree(name)
+    .with_context(|| format!("Failed to open worktree '{name}'"))?;
+  let old_path = worktree.path().to_path_buf();
+
+  if new_path.exists() {
+    return Err(anyhow::anyhow!(
+      "Cannot move worktree '{name}': {} already exists",
+      new_path.display()
+    ));
+  }
+
+  if let Some(parent) = new_path.parent() {
+    fs::create_dir_all(parent).with_context(|| format!("Failed to create directory {}", parent.display()))?;
+  }
+
+  move_directory(&old_path, new_path).with_context(|| {
+    format!(
+      "Failed to move worktree from {} to {}",
+      old_path.display(),
+      new_path.display()
+    )
+  })?;
+
+  // `.git/worktrees/<name>/gitdir` points at the checkout's `.git` file.
+  let admin_dir = repo.path().join("worktrees").join(name);
+  fs::write(
+    admin_dir.join("gitdir"),
+    format!("{}\n", new_path.join(".git").display()),
+  )
+  .with_context(|| format!("Failed to update gitdir link for worktree '{name}'"))?;
+
+  // The checkout's `.git` file points back at the administrative directory.
+  fs::write(new_path.join(".git"), format!("gitdir: {}\n", admin_dir.display()))
+    .with_context(|| format!("Failed to update .git file in {}", new_path.display()))?;
+
+  Ok(())
+}
+
+/// Rename `source` to `destination`, copying and then removing it when they
+/// are on different filesystems.
+fn move_directory(source: &Path, destination: &Path) -> Result<()> {
+  match fs::rename(source, destination) {
+    Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => copy_then_remove(source, destination),
+    result => Ok(result?),
+  }
+}
+
+fn copy_then_remove(source: &Path, destination: &Path) -> Result<()> {
+  // Fails if the destination exists, so only our own partial copy is cleaned up
+  fs::create_dir(destination).with_context(|| format!("Failed to create {}", destination.display()))?;
+  let copied = fs::read_dir(source).map_err(anyhow::Error::from).and_then(|entries| {
+    entries.into_iter().try_for_each(|entry| {
+      let entry = entry?;
+      copy_tree(&entry.path(), &destination.join(entry.file_name()))
+    })
+  });
+  if let Err(e) = copied {
+    // Leave the original checkout as the only copy
+    let _ = fs::remove_dir_all(destination);
+    return Err(e).with_context(|| format!("Failed to copy {}", source.display()));
+  }
+  fs::remove_dir_all(source).with_context(|| format!("Failed to remove {}", source.display()))
+}
+
+/// Copy a directory tree, recreating symlinks rather than following them.
+fn copy_tree(source: &Path, destination: &Path) -> Result<()> {
+  let file_type = fs::symlink_metadata(source)?.file_type();
+  if file_type.is_symlink() {
+    let target = fs::read_link(source)?;
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&target, destination)?;
+    #[cfg(windows)]
+    if source.is_dir() {
+      std::os::windows::fs::symlink_dir(&target, destination)?;
+    } else {
+      std::os::windows::fs::symlink_file(&target, destination)?;
+    }
+  } else if file_type.is_dir() {
+    fs::create_dir(destination)?;
+    for entry in fs::read_dir(source)? {
+      let entry = entry?;
+      copy_tree(&entry.path(), &destination.join(entry.file_name()))?;
+    }
+  } else {
+    fs::copy(source, destination)?;
+  }
+
+  Ok(())
+}
+
+/// A linked worktree together with the branch it currently has checked out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeEntry {
+  /// Git's administrative name for the worktree (`.git/worktrees/<name>`).
+  pub name: String,
+  /// Directory containing the worktree checkout.
+  pub path: PathBuf,
+  /// Branch checked out in the worktree, or `None` for a detached HEAD or a
+  /// worktree whose directory no longer exists.
+  pub branch: Option<String>,
+}
+
+/// List every linked worktree of `repo` along with its checked-out branch.
+///
+/// The main working directory is not included.
+pub fn list_worktree_entries(repo: &Repository) -> Result<Vec<WorktreeEntry>> {
+  let names = repo.worktrees().context("Failed to list worktrees")?;
+  let mut entries = Vec::with_capacity(names.len());
+
+  for name in names.iter().flatten() {
+    let worktree = repo
+      .find_worktree(name)
+      .with_context(|| format!("Failed to open worktree '{name}'"))?;
+    let path = worktree.path().to_path_buf();
+    let branch = worktree_branch(&path);
+
+    entries.push(WorktreeEntry {
+      name: name.to_string(),
+      path,
+      branch,
+    });
+  }
+
+  Ok(entries)
+}
+
+/// Find the linked worktree that has `branch` checked out.
+///
+/// Falls back to matching the worktree's administrative name so worktrees whose
+/// directory has gone missing can still be addressed.
+pub fn find_worktree_for_branch(repo: &Repository, branch: &str) -> Result<Option<WorktreeEntry>> {
+  let entries = list_worktree_entries(repo)?;
+
+  if let Some(entry) = entries.iter().find(|entry| entry.branch.as_deref() == Some(branch)) {
+    return Ok(Some(entry.clone()));
+  }
+
+  Ok(entries.into_iter().find(|entry| entry.name == branch))
+}
+
+/// Find the directory where `branch` is checked out, considering the main
+/// working directory as well as every linked worktree.
+pub fn find_checkout_path_for_branch(repo: &Repository, branch: &str) -> Result<Option<PathBuf>> {
+  if let Some(entry) = list_worktree_entries(repo)?
+    .into_iter()
+    .find(|entry| entry.branch.as_deref() == Some(branch))
+  {
+    return Ok(Some(entry.path));
+  }
+
+  // `commondir` is the main repository's git directory even when `repo` was
+  // opened from inside a linked worktree.
+  let main_repo = Repository::open(repo.commondir()).context("Failed to open main repository")?;
+  if let Some(workdir) = main_repo.workdir()
+    && worktree_branch(workdir).as_deref() == Some(branch)
+  {
+    return Ok(Some(workdir.to_path_buf()));
+  }
+
+  Ok(None)
+}
+
+/// Check whether the worktree at `path` has uncommitted changes.
+///
+/// Untracked files count as changes because removing the worktree would
+/// delete them; ignored files do not.
+pub fn worktree_has_uncommitted_changes<P: AsRef<Path>>(path: P) -> Result<bool> {
+  let path = path.as_ref();
+  let repo = Repository::open(path).with_context(|| format!("Failed to open worktree at {}", path.display()))?;
+
+  let mut options = StatusOptions::new();
+  options
+    .include_untracked(true)
+    .recurse_untracked_dirs(true)
+    .include_ignored(false);
+
+  let statuses = repo
+    .statuses(Some(&mut options))
+    .with_context(|| format!("Failed to read status of worktree at {}", path.display()))?;
+
+  Ok(!statuses.is_empty())
+}
+
+fn worktree_branch(path: &Path) -> Option<String> {
+  let repo = Repository::open(path).ok()?;
+  let head = repo.head().ok()?;
+
+  if !head.is_branch() {
+    return None;
+  }
+
+  head.shorthand().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::TempDir;
+  use twig_test_utils::git::{GitRepoTestGuard, create_commit};
+
+  use super::*;
+
+  fn add_worktree(repo: &Repository, name: &str, branch: &str, dir: &Path) -> PathBuf {
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    let branch_ref = repo.branch(branch, &head, false).unwrap();
+    let path = dir.join(name);
+
+    let mut opts = git2::WorktreeAddOptions::new();
+    opts.reference(Some(branch_ref.get()));
+    repo.worktree(name, &path, Some(&opts)).unwrap();
+
+    path
+  }
+
+  #[test]
+  fn find_worktree_for_branch_matches_checked_out_branch() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "base.txt", "base\n", "initial commit").unwrap();
+    let worktrees_dir = TempDir::new().unwrap();
+
+    let path = add_worktree(&guard.repo, "feature-foo", "feature/foo", worktrees_dir.path());
+
+    let entry = find_worktree_for_branch(&guard.repo, "feature/foo").unwrap().unwrap();
+    assert_eq!(entry.name, "feature-foo");
+    assert_eq!(entry.branch.as_deref(), Some("feature/foo"));
+    assert_eq!(fs::canonicalize(&entry.path).unwrap(), fs::canonicalize(&path).unwrap());
+
+    // The administrative name is accepted as a fallback.
+    let by_name = find_worktree_for_branch(&guard.repo, "feature-foo").unwrap().unwrap();
+    assert_eq!(by_name.name, "feature-foo");
+
+    assert!(find_worktree_for_branch(&guard.repo, "missing").unwrap().is_none());
+  }
+
+  #[test]
+  fn find_checkout_path_for_branch_includes_main_workdir() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "base.txt", "base\n", "initial commit").unwrap();
+    let worktrees_dir = TempDir::new().unwrap();
+
+    let path = add_worktree(&guard.repo, "feature", "feature", worktrees_dir.path());
+    let canonical = |p: &Path| fs::canonicalize(p).unwrap();
+
+    let main = find_checkout_path_for_branch(&guard.repo, "main").unwrap().unwrap();
+    assert_eq!(canonical(&main), canonical(guard.path()));
+
+    // Looking up from inside the linked worktree still finds the main checkout
+    let worktree_repo = Repository::open(&path).unwrap();
+    let main_from_worktree = find_checkout_path_for_branch(&worktree_repo, "main").unwrap().unwrap();
+    assert_eq!(canonical(&main_from_worktree), canonical(guard.path()));
+
+    let feature = find_checkout_path_for_branch(&guard.repo, "feature").unwrap().unwrap();
+    assert_eq!(canonical(&feature), canonical(&path));
+
+    assert!(find_checkout_path_for_branch(&guard.repo, "other").unwrap().is_none());
+  }
+
+  #[test]
+  fn worktree_has_uncommitted_changes_detects_untracked_files() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "base.txt", "base\n", "initial commit").unwrap();
+    let worktrees_dir = TempDir::new().unwrap();
+
+    let path = add_worktree(&guard.repo, "feature", "feature", worktrees_dir.path());
+    assert!(!worktree_has_uncommitted_changes(&path).unwrap());
+
+    fs::write(path.join("scratch.txt"), "wip\n").unwrap();
+    assert!(worktree_has_uncommitted_changes(&path).unwrap());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn copy_then_remove_moves_tree_with_symlinks() {
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join("source");
+    fs::create_dir_all(source.join("nested")).unwrap();
+    fs::write(source.join("nested/file.txt"), "content").unwrap();
+    std::os::unix::fs::symlink("nested/file.txt", source.join("link")).unwrap();
+
+    let destination = dir.path().join("destination");
+    copy_then_remove(&source, &destination).unwrap();
+
+    assert!(!source.exists());
+    assert_eq!(
+      fs::read_to_string(destination.join("nested/file.txt")).unwrap(),
+      "content"
+    );
+    assert_eq!(
+      fs::read_link(destination.join("link")).unwrap(),
+      Path::new("nested/file.txt")
+    );
+  }
+
+  #[test]
+  fn copy_then_remove_keeps_source_when_copy_fails() {
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("file.txt"), "content").unwrap();
+
+    // The destination's parent is a file, so nothing can be created there
+    fs::write(dir.path().join("blocker"), "").unwrap();
+    let destination = dir.path().join("blocker/destination");
+
+    assert!(copy_then_remove(&source, &destination).is_err());
+    assert_eq!(fs::read_to_string(source.join("file.txt")).unwrap(), "content");
+  }
+
+  #[test]
+  fn escape_branch_name_is_collision_free() {
+    assert_eq!(escape_branch_name("feature/foo"), "feature%2Ffoo");
+    assert_eq!(escape_branch_name("feature-foo"), "feature-foo");
+    assert_eq!(escape_branch_name("50%/off"), "50%25%2Foff");
+    assert_ne!(escape_branch_name("a/b"), escape_branch_name("a%2Fb"));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn worktree_path_renders_template() {
+    let repo_path = Path::new("/src/twig");
+
+    let default = WorktreeConfig::default();
+    assert_eq!(
+      default.worktree_path(repo_path, "feature/foo").unwrap(),
+      PathBuf::from("/src/twig-worktrees/feature%2Ffoo")
+    );
+
+    let absolute = WorktreeConfig {
+      path_template: "/wt/{repo}/{branch}".to_string(),
+      ..WorktreeConfig::default()
+    };
+    assert_eq!(
+      absolute.worktree_path(repo_path, "main").unwrap(),
+      PathBuf::from("/wt/twig/main")
+    );
+
+    let missing_branch = WorktreeConfig {
+      path_template: "{repo}-wt".to_string(),
+      ..WorktreeConfig::default()
+    };
+    assert!(missing_branch.worktree_path(repo_path, "main").is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn unique_worktree_path_suffixes_case_collisions() {
+    let repo_path = Path::new("/src/twig");
+    let config = WorktreeConfig::default();
+
+    assert_eq!(
+      config.unique_worktree_path(repo_path, "feature/x", &[]).unwrap(),
+      PathBuf::from("/src/twig-worktrees/feature%2Fx")
+    );
+
+    let taken = vec![
+      PathBuf::from("/src/twig-worktrees/Feature%2FX"),
+      PathBuf::from("/src/twig-worktrees/FEATURE%2FX-2"),
+    ];
+    assert_eq!(
+      config.unique_worktree_path(repo_path, "feature/x", &taken).unwrap(),
+      PathBuf::from("/src/twig-worktrees/feature%2Fx-3")
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn run_post_create_hooks_copies_files_and_runs_commands() {
+    let repo_dir = TempDir::new().unwrap();
+    let worktree_dir = TempDir::new().unwrap();
+    fs::write(repo_dir.path().join(".env"), "SECRET=1\n").unwrap();
+
+    let hooks = WorktreeHooks {
+      copy: vec![".env".to_string(), ".env.missing".to_string()],
+      post_create: vec!["printf '%s' \"$TWIG_BRANCH\" > branch.txt".to_string()],
+    };
+    run_post_create_hooks(&hooks, repo_dir.path(), worktree_dir.path(), "feature/foo").unwrap();
+
+    assert_eq!(
+      fs::read_to_string(worktree_dir.path().join(".env")).unwrap(),
+      "SECRET=1\n"
+    );
+    assert_eq!(
+      fs::read_to_string(worktree_dir.path().join("branch.txt")).unwrap(),
+      "feature/foo"
+    );
+
+    let failing = WorktreeHooks {
+      copy: Vec::new(),
+      post_create: vec!["exit 3".to_string()],
+    };
+    assert!(run_post_create_hooks(&failing, repo_dir.path(), worktree_dir.path(), "main").is_err());
+  }
+}
@@ -7,16 +7,35 @@
 //! the gitoxide project, using structured types and explicit scheme detection
 //! rather than ad-hoc string manipulation.
 
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
 
 use anyhow::{Context, Result};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
-static GITHUB_REPO_REGEX: LazyLock<Regex> =
-  LazyLock::new(|| Regex::new(r"github\.com[/:]([^/]+)/([^/\.]+)").expect("Failed to compile GitHub repo regex"));
+/// Host name of the public GitHub service.
+pub const GITHUB_COM_HOST: &str = "github.com";
+
+/// API base URL of the public GitHub service.
+pub const GITHUB_COM_API_URL: &str = "https://api.github.com";
+
+/// Matches `github.com/owner/repo` without a scheme, including inside local
+/// paths such as `~/src/github.com/owner/repo`.
+static GITHUB_REPO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"(?:^|/)(?:www\.)?github\.com[/:]([^/]+)/([^/\.]+)").expect("Failed to compile GitHub repo regex")
+});
+
+/// Matches remotes on any host: `scheme://[user@]host[:port]/owner/repo` or
+/// SCP-style `user@host:owner/repo`. The host is checked against the known
+/// GitHub hosts separately.
+static REMOTE_REPO_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"^(?:(?:https?|ssh|git)://(?:[^@/]+@)?([^/:]+)(?::\d+)?/|[^@/]+@([^/:]+):/?)([^/]+)/([^/\.]+)")
+    .expect("Failed to compile remote repo regex")
+});
 
 static GITHUB_PR_REGEX: LazyLock<Regex> =
-  LazyLock::new(|| Regex::new(r"github\.com/[^/]+/[^/]+/pull/(\d+)").expect("Failed to compile GitHub PR regex"));
+  LazyLock::new(|| Regex::new(r"[^/]+/[^/]+/pull/(\d+)").expect("Failed to compile GitHub PR regex"));
 
 /// Git remote URL scheme/protocol.
 ///
@@ -86,9 +105,13 @@ impl GitRemoteScheme {
 
 /// Parsed GitHub repository reference.
 ///
-/// Represents the owner and repository name extracted from a GitHub URL.
+/// Represents the host, owner and repository name extracted from a GitHub
+/// URL. The host is `github.com` unless the URL points at a GitHub Enterprise
+/// Server instance.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GitHubRepo {
+  /// Host name of the GitHub instance (e.g. `github.com`)
+  pub host: String,
   /// Repository owner (user or organization)
   pub owner: String,
   /// Repository name
@@ -99,30 +122,65 @@ impl GitHubRepo {
   /// Parse a GitHub URL to extract repository information.
   ///
   /// Supports HTTPS, SSH, and URLs containing additional path segments (e.g.,
-  /// pull request paths). Returns an error when the URL does not resemble a
-  /// GitHub repository path.
+  /// pull request paths). Only `github.com` and the GitHub Enterprise Server
+  /// hosts listed in `github.toml` are accepted (see
+  /// [`GitHubConfig::is_github_host`]). Returns an error when the URL does not
+  /// resemble a GitHub repository path or names another host.
   pub fn parse(url: &str) -> Result<Self> {
-    if let Some(captures) = GITHUB_REPO_REGEX.captures(url) {
-      let owner = captures
+    Self::parse_with_config(url, &GitHubConfig::load_or_default())
+  }
+
+  /// Parse a GitHub URL like [`GitHubRepo::parse`], using `config` for the
+  /// set of known GitHub hosts.
+  pub fn parse_with_config(url: &str, config: &GitHubConfig) -> Result<Self> {
+    let (host, captures) = if let Some(captures) = REMOTE_REPO_REGEX.captures(url) {
+      let host = captures
         .get(1)
-        .expect("capture group 1 must exist")
+        .or_else(|| captures.get(2))
+        .expect("one host capture group must exist")
         .as_str()
-        .to_string();
-      let repo = captures
-        .get(2)
-        .expect("capture group 2 must exist")
-        .as_str()
-        .to_string();
-      Ok(Self { owner, repo })
+        .to_lowercase();
+      (host, [captures.get(3), captures.get(4)])
+    } else if let Some(captures) = GITHUB_REPO_REGEX.captures(url) {
+      (GITHUB_COM_HOST.to_string(), [captures.get(1), captures.get(2)])
+    } else {
+      return Err(anyhow::anyhow!("Could not extract owner and repo from URL: {url}"));
+    };
+
+    let host = if host == "www.github.com" {
+      GITHUB_COM_HOST.to_string()
     } else {
-      Err(anyhow::anyhow!("Could not extract owner and repo from URL: {url}"))
+      host
+    };
+    if !config.is_github_host(&host) {
+      return Err(anyhow::anyhow!(
+        "'{host}' is not a known GitHub host. Add [hosts.\"{host}\"] to github.toml in twig's config directory to use \
+         it as a GitHub Enterprise Server instance."
+      ));
     }
+
+    let [owner, repo] = captures.map(|capture| capture.expect("owner and repo capture groups must exist").as_str());
+    Ok(Self {
+      host,
+      owner: owner.to_string(),
+      repo: repo.to_string(),
+    })
   }
 
   /// Returns the full repository path as `owner/repo`.
   pub fn full_name(&self) -> String {
     format!("{}/{}", self.owner, self.repo)
   }
+
+  /// Returns true when the repository is hosted on `github.com`.
+  pub fn is_github_com(&self) -> bool {
+    self.host == GITHUB_COM_HOST
+  }
+
+  /// Returns the web URL of the repository.
+  pub fn html_url(&self) -> String {
+    format!("https://{}/{}/{}", self.host, self.owner, self.repo)
+  }
 }
 
 /// Parsed GitHub pull request reference.
@@ -130,6 +188,8 @@ impl GitHubRepo {
 /// Contains the repository information plus the PR number.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GitHubPr {
+  /// Host name of the GitHub instance (e.g. `github.com`)
+  pub host: String,
   /// Repository owner (user or organization)
   pub owner: String,
   /// Repository name
@@ -142,10 +202,16 @@ impl GitHubPr {
   /// Parse a GitHub pull request URL.
   ///
   /// Accepts standard pull request URLs and URLs with fragments or query
-  /// parameters. Returns an error if the URL does not contain a valid
-  /// repository path or PR number.
+  /// parameters on the hosts [`GitHubRepo::parse`] accepts. Returns an error
+  /// if the URL does not contain a valid repository path or PR number.
   pub fn parse(url: &str) -> Result<Self> {
-    let repo = GitHubRepo::parse(url)?;
+    Self::parse_with_config(url, &GitHubConfig::load_or_default())
+  }
+
+  /// Parse a GitHub pull request URL like [`GitHubPr::parse`], using `config`
+  /// for the set of known GitHub hosts.
+  pub fn parse_with_config(url: &str, config: &GitHubConfig) -> Result<Self> {
+    let repo = GitHubRepo::parse_with_config(url, config)?;
 
     let number = if let Some(captures) = GITHUB_PR_REGEX.captures(url) {
       let pr_str = captures.get(1).expect("capture group 1 must exist").as_str();
@@ -157,6 +223,7 @@ impl GitHubPr {
     };
 
     Ok(Self {
+      host: repo.host,
       owner: repo.owner,
       repo: repo.repo,
       number,
@@ -166,12 +233,98 @@ impl GitHubPr {
   /// Returns the repository portion of this PR reference.
   pub fn repo(&self) -> GitHubRepo {
     GitHubRepo {
+      host: self.host.clone(),
       owner: self.owner.clone(),
       repo: self.repo.clone(),
     }
   }
 }
 
+/// Per-host GitHub settings loaded from `github.toml`.
+///
+/// ```toml
+/// [hosts."github.example.com"]
+/// api_url = "https://github.example.com/api/v3"
+/// oauth_client_id = "Iv1.0123456789abcdef"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitHubConfig {
+  /// Settings keyed by host name
+  #[serde(default)]
+  pub hosts: BTreeMap<String, GitHubHostConfig>,
+}
+
+/// Settings for a single GitHub host.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitHubHostConfig {
+  /// REST API base URL, overriding the default for the host
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub api_url: Option<String>,
+  /// Client ID of the OAuth app used for device-flow login
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub oauth_client_id: Option<String>,
+  /// Base URL of the OAuth endpoints, overriding `https://<host>`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub oauth_url: Option<String>,
+}
+
+impl GitHubConfig {
+  /// Load `github.toml` from twig's config directory, falling back to the
+  /// defaults when it is missing or cannot be read.
+  pub fn load_or_default() -> Self {
+    crate::config::get_config_dirs()
+      .and_then(|config_dirs| config_dirs.load_github_config())
+      .unwrap_or_else(|e| {
+        tracing::debug!("Using default GitHub config: {e:#}");
+        Self::default()
+      })
+  }
+
+  /// Returns true for `github.com` and the hosts listed in `github.toml`.
+  pub fn is_github_host(&self, host: &str) -> bool {
+    host.eq_ignore_ascii_case(GITHUB_COM_HOST) || self.hosts.keys().any(|known| known.eq_ignore_ascii_case(host))
+  }
+
+  /// Returns the REST API base URL for `host`.
+  ///
+  /// A configured `api_url` wins; otherwise `github.com` maps to
+  /// `https://api.github.com` and any other host to the GitHub Enterprise
+  /// Server default of `https://<host>/api/v3`.
+  pub fn api_url(&self, host: &str) -> String {
+    let host = host.to_lowercase();
+    if let Some(api_url) = self.hosts.get(&host).and_then(|h| h.api_url.as_deref()) {
+      return api_url.trim_end_matches('/').to_string();
+    }
+
+    if host == GITHUB_COM_HOST {
+      GITHUB_COM_API_URL.to_string()
+    } else {
+      format!("https://{host}/api/v3")
+    }
+  }
+
+  /// Returns the base URL of the OAuth endpoints (`/login/device/code`,
+  /// `/login/oauth/access_token`) for `host`.
+  ///
+  /// These live on the web host rather than the API host, for github.com and
+  /// GitHub Enterprise Server alike.
+  pub fn oauth_url(&self, host: &str) -> String {
+    let host = host.to_lowercase();
+    match self.hosts.get(&host).and_then(|h| h.oauth_url.as_deref()) {
+      Some(oauth_url) => oauth_url.trim_end_matches('/').to_string(),
+      None => format!("https://{host}"),
+    }
+  }
+
+  /// Returns the OAuth client ID configured for `host`, if any.
+  pub fn oauth_client_id(&self, host: &str) -> Option<&str> {
+    self
+      .hosts
+      .get(&host.to_lowercase())
+      .and_then(|h| h.oauth_client_id.as_deref())
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -273,6 +426,56 @@ mod tests {
       assert_eq!(repo.repo, "twig");
     }
 
+    fn enterprise_config() -> GitHubConfig {
+      toml::from_str("[hosts.\"github.example.com\"]\n[hosts.\"ghe.corp.local\"]\n").unwrap()
+    }
+
+    #[test]
+    fn parse_enterprise_hosts() {
+      let config = enterprise_config();
+      let repo = GitHubRepo::parse_with_config("https://github.example.com/team/service.git", &config).unwrap();
+      assert_eq!(repo.host, "github.example.com");
+      assert_eq!(repo.full_name(), "team/service");
+      assert!(!repo.is_github_com());
+
+      let repo = GitHubRepo::parse_with_config("git@GHE.corp.local:team/service.git", &config).unwrap();
+      assert_eq!(repo.host, "ghe.corp.local");
+      assert_eq!(repo.full_name(), "team/service");
+
+      let repo = GitHubRepo::parse_with_config("ssh://git@ghe.corp.local:2222/team/service", &config).unwrap();
+      assert_eq!(repo.host, "ghe.corp.local");
+      assert_eq!(repo.full_name(), "team/service");
+
+      assert!(GitHubRepo::parse_with_config("../relative/path", &config).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_unconfigured_hosts() {
+      let config = GitHubConfig::default();
+      for url in [
+        "https://github.example.com/team/service.git",
+        "git@gitlab.com:team/service.git",
+        "https://bitbucket.org/team/service",
+        "https://example.com/github.com/team/service",
+      ] {
+        let err = GitHubRepo::parse_with_config(url, &config).unwrap_err();
+        assert!(err.to_string().contains("not a known GitHub host"), "{url}: {err}");
+      }
+
+      let repo = GitHubRepo::parse_with_config("https://www.github.com/omenien/twig", &config).unwrap();
+      assert!(repo.is_github_com());
+      let repo = GitHubRepo::parse_with_config("github.com/omenien/twig", &config).unwrap();
+      assert!(repo.is_github_com());
+    }
+
+    #[test]
+    fn parse_github_com_sets_host() {
+      let repo = GitHubRepo::parse("git@github.com:omenien/twig.git").unwrap();
+      assert_eq!(repo.host, "github.com");
+      assert!(repo.is_github_com());
+      assert_eq!(repo.html_url(), "https://github.com/omenien/twig");
+    }
+
     #[test]
     fn parse_invalid() {
       assert!(GitHubRepo::parse("https://example.com/not-github").is_err());
@@ -312,6 +515,17 @@ mod tests {
       assert!(GitHubPr::parse("https://github.com/omenien/twig/pull/abc").is_err());
     }
 
+    #[test]
+    fn parse_enterprise() {
+      let config: GitHubConfig = toml::from_str("[hosts.\"github.example.com\"]\n").unwrap();
+      let pr = GitHubPr::parse_with_config("https://github.example.com/team/service/pull/7", &config).unwrap();
+      assert_eq!(pr.host, "github.example.com");
+      assert_eq!(pr.number, 7);
+      assert_eq!(pr.repo().host, "github.example.com");
+
+      assert!(GitHubPr::parse_with_config("https://gitlab.example.com/team/service/pull/7", &config).is_err());
+    }
+
     #[test]
     fn repo_accessor() {
       let pr = GitHubPr::parse("https://github.com/omenien/twig/pull/123").unwrap();
@@ -320,4 +534,35 @@ mod tests {
       assert_eq!(repo.repo, "twig");
     }
   }
+
+  mod github_config {
+    use super::*;
+
+    #[test]
+    fn default_api_urls() {
+      let config = GitHubConfig::default();
+      assert_eq!(config.api_url("github.com"), "https://api.github.com");
+      assert_eq!(config.api_url("ghe.corp.local"), "https://ghe.corp.local/api/v3");
+    }
+
+    #[test]
+    fn configured_api_url_wins() {
+      let config: GitHubConfig =
+        toml::from_str("[hosts.\"ghe.corp.local\"]\napi_url = \"https://api.ghe.corp.local/\"\n").unwrap();
+      assert_eq!(config.api_url("GHE.corp.local"), "https://api.ghe.corp.local");
+      assert_eq!(config.api_url("other.corp.local"), "https://other.corp.local/api/v3");
+    }
+
+    #[test]
+    fn oauth_settings() {
+      let config: GitHubConfig = toml::from_str(
+        "[hosts.\"ghe.corp.local\"]\noauth_client_id = \"Iv1.abc\"\noauth_url = \"http://127.0.0.1:8080/\"\n",
+      )
+      .unwrap();
+      assert_eq!(config.oauth_client_id("GHE.corp.local"), Some("Iv1.abc"));
+      assert_eq!(config.oauth_url("ghe.corp.local"), "http://127.0.0.1:8080");
+      assert_eq!(config.oauth_client_id("github.com"), None);
+      assert_eq!(config.oauth_url("github.com"), "https://github.com");
+    }
+  }
 }
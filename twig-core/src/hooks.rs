@@ -0,0 +1,563 @@
+//! Lifecycle hooks.
+//!
+//! Hooks let users and plugins run custom logic when twig switches branches,
+//! creates a branch, rebases a branch during a cascade, syncs, or prunes.
+//! Handlers are configured per event in `hooks.toml` in twig's config
+//! directory and in `.twig/hooks.toml` inside a repository:
+//!
+//! ```toml
+//! [[post_switch]]
+//! command = "npm ci"
+//!
+//! [[post_sync]]
+//! plugin = "chat"
+//! ```
+//!
+//! A repository's `.twig/hooks.toml` is ignored until the repository is
+//! trusted with `twig hooks trust`; see [`crate::trust`].
+//!
+//! Each handler receives a JSON description of the event ([`HookInvocation`])
+//! on stdin. A `command` runs through the shell in the repository directory;
+//! a `plugin` runs `twig-<name> --twig-hook <event>`. A non-zero exit from a
+//! `pre_*` handler aborts the operation, while failing `post_*` handlers only
+//! produce a warning.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::{env, fmt};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_config_dirs;
+use crate::git::worktree::shell_command;
+use crate::output::print_warning;
+use crate::plugin::discovery::resolve_plugin_path;
+use crate::state::BranchMetadata;
+
+/// Flag Twig passes to a plugin when it handles a hook
+pub const HOOK_FLAG: &str = "--twig-hook";
+
+/// Environment variable naming the event a hook handler runs for. Twig does
+/// not run hooks for commands started by a hook handler while it is set.
+pub const HOOK_EVENT_ENV: &str = "TWIG_HOOK_EVENT";
+
+/// Environment variable that disables hooks when set to `1`
+pub const NO_HOOKS_ENV: &str = "TWIG_NO_HOOKS";
+
+/// A point in twig's lifecycle that hooks can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+  /// Before `twig switch` checks out a branch
+  PreSwitch,
+  /// After `twig switch` checked out a branch
+  PostSwitch,
+  /// After twig created a local branch
+  PostBranchCreate,
+  /// Before `twig cascade` rebases a branch onto a parent
+  PreCascade,
+  /// After `twig cascade` rebased (or failed to rebase) a branch
+  PostCascade,
+  /// After `twig sync` saved new branch associations
+  PostSync,
+  /// Before branches are deleted by a prune
+  PrePrune,
+}
+
+impl HookEvent {
+  /// Every hook event, in lifecycle order
+  pub const ALL: [Self; 7] = [
+    Self::PreSwitch,
+    Self::PostSwitch,
+    Self::PostBranchCreate,
+    Self::PreCascade,
+    Self::PostCascade,
+    Self::PostSync,
+    Self::PrePrune,
+  ];
+
+  /// Name used in configuration files, payloads and [`HOOK_FLAG`]
+  pub const fn name(self) -> &'static str {
+    match self {
+      Self::PreSwitch => "pre_switch",
+      Self::PostSwitch => "post_switch",
+      Self::PostBranchCreate => "post_branch_create",
+      Self::PreCascade => "pre_cascade",
+      Self::PostCascade => "post_cascade",
+      Self::PostSync => "post_sync",
+      Self::PrePrune => "pre_prune",
+    }
+  }
+
+  /// Whether a failing handler aborts the operation
+  pub const fn is_pre(self) -> bool {
+    matches!(self, Self::PreSwitch | Self::PreCascade | Self::PrePrune)
+  }
+}
+
+impl fmt::Display for HookEvent {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.name())
+  }
+}
+
+/// Something that handles a hook event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HookHandler {
+  /// Shell command run in the repository directory
+  Command { command: String },
+  /// Plugin run as `twig-<plugin> --twig-hook <event>`
+  Plugin { plugin: String },
+}
+
+impl fmt::Display for HookHandler {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Command { command } => write!(f, "command '{command}'"),
+      Self::Plugin { plugin } => write!(f, "plugin 'twig-{plugin}'"),
+    }
+  }
+}
+
+/// Hook handlers per event.
+///
+/// Loaded from `hooks.toml` in twig's config directory and from
+/// `.twig/hooks.toml` inside a repository; see
+/// [`ConfigDirs::load_hooks_config`](crate::config::ConfigDirs::load_hooks_config).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub pre_switch: Vec<HookHandler>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub post_switch: Vec<HookHandler>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub post_branch_create: Vec<HookHandler>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub pre_cascade: Vec<HookHandler>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub post_cascade: Vec<HookHandler>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub post_sync: Vec<HookHandler>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub pre_prune: Vec<HookHandler>,
+}
+
+impl HooksConfig {
+  /// Handlers configured for `event`, in the order they run
+  pub fn handlers(&self, event: HookEvent) -> &[HookHandler] {
+    match event {
+      HookEvent::PreSwitch => &self.pre_switch,
+      HookEvent::PostSwitch => &self.post_switch,
+      HookEvent::PostBranchCreate => &self.post_branch_create,
+      HookEvent::PreCascade => &self.pre_cascade,
+      HookEvent::PostCascade => &self.post_cascade,
+      HookEvent::PostSync => &self.post_sync,
+      HookEvent::PrePrune => &self.pre_prune,
+    }
+  }
+
+  fn handlers_mut(&mut self, event: HookEvent) -> &mut Vec<HookHandler> {
+    match event {
+      HookEvent::PreSwitch => &mut self.pre_switch,
+      HookEvent::PostSwitch => &mut self.post_switch,
+      HookEvent::PostBranchCreate => &mut self.post_branch_create,
+      HookEvent::PreCascade => &mut self.pre_cascade,
+      HookEvent::PostCascade => &mut self.post_cascade,
+      HookEvent::PostSync => &mut self.post_sync,
+      HookEvent::PrePrune => &mut self.pre_prune,
+    }
+  }
+
+  /// Append `other`'s handlers after this configuration's, per event
+  pub fn extend(&mut self, mut other: Self) {
+    for event in HookEvent::ALL {
+      let handlers = std::mem::take(other.handlers_mut(event));
+      self.handlers_mut(event).extend(handlers);
+    }
+  }
+
+  /// Whether no handlers are configured at all
+  pub fn is_empty(&self) -> bool {
+    HookEvent::ALL.into_iter().all(|event| self.handlers(event).is_empty())
+  }
+}
+
+/// A branch mentioned in a hook payload, with the issue and PR twig knows
+/// it by
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookBranch {
+  pub branch: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub jira_issue: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub github_pr: Option<u32>,
+}
+
+impl From<&BranchMetadata> for HookBranch {
+  fn from(metadata: &BranchMetadata) -> Self {
+    Self {
+      branch: metadata.branch.clone(),
+      jira_issue: metadata.jira_issue.clone(),
+      github_pr: metadata.github_pr,
+    }
+  }
+}
+
+/// How rebasing a branch during a cascade ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CascadeStatus {
+  /// The branch was rebased onto its parent
+  Rebased,
+  /// The branch already contained its parent
+  UpToDate,
+  /// The rebase failed or was abandoned
+  Failed,
+}
+
+/// Event-specific data sent to hook handlers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookPayload {
+  PreSwitch {
+    /// Branch checked out before the switch, if HEAD was on a branch
+    from: Option<String>,
+    to: String,
+  },
+  PostSwitch {
+    from: Option<String>,
+    to: String,
+  },
+  PostBranchCreate {
+    branch: String,
+    /// Dependency parent recorded for the new branch, if any
+    parent: Option<String>,
+  },
+  PreCascade {
+    branch: String,
+    onto: String,
+    /// Directory the rebase runs in, which differs from the repository when
+    /// the branch is checked out in a worktree
+    work_dir: PathBuf,
+  },
+  PostCascade {
+    branch: String,
+    onto: String,
+    work_dir: PathBuf,
+    status: CascadeStatus,
+  },
+  PostSync {
+    /// Branches newly linked to an issue or PR
+    linked: Vec<HookBranch>,
+    /// Branches whose existing links were updated
+    updated: Vec<HookBranch>,
+    /// Branches whose pull request was merged since the last saved state.
+    /// Merges seen by an earlier sync are not repeated.
+    merged: Vec<HookBranch>,
+  },
+  PrePrune {
+    /// Branches about to be deleted
+    branches: Vec<HookBranch>,
+  },
+}
+
+impl HookPayload {
+  /// The event this payload describes
+  pub const fn event(&self) -> HookEvent {
+    match self {
+      Self::PreSwitch { .. } => HookEvent::PreSwitch,
+      Self::PostSwitch { .. } => HookEvent::PostSwitch,
+      Self::PostBranchCreate { .. } => HookEvent::PostBranchCreate,
+      Self::PreCascade { .. } => HookEvent::PreCascade,
+      Self::PostCascade { .. } => HookEvent::PostCascade,
+      Self::PostSync { .. } => HookEvent::PostSync,
+      Self::PrePrune { .. } => HookEvent::PrePrune,
+    }
+  }
+}
+
+/// The JSON document a hook handler receives on stdin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookInvocation {
+  /// Repository the event happened in
+  pub repo: PathBuf,
+  /// Version of the twig binary running the hook
+  pub twig_version: String,
+  #[serde(flatten)]
+  pub payload: HookPayload,
+}
+
+/// Runs the hooks configured for a repository.
+#[derive(Debug, Clone)]
+pub struct HookRunner {
+  repo_path: PathBuf,
+  config: HooksConfig,
+}
+
+impl HookRunner {
+  /// Create a runner for `repo_path` with an explicit configuration
+  pub fn new(repo_path: impl Into<PathBuf>, config: HooksConfig) -> Self {
+    Self {
+      repo_path: repo_path.into(),
+      config,
+    }
+  }
+
+  /// Load the global and repository hook configuration for `repo_path`.
+  ///
+  /// Hooks are disabled (an empty configuration) when [`NO_HOOKS_ENV`] is
+  /// `1` or when twig itself was started by a hook handler.
+  pub fn for_repo(repo_path: &Path) -> Result<Self> {
+    let disabled = env::var(NO_HOOKS_ENV).is_ok_and(|v| v == "1") || env::var_os(HOOK_EVENT_ENV).is_some();
+    let config = if disabled {
+      HooksConfig::default()
+    } else {
+      get_config_dirs()?.load_hooks_config(repo_path)?
+    };
+    Ok(Self::new(repo_path, config))
+  }
+
+  /// Run every handler for the payload's event, in order.
+  ///
+  /// For `pre_*` events the first failing handler stops the remaining ones
+  /// and its error is returned so the caller can abort. Failing `post_*`
+  /// handlers are reported as warnings and never return an error.
+  pub fn run(&self, payload: HookPayload) -> Result<()> {
+    let event = payload.event();
+    let handlers = self.config.handlers(event);
+    if handlers.is_empty() {
+      return Ok(());
+    }
+
+    let invocation = HookInvocation {
+      repo: self.repo_path.clone(),
+      twig_version: env!("CARGO_PKG_VERSION").to_string(),
+      payload,
+    };
+    let json = serde_json::to_vec(&invocation).context("Failed to serialize hook payload")?;
+
+    for handler in handlers {
+      tracing::debug!("Running {event} hook: {handler}");
+      if let Err(e) = self.run_handler(event, handler, &json) {
+        if event.is_pre() {
+          return Err(e.context(format!("{event} hook aborted the operation")));
+        }
+        print_warning(&format!("{e:#}"));
+      }
+    }
+
+    Ok(())
+  }
+
+  fn run_handler(&self, event: HookEvent, handler: &HookHandler, json: &[u8]) -> Result<()> {
+    let mut cmd = match handler {
+      HookHandler::Command { command } => shell_command(command),
+      HookHandler::Plugin { plugin } => {
+        let path = resolve_plugin_path(&format!("twig-{plugin}")).ok_or_else(|| {
+          anyhow::anyhow!("{event} hook plugin 'twig-{plugin}' was not found in PATH or the plugin directory")
+        })?;
+        let mut cmd = Command::new(path);
+        cmd.arg(HOOK_FLAG).arg(event.name());
+        cmd
+      }
+    };
+
+    cmd
+      .current_dir(&self.repo_path)
+      .env(HOOK_EVENT_ENV, event.name())
+      .env("TWIG_CURRENT_REPO", &self.repo_path)
+      .env("TWIG_VERSION", env!("CARGO_PKG_VERSION"))
+      .stdin(Stdio::piped());
+
+    let mut child = cmd
+      .spawn()
+      .with_context(|| format!("Failed to run {event} hook {handler}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+      // Handlers are free to ignore the payload, which closes the pipe early.
+      if let Err(e) = stdin.write_all(json)
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+      {
+        return Err(e).with_context(|| format!("Failed to send payload to {event} hook {handler}"));
+      }
+    }
+
+    let status = child
+      .wait()
+      .with_context(|| format!("Failed to wait for {event} hook {handler}"))?;
+    if !status.success() {
+      return Err(anyhow::anyhow!("{event} hook {handler} exited with {status}"));
+    }
+
+    Ok(())
+  }
+}
+
+/// Read the hook invocation if the plugin was started with [`HOOK_FLAG`].
+///
+/// Returns `None` for regular invocations. Plugins that handle hooks should
+/// call this before parsing their own arguments and exit once the hook is
+/// handled; a non-zero exit aborts `pre_*` operations.
+pub fn read_hook_invocation() -> Result<Option<HookInvocation>> {
+  if env::args().nth(1).as_deref() != Some(HOOK_FLAG) {
+    return Ok(None);
+  }
+
+  let mut input = String::new();
+  std::io::stdin()
+    .read_to_string(&mut input)
+    .context("Failed to read hook payload from stdin")?;
+  serde_json::from_str(&input)
+    .map(Some)
+    .context("Failed to parse hook payload")
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn config_parses_commands_and_plugins() {
+    let config: HooksConfig =
+      toml::from_str("[[post_switch]]\ncommand = \"npm ci\"\n\n[[post_sync]]\nplugin = \"chat\"\n").unwrap();
+
+    assert_eq!(
+      config.handlers(HookEvent::PostSwitch),
+      [HookHandler::Command {
+        command: "npm ci".to_string()
+      }]
+    );
+    assert_eq!(
+      config.handlers(HookEvent::PostSync),
+      [HookHandler::Plugin {
+        plugin: "chat".to_string()
+      }]
+    );
+    assert!(config.handlers(HookEvent::PreSwitch).is_empty());
+    assert!(toml::from_str::<HooksConfig>("[[post_land]]\ncommand = \"true\"\n").is_err());
+  }
+
+  #[test]
+  fn extend_appends_handlers_per_event() {
+    let command = |c: &str| HookHandler::Command { command: c.to_string() };
+    let mut global = HooksConfig {
+      post_switch: vec![command("global")],
+      ..Default::default()
+    };
+    global.extend(HooksConfig {
+      post_switch: vec![command("repo")],
+      pre_prune: vec![command("check")],
+      ..Default::default()
+    });
+
+    assert_eq!(global.post_switch, vec![command("global"), command("repo")]);
+    assert_eq!(global.pre_prune, vec![command("check")]);
+  }
+
+  #[test]
+  fn invocation_serializes_event_with_payload() {
+    let invocation = HookInvocation {
+      repo: PathBuf::from("/repo"),
+      twig_version: "0.0.0".to_string(),
+      payload: HookPayload::PostSwitch {
+        from: Some("main".to_string()),
+        to: "feature".to_string(),
+      },
+    };
+
+    let json = serde_json::to_value(&invocation).unwrap();
+    assert_eq!(
+      json,
+      serde_json::json!({
+        "repo": "/repo",
+        "twig_version": "0.0.0",
+        "event": "post_switch",
+        "from": "main",
+        "to": "feature",
+      })
+    );
+    assert_eq!(serde_json::from_value::<HookInvocation>(json).unwrap(), invocation);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn run_passes_payload_and_aborts_only_pre_hooks() {
+    let repo_dir = TempDir::new().unwrap();
+    let command = |c: &str| HookHandler::Command { command: c.to_string() };
+    let runner = HookRunner::new(
+      repo_dir.path(),
+      HooksConfig {
+        pre_switch: vec![command("cat > payload.json"), command("exit 3"), command("touch after")],
+        post_switch: vec![command("exit 1"), command("echo $TWIG_HOOK_EVENT > event")],
+        ..Default::default()
+      },
+    );
+
+    let err = runner
+      .run(HookPayload::PreSwitch {
+        from: None,
+        to: "feature".to_string(),
+      })
+      .unwrap_err();
+    assert!(format!("{err:#}").contains("exited with"));
+    assert!(!repo_dir.path().join("after").exists());
+
+    let payload: serde_json::Value =
+      serde_json::from_str(&fs::read_to_string(repo_dir.path().join("payload.json")).unwrap()).unwrap();
+    assert_eq!(payload["event"], "pre_switch");
+    assert_eq!(payload["to"], "feature");
+
+    runner
+      .run(HookPayload::PostSwitch {
+        from: None,
+        to: "feature".to_string(),
+      })
+      .unwrap();
+    assert_eq!(
+      fs::read_to_string(repo_dir.path().join("event")).unwrap().trim(),
+      "post_switch"
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn plugin_handlers_resolve_from_the_plugin_directory() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (_env_guard, _config_dirs) = twig_test_utils::setup_test_env_with_init().unwrap();
+    let bin_dir = get_config_dirs().unwrap().plugin_bin_dir();
+    fs::create_dir_all(&bin_dir).unwrap();
+    let plugin = bin_dir.join("twig-hook-test-notify");
+    fs::write(&plugin, "#!/bin/sh\necho \"$1 $2\" > invoked\n").unwrap();
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let repo_dir = TempDir::new().unwrap();
+    let runner = HookRunner::new(
+      repo_dir.path(),
+      HooksConfig {
+        post_switch: vec![HookHandler::Plugin {
+          plugin: "hook-test-notify".to_string(),
+        }],
+        ..Default::default()
+      },
+    );
+    runner
+      .run(HookPayload::PostSwitch {
+        from: None,
+        to: "feature".to_string(),
+      })
+      .unwrap();
+
+    assert_eq!(
+      fs::read_to_string(repo_dir.path().join("invoked")).unwrap().trim(),
+      format!("{HOOK_FLAG} post_switch")
+    );
+  }
+}
@@ -3,6 +3,7 @@
 //! Provides flexible parsing and normalization of Jira ticket identifiers.
 //! Supports various input formats while maintaining a canonical output format.
 
+use std::collections::BTreeMap;
 use std::sync::LazyLock;
 
 use regex::Regex;
@@ -11,19 +12,88 @@ use thiserror::Error;
 
 /// Configuration for Jira ticket parsing behavior
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct JiraParsingConfig {
   /// The parsing mode to use
   pub mode: JiraParsingMode,
+  /// Saved JQL queries keyed by name, used by `twig jira list <name>`
+  #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+  pub queries: BTreeMap<String, String>,
+  /// Automatic transitions applied on branch and pull request events
+  #[serde(skip_serializing_if = "JiraTransitionRules::is_empty")]
+  pub transitions: JiraTransitionRules,
 }
 
 impl Default for JiraParsingConfig {
   fn default() -> Self {
     Self {
       mode: JiraParsingMode::Flexible,
+      queries: BTreeMap::new(),
+      transitions: JiraTransitionRules::default(),
     }
   }
 }
 
+/// Branch and pull request events that can move a Jira issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JiraLifecycleEvent {
+  /// A branch was created for the issue
+  BranchCreated,
+  /// A pull request was opened or linked for the issue's branch
+  PrOpened,
+  /// The pull request for the issue's branch was merged
+  PrMerged,
+}
+
+impl std::fmt::Display for JiraLifecycleEvent {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::BranchCreated => write!(f, "branch created"),
+      Self::PrOpened => write!(f, "PR opened"),
+      Self::PrMerged => write!(f, "PR merged"),
+    }
+  }
+}
+
+/// Transition targets for lifecycle events, configured under
+/// `[transitions]` in `jira.toml`
+///
+/// Targets are transition names, transition IDs or destination status names,
+/// resolved against the transitions available for the issue.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct JiraTransitionRules {
+  /// Target when a branch is created for an issue, e.g. "In Progress"
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub on_branch_create: Option<String>,
+  /// Target when a pull request is opened, e.g. "In Review"
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub on_pr_open: Option<String>,
+  /// Target when a pull request is merged, e.g. "Done"
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub on_pr_merge: Option<String>,
+  /// Report transitions instead of applying them
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub dry_run: bool,
+}
+
+impl JiraTransitionRules {
+  /// Returns true when no rule is configured
+  pub fn is_empty(&self) -> bool {
+    self.on_branch_create.is_none() && self.on_pr_open.is_none() && self.on_pr_merge.is_none() && !self.dry_run
+  }
+
+  /// Returns the configured target for `event`, if any
+  pub fn target_for(&self, event: JiraLifecycleEvent) -> Option<&str> {
+    match event {
+      JiraLifecycleEvent::BranchCreated => self.on_branch_create.as_deref(),
+      JiraLifecycleEvent::PrOpened => self.on_pr_open.as_deref(),
+      JiraLifecycleEvent::PrMerged => self.on_pr_merge.as_deref(),
+    }
+    .filter(|target| !target.trim().is_empty())
+  }
+}
+
 /// Parsing mode for Jira tickets
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum JiraParsingMode {
@@ -85,6 +155,7 @@ impl JiraTicketParser {
   pub fn new_flexible() -> Self {
     Self::new(JiraParsingConfig {
       mode: JiraParsingMode::Flexible,
+      ..JiraParsingConfig::default()
     })
   }
 
@@ -92,6 +163,7 @@ impl JiraTicketParser {
   pub fn new_strict() -> Self {
     Self::new(JiraParsingConfig {
       mode: JiraParsingMode::Strict,
+      ..JiraParsingConfig::default()
     })
   }
 
@@ -200,6 +272,33 @@ pub fn create_jira_parser() -> Option<JiraTicketParser> {
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_config_with_saved_queries() {
+    let config: JiraParsingConfig =
+      toml::from_str("[queries]\nsprint = \"sprint in openSprints() AND assignee = currentUser()\"\n").unwrap();
+    assert_eq!(config.mode, JiraParsingMode::Flexible);
+    assert_eq!(
+      config.queries.get("sprint").map(String::as_str),
+      Some("sprint in openSprints() AND assignee = currentUser()")
+    );
+
+    let legacy: JiraParsingConfig = toml::from_str("mode = \"Strict\"\n").unwrap();
+    assert_eq!(legacy.mode, JiraParsingMode::Strict);
+    assert!(legacy.queries.is_empty());
+    assert!(legacy.transitions.is_empty());
+  }
+
+  #[test]
+  fn test_transition_rules() {
+    let config: JiraParsingConfig =
+      toml::from_str("[transitions]\non_branch_create = \"In Progress\"\non_pr_merge = \"\"\n").unwrap();
+    let rules = &config.transitions;
+    assert_eq!(rules.target_for(JiraLifecycleEvent::BranchCreated), Some("In Progress"));
+    assert_eq!(rules.target_for(JiraLifecycleEvent::PrOpened), None);
+    assert_eq!(rules.target_for(JiraLifecycleEvent::PrMerged), None);
+    assert!(!rules.dry_run);
+  }
+
   #[test]
   fn test_strict_mode_valid_formats() {
     let parser = JiraTicketParser::new_strict();
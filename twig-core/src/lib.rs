@@ -10,6 +10,7 @@ pub mod config;
 pub mod creds;
 pub mod git;
 pub mod github;
+pub mod hooks;
 pub mod jira_parser;
 pub mod output;
 pub mod plugin;
@@ -17,11 +18,13 @@ pub mod prompts;
 pub mod state;
 pub mod text;
 pub mod tree_renderer;
+pub mod trust;
 pub mod url;
 pub mod utils;
 
 // Re-export main types for plugin developers
-pub use config::{ConfigDirs, get_config_dirs};
+pub use config::{ConfigDirs, UpdatesConfig, get_config_dirs};
+pub use creds::chain::{CredentialBackendKind, CredentialChain, CredentialMatch, CredentialService, CredentialsConfig};
 pub use creds::{Credentials, netrc, platform};
 pub use git::switch::{
   BranchBase, BranchBaseResolution, BranchBaseSource, BranchCreationBase, BranchCreationPolicy, BranchParentReference,
@@ -36,17 +39,25 @@ pub use git::{
   checkout_branch, current_branch, delete_local_branch, detect_repository, detect_repository_from_path, get_repository,
   in_git_repository, resolve_to_main_repo_path,
 };
-pub use github::{GitHubPr, GitHubRepo, GitRemoteScheme};
-pub use jira_parser::{JiraParseError, JiraParsingConfig, JiraParsingMode, JiraTicketParser, create_jira_parser};
+pub use github::{GitHubConfig, GitHubHostConfig, GitHubPr, GitHubRepo, GitRemoteScheme};
+pub use hooks::{CascadeStatus, HookBranch, HookEvent, HookHandler, HookPayload, HookRunner, HooksConfig};
+pub use jira_parser::{
+  JiraLifecycleEvent, JiraParseError, JiraParsingConfig, JiraParsingMode, JiraTicketParser, JiraTransitionRules,
+  create_jira_parser,
+};
 pub use output::{ColorMode, cli_styles, format_repo_path, print_error, print_info, print_success, print_warning};
+pub use plugin::manifest::{PluginManifest, PluginSubcommand};
 pub use plugin::{PluginContext, plugin_config_dir, plugin_data_dir};
 pub use prompts::twig_theme;
 pub use state::{
-  BranchDependency, BranchMetadata as StateBranchMetadata, Registry, RepoState, Repository, RootBranch, create_worktree,
+  BranchDependency, BranchMetadata as StateBranchMetadata, Registry, RemoveWorktreeOptions, RepoState, RepoStateLock,
+  Repository, RootBranch, WorktreeMigration, create_worktree, create_worktree_with_config, migrate_worktrees,
+  remove_worktree_for_branch,
 };
 pub use text::{Hyperlink, HyperlinkExt, hyperlink, hyperlinks_disabled, set_hyperlinks_override, truncate_string};
 pub use url::{
   ENV_JIRA_HOST, ensure_url_scheme, resolve_github_repo, resolve_github_repo_from_git2, resolve_jira_base_url,
+  resolve_origin_github_repo,
 };
 pub use utils::{
   filter_stop_words, generate_branch_name_from_issue, get_current_branch_github_pr, get_current_branch_jira_issue,
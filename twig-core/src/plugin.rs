@@ -5,6 +5,10 @@
 //! context when those variables are missing (for example, when a plugin is
 //! executed directly during development).
 
+pub mod discovery;
+pub mod host;
+pub mod manifest;
+
 use std::env;
 use std::path::{Path, PathBuf};
 
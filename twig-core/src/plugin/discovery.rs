@@ -0,0 +1,154 @@
+//! Locating plugin binaries.
+//!
+//! Plugins are `twig-<name>` executables found on `PATH` or in the
+//! twig-managed plugin directory that `twig plugin install` installs into.
+//! Both the CLI and hook dispatch resolve plugins through these helpers so
+//! they agree on which binary a name refers to.
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use crate::config::get_config_dirs;
+
+/// `PATH` followed by the twig-managed plugin directory, so plugins on `PATH`
+/// take precedence over installed ones.
+pub fn plugin_search_path() -> OsString {
+  let path_var = env::var_os("PATH").unwrap_or_default();
+  let Ok(config_dirs) = get_config_dirs() else {
+    return path_var;
+  };
+
+  let paths = env::split_paths(&path_var).chain(std::iter::once(config_dirs.plugin_bin_dir()));
+  env::join_paths(paths).unwrap_or(path_var)
+}
+
+/// Resolve a plugin binary such as `twig-flow` in [`plugin_search_path`]
+/// order, returning a canonical path when found.
+pub fn resolve_plugin_path(binary_name: &str) -> Option<PathBuf> {
+  resolve_plugin_path_from_paths(binary_name, &plugin_search_path())
+}
+
+/// Resolve a plugin binary in the directories of `path_var`, in order.
+pub fn resolve_plugin_path_from_paths(binary_name: &str, path_var: &OsStr) -> Option<PathBuf> {
+  let candidate_names = candidate_filenames(binary_name);
+
+  for directory in env::split_paths(path_var) {
+    if directory.as_os_str().is_empty() || !directory.exists() {
+      continue;
+    }
+
+    for candidate in &candidate_names {
+      let candidate_path = directory.join(candidate);
+      if is_executable(&candidate_path) {
+        return Some(fs::canonicalize(&candidate_path).unwrap_or(candidate_path));
+      }
+    }
+  }
+
+  None
+}
+
+/// Get candidate filenames for a plugin, considering platform-specific
+/// executable extensions.
+///
+/// On non-Windows platforms, this simply returns the plugin name as-is.
+#[cfg(unix)]
+pub fn candidate_filenames(plugin_name: &str) -> Vec<String> {
+  vec![plugin_name.to_string()]
+}
+
+/// Check if a given path is an executable file.
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+  let Ok(metadata) = fs::metadata(path) else {
+    return false;
+  };
+
+  if !metadata.is_file() {
+    return false;
+  }
+
+  use std::os::unix::fs::PermissionsExt;
+  metadata.permissions().mode() & 0o111 != 0
+}
+
+/// Get candidate filenames for a plugin, considering platform-specific
+/// executable extensions.
+///
+/// This provides the Windows-specific behavior of appending `.exe` if not
+/// already present.
+#[cfg(not(unix))]
+pub fn candidate_filenames(plugin_name: &str) -> Vec<String> {
+  let mut names = vec![plugin_name.to_string()];
+
+  if !plugin_name.to_lowercase().ends_with(".exe") {
+    names.push(format!("{plugin_name}.exe"));
+  }
+
+  names
+}
+
+/// Check if a given path is an executable file.
+#[cfg(not(unix))]
+pub fn is_executable(path: &Path) -> bool {
+  fs::metadata(path).map(|meta| meta.is_file()).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn resolve_plugin_path_skips_non_files_and_respects_path_order() {
+    let first_dir = TempDir::new().expect("failed to create temp dir");
+    let second_dir = TempDir::new().expect("failed to create temp dir");
+
+    let non_file = first_dir.path().join("twig-example");
+    fs::create_dir_all(&non_file).expect("failed to create placeholder directory");
+
+    let real_plugin = second_dir.path().join("twig-example");
+    fs::write(&real_plugin, b"#!/bin/sh\necho real\n").unwrap();
+    make_executable(&real_plugin);
+
+    let custom_path = env::join_paths([first_dir.path(), second_dir.path()]).unwrap();
+
+    let resolved = resolve_plugin_path_from_paths("twig-example", &custom_path);
+
+    assert_eq!(resolved, Some(fs::canonicalize(&real_plugin).unwrap()));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn resolve_plugin_path_canonicalizes_symlinks() {
+    use std::os::unix::fs as unix_fs;
+
+    let target_dir = TempDir::new().expect("failed to create temp dir");
+    let binary = target_dir.path().join("twig-example");
+    fs::write(&binary, b"#!/bin/sh\necho linked\n").unwrap();
+    make_executable(&binary);
+
+    let symlink_dir = target_dir.path().join("symlink");
+    unix_fs::symlink(target_dir.path(), &symlink_dir).expect("failed to create symlink");
+
+    let custom_path = env::join_paths([symlink_dir]).unwrap();
+
+    let resolved = resolve_plugin_path_from_paths("twig-example", &custom_path);
+
+    assert_eq!(resolved, Some(fs::canonicalize(&binary).unwrap()));
+  }
+
+  #[cfg_attr(windows, allow(unused))]
+  fn make_executable(path: &Path) {
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+
+      let mut permissions = fs::metadata(path).unwrap().permissions();
+      permissions.set_mode(0o755);
+      fs::set_permissions(path, permissions).unwrap();
+    }
+  }
+}
@@ -0,0 +1,585 @@
+//! Host API for plugins.
+//!
+//! While a plugin runs, the Twig CLI serves a JSON-RPC 2.0 channel the plugin
+//! can call back into instead of re-implementing twig's internals: it exposes
+//! the branch graph, reads and mutates `RepoState` (mutations are applied by
+//! twig one request at a time), and makes authenticated GitHub and Jira
+//! requests with the credentials twig resolves.
+//!
+//! The channel is a Unix domain socket (a loopback TCP socket on Windows)
+//! whose address is passed in [`HOST_ADDR_ENV`]. Messages are newline
+//! delimited JSON-RPC objects. Every connection starts with an
+//! [`INITIALIZE`] request carrying [`HOST_PROTOCOL_VERSION`] and the token
+//! from [`HOST_TOKEN_ENV`]; other requests are rejected until it succeeds.
+//!
+//! ```no_run
+//! use twig_core::plugin::host::HostClient;
+//!
+//! if let Some(mut host) = HostClient::connect_from_env()? {
+//!   let graph = host.branch_graph()?;
+//!   let pr = host.github_request("GET", "/repos/eddieland/twig/pulls/1", None)?;
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::state::{BranchMetadata, RepoState};
+
+/// Version of the host protocol. Bumped on incompatible changes; methods may
+/// be added without a bump and are listed in [`InitializeResult::methods`].
+pub const HOST_PROTOCOL_VERSION: u32 = 1;
+
+/// Environment variable holding the host address, `unix:<path>` or
+/// `tcp:<addr>`
+pub const HOST_ADDR_ENV: &str = "TWIG_HOST_ADDR";
+
+/// Environment variable holding the token that authenticates a connection
+pub const HOST_TOKEN_ENV: &str = "TWIG_HOST_TOKEN";
+
+/// Handshake; must be the first request on a connection
+pub const INITIALIZE: &str = "initialize";
+/// Invocation context ([`HostContext`])
+pub const CONTEXT_GET: &str = "context.get";
+/// Branch dependency graph ([`BranchGraph`])
+pub const GRAPH_GET: &str = "graph.get";
+/// Current `RepoState`
+pub const STATE_GET: &str = "state.get";
+/// Apply [`StateMutation`]s atomically and return the new `RepoState`
+pub const STATE_APPLY: &str = "state.apply";
+/// Authenticated GitHub REST request ([`ApiRequest`])
+pub const GITHUB_REQUEST: &str = "github.request";
+/// Authenticated Jira REST request ([`ApiRequest`])
+pub const JIRA_REQUEST: &str = "jira.request";
+
+/// Every method served by this protocol version
+pub const METHODS: &[&str] = &[
+  INITIALIZE,
+  CONTEXT_GET,
+  GRAPH_GET,
+  STATE_GET,
+  STATE_APPLY,
+  GITHUB_REQUEST,
+  JIRA_REQUEST,
+];
+
+/// JSON-RPC error codes used by the host
+pub mod error_codes {
+  pub const PARSE_ERROR: i64 = -32700;
+  pub const INVALID_REQUEST: i64 = -32600;
+  pub const METHOD_NOT_FOUND: i64 = -32601;
+  pub const INVALID_PARAMS: i64 = -32602;
+  pub const INTERNAL_ERROR: i64 = -32603;
+  /// The connection has not completed the handshake, or the token is wrong
+  pub const NOT_INITIALIZED: i64 = -32000;
+  /// The client speaks a protocol version the host does not support
+  pub const UNSUPPORTED_VERSION: i64 = -32001;
+  /// The request was valid but the operation failed (e.g. a dependency cycle)
+  pub const OPERATION_FAILED: i64 = -32002;
+}
+
+/// A JSON-RPC request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcRequest {
+  pub jsonrpc: String,
+  pub id: Value,
+  pub method: String,
+  #[serde(default, skip_serializing_if = "Value::is_null")]
+  pub params: Value,
+}
+
+/// A JSON-RPC response carrying either a result or an error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcResponse {
+  pub jsonrpc: String,
+  pub id: Value,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub result: Option<Value>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+  /// Successful response to request `id`
+  pub fn success(id: Value, result: Value) -> Self {
+    Self {
+      jsonrpc: "2.0".to_string(),
+      id,
+      result: Some(result),
+      error: None,
+    }
+  }
+
+  /// Error response to request `id`
+  pub fn failure(id: Value, code: i64, message: impl Into<String>) -> Self {
+    Self {
+      jsonrpc: "2.0".to_string(),
+      id,
+      result: None,
+      error: Some(RpcError {
+        code,
+        message: message.into(),
+      }),
+    }
+  }
+}
+
+/// Error object of a failed JSON-RPC request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("{message} (code {code})")]
+pub struct RpcError {
+  pub code: i64,
+  pub message: String,
+}
+
+/// Parameters of [`INITIALIZE`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InitializeParams {
+  pub protocol_version: u32,
+  pub token: String,
+}
+
+/// Result of [`INITIALIZE`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InitializeResult {
+  pub protocol_version: u32,
+  pub twig_version: String,
+  pub methods: Vec<String>,
+}
+
+/// Result of [`CONTEXT_GET`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostContext {
+  pub repo: Option<PathBuf>,
+  pub branch: Option<String>,
+  pub config_dir: PathBuf,
+  pub data_dir: PathBuf,
+  pub twig_version: String,
+}
+
+/// Result of [`GRAPH_GET`]: every branch twig tracks, with its dependencies
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchGraph {
+  pub branches: Vec<GraphBranch>,
+}
+
+/// A branch in the [`BranchGraph`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphBranch {
+  pub name: String,
+  pub parents: Vec<String>,
+  pub children: Vec<String>,
+  pub is_root: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub jira_issue: Option<String>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub github_pr: Option<u32>,
+}
+
+impl BranchGraph {
+  /// Build the graph from the local branches and twig's state. Branches only
+  /// known to the state (e.g. a parent that was deleted) are included too.
+  pub fn from_state(local_branches: &[String], state: &RepoState) -> Self {
+    let mut names: Vec<String> = local_branches.to_vec();
+    for dependency in state.list_dependencies() {
+      names.push(dependency.child.clone());
+      names.push(dependency.parent.clone());
+    }
+    names.extend(state.get_root_branches());
+    names.sort();
+    names.dedup();
+
+    let branches = names
+      .into_iter()
+      .map(|name| {
+        let metadata = state.get_branch_metadata(&name);
+        GraphBranch {
+          parents: state
+            .get_dependency_parents(&name)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+          children: state
+            .get_dependency_children(&name)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+          is_root: state.is_root(&name),
+          jira_issue: metadata.and_then(|m| m.jira_issue.clone()),
+          github_pr: metadata.and_then(|m| m.github_pr),
+          name,
+        }
+      })
+      .collect();
+
+    Self { branches }
+  }
+}
+
+/// A change to `RepoState` requested through [`STATE_APPLY`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum StateMutation {
+  AddDependency {
+    child: String,
+    parent: String,
+  },
+  RemoveDependency {
+    child: String,
+    parent: String,
+  },
+  /// Link a branch to a Jira issue and/or PR, replacing existing links
+  SetBranchMetadata {
+    branch: String,
+    #[serde(default)]
+    jira_issue: Option<String>,
+    #[serde(default)]
+    github_pr: Option<u32>,
+  },
+  RemoveBranchMetadata {
+    branch: String,
+  },
+  AddRoot {
+    branch: String,
+    #[serde(default)]
+    is_default: bool,
+  },
+  RemoveRoot {
+    branch: String,
+  },
+}
+
+impl StateMutation {
+  /// Apply the mutation to `state`
+  pub fn apply(&self, state: &mut RepoState) -> Result<()> {
+    match self {
+      Self::AddDependency { child, parent } => state.add_dependency(child.clone(), parent.clone()),
+      Self::RemoveDependency { child, parent } => {
+        if state.remove_dependency(child, parent) {
+          Ok(())
+        } else {
+          Err(anyhow::anyhow!("No dependency from '{child}' to '{parent}'"))
+        }
+      }
+      Self::SetBranchMetadata {
+        branch,
+        jira_issue,
+        github_pr,
+      } => {
+        let created_at = state
+          .get_branch_metadata(branch)
+          .map_or_else(|| chrono::Utc::now().to_rfc3339(), |m| m.created_at.clone());
+        state.add_branch_issue(BranchMetadata {
+          branch: branch.clone(),
+          jira_issue: jira_issue.clone(),
+          github_pr: *github_pr,
+          created_at,
+        });
+        Ok(())
+      }
+      Self::RemoveBranchMetadata { branch } => {
+        state.remove_branch_metadata(branch);
+        Ok(())
+      }
+      Self::AddRoot { branch, is_default } => state.add_root(branch.clone(), *is_default),
+      Self::RemoveRoot { branch } => {
+        state.remove_root(branch);
+        Ok(())
+      }
+    }
+  }
+}
+
+/// Parameters of [`STATE_APPLY`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateApplyParams {
+  pub mutations: Vec<StateMutation>,
+}
+
+/// Parameters of [`GITHUB_REQUEST`] and [`JIRA_REQUEST`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiRequest {
+  /// HTTP method, e.g. `GET` or `POST`
+  pub method: String,
+  /// Path below the API base URL, e.g. `/repos/owner/repo/pulls/1`
+  pub path: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub body: Option<Value>,
+  /// GitHub host to talk to; defaults to the host of the `origin` remote.
+  /// Ignored for Jira.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub host: Option<String>,
+}
+
+/// Result of [`GITHUB_REQUEST`] and [`JIRA_REQUEST`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiResponse {
+  pub status: u16,
+  /// Response body, parsed as JSON when possible and a string otherwise
+  pub body: Value,
+}
+
+/// Connection to the host a plugin was started by
+pub struct HostClient {
+  reader: BufReader<HostStream>,
+  writer: HostStream,
+  next_id: u64,
+  info: InitializeResult,
+}
+
+impl HostClient {
+  /// Connect to the host named by [`HOST_ADDR_ENV`] and [`HOST_TOKEN_ENV`].
+  ///
+  /// Returns `None` when the plugin was not started by a twig that offers
+  /// the host API, e.g. when it runs directly during development.
+  pub fn connect_from_env() -> Result<Option<Self>> {
+    let (Ok(addr), Ok(token)) = (std::env::var(HOST_ADDR_ENV), std::env::var(HOST_TOKEN_ENV)) else {
+      return Ok(None);
+    };
+    Self::connect(&addr, &token).map(Some)
+  }
+
+  /// Connect to `addr` and perform the handshake
+  pub fn connect(addr: &str, token: &str) -> Result<Self> {
+    let stream = HostStream::connect(addr)?;
+    let mut client = Self {
+      reader: BufReader::new(stream.try_clone()?),
+      writer: stream,
+      next_id: 1,
+      info: InitializeResult {
+        protocol_version: HOST_PROTOCOL_VERSION,
+        twig_version: String::new(),
+        methods: Vec::new(),
+      },
+    };
+    client.info = client.call(
+      INITIALIZE,
+      InitializeParams {
+        protocol_version: HOST_PROTOCOL_VERSION,
+        token: token.to_string(),
+      },
+    )?;
+    Ok(client)
+  }
+
+  /// Handshake result: host protocol and twig versions and supported methods
+  pub const fn info(&self) -> &InitializeResult {
+    &self.info
+  }
+
+  /// Whether the host serves `method`
+  pub fn supports(&self, method: &str) -> bool {
+    self.info.methods.iter().any(|m| m == method)
+  }
+
+  /// Call `method` and deserialize its result
+  pub fn call<T: DeserializeOwned>(&mut self, method: &str, params: impl Serialize) -> Result<T> {
+    let id = self.next_id;
+    self.next_id += 1;
+    let request = RpcRequest {
+      jsonrpc: "2.0".to_string(),
+      id: Value::from(id),
+      method: method.to_string(),
+      params: serde_json::to_value(params).context("Failed to serialize host request")?,
+    };
+
+    let mut line = serde_json::to_string(&request).context("Failed to serialize host request")?;
+    line.push('\n');
+    self
+      .writer
+      .write_all(line.as_bytes())
+      .context("Failed to send request to twig")?;
+
+    let mut response = String::new();
+    self
+      .reader
+      .read_line(&mut response)
+      .context("Failed to read response from twig")?;
+    if response.is_empty() {
+      return Err(anyhow::anyhow!("twig closed the host connection"));
+    }
+
+    let response: RpcResponse = serde_json::from_str(&response).context("Failed to parse host response")?;
+    if let Some(error) = response.error {
+      return Err(anyhow::Error::new(error).context(format!("twig host call '{method}' failed")));
+    }
+    serde_json::from_value(response.result.unwrap_or(Value::Null))
+      .with_context(|| format!("Unexpected result from twig host call '{method}'"))
+  }
+
+  /// Invocation context
+  pub fn context(&mut self) -> Result<HostContext> {
+    self.call(CONTEXT_GET, Value::Null)
+  }
+
+  /// Branch dependency graph of the current repository
+  pub fn branch_graph(&mut self) -> Result<BranchGraph> {
+    self.call(GRAPH_GET, Value::Null)
+  }
+
+  /// Current repository state
+  pub fn state(&mut self) -> Result<RepoState> {
+    let mut state: RepoState = self.call(STATE_GET, Value::Null)?;
+    state.rebuild_indices();
+    Ok(state)
+  }
+
+  /// Apply `mutations` atomically and return the resulting state. Either all
+  /// mutations are saved or, when one fails, none are.
+  pub fn apply_state(&mut self, mutations: Vec<StateMutation>) -> Result<RepoState> {
+    let mut state: RepoState = self.call(STATE_APPLY, StateApplyParams { mutations })?;
+    state.rebuild_indices();
+    Ok(state)
+  }
+
+  /// Authenticated GitHub REST request
+  pub fn github_request(&mut self, method: &str, path: &str, body: Option<Value>) -> Result<ApiResponse> {
+    self.call(
+      GITHUB_REQUEST,
+      ApiRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        body,
+        host: None,
+      },
+    )
+  }
+
+  /// Authenticated Jira REST request
+  pub fn jira_request(&mut self, method: &str, path: &str, body: Option<Value>) -> Result<ApiResponse> {
+    self.call(
+      JIRA_REQUEST,
+      ApiRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        body,
+        host: None,
+      },
+    )
+  }
+}
+
+/// Stream to the host, over whichever transport the address names
+enum HostStream {
+  #[cfg(unix)]
+  Unix(UnixStream),
+  Tcp(TcpStream),
+}
+
+impl HostStream {
+  fn connect(addr: &str) -> Result<Self> {
+    if let Some(path) = addr.strip_prefix("unix:") {
+      #[cfg(unix)]
+      return UnixStream::connect(path)
+        .map(Self::Unix)
+        .with_context(|| format!("Failed to connect to twig at {path}"));
+      #[cfg(not(unix))]
+      return Err(anyhow::anyhow!(
+        "Unix sockets are not supported on this platform: {path}"
+      ));
+    }
+
+    let tcp_addr = addr
+      .strip_prefix("tcp:")
+      .ok_or_else(|| anyhow::anyhow!("Unsupported twig host address '{addr}'"))?;
+    TcpStream::connect(tcp_addr)
+      .map(Self::Tcp)
+      .with_context(|| format!("Failed to connect to twig at {tcp_addr}"))
+  }
+
+  fn try_clone(&self) -> Result<Self> {
+    match self {
+      #[cfg(unix)]
+      Self::Unix(stream) => Ok(Self::Unix(stream.try_clone()?)),
+      Self::Tcp(stream) => Ok(Self::Tcp(stream.try_clone()?)),
+    }
+  }
+}
+
+impl std::io::Read for HostStream {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    match self {
+      #[cfg(unix)]
+      Self::Unix(stream) => stream.read(buf),
+      Self::Tcp(stream) => stream.read(buf),
+    }
+  }
+}
+
+impl Write for HostStream {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    match self {
+      #[cfg(unix)]
+      Self::Unix(stream) => stream.write(buf),
+      Self::Tcp(stream) => stream.write(buf),
+    }
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    match self {
+      #[cfg(unix)]
+      Self::Unix(stream) => stream.flush(),
+      Self::Tcp(stream) => stream.flush(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn state_mutations_apply_and_round_trip() {
+    let mut state = RepoState::default();
+    let mutations: Vec<StateMutation> = serde_json::from_value(serde_json::json!([
+      { "op": "add_root", "branch": "main", "is_default": true },
+      { "op": "add_dependency", "child": "feature", "parent": "main" },
+      { "op": "set_branch_metadata", "branch": "feature", "jira_issue": "PROJ-1" },
+    ]))
+    .unwrap();
+
+    for mutation in &mutations {
+      mutation.apply(&mut state).unwrap();
+    }
+
+    assert_eq!(state.get_dependency_parents("feature"), vec!["main"]);
+    assert_eq!(state.get_default_root(), Some("main"));
+    assert_eq!(
+      state
+        .get_branch_metadata("feature")
+        .and_then(|m| m.jira_issue.as_deref()),
+      Some("PROJ-1")
+    );
+    assert!(
+      StateMutation::AddDependency {
+        child: "main".to_string(),
+        parent: "feature".to_string(),
+      }
+      .apply(&mut state)
+      .is_err()
+    );
+  }
+
+  #[test]
+  fn branch_graph_includes_state_only_branches() {
+    let mut state = RepoState::default();
+    state.add_dependency("feature".to_string(), "gone".to_string()).unwrap();
+    state.add_root("main".to_string(), true).unwrap();
+
+    let graph = BranchGraph::from_state(&["feature".to_string(), "main".to_string()], &state);
+    let names: Vec<&str> = graph.branches.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["feature", "gone", "main"]);
+    assert_eq!(graph.branches[0].parents, ["gone"]);
+    assert_eq!(graph.branches[1].children, ["feature"]);
+    assert!(graph.branches[2].is_root);
+  }
+}
@@ -0,0 +1,253 @@
+//! Plugin manifests.
+//!
+//! A manifest describes a plugin to the Twig CLI: what it does, which
+//! `twig-core` versions it works with, and the subcommands it offers so they
+//! can be listed in `twig --help` and shell completions. Twig reads the
+//! manifest from a sidecar file next to the plugin binary
+//! (`twig-<name>.plugin.toml`) or, when there is none, by running the plugin
+//! with [`MANIFEST_FLAG`] and parsing the JSON it prints.
+//!
+//! Plugins built on `twig-core` can answer the handshake with
+//! [`print_manifest_if_requested`]:
+//!
+//! ```no_run
+//! # fn build_cli() -> clap::Command { clap::Command::new("twig-example") }
+//! use twig_core::plugin::manifest::{PluginManifest, print_manifest_if_requested};
+//!
+//! if print_manifest_if_requested(|| PluginManifest::from_command(&build_cli()))? {
+//!   return Ok(());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+/// Flag Twig passes to a plugin to request its manifest on stdout
+pub const MANIFEST_FLAG: &str = "--twig-manifest";
+
+/// Suffix of the sidecar manifest file stored next to a plugin binary
+pub const MANIFEST_SIDECAR_SUFFIX: &str = ".plugin.toml";
+
+/// Version of the `twig-core` crate plugins are built against
+pub const TWIG_CORE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Self-description of a Twig plugin
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+  /// Plugin name without the `twig-` prefix
+  pub name: String,
+  /// One-line description shown in `twig --help` and `twig self plugins`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  /// Version of the plugin itself
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub version: Option<String>,
+  /// Semver requirement on the `twig-core` version, e.g. `">=0.7, <0.8"`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub twig_core: Option<String>,
+  /// Subcommands the plugin accepts
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub subcommands: Vec<PluginSubcommand>,
+  /// Whether the plugin answers dynamic completion requests (`COMPLETE=<shell>`)
+  #[serde(default)]
+  pub completions: bool,
+}
+
+/// A subcommand offered by a plugin
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginSubcommand {
+  pub name: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub about: Option<String>,
+}
+
+/// Result of checking a manifest's `twig_core` requirement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+  /// The requirement is met, or the manifest declares none
+  Compatible,
+  /// The requirement is not met by the running version
+  Incompatible { required: String },
+  /// The requirement could not be parsed
+  Invalid { required: String, error: String },
+}
+
+impl PluginManifest {
+  /// Build a manifest from a plugin's clap command.
+  ///
+  /// The name, description, version and visible subcommands are taken from
+  /// the command; the `twig_core` requirement is the caret range of the
+  /// `twig-core` version the plugin was compiled against. Completion support
+  /// is assumed since clap-based plugins can register `CompleteEnv`.
+  pub fn from_command(command: &clap::Command) -> Self {
+    let name = command.get_name();
+    Self {
+      name: name.strip_prefix("twig-").unwrap_or(name).to_string(),
+      description: command.get_about().map(|about| about.to_string()),
+      version: command.get_version().map(str::to_string),
+      twig_core: Some(format!("^{TWIG_CORE_VERSION}")),
+      subcommands: command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .map(|subcommand| PluginSubcommand {
+          name: subcommand.get_name().to_string(),
+          about: subcommand.get_about().map(|about| about.to_string()),
+        })
+        .collect(),
+      completions: true,
+    }
+  }
+
+  /// Parse a manifest printed in response to [`MANIFEST_FLAG`]
+  pub fn from_json(json: &str) -> Result<Self> {
+    serde_json::from_str(json.trim()).context("Failed to parse plugin manifest JSON")
+  }
+
+  /// Parse a sidecar manifest file
+  pub fn from_toml(toml: &str) -> Result<Self> {
+    toml::from_str(toml).context("Failed to parse plugin manifest TOML")
+  }
+
+  /// Check the `twig_core` requirement against `core_version`
+  pub fn compatibility_with(&self, core_version: &str) -> Compatibility {
+    let Some(required) = self.twig_core.as_deref() else {
+      return Compatibility::Compatible;
+    };
+    let parsed = VersionReq::parse(required).map_err(|e| e.to_string()).and_then(|req| {
+      Version::parse(core_version)
+        .map(|version| req.matches(&version))
+        .map_err(|e| e.to_string())
+    });
+    match parsed {
+      Ok(true) => Compatibility::Compatible,
+      Ok(false) => Compatibility::Incompatible {
+        required: required.to_string(),
+      },
+      Err(error) => Compatibility::Invalid {
+        required: required.to_string(),
+        error,
+      },
+    }
+  }
+
+  /// Check the `twig_core` requirement against this crate's version
+  pub fn compatibility(&self) -> Compatibility {
+    self.compatibility_with(TWIG_CORE_VERSION)
+  }
+}
+
+/// Path of the sidecar manifest for a plugin binary, e.g.
+/// `/usr/local/bin/twig-flow.plugin.toml` for `/usr/local/bin/twig-flow`
+pub fn sidecar_path(plugin_path: &Path) -> PathBuf {
+  let stem = match plugin_path.extension() {
+    Some(ext) if ext.eq_ignore_ascii_case("exe") => plugin_path.with_extension(""),
+    _ => plugin_path.to_path_buf(),
+  };
+  let mut path = stem.into_os_string();
+  path.push(MANIFEST_SIDECAR_SUFFIX);
+  PathBuf::from(path)
+}
+
+/// Print the manifest as JSON if the plugin was invoked with
+/// [`MANIFEST_FLAG`].
+///
+/// Returns `true` when the manifest was printed and the plugin should exit.
+pub fn print_manifest_if_requested(manifest: impl FnOnce() -> PluginManifest) -> Result<bool> {
+  if std::env::args().nth(1).as_deref() != Some(MANIFEST_FLAG) {
+    return Ok(false);
+  }
+  let json = serde_json::to_string(&manifest()).context("Failed to serialize plugin manifest")?;
+  println!("{json}");
+  Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::{Arg, Command};
+
+  use super::*;
+
+  #[test]
+  fn from_command_collects_visible_subcommands() {
+    let command = Command::new("twig-example")
+      .about("Example plugin")
+      .version("1.2.3")
+      .subcommand(Command::new("run").about("Run it"))
+      .subcommand(Command::new("secret").hide(true))
+      .arg(Arg::new("verbose").long("verbose"));
+
+    let manifest = PluginManifest::from_command(&command);
+    assert_eq!(manifest.name, "example");
+    assert_eq!(manifest.description.as_deref(), Some("Example plugin"));
+    assert_eq!(manifest.version.as_deref(), Some("1.2.3"));
+    assert_eq!(
+      manifest.subcommands,
+      vec![PluginSubcommand {
+        name: "run".to_string(),
+        about: Some("Run it".to_string()),
+      }]
+    );
+    assert_eq!(manifest.compatibility(), Compatibility::Compatible);
+  }
+
+  #[test]
+  fn json_and_toml_round_trip() {
+    let manifest = PluginManifest {
+      name: "example".to_string(),
+      description: Some("Example plugin".to_string()),
+      twig_core: Some(">=0.7, <0.8".to_string()),
+      subcommands: vec![PluginSubcommand {
+        name: "run".to_string(),
+        about: None,
+      }],
+      ..Default::default()
+    };
+
+    let json = serde_json::to_string(&manifest).unwrap();
+    assert_eq!(PluginManifest::from_json(&json).unwrap(), manifest);
+
+    let toml = toml::to_string(&manifest).unwrap();
+    assert_eq!(PluginManifest::from_toml(&toml).unwrap(), manifest);
+  }
+
+  #[test]
+  fn compatibility_checks_version_requirement() {
+    let manifest = PluginManifest {
+      name: "example".to_string(),
+      twig_core: Some(">=0.7, <0.8".to_string()),
+      ..Default::default()
+    };
+    assert_eq!(manifest.compatibility_with("0.7.4"), Compatibility::Compatible);
+    assert_eq!(
+      manifest.compatibility_with("0.8.0"),
+      Compatibility::Incompatible {
+        required: ">=0.7, <0.8".to_string()
+      }
+    );
+
+    let invalid = PluginManifest {
+      twig_core: Some("not a range".to_string()),
+      ..manifest
+    };
+    assert!(matches!(
+      invalid.compatibility_with("0.7.0"),
+      Compatibility::Invalid { .. }
+    ));
+  }
+
+  #[test]
+  fn sidecar_path_strips_exe_extension() {
+    assert_eq!(
+      sidecar_path(Path::new("/bin/twig-flow")),
+      PathBuf::from("/bin/twig-flow.plugin.toml")
+    );
+    assert_eq!(
+      sidecar_path(Path::new("C:/bin/twig-flow.exe")),
+      PathBuf::from("C:/bin/twig-flow.plugin.toml")
+    );
+  }
+}
@@ -207,6 +207,10 @@ pub struct RepoState {
   pub branches: HashMap<String, BranchMetadata>,
   pub dependencies: Vec<BranchDependency>,
   pub root_branches: Vec<RootBranch>,
+  /// Branches `twig sync` has seen merged, with the merged PR number, so
+  /// merge events are acted on once
+  #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+  pub merged_branches: HashMap<String, u32>,
 
   // Pre-built indices for fast lookups (rebuilt on load, not saved)
   #[serde(skip)]
@@ -231,9 +235,45 @@ struct RepoStateForSave<'a> {
   branches: &'a HashMap<String, BranchMetadata>,
   dependencies: &'a Vec<BranchDependency>,
   root_branches: &'a Vec<RootBranch>,
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  merged_branches: &'a HashMap<String, u32>,
+}
+
+/// Exclusive lock on a repository's twig state, released when dropped.
+///
+/// See [`RepoState::lock`].
+#[derive(Debug)]
+pub struct RepoStateLock {
+  _file: fs::File,
 }
 
 impl RepoState {
+  /// Lock the repository's state against other load-modify-save cycles.
+  ///
+  /// Hold the returned guard from before [`RepoState::load`] until after
+  /// [`RepoState::save`] so concurrent writers, in this process or another
+  /// one, cannot overwrite each other's changes. Blocks until the lock is
+  /// available.
+  pub fn lock<P: AsRef<Path>>(repo_path: P) -> Result<RepoStateLock> {
+    let config_dirs = crate::config::ConfigDirs::new()?;
+    let twig_dir = config_dirs.repo_state_dir(&repo_path);
+    fs::create_dir_all(&twig_dir).context("Failed to create .twig directory")?;
+    ensure_twig_internal_gitignore(repo_path.as_ref())?;
+
+    let lock_path = twig_dir.join("state.lock");
+    let file = fs::OpenOptions::new()
+      .create(true)
+      .truncate(false)
+      .write(true)
+      .open(&lock_path)
+      .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+    file
+      .lock()
+      .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+    Ok(RepoStateLock { _file: file })
+  }
+
   /// Load the repository state from disk
   pub fn load<P: AsRef<Path>>(repo_path: P) -> Result<Self> {
     // Use the ConfigDirs to get the state path
@@ -248,6 +288,7 @@ impl RepoState {
         branches: HashMap::new(),
         dependencies: Vec::new(),
         root_branches: Vec::new(),
+        merged_branches: HashMap::new(),
         branch_to_jira_index: HashMap::new(),
         jira_to_branch_index: HashMap::new(),
         pr_to_branch_index: HashMap::new(),
@@ -267,8 +308,11 @@ impl RepoState {
     Ok(state)
   }
 
-  /// Rebuild all indices for fast lookups
-  fn rebuild_indices(&mut self) {
+  /// Rebuild all indices for fast lookups.
+  ///
+  /// Indices are not serialized, so a state deserialized by other means than
+  /// [`RepoState::load`] must call this before it is queried.
+  pub fn rebuild_indices(&mut self) {
     // Clear existing indices
     self.branch_to_jira_index.clear();
     self.jira_to_branch_index.clear();
@@ -325,12 +369,17 @@ impl RepoState {
       branches: &self.branches,
       dependencies: &self.dependencies,
       root_branches: &self.root_branches,
+      merged_branches: &self.merged_branches,
     };
 
     let state_path = config_dirs.repo_state_path(&repo_path);
     let content = serde_json::to_string_pretty(&state_to_save).context("Failed to serialize state")?;
 
-    fs::write(&state_path, content).context("Failed to write state file")?;
+    // Write a sibling file and rename it into place so readers that do not
+    // take the lock never see a partially written state
+    let temp_path = state_path.with_extension("json.tmp");
+    fs::write(&temp_path, content).context("Failed to write state file")?;
+    fs::rename(&temp_path, &state_path).context("Failed to replace state file")?;
 
     Ok(())
   }
@@ -646,6 +695,8 @@ impl RepoState {
     self.dependencies.retain(|dep| keep(&dep.child));
     let dependencies_removed = deps_before - self.dependencies.len();
 
+    self.merged_branches.retain(|name, _| keep(name));
+
     if branches_removed > 0 || dependencies_removed > 0 {
       self.rebuild_indices();
     }
@@ -656,6 +707,53 @@ impl RepoState {
     }
   }
 
+  /// Whether `branch` has already been recorded as merged through PR
+  /// `pr_number`
+  pub fn is_recorded_merged(&self, branch: &str, pr_number: u32) -> bool {
+    self.merged_branches.get(branch) == Some(&pr_number)
+  }
+
+  /// Record that `branch` was merged through PR `pr_number`
+  pub fn record_merged(&mut self, branch: &str, pr_number: u32) {
+    self.merged_branches.insert(branch.to_string(), pr_number);
+  }
+
+  /// List every branch in the stack containing `branch`.
+  ///
+  /// The stack is the ancestor path from the top of `branch`'s dependency tree
+  /// down to `branch`, followed by `branch`'s descendants breadth-first, so
+  /// parents always precede their children. Sibling stacks and configured root
+  /// branches (e.g. `main`) are not part of it.
+  pub fn get_stack_branches(&self, branch: &str) -> Vec<String> {
+    use std::collections::{HashSet, VecDeque};
+
+    let mut ancestors = Vec::new();
+    let mut visited = HashSet::from([branch.to_string()]);
+    let mut current = branch;
+    while let Some(parent) = self.get_dependency_parents(current).first().copied() {
+      if !visited.insert(parent.to_string()) {
+        break;
+      }
+      ancestors.push(parent.to_string());
+      current = parent;
+    }
+    ancestors.reverse();
+
+    let mut stack = ancestors;
+    let mut queue = VecDeque::from([branch.to_string()]);
+    while let Some(current) = queue.pop_front() {
+      for child in self.get_dependency_children(&current) {
+        if visited.insert(child.to_string()) {
+          queue.push_back(child.to_string());
+        }
+      }
+      stack.push(current);
+    }
+
+    stack.retain(|name| !self.is_root(name));
+    stack
+  }
+
   /// Find the root of a branch's dependency tree
   ///
   /// Traverses up the dependency chain to find the topmost parent.
@@ -695,168 +793,128 @@ impl RepoState {
 }
 
 /// Create a new worktree
+///
+/// The location and post-create hooks come from the repository's
+/// [`WorktreeConfig`](crate::git::WorktreeConfig).
 pub fn create_worktree<P: AsRef<Path>>(repo_path: P, branch_name: &str) -> Result<PathBuf> {
+  let config_dirs = crate::config::ConfigDirs::new()?;
+  let worktree_config = config_dirs.load_worktree_config(repo_path.as_ref())?;
+
+  create_worktree_with_config(repo_path, branch_name, &worktree_config)
+}
+
+/// Create a new worktree using an explicit worktree configuration
+pub fn create_worktree_with_config<P: AsRef<Path>>(
+  repo_path: P,
+  branch_name: &str,
+  worktree_config: &crate::git::WorktreeConfig,
+) -> Result<PathBuf> {
+  use crate::git::{escape_branch_name, find_worktree_for_branch, list_worktree_entries, run_post_create_hooks};
   use crate::output::{format_repo_path, print_success, print_warning};
 
   let repo_path = repo_path.as_ref();
   let repo =
     Git2Repository::open(repo_path).context(format!("Failed to open git repository at {}", repo_path.display()))?;
 
-  // Determine the worktree path
-  // By default, create worktrees in a directory named after the repo with
-  // "-worktrees" suffix
-  let repo_name = repo_path.file_name().and_then(|n| n.to_str()).unwrap_or("repo");
-
-  let parent_dir = repo_path.parent().unwrap_or(Path::new("."));
-  let worktrees_dir = parent_dir.join(format!("{repo_name}-worktrees"));
-
-  // Create the worktrees directory if it doesn't exist
-  if !worktrees_dir.exists() {
-    fs::create_dir_all(&worktrees_dir).context(format!(
+  // Refuse early if the branch is already checked out in a worktree
+  if let Some(existing) = find_worktree_for_branch(&repo, branch_name)?
+    && existing.branch.as_deref() == Some(branch_name)
+  {
+    return Err(anyhow::anyhow!(
+      "Branch '{branch_name}' is already checked out in a worktree at {}",
+      existing.path.display()
+    ));
+  }
+
+  // Determine the worktree path from the configured template, steering clear
+  // of directories that differ from another worktree's only in case
+  let taken: Vec<PathBuf> = list_worktree_entries(&repo)?
+    .into_iter()
+    .map(|entry| entry.path)
+    .collect();
+  let worktree_path = worktree_config.unique_worktree_path(repo_path, branch_name, &taken)?;
+
+  // Create the parent directory if it doesn't exist
+  if let Some(worktrees_dir) = worktree_path.parent()
+    && !worktrees_dir.exists()
+  {
+    fs::create_dir_all(worktrees_dir).context(format!(
       "Failed to create worktrees directory at {}",
       worktrees_dir.display()
     ))?;
   }
 
-  // Sanitize branch name for use as directory name
-  let safe_branch_name = branch_name.replace('/', "-");
-  let worktree_path = worktrees_dir.join(&safe_branch_name);
+  // Git's administrative name for the worktree must be unique; worktrees
+  // created by older versions of twig may already hold the escaped name.
+  let base_name = escape_branch_name(branch_name);
+  let mut worktree_name = base_name.clone();
+  let mut suffix = 1;
+  while repo.find_worktree(&worktree_name).is_ok() {
+    suffix += 1;
+    worktree_name = format!("{base_name}-{suffix}");
+  }
 
   println!(
     "Creating worktree at {}",
     format_repo_path(&worktree_path.display().to_string())
   );
 
-  // Check if branch exists
-  let branch_exists = repo.find_branch(branch_name, git2::BranchType::Local).is_ok();
-
-  // Also check if a branch with the sanitized name exists (could happen with
-  // previous attempts)
-  let sanitized_branch_exists = repo.find_branch(&safe_branch_name, git2::BranchType::Local).is_ok();
-
-  if branch_exists {
-    // Use existing branch
-    println!("Using existing branch: {branch_name}");
-    // Check if the worktree directory already exists
-    if worktree_path.exists() {
-      print_warning(&format!(
-        "Worktree directory already exists at {}",
-        format_repo_path(&worktree_path.display().to_string())
-      ));
-      return Err(anyhow::anyhow!(
-        "Worktree directory already exists at {}. Please remove it or use a different branch name.",
-        worktree_path.display()
-      ));
+  // Check if the worktree directory already exists
+  if worktree_path.exists() {
+    print_warning(&format!(
+      "Worktree directory already exists at {}",
+      format_repo_path(&worktree_path.display().to_string())
+    ));
+    return Err(anyhow::anyhow!(
+      "Worktree directory already exists at {}. Please remove it or use a different branch name.",
+      worktree_path.display()
+    ));
+  }
+
+  let branch = match repo.find_branch(branch_name, git2::BranchType::Local) {
+    Ok(branch) => {
+      println!("Using existing branch: {branch_name}");
+      branch
     }
-
-    // Check if a worktree with this name already exists
-    if repo.find_worktree(&safe_branch_name).is_ok() {
-      print_warning(&format!("A worktree named '{safe_branch_name}' already exists",));
-      return Err(anyhow::anyhow!(
-        "A worktree named '{safe_branch_name}' already exists. This could be due to a previous attempt to create this worktree."
-      ));
+    Err(_) => {
+      println!("Creating new branch: {branch_name}");
+
+      // Get the HEAD commit to branch from
+      let head = repo.head()?;
+      let target = head
+        .target()
+        .ok_or_else(|| anyhow::anyhow!("HEAD is not a direct reference"))?;
+      let commit = repo.find_commit(target)?;
+
+      repo
+        .branch(branch_name, &commit, false)
+        .context(format!("Failed to create branch '{branch_name}'"))?
     }
+  };
 
-    // Check if a branch with the sanitized name exists (would conflict with
-    // worktree creation)
-    if sanitized_branch_exists {
-      print_warning(&format!(
-        "A branch named '{safe_branch_name}' already exists, which conflicts with the worktree name",
-      ));
-      return Err(anyhow::anyhow!(
-        "A branch named '{safe_branch_name}' already exists, which conflicts with the worktree name. Please delete this branch first or use a different branch name."
-      ));
-    }
+  // Check out the requested branch in the worktree. Without an explicit
+  // reference libgit2 would create a new branch named after the worktree.
+  let mut add_options = git2::WorktreeAddOptions::new();
+  add_options.reference(Some(branch.get()));
 
-    // Try to create the worktree
-    match repo.worktree(safe_branch_name.as_str(), worktree_path.as_path(), None) {
-      Ok(worktree) => worktree,
-      Err(err) => {
-        // Get the raw error message from git2
-        let git_error = err.message();
+  if let Err(err) = repo.worktree(worktree_name.as_str(), worktree_path.as_path(), Some(&add_options)) {
+    // Get the raw error message from git2
+    let git_error = err.message();
 
-        return Err(anyhow::anyhow!(
-          "Failed to create worktree for branch '{}': {}. This could be due to:
+    return Err(anyhow::anyhow!(
+      "Failed to create worktree for branch '{}': {}. This could be due to:
   - The worktree directory already exists but is not registered with Git
   - The branch is already checked out in another worktree
   - There are uncommitted changes that conflict with the branch
   - You don't have permission to create directories at {}",
-          branch_name,
-          git_error,
-          worktree_path.parent().unwrap_or(Path::new(".")).display()
-        ));
-      }
-    };
-  } else {
-    // Create a new branch
-    println!("Creating new branch: {branch_name}");
-
-    // Get the HEAD commit to branch from
-    let head = repo.head()?;
-    let target = head
-      .target()
-      .ok_or_else(|| anyhow::anyhow!("HEAD is not a direct reference"))?;
-    let commit = repo.find_commit(target)?;
-
-    // Create the branch
-    repo
-      .branch(branch_name, &commit, false)
-      .context(format!("Failed to create branch '{branch_name}'"))?;
-
-    // Create the worktree
-    // Check if the worktree directory already exists
-    if worktree_path.exists() {
-      print_warning(&format!(
-        "Worktree directory already exists at {}",
-        format_repo_path(&worktree_path.display().to_string())
-      ));
-      return Err(anyhow::anyhow!(
-        "Worktree directory already exists at {}. Please remove it or use a different branch name.",
-        worktree_path.display()
-      ));
-    }
-
-    // Check if a worktree with this name already exists
-    if repo.find_worktree(&safe_branch_name).is_ok() {
-      print_warning(&format!("A worktree named '{safe_branch_name}' already exists",));
-      return Err(anyhow::anyhow!(
-        "A worktree named '{safe_branch_name}' already exists. This could be due to a previous attempt to create this worktree.",
-      ));
-    }
-
-    // Check if a branch with the sanitized name exists (would conflict with
-    // worktree creation)
-    if repo.find_branch(&safe_branch_name, git2::BranchType::Local).is_ok() {
-      print_warning(&format!(
-        "A branch named '{safe_branch_name}' already exists, which conflicts with the worktree name",
-      ));
-      return Err(anyhow::anyhow!(
-        "A branch named '{safe_branch_name}' already exists, which conflicts with the worktree name. Please delete this branch first or use a different branch name.",
-      ));
-    }
-
-    // Try to create the worktree
-    match repo.worktree(safe_branch_name.as_str(), worktree_path.as_path(), None) {
-      Ok(worktree) => worktree,
-      Err(err) => {
-        // Get the raw error message from git2
-        let git_error = err.message();
-
-        return Err(anyhow::anyhow!(
-          "Failed to create worktree for branch '{}': {}. This could be due to:
-  - The worktree directory already exists but is not registered with Git
-  - The branch is already checked out in another worktree
-  - There are uncommitted changes that conflict with the branch
-  - You don't have permission to create directories at {}",
-          branch_name,
-          git_error,
-          worktree_path.parent().unwrap_or(Path::new(".")).display()
-        ));
-      }
-    };
+      branch_name,
+      git_error,
+      worktree_path.parent().unwrap_or(Path::new(".")).display()
+    ));
   }
 
   // Update the repository state
+  let state_lock = RepoState::lock(repo_path)?;
   let mut state = RepoState::load(repo_path)?;
 
   // Get current timestamp
@@ -870,13 +928,14 @@ pub fn create_worktree<P: AsRef<Path>>(repo_path: P, branch_name: &str) -> Resul
 
   // Add the worktree to the state
   state.add_worktree(Worktree {
-    name: safe_branch_name,
+    name: worktree_name,
     path: worktree_path.to_string_lossy().to_string(),
     branch: branch_name.to_string(),
     created_at: time_str,
   });
 
   state.save(repo_path)?;
+  drop(state_lock);
 
   print_success(&format!(
     "Successfully created worktree for branch '{}' at {}",
@@ -884,9 +943,205 @@ pub fn create_worktree<P: AsRef<Path>>(repo_path: P, branch_name: &str) -> Resul
     format_repo_path(&worktree_path.display().to_string())
   ));
 
+  // Hooks run after the worktree is registered, so a failing hook leaves a
+  // usable worktree behind rather than rolling it back.
+  if let Err(e) = run_post_create_hooks(&worktree_config.hooks, repo_path, &worktree_path, branch_name) {
+    print_warning(&format!("Post-create hook failed: {e:#}"));
+  }
+
   Ok(worktree_path)
 }
 
+/// Options controlling [`remove_worktree_for_branch`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoveWorktreeOptions {
+  /// Remove the worktree even if it has uncommitted or untracked changes.
+  pub force: bool,
+  /// Delete the local branch once its worktree has been removed.
+  pub delete_branch: bool,
+}
+
+/// Remove the worktree that has `branch_name` checked out.
+///
+/// Refuses to remove a worktree with uncommitted changes unless
+/// [`RemoveWorktreeOptions::force`] is set. The matching [`Worktree`] record is
+/// dropped from the repository state, and when the branch is deleted as well
+/// its metadata and dependencies go with it, with its children reparented
+/// onto its parents. Returns the path of the removed worktree.
+pub fn remove_worktree_for_branch<P: AsRef<Path>>(
+  repo_path: P,
+  branch_name: &str,
+  options: RemoveWorktreeOptions,
+) -> Result<PathBuf> {
+  use crate::git::{delete_local_branch, find_worktree_for_branch, worktree_has_uncommitted_changes};
+
+  let repo_path = repo_path.as_ref();
+  let repo =
+    Git2Repository::open(repo_path).context(format!("Failed to open git repository at {}", repo_path.display()))?;
+
+  let entry = find_worktree_for_branch(&repo, branch_name)?
+    .ok_or_else(|| anyhow::anyhow!("No worktree found for branch '{branch_name}'"))?;
+
+  if entry.path.exists() && !options.force && worktree_has_uncommitted_changes(&entry.path)? {
+    return Err(anyhow::anyhow!(
+      "Worktree at {} has uncommitted changes. Commit or stash them first, or use --force to discard them.",
+      entry.path.display()
+    ));
+  }
+
+  let worktree = repo
+    .find_worktree(&entry.name)
+    .context(format!("Failed to open worktree '{}'", entry.name))?;
+
+  let mut prune_options = git2::WorktreePruneOptions::new();
+  prune_options.valid(true).locked(options.force).working_tree(true);
+  worktree
+    .prune(Some(&mut prune_options))
+    .context(format!("Failed to remove worktree '{}'", entry.name))?;
+
+  // libgit2 only removes the working tree when its gitdir is intact, so clean
+  // up anything left behind.
+  if entry.path.exists() {
+    fs::remove_dir_all(&entry.path).context(format!(
+      "Failed to remove worktree directory at {}",
+      entry.path.display()
+    ))?;
+  }
+
+  let _lock = RepoState::lock(repo_path)?;
+  let mut state = RepoState::load(repo_path)?;
+  state.remove_worktree(&entry.name);
+  state.worktrees.retain(|w| w.branch != branch_name);
+
+  if options.delete_branch {
+    let checked_out_branch = entry.branch.as_deref().unwrap_or(branch_name);
+    delete_local_branch(&repo, checked_out_branch)?;
+
+    state.remove_branch_metadata(checked_out_branch);
+    let parents: Vec<String> = state
+      .get_dependency_parents(checked_out_branch)
+      .into_iter()
+      .map(str::to_string)
+      .collect();
+    let children: Vec<String> = state
+      .get_dependency_children(checked_out_branch)
+      .into_iter()
+      .map(str::to_string)
+      .collect();
+    for parent in &parents {
+      state.remove_dependency(checked_out_branch, parent);
+    }
+
+    // Hand the deleted branch's children over to its parents so the stack
+    // stays connected
+    for child in &children {
+      state.remove_dependency(child, checked_out_branch);
+      for parent in &parents {
+        if !state.get_dependency_parents(child).contains(&parent.as_str()) {
+          state.add_dependency(child.clone(), parent.clone())?;
+        }
+      }
+    }
+  }
+
+  state.save(repo_path)?;
+
+  Ok(entry.path)
+}
+
+/// A worktree whose location differs from the configured layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorktreeMigration {
+  pub branch: String,
+  pub from: PathBuf,
+  pub to: PathBuf,
+}
+
+/// Move existing worktrees to the locations prescribed by `worktree_config`.
+///
+/// Worktrees with a detached HEAD or a missing directory are left alone. With
+/// `dry_run` set nothing is moved and the planned moves are returned as-is.
+pub fn migrate_worktrees<P: AsRef<Path>>(
+  repo_path: P,
+  worktree_config: &crate::git::WorktreeConfig,
+  dry_run: bool,
+) -> Result<Vec<WorktreeMigration>> {
+  use crate::git::{list_worktree_entries, relocate_worktree};
+
+  let repo_path = repo_path.as_ref();
+  let repo =
+    Git2Repository::open(repo_path).context(format!("Failed to open git repository at {}", repo_path.display()))?;
+
+  let mut migrations = Vec::new();
+  let _lock = if dry_run {
+    None
+  } else {
+    Some(RepoState::lock(repo_path)?)
+  };
+  let mut state = RepoState::load(repo_path)?;
+
+  let entries = list_worktree_entries(&repo)?;
+  // Where each worktree lives once the entries before it have been moved
+  let mut taken: Vec<PathBuf> = entries.iter().map(|entry| entry.path.clone()).collect();
+
+  for (index, entry) in entries.into_iter().enumerate() {
+    let Some(branch) = entry.branch else {
+      continue;
+    };
+    if !entry.path.exists() {
+      continue;
+    }
+
+    let others: Vec<PathBuf> = taken
+      .iter()
+      .enumerate()
+      .filter(|(other, _)| *other != index)
+      .map(|(_, path)| path.clone())
+      .collect();
+    let target = worktree_config.unique_worktree_path(repo_path, &branch, &others)?;
+    let current = fs::canonicalize(&entry.path).unwrap_or_else(|_| entry.path.clone());
+    let unchanged = target
+      .parent()
+      .and_then(|parent| fs::canonicalize(parent).ok())
+      .is_some_and(|parent| target.file_name().is_some_and(|name| parent.join(name) == current));
+    if unchanged {
+      continue;
+    }
+
+    if !dry_run {
+      relocate_worktree(&repo, &entry.name, &target)?;
+
+      // Drop the old container directory once it has been emptied.
+      if let Some(old_parent) = entry.path.parent() {
+        let _ = fs::remove_dir(old_parent);
+      }
+
+      let created_at = state
+        .get_worktree(&entry.name)
+        .map(|wt| wt.created_at.clone())
+        .unwrap_or_else(|| Utc::now().to_rfc3339());
+      state.add_worktree(Worktree {
+        name: entry.name.clone(),
+        path: target.to_string_lossy().to_string(),
+        branch: branch.clone(),
+        created_at,
+      });
+      // Record each move as it happens so a later failure leaves the state
+      // pointing at the directories that were already moved
+      state.save(repo_path)?;
+    }
+
+    taken[index] = target.clone();
+    migrations.push(WorktreeMigration {
+      branch,
+      from: entry.path,
+      to: target,
+    });
+  }
+
+  Ok(migrations)
+}
+
 /// List all worktrees for a repository
 pub fn list_worktrees<P: AsRef<Path>>(repo_path: P) -> Result<()> {
   use crate::output::{format_command, format_repo_path, format_timestamp, print_header, print_warning};
@@ -951,6 +1206,7 @@ mod tests {
   use tempfile::TempDir;
 
   use super::*;
+  use crate::git::WorktreeConfig;
 
   #[test]
   fn test_repository_creation() {
@@ -1495,6 +1751,51 @@ mod tests {
     assert_eq!(state.dependencies.len(), 1);
   }
 
+  #[test]
+  fn merged_records_survive_round_trip_and_eviction() {
+    let mut state = RepoState::default();
+    state.record_merged("done", 7);
+    state.record_merged("gone", 8);
+    assert!(state.is_recorded_merged("done", 7));
+    // A later PR from the same branch is a new merge
+    assert!(!state.is_recorded_merged("done", 9));
+
+    let json = serde_json::to_string(&state).unwrap();
+    let mut state: RepoState = serde_json::from_str(&json).unwrap();
+    assert!(state.is_recorded_merged("done", 7));
+
+    state.evict_stale_branches(&local_set(&["done"]));
+    assert!(state.is_recorded_merged("done", 7));
+    assert!(!state.merged_branches.contains_key("gone"));
+  }
+
+  #[test]
+  fn state_lock_excludes_other_holders_until_dropped() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let repo_dir = tempfile::TempDir::new().unwrap();
+    let lock = RepoState::lock(repo_dir.path()).unwrap();
+    assert!(repo_dir.path().join(".twig/.gitignore").exists());
+
+    let acquired = Arc::new(AtomicBool::new(false));
+    let waiter = {
+      let repo_path = repo_dir.path().to_path_buf();
+      let acquired = acquired.clone();
+      std::thread::spawn(move || {
+        let _lock = RepoState::lock(&repo_path).unwrap();
+        acquired.store(true, Ordering::SeqCst);
+      })
+    };
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(!acquired.load(Ordering::SeqCst));
+
+    drop(lock);
+    waiter.join().unwrap();
+    assert!(acquired.load(Ordering::SeqCst));
+  }
+
   #[test]
   fn evict_noop_when_all_exist() {
     let mut state = RepoState::default();
@@ -1635,4 +1936,210 @@ mod tests {
       "update_fetch_time via worktree path should update the main repo entry"
     );
   }
+
+  #[test]
+  fn test_get_stack_branches_follows_ancestors_and_descendants() {
+    let mut state = RepoState::default();
+    state.add_root("main".to_string(), true).unwrap();
+    state.add_dependency("feature".to_string(), "main".to_string()).unwrap();
+    state
+      .add_dependency("feature-2".to_string(), "feature".to_string())
+      .unwrap();
+    state
+      .add_dependency("feature-3".to_string(), "feature-2".to_string())
+      .unwrap();
+    state
+      .add_dependency("feature-side".to_string(), "feature".to_string())
+      .unwrap();
+    state.add_dependency("other".to_string(), "main".to_string()).unwrap();
+
+    // Siblings of the branch and of its ancestors belong to other stacks, and
+    // the trunk is not part of any stack
+    assert_eq!(
+      state.get_stack_branches("feature-2"),
+      vec!["feature", "feature-2", "feature-3"]
+    );
+    assert_eq!(
+      state.get_stack_branches("feature"),
+      vec!["feature", "feature-2", "feature-side", "feature-3"]
+    );
+    assert_eq!(state.get_stack_branches("other"), vec!["other"]);
+
+    assert_eq!(state.get_stack_branches("unrelated"), vec!["unrelated".to_string()]);
+  }
+
+  #[test]
+  fn test_create_and_remove_worktree_for_branch() {
+    use git2::Repository as GitRepository;
+
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main-repo");
+    fs::create_dir_all(&main_path).unwrap();
+    let repo = GitRepository::init(&main_path).unwrap();
+    let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo
+      .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+      .unwrap();
+
+    let wt_path = create_worktree_with_config(&main_path, "feature/foo", &WorktreeConfig::default()).unwrap();
+    assert_eq!(wt_path, temp_dir.path().join("main-repo-worktrees/feature%2Ffoo"));
+    let wt_repo = GitRepository::open(&wt_path).unwrap();
+    assert_eq!(wt_repo.head().unwrap().shorthand(), Some("feature/foo"));
+    assert!(
+      RepoState::load(&main_path)
+        .unwrap()
+        .get_worktree("feature%2Ffoo")
+        .is_some()
+    );
+
+    // A branch matching the old slash-to-hyphen directory name no longer collides
+    let other_path = create_worktree_with_config(&main_path, "feature-foo", &WorktreeConfig::default()).unwrap();
+    assert_ne!(other_path, wt_path);
+
+    // Stack feature-foo on feature/foo, which sits on main
+    let mut state = RepoState::load(&main_path).unwrap();
+    state
+      .add_dependency("feature/foo".to_string(), "main".to_string())
+      .unwrap();
+    state
+      .add_dependency("feature-foo".to_string(), "feature/foo".to_string())
+      .unwrap();
+    state.save(&main_path).unwrap();
+
+    // Untracked files block removal unless forced
+    fs::write(wt_path.join("scratch.txt"), "wip").unwrap();
+    assert!(remove_worktree_for_branch(&main_path, "feature/foo", RemoveWorktreeOptions::default()).is_err());
+    assert!(wt_path.exists());
+
+    let options = RemoveWorktreeOptions {
+      force: true,
+      delete_branch: true,
+    };
+    remove_worktree_for_branch(&main_path, "feature/foo", options).unwrap();
+
+    assert!(!wt_path.exists());
+    assert!(repo.find_worktree("feature%2Ffoo").is_err());
+    assert!(repo.find_branch("feature/foo", git2::BranchType::Local).is_err());
+    let state = RepoState::load(&main_path).unwrap();
+    assert!(state.get_worktree("feature%2Ffoo").is_none());
+
+    // The deleted branch's child now hangs off its parent
+    assert!(state.get_dependency_children("feature/foo").is_empty());
+    assert_eq!(state.get_dependency_parents("feature-foo"), vec!["main"]);
+  }
+
+  #[test]
+  fn test_migrate_worktrees_moves_to_configured_layout() {
+    use git2::Repository as GitRepository;
+
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main-repo");
+    fs::create_dir_all(&main_path).unwrap();
+    let repo = GitRepository::init(&main_path).unwrap();
+    let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo
+      .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+      .unwrap();
+
+    let old_path = create_worktree_with_config(&main_path, "feature/foo", &WorktreeConfig::default()).unwrap();
+    fs::write(old_path.join("wip.txt"), "wip").unwrap();
+
+    let new_config = WorktreeConfig {
+      path_template: "{repo}-wt/{branch}".to_string(),
+      ..WorktreeConfig::default()
+    };
+
+    let planned = migrate_worktrees(&main_path, &new_config, true).unwrap();
+    assert_eq!(planned.len(), 1);
+    assert!(old_path.exists());
+
+    let moved = migrate_worktrees(&main_path, &new_config, false).unwrap();
+    let new_path = temp_dir.path().join("main-repo-wt/feature%2Ffoo");
+    assert_eq!(moved[0].to, new_path);
+    assert!(!old_path.exists());
+    assert!(new_path.join("wip.txt").exists());
+
+    // Git still recognises the relocated worktree
+    let wt_repo = GitRepository::open(&new_path).unwrap();
+    assert_eq!(wt_repo.head().unwrap().shorthand(), Some("feature/foo"));
+    let worktree = repo.find_worktree("feature%2Ffoo").unwrap();
+    assert!(worktree.validate().is_ok());
+    assert_eq!(
+      RepoState::load(&main_path)
+        .unwrap()
+        .get_worktree("feature%2Ffoo")
+        .unwrap()
+        .path,
+      new_path.to_string_lossy()
+    );
+
+    // Already in place: nothing left to migrate
+    assert!(migrate_worktrees(&main_path, &new_config, false).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_migrate_worktrees_records_moves_before_a_failure() {
+    use git2::Repository as GitRepository;
+
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main-repo");
+    fs::create_dir_all(&main_path).unwrap();
+    let repo = GitRepository::init(&main_path).unwrap();
+    let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo
+      .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+      .unwrap();
+
+    create_worktree_with_config(&main_path, "alpha", &WorktreeConfig::default()).unwrap();
+    create_worktree_with_config(&main_path, "beta", &WorktreeConfig::default()).unwrap();
+    let entries = crate::git::list_worktree_entries(&repo).unwrap();
+    let (first, second) = (&entries[0], &entries[1]);
+
+    let new_config = WorktreeConfig {
+      path_template: "{repo}-wt/{branch}".to_string(),
+      ..WorktreeConfig::default()
+    };
+    // Occupy the second destination so its move fails after the first succeeded
+    fs::create_dir_all(temp_dir.path().join("main-repo-wt").join(&second.name)).unwrap();
+
+    assert!(migrate_worktrees(&main_path, &new_config, false).is_err());
+
+    let state = RepoState::load(&main_path).unwrap();
+    assert_eq!(
+      state.get_worktree(&first.name).unwrap().path,
+      temp_dir.path().join("main-repo-wt").join(&first.name).to_string_lossy()
+    );
+    assert_eq!(
+      state.get_worktree(&second.name).unwrap().path,
+      second.path.to_string_lossy()
+    );
+  }
+
+  #[test]
+  fn test_create_worktree_suffixes_case_colliding_directories() {
+    use git2::Repository as GitRepository;
+
+    let temp_dir = TempDir::new().unwrap();
+    let main_path = temp_dir.path().join("main-repo");
+    fs::create_dir_all(&main_path).unwrap();
+    let repo = GitRepository::init(&main_path).unwrap();
+    let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo
+      .commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[])
+      .unwrap();
+
+    let upper = create_worktree_with_config(&main_path, "Feature/X", &WorktreeConfig::default()).unwrap();
+    let lower = create_worktree_with_config(&main_path, "feature/x", &WorktreeConfig::default()).unwrap();
+
+    assert_eq!(upper.file_name().unwrap(), "Feature%2FX");
+    assert_eq!(lower.file_name().unwrap(), "feature%2Fx-2");
+  }
 }
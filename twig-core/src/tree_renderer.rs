@@ -20,6 +20,18 @@ pub struct BranchNode {
   pub children: Vec<String>,
 }
 
+/// Live pull request state shown in the PR column, keyed by branch name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrStatus {
+  pub number: u32,
+  /// "open", "draft", "merged" or "closed"
+  pub state: String,
+  /// Combined check result: "success", "failure" or "pending"
+  pub checks: Option<String>,
+  /// Review decision: "approved" or "changes requested"
+  pub review: Option<String>,
+}
+
 /// Renderer for the branch tree
 pub struct TreeRenderer<'a> {
   pub branch_nodes: &'a HashMap<String, BranchNode>,
@@ -28,6 +40,7 @@ pub struct TreeRenderer<'a> {
   pub max_depth: Option<u32>,
   pub no_color: bool,
   pub tree_width: usize, // Add field to store calculated tree width
+  pub pr_statuses: HashMap<String, PrStatus>,
 }
 
 impl<'a> TreeRenderer<'a> {
@@ -261,7 +274,13 @@ impl<'a> TreeRenderer<'a> {
       .and_then(|issue| issue.jira_issue.as_ref())
       .map(|jira| !jira.is_empty())
       .unwrap_or(false);
-    let has_pr = node.metadata.as_ref().and_then(|issue| issue.github_pr).is_some();
+    let pr_status = self.pr_statuses.get(&node.name);
+    let pr_number = node
+      .metadata
+      .as_ref()
+      .and_then(|issue| issue.github_pr)
+      .or(pr_status.map(|status| status.number));
+    let has_pr = pr_number.is_some();
     let has_cross_refs = self
       .cross_refs
       .get(&node.name)
@@ -272,40 +291,40 @@ impl<'a> TreeRenderer<'a> {
       // Use tree width for metadata alignment with proper spacing
       let jira_column_pos = std::cmp::max(current_width + 2, self.tree_width);
       let pr_column_pos = jira_column_pos + 12; // Space for "[JIRA-123]"
-      let cross_ref_column_pos = pr_column_pos + 12; // Space for "[PR#123]"
+      let pr_column_width = if self.pr_statuses.is_empty() { 12 } else { 34 }; // "[PR#123 open ✓ changes requested]"
+      let cross_ref_column_pos = pr_column_pos + pr_column_width;
 
-      // Add issue/PR metadata with proper alignment
-      if let Some(issue) = &node.metadata {
-        let mut current_pos = current_width;
+      let mut current_pos = current_width;
 
-        // Add Jira issue if it exists and is not empty
-        if has_jira {
-          let spaces_needed = jira_column_pos.saturating_sub(current_pos);
-          line.push_str(&" ".repeat(spaces_needed));
+      // Add Jira issue if it exists and is not empty
+      if has_jira && let Some(jira_issue) = node.metadata.as_ref().and_then(|issue| issue.jira_issue.as_ref()) {
+        let spaces_needed = jira_column_pos.saturating_sub(current_pos);
+        line.push_str(&" ".repeat(spaces_needed));
 
-          let jira_issue = issue.jira_issue.as_ref().unwrap();
-          let jira_display = if self.no_color {
-            format!("[{jira_issue}]",)
-          } else {
-            format!("[{}]", jira_issue.cyan())
-          };
-          line.push_str(&jira_display);
-          current_pos = self.display_width(&line);
-        }
+        let jira_display = if self.no_color {
+          format!("[{jira_issue}]",)
+        } else {
+          format!("[{}]", jira_issue.cyan())
+        };
+        line.push_str(&jira_display);
+        current_pos = self.display_width(&line);
+      }
 
-        // Add GitHub PR if available
-        if let Some(pr_number) = issue.github_pr {
-          // Always position PRs at the PR column position for consistent alignment
-          let spaces_needed = pr_column_pos.saturating_sub(current_pos);
-          line.push_str(&" ".repeat(spaces_needed));
+      // Add GitHub PR if available
+      if let Some(pr_number) = pr_number {
+        // Always position PRs at the PR column position for consistent alignment
+        let spaces_needed = pr_column_pos.saturating_sub(current_pos);
+        line.push_str(&" ".repeat(spaces_needed));
 
-          let pr_display = if self.no_color {
-            format!("[PR#{pr_number}]")
-          } else {
-            format!("[PR#{}]", pr_number.to_string().yellow())
-          };
-          line.push_str(&pr_display);
-        }
+        let number_display = if self.no_color {
+          pr_number.to_string()
+        } else {
+          pr_number.to_string().yellow().to_string()
+        };
+        let status_display = pr_status
+          .map(|status| format!(" {}", self.format_pr_status(status)))
+          .unwrap_or_default();
+        line.push_str(&format!("[PR#{number_display}{status_display}]"));
       }
 
       // Add cross-references with alignment (only if they exist)
@@ -339,6 +358,56 @@ impl<'a> TreeRenderer<'a> {
     writeln!(writer, "{line}")
   }
 
+  /// Format the live state, check result and review decision of a PR
+  fn format_pr_status(&self, status: &PrStatus) -> String {
+    let mut parts = Vec::new();
+    parts.push(if self.no_color {
+      status.state.clone()
+    } else {
+      match status.state.as_str() {
+        "open" => status.state.green().to_string(),
+        "merged" => status.state.magenta().to_string(),
+        "closed" => status.state.red().to_string(),
+        _ => status.state.dimmed().to_string(),
+      }
+    });
+
+    if let Some(checks) = &status.checks {
+      let glyph = match checks.as_str() {
+        "success" => "✓",
+        "failure" => "✗",
+        _ => "…",
+      };
+      parts.push(if self.no_color {
+        glyph.to_string()
+      } else {
+        match checks.as_str() {
+          "success" => glyph.green().to_string(),
+          "failure" => glyph.red().to_string(),
+          _ => glyph.yellow().to_string(),
+        }
+      });
+    }
+
+    if let Some(review) = &status.review {
+      parts.push(if self.no_color {
+        review.clone()
+      } else if review == "approved" {
+        review.green().to_string()
+      } else {
+        review.yellow().to_string()
+      });
+    }
+
+    parts.join(" ")
+  }
+
+  /// Show live pull request state in the PR column
+  pub fn with_pr_statuses(mut self, pr_statuses: HashMap<String, PrStatus>) -> Self {
+    self.pr_statuses = pr_statuses;
+    self
+  }
+
   /// Initialize the renderer with proper tree width calculation
   pub fn new(
     branch_nodes: &'a HashMap<String, BranchNode>,
@@ -353,6 +422,7 @@ impl<'a> TreeRenderer<'a> {
       max_depth,
       no_color,
       tree_width: 0,
+      pr_statuses: HashMap::new(),
     };
 
     // Calculate tree width before rendering
@@ -391,6 +461,7 @@ mod tests {
       max_depth: None,
       no_color: true,
       tree_width: 0,
+      pr_statuses: HashMap::new(),
     };
 
     renderer.build_cross_references();
@@ -425,6 +496,7 @@ mod tests {
       max_depth: None,
       no_color: true,
       tree_width: 0,
+      pr_statuses: HashMap::new(),
     };
 
     renderer.build_cross_references();
@@ -456,6 +528,7 @@ mod tests {
       max_depth: None,
       no_color: true,
       tree_width: 0,
+      pr_statuses: HashMap::new(),
     };
 
     let roots = vec!["main".to_string()];
@@ -494,6 +567,7 @@ mod tests {
       max_depth: Some(1),
       no_color: true,
       tree_width: 0,
+      pr_statuses: HashMap::new(),
     };
 
     let roots = vec!["main".to_string()];
@@ -506,6 +580,7 @@ mod tests {
       max_depth: None,
       no_color: true,
       tree_width: 0,
+      pr_statuses: HashMap::new(),
     };
 
     let width_unlimited = renderer_unlimited.calculate_max_tree_width(&roots);
@@ -524,6 +599,7 @@ mod tests {
       max_depth: None,
       no_color: true,
       tree_width: 0,
+      pr_statuses: HashMap::new(),
     };
 
     let text = "hello world";
@@ -549,6 +625,7 @@ mod tests {
       max_depth: None,
       no_color: true,
       tree_width: 20,
+      pr_statuses: HashMap::new(),
     };
 
     // Render the tree to a buffer
@@ -587,6 +664,7 @@ mod tests {
       max_depth: Some(1),
       no_color: true,
       tree_width: 20,
+      pr_statuses: HashMap::new(),
     };
 
     // Render the tree to a buffer
@@ -618,6 +696,7 @@ mod tests {
       max_depth: None,
       no_color: true,
       tree_width: 20,
+      pr_statuses: HashMap::new(),
     };
 
     // Pre-mark a branch as visited
@@ -715,6 +794,59 @@ mod tests {
     assert!(output_str.contains("[PR#789]"));
     assert_snapshot!("tree_with_jira_issue", output_str);
   }
+
+  #[test]
+  fn test_render_tree_with_pr_statuses() {
+    let mut nodes = HashMap::new();
+    nodes.insert(
+      "main".to_string(),
+      create_test_branch(
+        "main",
+        false,
+        vec![],
+        vec!["linked".to_string(), "detected".to_string()],
+      ),
+    );
+    nodes.insert(
+      "linked".to_string(),
+      create_test_branch_with_metadata("linked", false, vec!["main".to_string()], vec![], None, Some(456)),
+    );
+    nodes.insert(
+      "detected".to_string(),
+      create_test_branch("detected", false, vec!["main".to_string()], vec![]),
+    );
+
+    let statuses = HashMap::from([
+      (
+        "linked".to_string(),
+        PrStatus {
+          number: 456,
+          state: "open".to_string(),
+          checks: Some("failure".to_string()),
+          review: Some("changes requested".to_string()),
+        },
+      ),
+      (
+        "detected".to_string(),
+        PrStatus {
+          number: 12,
+          state: "merged".to_string(),
+          checks: Some("success".to_string()),
+          review: None,
+        },
+      ),
+    ]);
+
+    let roots = vec!["main".to_string()];
+    let mut renderer = TreeRenderer::new(&nodes, &roots, None, true).with_pr_statuses(statuses);
+
+    let mut output = Vec::new();
+    renderer.render_tree(&mut output, "main", 0, &[], true).unwrap();
+
+    let output_str = String::from_utf8(output).unwrap();
+    assert!(output_str.contains("[PR#456 open ✗ changes requested]"));
+    assert!(output_str.contains("[PR#12 merged ✓]"));
+  }
   #[test]
   fn test_render_tree_with_diamond_case() {
     let mut nodes = HashMap::new();
@@ -1233,10 +1365,7 @@ mod tests {
       is_current,
       metadata: Some(BranchMetadata {
         branch: name.to_string(),
-        jira_issue: match jira_issue {
-          Some(s) => Some(s.to_string()),
-          None => None,
-        },
+        jira_issue: jira_issue.map(|s| s.to_string()),
         github_pr,
         created_at: "".to_string(),
       }),
@@ -0,0 +1,99 @@
+//! Repository trust.
+//!
+//! A repository's `.twig/hooks.toml` and the `post_create` commands in its
+//! `.twig/worktree.toml` run shell commands, and a cloned repository may ship
+//! either file. Twig only runs those commands once the user trusts the repository with
+//! `twig hooks trust`, which records it in `trusted-repos.toml` in twig's
+//! config directory:
+//!
+//! ```toml
+//! repos = ["/home/me/src/app"]
+//! ```
+//!
+//! Linked worktrees share the trust of their main repository.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::git::detection::resolve_to_main_repo_path;
+
+/// Repositories whose local configuration may run commands
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustedRepos {
+  /// Main repository directories, canonicalized
+  pub repos: Vec<PathBuf>,
+}
+
+impl TrustedRepos {
+  /// Whether `repo_path`, or the main repository of a linked worktree, is
+  /// trusted
+  pub fn contains(&self, repo_path: &Path) -> bool {
+    let key = trust_key(repo_path);
+    self.repos.contains(&key)
+  }
+
+  /// Trust `repo_path`. Returns `false` if it was already trusted.
+  pub fn add(&mut self, repo_path: &Path) -> bool {
+    let key = trust_key(repo_path);
+    if self.repos.contains(&key) {
+      return false;
+    }
+    self.repos.push(key);
+    true
+  }
+
+  /// Stop trusting `repo_path`. Returns `false` if it was not trusted.
+  pub fn remove(&mut self, repo_path: &Path) -> bool {
+    let key = trust_key(repo_path);
+    let before = self.repos.len();
+    self.repos.retain(|repo| *repo != key);
+    self.repos.len() < before
+  }
+}
+
+/// The path a repository is trusted under: its main repository directory,
+/// canonicalized so different spellings of the same path match
+fn trust_key(repo_path: &Path) -> PathBuf {
+  resolve_to_main_repo_path(repo_path)
+    .or_else(|| fs::canonicalize(repo_path).ok())
+    .unwrap_or_else(|| repo_path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+  use twig_test_utils::git::GitRepoTestGuard;
+
+  use super::*;
+
+  #[test]
+  fn trust_matches_any_spelling_of_the_path() {
+    let dir = TempDir::new().unwrap();
+    let mut trusted = TrustedRepos::default();
+
+    assert!(!trusted.contains(dir.path()));
+    assert!(trusted.add(&dir.path().join(".")));
+    assert!(!trusted.add(dir.path()));
+    assert!(trusted.contains(dir.path()));
+
+    assert!(trusted.remove(dir.path()));
+    assert!(!trusted.contains(dir.path()));
+    assert!(!trusted.remove(dir.path()));
+  }
+
+  #[test]
+  fn worktrees_share_the_main_repository_trust() {
+    let guard = GitRepoTestGuard::new();
+    twig_test_utils::git::create_commit(&guard.repo, "file.txt", "content", "initial").unwrap();
+    let worktree_dir = TempDir::new().unwrap();
+    let worktree_path = worktree_dir.path().join("wt");
+    guard.repo.worktree("wt", &worktree_path, None).unwrap();
+
+    let mut trusted = TrustedRepos::default();
+    trusted.add(guard.path());
+    assert!(trusted.contains(&worktree_path));
+  }
+}
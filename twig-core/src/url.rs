@@ -44,12 +44,18 @@ pub fn resolve_github_repo<P: AsRef<Path>>(repo_path: P) -> Result<(String, Stri
 /// Finds the "origin" remote and extracts the owner/repo information
 /// from the remote URL.
 pub fn resolve_github_repo_from_git2(repo: &Git2Repository) -> Result<(String, String)> {
+  let repo = resolve_origin_github_repo(repo)?;
+  Ok((repo.owner, repo.repo))
+}
+
+/// Parse the "origin" remote of an open git2 Repository, keeping the host so
+/// callers can talk to GitHub Enterprise Server instances.
+pub fn resolve_origin_github_repo(repo: &Git2Repository) -> Result<GitHubRepo> {
   let remote = repo.find_remote("origin").context("Failed to find remote 'origin'")?;
 
   let remote_url = remote.url().context("Remote 'origin' has no URL")?;
 
-  let repo = GitHubRepo::parse(remote_url)?;
-  Ok((repo.owner, repo.repo))
+  GitHubRepo::parse(remote_url)
 }
 
 /// Normalize a URL by removing trailing slashes from the path when it's just
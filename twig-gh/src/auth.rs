@@ -9,38 +9,72 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 use tokio::runtime::Runtime;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
+use twig_core::config::ConfigDirs;
 use twig_core::creds::Credentials;
-use twig_core::creds::platform::{CredentialProvider, get_credential_provider};
+use twig_core::creds::chain::{CredentialChain, CredentialMatch, CredentialService};
+use twig_core::github::GitHubRepo;
 
 use crate::{GitHubClient, create_github_client};
 
 const GITHUB_MACHINE: &str = "github.com";
 
+/// Subdirectory of the twig cache directory holding cached API responses.
+const GITHUB_CACHE_DIR: &str = "github";
+
 /// Check if GitHub credentials are available for the current user.
 #[instrument(level = "debug", skip(home))]
 pub fn check_github_credentials(home: &Path) -> Result<bool> {
-  let provider = get_credential_provider(home);
-  let creds = provider.get_credentials(GITHUB_MACHINE)?;
+  let creds = find_github_credentials(home, GITHUB_MACHINE)?;
   debug!("Checked for GitHub credentials");
   Ok(creds.is_some())
 }
 
+/// Look up credentials for a GitHub host through the configured credential
+/// backends, reporting which backend provided them.
+#[instrument(level = "debug", skip(home))]
+pub fn find_github_credentials(home: &Path, host: &str) -> Result<Option<CredentialMatch>> {
+  CredentialChain::load(home)?.find(CredentialService::GitHub, host)
+}
+
 /// Load GitHub credentials from the configured credential provider.
 #[instrument(level = "debug", skip(home))]
 pub fn get_github_credentials(home: &Path) -> Result<Credentials> {
-  let provider = get_credential_provider(home);
-  match provider.get_credentials(GITHUB_MACHINE)? {
-    Some(creds) => {
-      debug!(username = %creds.username, "Loaded GitHub credentials");
-      Ok(creds)
+  get_github_credentials_for_host(home, GITHUB_MACHINE)
+}
+
+/// Load credentials for a specific GitHub host.
+///
+/// Credentials come from the backends configured in `creds.toml`. GitHub
+/// Enterprise Server instances are looked up by their host name, so a `.netrc`
+/// entry for `machine github.example.com` authenticates against that server.
+/// Tokens obtained through `twig creds setup` device login are refreshed when
+/// they are about to expire.
+#[instrument(level = "debug", skip(home))]
+pub fn get_github_credentials_for_host(home: &Path, host: &str) -> Result<Credentials> {
+  match find_github_credentials(home, host)? {
+    Some(found) => {
+      debug!(username = %found.credentials.username, source = %found.source(), "Loaded GitHub credentials");
+      let fallback = found.credentials.clone();
+      match crate::oauth::refresh_if_expiring(home, host, found) {
+        Ok(credentials) => Ok(credentials),
+        Err(e) => {
+          warn!("Could not refresh GitHub token for {host}: {e:#}");
+          Ok(fallback)
+        }
+      }
     }
     None => {
       #[cfg(unix)]
-      let error_msg = "GitHub credentials not found in .netrc file. Please add credentials for machine 'github.com'.";
+      let error_msg = format!(
+        "GitHub credentials not found. Set TWIG_GITHUB_TOKEN or add credentials for machine '{host}' to your .netrc \
+         file."
+      );
       #[cfg(windows)]
-      let error_msg =
-        "GitHub credentials not found. Please run 'twig creds setup' to configure credentials for 'github.com'.";
+      let error_msg = format!(
+        "GitHub credentials not found. Set TWIG_GITHUB_TOKEN or run 'twig creds setup' to configure credentials for \
+         '{host}'."
+      );
       Err(anyhow::anyhow!(error_msg))
     }
   }
@@ -49,16 +83,50 @@ pub fn get_github_credentials(home: &Path) -> Result<Credentials> {
 /// Creates an authenticated GitHub client using credentials from .netrc.
 #[instrument(level = "debug", skip(home))]
 pub fn create_github_client_from_netrc(home: &Path) -> Result<GitHubClient> {
-  let credentials = get_github_credentials(home).context("Failed to get credentials")?;
-  debug!(username = %credentials.username, "Creating GitHub client from .netrc credentials");
-  Ok(create_github_client(&credentials.username, &credentials.password))
+  create_github_client_for_host(home, GITHUB_MACHINE)
+}
+
+/// Creates an authenticated GitHub client for `host`.
+///
+/// Credentials are looked up for the host and requests are sent to the API
+/// base URL configured for it in `github.toml`, defaulting to
+/// `https://<host>/api/v3` for GitHub Enterprise Server hosts.
+#[instrument(level = "debug", skip(home))]
+pub fn create_github_client_for_host(home: &Path, host: &str) -> Result<GitHubClient> {
+  let credentials = get_github_credentials_for_host(home, host).context("Failed to get credentials")?;
+  debug!(username = %credentials.username, "Creating GitHub client");
+  let mut client = create_github_client(&credentials.username, &credentials.password);
+
+  let config_dirs = ConfigDirs::new().ok();
+  let github_config = config_dirs
+    .as_ref()
+    .map(|dirs| dirs.load_github_config())
+    .transpose()?
+    .unwrap_or_default();
+  client.set_base_url(github_config.api_url(host));
+
+  if let Some(cache_dir) = config_dirs.as_ref().and_then(|dirs| dirs.cache_dir()) {
+    client.set_cache_dir(cache_dir.join(GITHUB_CACHE_DIR));
+  }
+  Ok(client)
+}
+
+/// Creates an authenticated GitHub client for the host serving `repo`.
+pub fn create_github_client_for_repo(home: &Path, repo: &GitHubRepo) -> Result<GitHubClient> {
+  create_github_client_for_host(home, &repo.host)
 }
 
 /// Creates a tokio runtime and an authenticated GitHub client.
 #[instrument(level = "debug", skip(home))]
 pub fn create_github_runtime_and_client(home: &Path) -> Result<(Runtime, GitHubClient)> {
+  create_github_runtime_and_client_for_host(home, GITHUB_MACHINE)
+}
+
+/// Creates a tokio runtime and an authenticated GitHub client for `host`.
+#[instrument(level = "debug", skip(home))]
+pub fn create_github_runtime_and_client_for_host(home: &Path, host: &str) -> Result<(Runtime, GitHubClient)> {
   let rt = Runtime::new().context("Failed to create async runtime")?;
-  let client = create_github_client_from_netrc(home)?;
+  let client = create_github_client_for_host(home, host)?;
   Ok((rt, client))
 }
 
@@ -90,6 +158,28 @@ mod tests {
     assert!(error.contains("github.com"));
   }
 
+  #[test]
+  fn test_get_github_credentials_for_enterprise_host() {
+    let content = r#"machine github.com
+  login public-user
+  password public-token
+
+machine github.example.com
+  login enterprise-user
+  password enterprise-token
+"#;
+    let guard = NetrcGuard::new(content);
+
+    let creds = get_github_credentials_for_host(guard.home_dir(), "github.example.com").unwrap();
+    assert_eq!(creds.username, "enterprise-user");
+    assert_eq!(creds.password, "enterprise-token");
+
+    let error = get_github_credentials_for_host(guard.home_dir(), "ghe.other.local")
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("ghe.other.local"));
+  }
+
   #[test]
   fn test_check_github_credentials() {
     let content = r#"machine github.com
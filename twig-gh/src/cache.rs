@@ -0,0 +1,119 @@
+//! # Conditional Request Cache
+//!
+//! On-disk cache of GitHub API responses keyed by request URL and account.
+//! Cached ETags are sent back as `If-None-Match`, and a `304 Not Modified`
+//! answer is served from disk without counting against the rate limit.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// A cached response body together with the ETag GitHub returned for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+  pub etag: String,
+  pub body: String,
+}
+
+/// Directory-backed store of ETag-tagged responses
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+  dir: PathBuf,
+}
+
+impl ResponseCache {
+  /// Create a cache that stores entries under `dir`
+  ///
+  /// The directory is created lazily on the first write.
+  pub fn new(dir: impl Into<PathBuf>) -> Self {
+    Self { dir: dir.into() }
+  }
+
+  /// Directory the cache writes to
+  pub fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  /// Look up the cached response for `key`
+  ///
+  /// Missing or unreadable entries are treated as cache misses.
+  pub fn load(&self, key: &str) -> Option<CachedResponse> {
+    let content = fs::read_to_string(self.entry_path(key)).ok()?;
+    serde_json::from_str(&content).ok()
+  }
+
+  /// Store `response` for `key`, replacing any existing entry
+  pub fn store(&self, key: &str, response: &CachedResponse) -> Result<()> {
+    fs::create_dir_all(&self.dir).context(format!("Failed to create cache directory {}", self.dir.display()))?;
+
+    let path = self.entry_path(key);
+    let tmp = path.with_extension("json.tmp");
+    let content = serde_json::to_string(response).context("Failed to serialize cached response")?;
+    fs::write(&tmp, content).context(format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).context(format!("Failed to write {}", path.display()))?;
+    Ok(())
+  }
+
+  /// Remove every cached entry
+  pub fn clear(&self) -> Result<()> {
+    if self.dir.exists() {
+      fs::remove_dir_all(&self.dir).context(format!("Failed to remove {}", self.dir.display()))?;
+    }
+    Ok(())
+  }
+
+  fn entry_path(&self, key: &str) -> PathBuf {
+    self.dir.join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+  }
+}
+
+/// 64-bit FNV-1a hash; stable across builds, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+  const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+  const PRIME: u64 = 0x0000_0100_0000_01b3;
+  bytes
+    .iter()
+    .fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(PRIME))
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_store_and_load_round_trip() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let cache = ResponseCache::new(temp_dir.path().join("github"));
+
+    assert!(cache.load("user GET /repos/a/b/pulls/1").is_none());
+
+    cache.store(
+      "user GET /repos/a/b/pulls/1",
+      &CachedResponse {
+        etag: "\"abc\"".to_string(),
+        body: "{}".to_string(),
+      },
+    )?;
+
+    let cached = cache
+      .load("user GET /repos/a/b/pulls/1")
+      .expect("entry should be cached");
+    assert_eq!(cached.etag, "\"abc\"");
+    assert_eq!(cached.body, "{}");
+    assert!(cache.load("other GET /repos/a/b/pulls/1").is_none());
+
+    cache.clear()?;
+    assert!(cache.load("user GET /repos/a/b/pulls/1").is_none());
+    Ok(())
+  }
+
+  #[test]
+  fn test_fnv1a_is_stable() {
+    assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+  }
+}
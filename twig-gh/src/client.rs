@@ -2,14 +2,25 @@
 //!
 //! HTTP client implementation for GitHub API interactions, handling
 //! authentication, request building, and response parsing for GitHub REST API
-//! operations.
+//! operations. GET requests are made conditional on a cached ETag when a
+//! response cache is configured, and secondary rate limits are retried with
+//! backoff.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
-use reqwest::{Client, header};
+use reqwest::{Client, Method, RequestBuilder, StatusCode, header};
+use serde::de::DeserializeOwned;
 use tracing::{debug, info, instrument, trace, warn};
 
+use crate::cache::{CachedResponse, ResponseCache};
 use crate::consts::{ACCEPT, API_BASE_URL, USER_AGENT};
 use crate::models::GitHubAuth;
+use crate::rate_limit::{RateLimit, RetryPolicy, is_primary_rate_limit};
+
+/// Remaining-request count below which a warning is logged
+const LOW_RATE_LIMIT_THRESHOLD: u32 = 100;
 
 /// Represents a GitHub API client
 #[derive(Clone)]
@@ -17,6 +28,23 @@ pub struct GitHubClient {
   pub(crate) client: Client,
   pub(crate) base_url: String,
   pub(crate) auth: GitHubAuth,
+  pub(crate) cache: Option<ResponseCache>,
+  pub(crate) retry_policy: RetryPolicy,
+  rate_limit: Arc<Mutex<Option<RateLimit>>>,
+}
+
+/// Status, headers and body of a completed request
+pub(crate) struct ApiResponse {
+  pub(crate) status: StatusCode,
+  pub(crate) headers: header::HeaderMap,
+  pub(crate) body: String,
+}
+
+impl ApiResponse {
+  /// Deserialize the response body
+  pub(crate) fn json<T: DeserializeOwned>(&self) -> Result<T> {
+    serde_json::from_str(&self.body).context("Failed to parse GitHub API response")
+  }
 }
 
 impl GitHubClient {
@@ -29,6 +57,9 @@ impl GitHubClient {
       client,
       base_url: API_BASE_URL.to_string(),
       auth,
+      cache: None,
+      retry_policy: RetryPolicy::default(),
+      rate_limit: Arc::new(Mutex::new(None)),
     };
     info!("GitHub client created with base URL: {}", instance.base_url);
     instance
@@ -36,10 +67,191 @@ impl GitHubClient {
 
   /// Overrides the base URL used for GitHub API requests.
   ///
-  /// Primarily intended for tests that need to route requests through a mock
+  /// GitHub Enterprise Server instances serve the REST API from
+  /// `https://<host>/api/v3`; tests use this to route requests through a mock
   /// server.
   pub fn set_base_url(&mut self, base_url: impl Into<String>) {
-    self.base_url = base_url.into();
+    self.base_url = base_url.into().trim_end_matches('/').to_string();
+  }
+
+  /// REST API base URL requests are sent to.
+  pub fn base_url(&self) -> &str {
+    &self.base_url
+  }
+
+  /// GraphQL endpoint matching the REST base URL.
+  ///
+  /// GitHub Enterprise Server serves GraphQL from `/api/graphql` next to the
+  /// `/api/v3` REST prefix rather than below it.
+  pub fn graphql_url(&self) -> String {
+    match self.base_url.strip_suffix("/api/v3") {
+      Some(root) => format!("{root}/api/graphql"),
+      None => format!("{}/graphql", self.base_url),
+    }
+  }
+
+  /// Cache ETag-tagged responses under `dir` and revalidate them with
+  /// conditional requests.
+  pub fn set_cache_dir(&mut self, dir: impl Into<PathBuf>) {
+    self.cache = Some(ResponseCache::new(dir));
+  }
+
+  /// Overrides how secondary rate limit responses are retried.
+  pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+    self.retry_policy = retry_policy;
+  }
+
+  /// Rate limit state reported by the most recent response, if any.
+  pub fn rate_limit(&self) -> Option<RateLimit> {
+    self.rate_limit.lock().ok().and_then(|guard| guard.clone())
+  }
+
+  /// Build an authenticated REST API request with twig's standard headers.
+  pub(crate) fn api_request(&self, method: Method, url: &str) -> RequestBuilder {
+    self
+      .client
+      .request(method, url)
+      .header(header::ACCEPT, ACCEPT)
+      .header(header::USER_AGENT, USER_AGENT)
+      .basic_auth(&self.auth.username, Some(&self.auth.token))
+  }
+
+  /// Send the request built by `build` and read its response.
+  ///
+  /// Secondary rate limits are retried according to the client's
+  /// [`RetryPolicy`], calling `build` again for every attempt; an exhausted
+  /// primary rate limit is reported as an error. Every other status is left
+  /// to the caller.
+  pub(crate) async fn send_api(&self, url: &str, build: impl Fn() -> RequestBuilder) -> Result<ApiResponse> {
+    let mut attempt = 0;
+
+    loop {
+      let response = build().send().await.context(format!("Request to {url} failed"))?;
+      let status = response.status();
+      let headers = response.headers().clone();
+      self.record_rate_limit(&headers);
+      let body = response.text().await.context("Failed to read response body")?;
+
+      if let Some(delay) = self
+        .retry_policy
+        .secondary_limit_delay(status, &headers, &body, attempt)
+      {
+        warn!(
+          "Hit GitHub secondary rate limit; retrying in {}s (attempt {}/{})",
+          delay.as_secs(),
+          attempt + 1,
+          self.retry_policy.max_retries
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+        continue;
+      }
+
+      if is_primary_rate_limit(status, &headers) {
+        let reset = self
+          .rate_limit()
+          .map(|r| r.time_until_reset().as_secs())
+          .unwrap_or_default();
+        return Err(anyhow::anyhow!(
+          "GitHub API rate limit exceeded. The limit resets in {} minutes.",
+          reset.div_ceil(60)
+        ));
+      }
+
+      return Ok(ApiResponse { status, headers, body });
+    }
+  }
+
+  /// Send an authenticated GET request.
+  ///
+  /// Cached ETags are sent as `If-None-Match` and `304 Not Modified` answers
+  /// are served from the cache. Rate limits are handled as in
+  /// [`Self::send_api`].
+  pub(crate) async fn get_api(&self, url: &str) -> Result<ApiResponse> {
+    let cache_key = format!("{} GET {url}", self.auth.username);
+    let cached = self.cache.as_ref().and_then(|cache| cache.load(&cache_key));
+
+    let response = self
+      .send_api(url, || {
+        let request = self.api_request(Method::GET, url);
+        match &cached {
+          Some(cached) => request.header(header::IF_NONE_MATCH, &cached.etag),
+          None => request,
+        }
+      })
+      .await?;
+
+    if response.status == StatusCode::NOT_MODIFIED
+      && let Some(cached) = cached
+    {
+      debug!("Serving {} from cache (304 Not Modified)", url);
+      return Ok(ApiResponse {
+        status: StatusCode::OK,
+        headers: response.headers,
+        body: cached.body,
+      });
+    }
+
+    if response.status == StatusCode::OK
+      && let Some(cache) = &self.cache
+      && let Some(etag) = response.headers.get(header::ETAG).and_then(|v| v.to_str().ok())
+    {
+      let entry = CachedResponse {
+        etag: etag.to_string(),
+        body: response.body.clone(),
+      };
+      if let Err(e) = cache.store(&cache_key, &entry) {
+        debug!("Failed to cache GitHub response: {e:#}");
+      }
+    }
+
+    Ok(response)
+  }
+
+  pub(crate) fn record_rate_limit(&self, headers: &header::HeaderMap) {
+    let Some(rate_limit) = RateLimit::from_headers(headers) else {
+      return;
+    };
+    trace!(
+      "GitHub rate limit: {}/{} remaining",
+      rate_limit.remaining, rate_limit.limit
+    );
+    if rate_limit.remaining < LOW_RATE_LIMIT_THRESHOLD {
+      warn!(
+        "GitHub API rate limit is low: {} of {} requests remaining",
+        rate_limit.remaining, rate_limit.limit
+      );
+    }
+    if let Ok(mut guard) = self.rate_limit.lock() {
+      *guard = Some(rate_limit);
+    }
+  }
+
+  /// Send an authenticated request to `path` below the API base URL and
+  /// return the status and body without interpreting them.
+  ///
+  /// Backs the plugin host API. Absolute URLs are rejected so credentials
+  /// are only ever sent to the configured host. Rate limits are handled as in
+  /// [`Self::send_api`].
+  #[instrument(skip(self, body), level = "debug")]
+  pub async fn send_raw(&self, method: &str, path: &str, body: Option<&serde_json::Value>) -> Result<(u16, String)> {
+    if !path.starts_with('/') {
+      return Err(anyhow::anyhow!("API path '{path}' must start with '/'"));
+    }
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+      .with_context(|| format!("Invalid HTTP method '{method}'"))?;
+    let url = format!("{}{path}", self.base_url);
+
+    let response = self
+      .send_api(&url, || {
+        let request = self.api_request(method.clone(), &url);
+        match body {
+          Some(body) => request.json(body),
+          None => request,
+        }
+      })
+      .await?;
+    Ok((response.status.as_u16(), response.body))
   }
 
   /// Test the GitHub connection by fetching the current user
@@ -50,11 +262,7 @@ impl GitHubClient {
 
     trace!("Sending request to GitHub API");
     let response = self
-      .client
-      .get(&url)
-      .header(header::ACCEPT, ACCEPT)
-      .header(header::USER_AGENT, USER_AGENT)
-      .basic_auth(&self.auth.username, Some(&self.auth.token))
+      .api_request(Method::GET, &url)
       .send()
       .await
       .context("Failed to connect to GitHub")?;
@@ -106,6 +314,40 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_graphql_url_follows_base_url() {
+    let mut client = create_github_client("test_user", "test_token");
+    assert_eq!(client.graphql_url(), "https://api.github.com/graphql");
+
+    client.set_base_url("https://github.example.com/api/v3/");
+    assert_eq!(client.base_url(), "https://github.example.com/api/v3");
+    assert_eq!(client.graphql_url(), "https://github.example.com/api/graphql");
+  }
+
+  #[tokio::test]
+  async fn test_send_raw_returns_status_and_body() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+
+    Mock::given(method("POST"))
+      .and(path("/repos/owner/repo/issues/1/comments"))
+      .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({ "id": 7 })))
+      .mount(&mock_server)
+      .await;
+
+    let body = serde_json::json!({ "body": "hello" });
+    let (status, response) = client
+      .send_raw("post", "/repos/owner/repo/issues/1/comments", Some(&body))
+      .await?;
+    assert_eq!(status, 201);
+    assert_eq!(response, r#"{"id":7}"#);
+
+    assert!(client.send_raw("GET", "https://example.com/", None).await.is_err());
+
+    Ok(())
+  }
+
   /// Test that GitHub client handles authentication correctly
   #[tokio::test]
   async fn test_github_client_auth() -> Result<()> {
@@ -132,7 +374,7 @@ mod tests {
     // Make the request - if auth is wrong, this will fail
     let response = client
       .client
-      .get(&format!("{}/user", client.base_url))
+      .get(format!("{}/user", client.base_url))
       .header(header::AUTHORIZATION, format!("token {}", "test_token"))
       .send()
       .await?;
@@ -140,4 +382,140 @@ mod tests {
     assert!(response.status().is_success());
     Ok(())
   }
+
+  fn test_client(mock_server: &MockServer) -> GitHubClient {
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+    client
+  }
+
+  /// Test that cached ETags are revalidated and 304s are served from disk
+  #[tokio::test]
+  async fn test_get_api_uses_etag_cache() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    let temp_dir = tempfile::TempDir::new()?;
+    let mut client = test_client(&mock_server);
+    client.set_cache_dir(temp_dir.path());
+
+    Mock::given(method("GET"))
+      .and(path("/repos/octocat/Hello-World/pulls/1"))
+      .and(header(header::IF_NONE_MATCH, "\"v1\""))
+      .respond_with(ResponseTemplate::new(304))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("GET"))
+      .and(path("/repos/octocat/Hello-World/pulls/1"))
+      .respond_with(
+        ResponseTemplate::new(200)
+          .insert_header("etag", "\"v1\"")
+          .insert_header("x-ratelimit-limit", "5000")
+          .insert_header("x-ratelimit-remaining", "4999")
+          .insert_header("x-ratelimit-reset", "1700000000")
+          .set_body_string(r#"{"number":1}"#),
+      )
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let url = format!("{}/repos/octocat/Hello-World/pulls/1", mock_server.uri());
+    let first = client.get_api(&url).await?;
+    assert_eq!(first.status, StatusCode::OK);
+    assert_eq!(client.rate_limit().map(|r| r.remaining), Some(4999));
+
+    let second = client.get_api(&url).await?;
+    assert_eq!(second.status, StatusCode::OK);
+    assert_eq!(second.body, r#"{"number":1}"#);
+
+    Ok(())
+  }
+
+  /// Test that secondary rate limits are retried after the advertised delay
+  #[tokio::test]
+  async fn test_get_api_retries_secondary_rate_limit() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    let client = test_client(&mock_server);
+
+    Mock::given(method("GET"))
+      .and(path("/user"))
+      .respond_with(
+        ResponseTemplate::new(403)
+          .insert_header("retry-after", "0")
+          .set_body_string(r#"{"message":"You have exceeded a secondary rate limit."}"#),
+      )
+      .up_to_n_times(1)
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("GET"))
+      .and(path("/user"))
+      .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let response = client.get_api(&format!("{}/user", mock_server.uri())).await?;
+    assert_eq!(response.status, StatusCode::OK);
+
+    Ok(())
+  }
+
+  /// Test that plugin requests share the secondary rate limit retries
+  #[tokio::test]
+  async fn test_send_raw_retries_secondary_rate_limit() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    let client = test_client(&mock_server);
+
+    Mock::given(method("POST"))
+      .and(path("/repos/owner/repo/issues/1/comments"))
+      .respond_with(
+        ResponseTemplate::new(403)
+          .insert_header("retry-after", "0")
+          .set_body_string(r#"{"message":"You have exceeded a secondary rate limit."}"#),
+      )
+      .up_to_n_times(1)
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("POST"))
+      .and(path("/repos/owner/repo/issues/1/comments"))
+      .respond_with(ResponseTemplate::new(201).set_body_string("{}"))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let body = serde_json::json!({ "body": "hello" });
+    let (status, _) = client
+      .send_raw("POST", "/repos/owner/repo/issues/1/comments", Some(&body))
+      .await?;
+    assert_eq!(status, 201);
+
+    Ok(())
+  }
+
+  /// Test that an exhausted primary rate limit is reported instead of retried
+  #[tokio::test]
+  async fn test_get_api_reports_primary_rate_limit() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    let client = test_client(&mock_server);
+
+    Mock::given(method("GET"))
+      .and(path("/user"))
+      .respond_with(
+        ResponseTemplate::new(403)
+          .insert_header("x-ratelimit-limit", "5000")
+          .insert_header("x-ratelimit-remaining", "0")
+          .insert_header("x-ratelimit-reset", "1700000000")
+          .set_body_string(r#"{"message":"API rate limit exceeded"}"#),
+      )
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let result = client.get_api(&format!("{}/user", mock_server.uri())).await;
+    let err = result.err().expect("rate limited request should fail");
+    assert!(err.to_string().contains("rate limit exceeded"));
+
+    Ok(())
+  }
 }
@@ -0,0 +1,234 @@
+use anyhow::{Context, Result};
+use reqwest::{Method, StatusCode};
+use tracing::{info, instrument, trace, warn};
+
+use crate::client::GitHubClient;
+use crate::models::WorkflowJob;
+
+impl GitHubClient {
+  /// Get a GitHub Actions workflow job
+  #[instrument(skip(self), level = "debug")]
+  pub async fn get_workflow_job(&self, owner: &str, repo: &str, job_id: u64) -> Result<WorkflowJob> {
+    info!("Fetching workflow job {} for {}/{}", job_id, owner, repo);
+
+    let url = format!("{}/repos/{owner}/{repo}/actions/jobs/{job_id}", self.base_url);
+    let response = self.get_api(&url).await?;
+
+    match response.status {
+      StatusCode::OK => response.json().context("Failed to parse workflow job"),
+      StatusCode::NOT_FOUND => Err(anyhow::anyhow!("Workflow job {job_id} not found")),
+      StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow::anyhow!(
+        "Authentication failed. Please check your GitHub credentials."
+      )),
+      status => Err(anyhow::anyhow!("Unexpected error: HTTP {} - {}", status, response.body)),
+    }
+  }
+
+  /// Download the plain-text log of a GitHub Actions workflow job
+  ///
+  /// GitHub answers with a redirect to a short-lived download URL, which is
+  /// followed automatically. Logs are not cached.
+  #[instrument(skip(self), level = "debug")]
+  pub async fn get_job_logs(&self, owner: &str, repo: &str, job_id: u64) -> Result<String> {
+    info!("Downloading logs for job {} in {}/{}", job_id, owner, repo);
+
+    let url = format!("{}/repos/{owner}/{repo}/actions/jobs/{job_id}/logs", self.base_url);
+    trace!("GitHub API URL: {}", url);
+
+    let response = self.send_api(&url, || self.api_request(Method::GET, &url)).await?;
+
+    match response.status {
+      StatusCode::OK => Ok(response.body),
+      StatusCode::NOT_FOUND | StatusCode::GONE => Err(anyhow::anyhow!(
+        "Logs for job {job_id} are not available (they may have expired)"
+      )),
+      StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow::anyhow!(
+        "Authentication failed. Please check your GitHub credentials."
+      )),
+      status => Err(anyhow::anyhow!("Unexpected error: HTTP {} - {}", status, response.body)),
+    }
+  }
+
+  /// Re-run only the failed jobs of a workflow run
+  #[instrument(skip(self), level = "debug")]
+  pub async fn rerun_failed_jobs(&self, owner: &str, repo: &str, run_id: u64) -> Result<()> {
+    info!(
+      "Re-running failed jobs of workflow run {} in {}/{}",
+      run_id, owner, repo
+    );
+    let url = format!(
+      "{}/repos/{owner}/{repo}/actions/runs/{run_id}/rerun-failed-jobs",
+      self.base_url
+    );
+    self.post_rerun(&url, run_id).await
+  }
+
+  /// Re-run every job of a workflow run
+  #[instrument(skip(self), level = "debug")]
+  pub async fn rerun_workflow(&self, owner: &str, repo: &str, run_id: u64) -> Result<()> {
+    info!("Re-running workflow run {} in {}/{}", run_id, owner, repo);
+    let url = format!("{}/repos/{owner}/{repo}/actions/runs/{run_id}/rerun", self.base_url);
+    self.post_rerun(&url, run_id).await
+  }
+
+  async fn post_rerun(&self, url: &str, run_id: u64) -> Result<()> {
+    trace!("GitHub API URL: {}", url);
+
+    let response = self.send_api(url, || self.api_request(Method::POST, url)).await?;
+
+    match response.status {
+      StatusCode::CREATED | StatusCode::NO_CONTENT | StatusCode::OK => Ok(()),
+      StatusCode::NOT_FOUND => Err(anyhow::anyhow!("Workflow run {run_id} not found")),
+      StatusCode::UNAUTHORIZED => Err(anyhow::anyhow!(
+        "Authentication failed. Please check your GitHub credentials."
+      )),
+      status => {
+        let error_text = response.body;
+        warn!(
+          "Failed to re-run workflow run {}: HTTP {} - {}",
+          run_id, status, error_text
+        );
+        Err(anyhow::anyhow!(
+          "Could not re-run workflow run {run_id}: HTTP {status} - {error_text}"
+        ))
+      }
+    }
+  }
+}
+
+/// Return the last `lines` lines of a job log
+///
+/// The timestamp GitHub prefixes to every log line is removed.
+pub fn log_tail(log: &str, lines: usize) -> String {
+  let all: Vec<&str> = log.lines().collect();
+  all[all.len().saturating_sub(lines)..]
+    .iter()
+    .map(|line| strip_timestamp(line))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Strip a leading RFC 3339 timestamp such as `2024-01-01T00:00:00.0000000Z `
+fn strip_timestamp(line: &str) -> &str {
+  match line.split_once(' ') {
+    Some((prefix, rest))
+      if prefix.len() >= 20
+        && prefix.ends_with('Z')
+        && prefix.as_bytes().get(10) == Some(&b'T')
+        && prefix.as_bytes()[..4].iter().all(u8::is_ascii_digit) =>
+    {
+      rest
+    }
+    _ => line,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::matchers::{method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+  use crate::create_github_client;
+
+  fn test_client(mock_server: &MockServer) -> GitHubClient {
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+    client
+  }
+
+  #[tokio::test]
+  async fn test_get_workflow_job() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let client = test_client(&mock_server);
+
+    Mock::given(method("GET"))
+      .and(path("/repos/owner/repo/actions/jobs/42"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "id": 42,
+        "run_id": 7,
+        "name": "test",
+        "status": "completed",
+        "conclusion": "failure",
+        "html_url": "https://github.com/owner/repo/actions/runs/7/job/42",
+        "steps": [
+          { "number": 1, "name": "Checkout", "status": "completed", "conclusion": "success" },
+          { "number": 2, "name": "Run tests", "status": "completed", "conclusion": "failure" }
+        ]
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let job = client.get_workflow_job("owner", "repo", 42).await?;
+    assert_eq!(job.run_id, 7);
+    assert_eq!(job.steps.len(), 2);
+    assert_eq!(job.steps[1].conclusion.as_deref(), Some("failure"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_get_job_logs_follows_redirect() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let client = test_client(&mock_server);
+
+    Mock::given(method("GET"))
+      .and(path("/repos/owner/repo/actions/jobs/42/logs"))
+      .respond_with(
+        ResponseTemplate::new(302).insert_header("location", format!("{}/download/42.txt", mock_server.uri())),
+      )
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("GET"))
+      .and(path("/download/42.txt"))
+      .respond_with(ResponseTemplate::new(200).set_body_string("line one\nline two\n"))
+      .mount(&mock_server)
+      .await;
+
+    let logs = client.get_job_logs("owner", "repo", 42).await?;
+    assert_eq!(logs, "line one\nline two\n");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_rerun_failed_jobs() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let client = test_client(&mock_server);
+
+    Mock::given(method("POST"))
+      .and(path("/repos/owner/repo/actions/runs/7/rerun-failed-jobs"))
+      .respond_with(ResponseTemplate::new(201))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("POST"))
+      .and(path("/repos/owner/repo/actions/runs/8/rerun"))
+      .respond_with(ResponseTemplate::new(403).set_body_string(r#"{"message":"This workflow is already running"}"#))
+      .mount(&mock_server)
+      .await;
+
+    client.rerun_failed_jobs("owner", "repo", 7).await?;
+    let err = client
+      .rerun_workflow("owner", "repo", 8)
+      .await
+      .expect_err("rerun of a running workflow should fail");
+    assert!(err.to_string().contains("already running"));
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_log_tail_strips_timestamps() {
+    let log = "2024-05-01T10:00:00.1234567Z first\n\
+               2024-05-01T10:00:01.1234567Z second\n\
+               2024-05-01T10:00:02.1234567Z ##[error]Process completed with exit code 1.\n\
+               plain line";
+
+    assert_eq!(
+      log_tail(log, 3),
+      "second\n##[error]Process completed with exit code 1.\nplain line"
+    );
+    assert_eq!(log_tail(log, 10).lines().count(), 4);
+  }
+}
@@ -1,10 +1,9 @@
 use anyhow::{Context, Result};
-use reqwest::{StatusCode, header};
+use reqwest::StatusCode;
 use serde::Deserialize;
 use tracing::{info, instrument};
 
 use crate::client::GitHubClient;
-use crate::consts::{ACCEPT, USER_AGENT};
 use crate::models::CheckRun;
 
 impl GitHubClient {
@@ -15,27 +14,18 @@ impl GitHubClient {
 
     let url = format!("{}/repos/{owner}/{repo}/commits/{ref_sha}/check-runs", self.base_url);
 
-    let response = self
-      .client
-      .get(&url)
-      .header(header::ACCEPT, ACCEPT)
-      .header(header::USER_AGENT, USER_AGENT)
-      .basic_auth(&self.auth.username, Some(&self.auth.token))
-      .send()
-      .await
-      .context("Failed to fetch check runs")?;
+    let response = self.get_api(&url).await.context("Failed to fetch check runs")?;
 
     #[derive(Deserialize)]
     struct CheckRunsResponse {
       check_runs: Vec<CheckRun>,
     }
 
-    match response.status() {
+    match response.status {
       StatusCode::OK => {
-        // First get the response body as text
-        let body = response.text().await.context("Failed to read response body")?;
+        let body = response.body;
 
-        // Then try to parse it as JSON
+        // Try to parse the body as JSON
         let check_runs_response = match serde_json::from_str::<CheckRunsResponse>(&body) {
           Ok(response) => response,
           Err(e) => {
@@ -58,21 +48,19 @@ impl GitHubClient {
       StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow::anyhow!(
         "Authentication failed. Please check your GitHub credentials."
       )),
-      _ => Err(anyhow::anyhow!(
-        "Unexpected error: HTTP {} - {}",
-        response.status(),
-        response.text().await.unwrap_or_default()
-      )),
+      status => Err(anyhow::anyhow!("Unexpected error: HTTP {} - {}", status, response.body)),
     }
   }
 }
 #[cfg(test)]
 mod tests {
+  use reqwest::header;
   use wiremock::matchers::{header, method, path};
   use wiremock::{Mock, MockServer, ResponseTemplate};
 
   use super::*;
   use crate::GitHubAuth;
+  use crate::consts::{ACCEPT, USER_AGENT};
 
   #[tokio::test]
   async fn test_get_check_runs() -> anyhow::Result<()> {
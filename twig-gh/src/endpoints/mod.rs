@@ -1,8 +1,11 @@
 //! # GitHub API Endpoints
 //!
 //! Organized endpoint implementations for different GitHub API resource types,
-//! including pulls, checks, and user management functionality.
+//! including pulls, comments, checks, Actions jobs, and user management functionality.
 
+pub mod actions;
 pub mod checks;
+pub mod comments;
+pub mod pull_summaries;
 pub mod pulls;
 pub mod users;
@@ -0,0 +1,392 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tracing::{info, instrument, warn};
+
+use crate::client::GitHubClient;
+use crate::models::PullRequestSummary;
+
+/// Number of aliased lookups packed into a single GraphQL query
+const BATCH_SIZE: usize = 50;
+
+/// Pull requests fetched per head ref; enough to find an open one among
+/// recently closed attempts
+const PRS_PER_HEAD_REF: u32 = 5;
+
+const PULL_REQUEST_SUMMARY_FRAGMENT: &str = r#"
+fragment PullRequestSummary on PullRequest {
+  number
+  title
+  url
+  state
+  isDraft
+  reviewDecision
+  mergeable
+  mergedAt
+  headRefName
+  commits(last: 1) { nodes { commit { statusCheckRollup { state } } } }
+}
+"#;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryNode {
+  number: u32,
+  title: String,
+  url: String,
+  state: String,
+  is_draft: bool,
+  review_decision: Option<String>,
+  mergeable: Option<String>,
+  merged_at: Option<String>,
+  head_ref_name: String,
+  commits: CommitConnection,
+}
+
+#[derive(Deserialize)]
+struct CommitConnection {
+  nodes: Vec<CommitNode>,
+}
+
+#[derive(Deserialize)]
+struct CommitNode {
+  commit: Commit,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Commit {
+  status_check_rollup: Option<StatusCheckRollup>,
+}
+
+#[derive(Deserialize)]
+struct StatusCheckRollup {
+  state: String,
+}
+
+#[derive(Deserialize)]
+struct PullRequestConnection {
+  nodes: Vec<SummaryNode>,
+}
+
+#[derive(Deserialize)]
+struct RepositoryData {
+  repository: Option<HashMap<String, Option<serde_json::Value>>>,
+}
+
+impl From<SummaryNode> for PullRequestSummary {
+  fn from(node: SummaryNode) -> Self {
+    let check_state = node
+      .commits
+      .nodes
+      .into_iter()
+      .next()
+      .and_then(|n| n.commit.status_check_rollup)
+      .map(|rollup| rollup.state);
+    Self {
+      number: node.number,
+      title: node.title,
+      url: node.url,
+      state: node.state,
+      is_draft: node.is_draft,
+      review_decision: node.review_decision,
+      mergeable: node.mergeable,
+      merged_at: node.merged_at,
+      head_ref: node.head_ref_name,
+      check_state,
+    }
+  }
+}
+
+impl GitHubClient {
+  /// Find the pull requests opened from each of `head_refs`, newest first
+  ///
+  /// Lookups are batched into as few GraphQL queries as possible. Every
+  /// requested ref is present in the result, mapped to an empty list when it
+  /// has no pull requests, except refs whose batch failed: those are left out
+  /// so the other batches' results are still returned. An error is returned
+  /// only when every batch fails.
+  #[instrument(skip(self, head_refs), level = "debug")]
+  pub async fn find_pull_requests_by_head_refs(
+    &self,
+    owner: &str,
+    repo: &str,
+    head_refs: &[String],
+  ) -> Result<HashMap<String, Vec<PullRequestSummary>>> {
+    info!(
+      "Fetching pull requests for {} head refs in {}/{}",
+      head_refs.len(),
+      owner,
+      repo
+    );
+
+    let mut results = HashMap::new();
+    let mut last_error = None;
+    for chunk in head_refs.chunks(BATCH_SIZE) {
+      match self.find_pull_requests_for_batch(owner, repo, chunk).await {
+        Ok(batch) => results.extend(batch),
+        Err(e) => {
+          warn!("Failed to fetch pull requests for {} head refs: {e:#}", chunk.len());
+          last_error = Some(e);
+        }
+      }
+    }
+
+    match last_error {
+      Some(e) if results.is_empty() => Err(e),
+      _ => Ok(results),
+    }
+  }
+
+  /// Look up the pull requests for one batch of head refs in a single query
+  async fn find_pull_requests_for_batch(
+    &self,
+    owner: &str,
+    repo: &str,
+    chunk: &[String],
+  ) -> Result<HashMap<String, Vec<PullRequestSummary>>> {
+    let mut declarations = vec!["$owner: String!".to_string(), "$repo: String!".to_string()];
+    let mut fields = Vec::new();
+    let mut variables = serde_json::Map::new();
+    variables.insert("owner".into(), owner.into());
+    variables.insert("repo".into(), repo.into());
+
+    for (i, head_ref) in chunk.iter().enumerate() {
+      declarations.push(format!("$h{i}: String!"));
+      fields.push(format!(
+        "h{i}: pullRequests(headRefName: $h{i}, first: {PRS_PER_HEAD_REF}, \
+         orderBy: {{field: CREATED_AT, direction: DESC}}) {{ nodes {{ ...PullRequestSummary }} }}"
+      ));
+      variables.insert(format!("h{i}"), head_ref.as_str().into());
+    }
+
+    let query = build_query(&declarations, &fields);
+    let data: RepositoryData = self.graphql(&query, variables.into()).await?;
+    let mut aliases = data
+      .repository
+      .ok_or_else(|| anyhow::anyhow!("Repository {owner}/{repo} not found"))?;
+
+    let mut results = HashMap::new();
+    for (i, head_ref) in chunk.iter().enumerate() {
+      let prs = match aliases.remove(&format!("h{i}")).flatten() {
+        Some(value) => serde_json::from_value::<PullRequestConnection>(value)
+          .context("Failed to parse pull request summaries")?
+          .nodes
+          .into_iter()
+          .map(PullRequestSummary::from)
+          .collect(),
+        None => Vec::new(),
+      };
+      results.insert(head_ref.clone(), prs);
+    }
+
+    Ok(results)
+  }
+
+  /// Fetch summaries for the given pull request numbers
+  ///
+  /// Numbers that do not resolve to a pull request are left out of the
+  /// result rather than failing the whole batch.
+  #[instrument(skip(self, numbers), level = "debug")]
+  pub async fn get_pull_request_summaries(
+    &self,
+    owner: &str,
+    repo: &str,
+    numbers: &[u32],
+  ) -> Result<HashMap<u32, PullRequestSummary>> {
+    info!(
+      "Fetching {} pull request summaries in {}/{}",
+      numbers.len(),
+      owner,
+      repo
+    );
+
+    let mut results = HashMap::new();
+    for chunk in numbers.chunks(BATCH_SIZE) {
+      let mut declarations = vec!["$owner: String!".to_string(), "$repo: String!".to_string()];
+      let mut fields = Vec::new();
+      let mut variables = serde_json::Map::new();
+      variables.insert("owner".into(), owner.into());
+      variables.insert("repo".into(), repo.into());
+
+      for (i, number) in chunk.iter().enumerate() {
+        declarations.push(format!("$n{i}: Int!"));
+        fields.push(format!("n{i}: pullRequest(number: $n{i}) {{ ...PullRequestSummary }}"));
+        variables.insert(format!("n{i}"), (*number).into());
+      }
+
+      let query = build_query(&declarations, &fields);
+      let (data, errors) = self.graphql_partial::<RepositoryData>(&query, variables.into()).await?;
+      for error in &errors {
+        warn!("GitHub GraphQL error: {error}");
+      }
+      let Some(mut aliases) = data.and_then(|d| d.repository) else {
+        return Err(anyhow::anyhow!(
+          "Failed to fetch pull requests for {owner}/{repo}: {}",
+          errors.join("; ")
+        ));
+      };
+
+      for (i, number) in chunk.iter().enumerate() {
+        if let Some(value) = aliases.remove(&format!("n{i}")).flatten() {
+          let node: SummaryNode = serde_json::from_value(value).context("Failed to parse pull request summary")?;
+          results.insert(*number, node.into());
+        }
+      }
+    }
+
+    Ok(results)
+  }
+}
+
+fn build_query(declarations: &[String], fields: &[String]) -> String {
+  format!(
+    "query({}) {{\n  repository(owner: $owner, name: $repo) {{\n    {}\n  }}\n}}\n{}",
+    declarations.join(", "),
+    fields.join("\n    "),
+    PULL_REQUEST_SUMMARY_FRAGMENT
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::matchers::{body_partial_json, method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use crate::create_github_client;
+  use crate::models::primary_pull_request;
+
+  fn summary_json(number: u32, state: &str, head: &str) -> serde_json::Value {
+    serde_json::json!({
+      "number": number,
+      "title": format!("PR {number}"),
+      "url": format!("https://github.com/octocat/Hello-World/pull/{number}"),
+      "state": state,
+      "isDraft": false,
+      "reviewDecision": "APPROVED",
+      "mergeable": "MERGEABLE",
+      "mergedAt": null,
+      "headRefName": head,
+      "commits": { "nodes": [ { "commit": { "statusCheckRollup": { "state": "SUCCESS" } } } ] }
+    })
+  }
+
+  #[tokio::test]
+  async fn test_find_pull_requests_by_head_refs() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+
+    Mock::given(method("POST"))
+      .and(path("/graphql"))
+      .and(body_partial_json(serde_json::json!({
+        "variables": { "owner": "octocat", "repo": "Hello-World", "h0": "feature-a", "h1": "feature-b" }
+      })))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "data": {
+          "repository": {
+            "h0": { "nodes": [
+              summary_json(12, "CLOSED", "feature-a"),
+              summary_json(10, "OPEN", "feature-a")
+            ] },
+            "h1": { "nodes": [] }
+          }
+        }
+      })))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let refs = vec!["feature-a".to_string(), "feature-b".to_string()];
+    let results = client
+      .find_pull_requests_by_head_refs("octocat", "Hello-World", &refs)
+      .await?;
+
+    assert_eq!(results.len(), 2);
+    assert!(results["feature-b"].is_empty());
+    let primary = primary_pull_request(&results["feature-a"]).expect("feature-a has PRs");
+    assert_eq!(primary.number, 10);
+    assert_eq!(primary.review_decision.as_deref(), Some("APPROVED"));
+    assert_eq!(primary.check_state.as_deref(), Some("SUCCESS"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_find_pull_requests_by_head_refs_keeps_successful_batches() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+
+    // The first batch fails, the second (holding only the last ref) succeeds
+    Mock::given(method("POST"))
+      .and(path("/graphql"))
+      .and(body_partial_json(
+        serde_json::json!({ "variables": { "h0": "feature-0" } }),
+      ))
+      .respond_with(ResponseTemplate::new(502))
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("POST"))
+      .and(path("/graphql"))
+      .and(body_partial_json(
+        serde_json::json!({ "variables": { "h0": "feature-50" } }),
+      ))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "data": { "repository": { "h0": { "nodes": [summary_json(50, "OPEN", "feature-50")] } } }
+      })))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let refs: Vec<String> = (0..=50).map(|i| format!("feature-{i}")).collect();
+    let results = client
+      .find_pull_requests_by_head_refs("octocat", "Hello-World", &refs)
+      .await?;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results["feature-50"][0].number, 50);
+    assert!(!results.contains_key("feature-0"));
+
+    // With every batch failing the error is surfaced
+    let refs = vec!["feature-0".to_string()];
+    assert!(
+      client
+        .find_pull_requests_by_head_refs("octocat", "Hello-World", &refs)
+        .await
+        .is_err()
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_get_pull_request_summaries_skips_missing_numbers() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+
+    let mut merged = summary_json(7, "MERGED", "done");
+    merged["mergedAt"] = "2024-01-01T00:00:00Z".into();
+
+    Mock::given(method("POST"))
+      .and(path("/graphql"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "data": { "repository": { "n0": merged, "n1": null } },
+        "errors": [ { "message": "Could not resolve to a PullRequest with the number of 999." } ]
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let results = client
+      .get_pull_request_summaries("octocat", "Hello-World", &[7, 999])
+      .await?;
+
+    assert_eq!(results.len(), 1);
+    assert!(results[&7].is_merged());
+    assert!(!results.contains_key(&999));
+
+    Ok(())
+  }
+}
@@ -1,9 +1,7 @@
 use anyhow::{Context, Result};
-use reqwest::header;
 use tracing::{debug, info, instrument, trace, warn};
 
 use crate::client::GitHubClient;
-use crate::consts::{ACCEPT, USER_AGENT};
 use crate::models::{GitHubPullRequest, PullRequestReview, PullRequestStatus};
 
 /// Pagination options for GitHub API requests
@@ -47,25 +45,17 @@ impl GitHubClient {
 
     trace!("GitHub API URL: {}", url);
 
-    let response = self
-      .client
-      .get(&url)
-      .header(header::ACCEPT, ACCEPT)
-      .header(header::USER_AGENT, USER_AGENT)
-      .basic_auth(&self.auth.username, Some(&self.auth.token))
-      .send()
-      .await
-      .context(format!("GET {url} failed"))?;
-
-    if !response.status().is_success() {
-      let status = response.status();
-      let error_text = response.text().await.unwrap_or_default();
+    let response = self.get_api(&url).await?;
+
+    if !response.status.is_success() {
+      let status = response.status;
+      let error_text = response.body;
       return Err(anyhow::anyhow!(
         "GitHub API returned error status {status}: {error_text}"
       ));
     }
 
-    let pull_requests: Vec<GitHubPullRequest> = response.json().await.context("Failed to parse GitHub API response")?;
+    let pull_requests: Vec<GitHubPullRequest> = response.json()?;
 
     Ok(pull_requests)
   }
@@ -79,17 +69,9 @@ impl GitHubClient {
 
     trace!("GitHub API URL: {}", url);
 
-    let response = self
-      .client
-      .get(&url)
-      .header(header::ACCEPT, ACCEPT)
-      .header(header::USER_AGENT, USER_AGENT)
-      .basic_auth(&self.auth.username, Some(&self.auth.token))
-      .send()
-      .await
-      .context(format!("GET {url} failed"))?;
-
-    let status = response.status();
+    let response = self.get_api(&url).await?;
+
+    let status = response.status;
     debug!("GitHub API response status: {}", status);
 
     match status {
@@ -97,7 +79,6 @@ impl GitHubClient {
         info!("Successfully received pull request data");
         let pull_request = response
           .json::<GitHubPullRequest>()
-          .await
           .context("Failed to parse GitHub pull request")?;
 
         trace!("Pull request title: {}", pull_request.title);
@@ -114,7 +95,7 @@ impl GitHubClient {
         Err(anyhow::anyhow!("Pull request #{pr_number} not found"))
       }
       _ => {
-        let error_text = response.text().await.unwrap_or_default();
+        let error_text = response.body;
         warn!("Unexpected GitHub API error: HTTP {} - {}", status, error_text);
         Err(anyhow::anyhow!("Unexpected error: HTTP {status} - {error_text}"))
       }
@@ -135,17 +116,9 @@ impl GitHubClient {
 
     trace!("GitHub API URL: {}", url);
 
-    let response = self
-      .client
-      .get(&url)
-      .header(header::ACCEPT, ACCEPT)
-      .header(header::USER_AGENT, USER_AGENT)
-      .basic_auth(&self.auth.username, Some(&self.auth.token))
-      .send()
-      .await
-      .context(format!("GET {url} failed"))?;
-
-    let status = response.status();
+    let response = self.get_api(&url).await?;
+
+    let status = response.status;
     debug!("GitHub API response status: {}", status);
 
     match status {
@@ -153,7 +126,6 @@ impl GitHubClient {
         info!("Successfully received PR reviews data");
         let reviews = response
           .json::<Vec<PullRequestReview>>()
-          .await
           .context("Failed to parse GitHub PR reviews")?;
 
         trace!("Received {} reviews", reviews.len());
@@ -170,7 +142,7 @@ impl GitHubClient {
         Err(anyhow::anyhow!("Pull request #{pr_number} not found"))
       }
       _ => {
-        let error_text = response.text().await.unwrap_or_default();
+        let error_text = response.body;
         warn!("Unexpected GitHub API error: HTTP {} - {}", status, error_text);
         Err(anyhow::anyhow!("Unexpected error: HTTP {status} - {error_text}"))
       }
@@ -250,11 +222,13 @@ impl GitHubClient {
 
 #[cfg(test)]
 mod tests {
+  use reqwest::header;
   use wiremock::matchers::{header, method, path, query_param};
   use wiremock::{Mock, MockServer, ResponseTemplate};
 
   use super::*;
   use crate::GitHubAuth;
+  use crate::consts::{ACCEPT, USER_AGENT};
 
   #[tokio::test]
   async fn test_list_pull_requests() -> anyhow::Result<()> {
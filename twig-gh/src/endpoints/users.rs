@@ -1,10 +1,10 @@
-use anyhow::{Context, Result};
-use reqwest::{StatusCode, header};
+use anyhow::Result;
+use reqwest::{Method, StatusCode};
 use tracing::{info, instrument};
 
 use crate::client::GitHubClient;
-use crate::consts::{ACCEPT, USER_AGENT};
 use crate::models::GitHubUser;
+use crate::oauth::parse_scopes;
 
 impl GitHubClient {
   /// Get the current authenticated user
@@ -14,22 +14,13 @@ impl GitHubClient {
 
     let url = format!("{}/user", self.base_url);
 
-    let response = self
-      .client
-      .get(&url)
-      .header(header::ACCEPT, ACCEPT)
-      .header(header::USER_AGENT, USER_AGENT)
-      .basic_auth(&self.auth.username, Some(&self.auth.token))
-      .send()
-      .await
-      .context(format!("GET {url} failed"))?;
-
-    match response.status() {
+    let response = self.send_api(&url, || self.api_request(Method::GET, &url)).await?;
+
+    match response.status {
       StatusCode::OK => {
-        // First get the response body as text
-        let body = response.text().await.context("Failed to read response body")?;
+        let body = response.body;
 
-        // Then try to parse it as JSON
+        // Try to parse it as JSON
         let user = match serde_json::from_str::<GitHubUser>(&body) {
           Ok(user) => user,
           Err(e) => {
@@ -51,21 +42,44 @@ impl GitHubClient {
       StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow::anyhow!(
         "Authentication failed. Please check your GitHub credentials."
       )),
-      _ => Err(anyhow::anyhow!(
-        "Unexpected error: HTTP {} - {}",
-        response.status(),
-        response.text().await.unwrap_or_default()
+      status => Err(anyhow::anyhow!("Unexpected error: HTTP {} - {}", status, response.body)),
+    }
+  }
+
+  /// Get the OAuth scopes granted to the current token.
+  ///
+  /// Returns `None` when GitHub does not report scopes, which is the case for
+  /// fine-grained personal access tokens and GitHub App user tokens.
+  #[instrument(skip(self), level = "debug")]
+  pub async fn get_token_scopes(&self) -> Result<Option<Vec<String>>> {
+    let url = format!("{}/user", self.base_url);
+
+    let response = self.send_api(&url, || self.api_request(Method::GET, &url)).await?;
+
+    match response.status {
+      StatusCode::OK => Ok(
+        response
+          .headers
+          .get("x-oauth-scopes")
+          .and_then(|value| value.to_str().ok())
+          .map(parse_scopes),
+      ),
+      StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow::anyhow!(
+        "Authentication failed. Please check your GitHub credentials."
       )),
+      status => Err(anyhow::anyhow!("Unexpected error: HTTP {status}")),
     }
   }
 }
 #[cfg(test)]
 mod tests {
+  use reqwest::header;
   use wiremock::matchers::{header, method, path};
   use wiremock::{Mock, MockServer, ResponseTemplate};
 
   use super::*;
   use crate::GitHubAuth;
+  use crate::consts::{ACCEPT, USER_AGENT};
 
   #[tokio::test]
   async fn test_get_current_user() -> anyhow::Result<()> {
@@ -130,4 +144,29 @@ mod tests {
 
     Ok(())
   }
+
+  #[tokio::test]
+  async fn test_get_token_scopes() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let mut client = GitHubClient::new(GitHubAuth {
+      username: "test_user".to_string(),
+      token: "test_token".to_string(),
+    });
+    client.base_url = mock_server.uri();
+
+    Mock::given(method("GET"))
+      .and(path("/user"))
+      .respond_with(
+        ResponseTemplate::new(200)
+          .insert_header("X-OAuth-Scopes", "repo, read:org")
+          .set_body_json(serde_json::json!({"login": "test_user", "id": 1})),
+      )
+      .mount(&mock_server)
+      .await;
+
+    let scopes = client.get_token_scopes().await?;
+    assert_eq!(scopes, Some(vec!["repo".to_string(), "read:org".to_string()]));
+
+    Ok(())
+  }
 }
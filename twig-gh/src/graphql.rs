@@ -0,0 +1,145 @@
+//! # GitHub GraphQL Client
+//!
+//! GraphQL support for data the REST API does not expose, such as the
+//! resolved state of pull request review threads, and for batching many
+//! lookups into a single request.
+
+use anyhow::{Context, Result};
+use reqwest::header;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tracing::{instrument, trace};
+
+use crate::client::GitHubClient;
+use crate::consts::USER_AGENT;
+
+#[derive(Deserialize)]
+struct GraphQlResponse<T> {
+  data: Option<T>,
+  #[serde(default)]
+  errors: Vec<GraphQlError>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlError {
+  message: String,
+}
+
+impl GitHubClient {
+  /// Execute a GraphQL query and deserialize its `data` payload
+  ///
+  /// Any error reported by the API fails the whole query.
+  #[instrument(skip(self, query, variables), level = "debug")]
+  pub async fn graphql<T: DeserializeOwned>(&self, query: &str, variables: serde_json::Value) -> Result<T> {
+    let (data, errors) = self.graphql_partial(query, variables).await?;
+    if !errors.is_empty() {
+      return Err(anyhow::anyhow!("GitHub GraphQL error: {}", errors.join("; ")));
+    }
+    data.ok_or_else(|| anyhow::anyhow!("GitHub GraphQL response contained no data"))
+  }
+
+  /// Execute a GraphQL query that may partially succeed
+  ///
+  /// Returns whatever `data` the API produced alongside its error messages,
+  /// e.g. when one aliased field of a batched query refers to a missing object.
+  #[instrument(skip(self, query, variables), level = "debug")]
+  pub async fn graphql_partial<T: DeserializeOwned>(
+    &self,
+    query: &str,
+    variables: serde_json::Value,
+  ) -> Result<(Option<T>, Vec<String>)> {
+    let url = self.graphql_url();
+    trace!("GitHub GraphQL URL: {}", url);
+
+    let payload = serde_json::json!({ "query": query, "variables": variables });
+    let response = self
+      .send_api(&url, || {
+        self
+          .client
+          .post(&url)
+          .header(header::USER_AGENT, USER_AGENT)
+          .bearer_auth(&self.auth.token)
+          .json(&payload)
+      })
+      .await?;
+
+    let status = response.status;
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+      return Err(anyhow::anyhow!(
+        "Authentication failed. Please check your GitHub credentials."
+      ));
+    }
+    if !status.is_success() {
+      return Err(anyhow::anyhow!(
+        "GitHub GraphQL API returned error status {status}: {}",
+        response.body
+      ));
+    }
+
+    let body: GraphQlResponse<T> =
+      serde_json::from_str(&response.body).context("Failed to parse GitHub GraphQL response")?;
+
+    Ok((body.data, body.errors.into_iter().map(|e| e.message).collect()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::matchers::{method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use crate::create_github_client;
+
+  #[tokio::test]
+  async fn test_graphql_retries_secondary_rate_limit() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+
+    Mock::given(method("POST"))
+      .and(path("/graphql"))
+      .respond_with(
+        ResponseTemplate::new(403)
+          .insert_header("retry-after", "0")
+          .set_body_string(r#"{"message":"You have exceeded a secondary rate limit."}"#),
+      )
+      .up_to_n_times(1)
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("POST"))
+      .and(path("/graphql"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": { "viewer": "octocat" } })))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let data: serde_json::Value = client.graphql("{ viewer }", serde_json::json!({})).await?;
+    assert_eq!(data["viewer"], "octocat");
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_graphql_reports_forbidden_responses() {
+    let mock_server = MockServer::start().await;
+    let mut client = create_github_client("test_user", "test_token");
+    client.set_base_url(mock_server.uri());
+
+    Mock::given(method("POST"))
+      .and(path("/graphql"))
+      .respond_with(
+        ResponseTemplate::new(403).set_body_string(r#"{"message":"Resource not accessible by integration"}"#),
+      )
+      .mount(&mock_server)
+      .await;
+
+    let error = client
+      .graphql::<serde_json::Value>("{ viewer }", serde_json::json!({}))
+      .await
+      .unwrap_err();
+    let message = error.to_string();
+    assert!(message.contains("403"), "{message}");
+    assert!(message.contains("Resource not accessible"), "{message}");
+  }
+}
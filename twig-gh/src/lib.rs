@@ -1,24 +1,36 @@
 //! # GitHub API Client
 //!
-//! Provides GitHub REST API integration for pull requests, checks, and user
-//! data, supporting authentication and common GitHub operations for twig
-//! workflows.
+//! Provides GitHub REST and GraphQL API integration for pull requests, review
+//! comments, checks, and user data, supporting authentication and common
+//! GitHub operations for twig workflows.
 
 pub mod auth;
+pub mod cache;
 pub mod client;
 pub mod consts;
 pub mod endpoints;
+pub mod graphql;
 pub mod models;
+pub mod oauth;
+pub mod rate_limit;
 pub mod utils;
 
 // Re-export the client
 pub use auth::{
-  check_github_credentials, create_github_client_from_netrc, create_github_runtime_and_client, get_github_credentials,
+  check_github_credentials, create_github_client_for_host, create_github_client_for_repo,
+  create_github_client_from_netrc, create_github_runtime_and_client, create_github_runtime_and_client_for_host,
+  find_github_credentials, get_github_credentials, get_github_credentials_for_host,
 };
 pub use client::{GitHubClient, create_github_client};
 // Re-export models
 pub use models::{
-  CheckRun, CheckSuite, GitHubAuth, GitHubPullRequest, GitHubUser, PullRequestRef, PullRequestReview, PullRequestStatus,
+  CheckRun, CheckRunApp, CheckSuite, GitHubAuth, GitHubPullRequest, GitHubUser, IssueComment, PullRequestRef,
+  PullRequestReview, PullRequestReviewComment, PullRequestStatus, PullRequestSummary, ReviewThread,
+  ReviewThreadComment, WorkflowJob, WorkflowStep, group_threads_by_file, primary_pull_request,
 };
+// Re-export OAuth device flow types
+pub use oauth::{DeviceCode, DeviceFlow, OAuthToken, REQUIRED_SCOPES, missing_scopes, store_oauth_token};
+// Re-export rate limit types
+pub use rate_limit::{RateLimit, RetryPolicy};
 // Re-export utilities
 pub use utils::{GitHubPr, GitHubRepo, GitRemoteScheme};
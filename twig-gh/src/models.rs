@@ -5,6 +5,8 @@
 
 #![allow(dead_code)]
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Represents GitHub authentication credentials
@@ -79,6 +81,59 @@ pub struct CheckRun {
   pub details_url: Option<String>,
   pub started_at: String,
   pub completed_at: Option<String>,
+  /// App that created the check run; `github-actions` for workflow jobs
+  #[serde(default)]
+  pub app: Option<CheckRunApp>,
+}
+
+impl CheckRun {
+  /// Whether the check run has finished
+  pub fn is_completed(&self) -> bool {
+    self.status == "completed"
+  }
+
+  /// Whether the check run finished with a conclusion that blocks merging
+  pub fn is_failed(&self) -> bool {
+    matches!(
+      self.conclusion.as_deref(),
+      Some("failure" | "timed_out" | "cancelled" | "action_required" | "startup_failure")
+    )
+  }
+
+  /// Whether the check run is a GitHub Actions job
+  ///
+  /// For Actions, the check run id is also the workflow job id.
+  pub fn is_actions_job(&self) -> bool {
+    self.app.as_ref().is_some_and(|app| app.slug == "github-actions")
+  }
+}
+
+/// Represents the app that created a check run
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CheckRunApp {
+  pub slug: String,
+}
+
+/// Represents a GitHub Actions workflow job
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WorkflowJob {
+  pub id: u64,
+  pub run_id: u64,
+  pub name: String,
+  pub status: String,
+  pub conclusion: Option<String>,
+  pub html_url: Option<String>,
+  #[serde(default)]
+  pub steps: Vec<WorkflowStep>,
+}
+
+/// Represents a step of a GitHub Actions workflow job
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WorkflowStep {
+  pub number: u32,
+  pub name: String,
+  pub status: String,
+  pub conclusion: Option<String>,
 }
 
 /// Represents a GitHub check suite
@@ -98,6 +153,112 @@ pub struct PullRequestStatus {
   pub check_runs: Vec<CheckRun>,
 }
 
+/// Compact pull request state fetched in bulk over GraphQL
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PullRequestSummary {
+  pub number: u32,
+  pub title: String,
+  pub url: String,
+  /// "OPEN", "CLOSED" or "MERGED"
+  pub state: String,
+  pub is_draft: bool,
+  /// "APPROVED", "CHANGES_REQUESTED" or "REVIEW_REQUIRED"; `None` when no
+  /// review is required
+  pub review_decision: Option<String>,
+  /// "MERGEABLE", "CONFLICTING" or "UNKNOWN"
+  pub mergeable: Option<String>,
+  pub merged_at: Option<String>,
+  pub head_ref: String,
+  /// Combined status of the head commit's checks: "SUCCESS", "FAILURE",
+  /// "ERROR", "PENDING" or "EXPECTED"
+  pub check_state: Option<String>,
+}
+
+impl PullRequestSummary {
+  /// Whether the pull request is still open
+  pub fn is_open(&self) -> bool {
+    self.state == "OPEN"
+  }
+
+  /// Whether the pull request has been merged
+  pub fn is_merged(&self) -> bool {
+    self.state == "MERGED" || self.merged_at.is_some()
+  }
+}
+
+/// Pick the pull request that best represents a branch: the first open one,
+/// otherwise the most recent
+///
+/// `prs` is expected newest first, as returned by
+/// [`GitHubClient::find_pull_requests_by_head_refs`](crate::GitHubClient::find_pull_requests_by_head_refs).
+pub fn primary_pull_request(prs: &[PullRequestSummary]) -> Option<&PullRequestSummary> {
+  prs.iter().find(|pr| pr.is_open()).or_else(|| prs.first())
+}
+
+/// Represents an inline review comment on a pull request diff
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PullRequestReviewComment {
+  pub id: u64,
+  pub user: GitHubUser,
+  pub body: String,
+  pub path: String,
+  /// Line in the current diff. `None` when the comment is outdated.
+  pub line: Option<u32>,
+  pub original_line: Option<u32>,
+  /// Id of the comment this one replies to, if it is part of a thread.
+  pub in_reply_to_id: Option<u64>,
+  pub html_url: String,
+  pub created_at: String,
+  pub updated_at: String,
+}
+
+/// Represents a top-level comment on a pull request's conversation tab
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IssueComment {
+  pub id: u64,
+  pub user: GitHubUser,
+  pub body: String,
+  pub html_url: String,
+  pub created_at: String,
+  pub updated_at: String,
+}
+
+/// Represents a review thread: an inline comment and its replies
+#[derive(Debug, Serialize, Clone)]
+pub struct ReviewThread {
+  /// GraphQL node id of the thread
+  pub id: String,
+  pub path: String,
+  pub line: Option<u32>,
+  pub is_resolved: bool,
+  pub is_outdated: bool,
+  pub comments: Vec<ReviewThreadComment>,
+}
+
+/// Group review threads by the file they were left on, ordered by path and
+/// then line
+pub fn group_threads_by_file<'a>(
+  threads: impl IntoIterator<Item = &'a ReviewThread>,
+) -> BTreeMap<&'a str, Vec<&'a ReviewThread>> {
+  let mut grouped: BTreeMap<&str, Vec<&ReviewThread>> = BTreeMap::new();
+  for thread in threads {
+    grouped.entry(thread.path.as_str()).or_default().push(thread);
+  }
+  for file_threads in grouped.values_mut() {
+    file_threads.sort_by_key(|t| t.line.unwrap_or(0));
+  }
+  grouped
+}
+
+/// Represents a single comment within a review thread
+#[derive(Debug, Serialize, Clone)]
+pub struct ReviewThreadComment {
+  pub author: String,
+  pub body: String,
+  pub url: String,
+  pub created_at: String,
+}
+
 #[cfg(test)]
 mod tests {
   use serde_json::json;
@@ -222,4 +383,57 @@ mod tests {
       Some("https://github.com/octocat/Hello-World/runs/4".to_string())
     );
   }
+
+  #[test]
+  fn test_check_run_outcomes() {
+    let check = |status: &str, conclusion: Option<&str>, app: Option<&str>| -> CheckRun {
+      serde_json::from_value(json!({
+        "id": 1,
+        "name": "ci",
+        "status": status,
+        "conclusion": conclusion,
+        "started_at": "2024-01-01T00:00:00Z",
+        "app": app.map(|slug| json!({ "slug": slug })),
+      }))
+      .unwrap()
+    };
+
+    let failed = check("completed", Some("timed_out"), Some("github-actions"));
+    assert!(failed.is_completed());
+    assert!(failed.is_failed());
+    assert!(failed.is_actions_job());
+
+    let skipped = check("completed", Some("skipped"), None);
+    assert!(!skipped.is_failed());
+    assert!(!skipped.is_actions_job());
+
+    let running = check("in_progress", None, Some("circleci"));
+    assert!(!running.is_completed());
+    assert!(!running.is_failed());
+    assert!(!running.is_actions_job());
+  }
+
+  #[test]
+  fn test_group_threads_by_file() {
+    let thread = |id: &str, path: &str, line: Option<u32>| ReviewThread {
+      id: id.to_string(),
+      path: path.to_string(),
+      line,
+      is_resolved: false,
+      is_outdated: false,
+      comments: Vec::new(),
+    };
+    let threads = vec![
+      thread("a", "src/main.rs", Some(20)),
+      thread("b", "README.md", Some(1)),
+      thread("c", "src/main.rs", Some(5)),
+    ];
+
+    let grouped = group_threads_by_file(&threads);
+
+    let files: Vec<&str> = grouped.keys().copied().collect();
+    assert_eq!(files, vec!["README.md", "src/main.rs"]);
+    let ids: Vec<&str> = grouped["src/main.rs"].iter().map(|t| t.id.as_str()).collect();
+    assert_eq!(ids, vec!["c", "a"]);
+  }
 }
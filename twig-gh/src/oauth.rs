@@ -0,0 +1,603 @@
+//! # OAuth Device Flow
+//!
+//! Implements GitHub's OAuth device authorization flow so users can log in
+//! from the terminal without creating a personal access token by hand. The
+//! flow works against github.com and GitHub Enterprise Server alike; the OAuth
+//! endpoints live on the web host (`https://<host>/login/...`) and the client
+//! ID of an OAuth or GitHub App registered on that host must be supplied.
+//!
+//! Tokens issued by GitHub Apps with expiring user tokens come with a refresh
+//! token. The refresh token is stored through the credential backend chain
+//! under [`refresh_token_key`], and the non-secret expiry metadata in
+//! `github-oauth.json` in the twig data directory, so expiring tokens can be
+//! refreshed transparently when a client is created.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use reqwest::{Client, header};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+use twig_core::config::ConfigDirs;
+use twig_core::creds::Credentials;
+use twig_core::creds::chain::{CredentialBackendKind, CredentialChain, CredentialMatch, CredentialService};
+use twig_core::github::GitHubConfig;
+
+use crate::consts::USER_AGENT;
+
+/// Scopes twig requests and needs: private repositories and pull requests,
+/// and the authenticated user's profile.
+pub const REQUIRED_SCOPES: &[&str] = &["repo", "read:user"];
+
+/// Environment variable holding the OAuth client ID used for device-flow login
+pub const ENV_GITHUB_CLIENT_ID: &str = "TWIG_GITHUB_CLIENT_ID";
+
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Seconds added to the polling interval when GitHub answers `slow_down`
+const SLOW_DOWN_INCREMENT: u64 = 5;
+
+/// Tokens expiring within this many seconds are refreshed before use
+const REFRESH_MARGIN_SECS: u64 = 300;
+
+/// File in the twig data directory holding token expiry metadata
+const OAUTH_STATE_FILE: &str = "github-oauth.json";
+
+fn default_interval() -> u64 {
+  5
+}
+
+/// Verification code returned when a device flow is started
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+  pub device_code: String,
+  /// Code the user enters at `verification_uri`
+  pub user_code: String,
+  pub verification_uri: String,
+  /// Seconds until `device_code` expires
+  pub expires_in: u64,
+  /// Minimum number of seconds between polls
+  #[serde(default = "default_interval")]
+  pub interval: u64,
+}
+
+/// Access token issued by the OAuth token endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthToken {
+  pub access_token: String,
+  #[serde(default)]
+  pub token_type: String,
+  /// Comma-separated scopes granted to the token
+  #[serde(default)]
+  pub scope: String,
+  /// Seconds until the access token expires, absent for non-expiring tokens
+  pub expires_in: Option<u64>,
+  pub refresh_token: Option<String>,
+  pub refresh_token_expires_in: Option<u64>,
+}
+
+/// Body of the OAuth token endpoint, which answers 200 for errors too
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TokenResponse {
+  Token(OAuthToken),
+  Error {
+    error: String,
+    error_description: Option<String>,
+    interval: Option<u64>,
+  },
+}
+
+/// Client for the OAuth endpoints of a GitHub host
+pub struct DeviceFlow {
+  client: Client,
+  oauth_url: String,
+  client_id: String,
+}
+
+impl DeviceFlow {
+  /// Create a device flow client for the OAuth endpoints under `oauth_url`,
+  /// e.g. `https://github.com` or `https://github.example.com`
+  pub fn new(oauth_url: impl Into<String>, client_id: impl Into<String>) -> Self {
+    Self {
+      client: Client::new(),
+      oauth_url: oauth_url.into().trim_end_matches('/').to_string(),
+      client_id: client_id.into(),
+    }
+  }
+
+  /// Create a device flow client for `host` using the OAuth URL configured
+  /// in `github.toml`
+  pub fn for_host(config: &GitHubConfig, host: &str, client_id: impl Into<String>) -> Self {
+    Self::new(config.oauth_url(host), client_id)
+  }
+
+  /// Start a device flow requesting `scopes`
+  #[instrument(skip(self), level = "debug")]
+  pub async fn request_code(&self, scopes: &[&str]) -> Result<DeviceCode> {
+    let url = format!("{}/login/device/code", self.oauth_url);
+    let scope = scopes.join(" ");
+    let response = self
+      .client
+      .post(&url)
+      .header(header::ACCEPT, "application/json")
+      .header(header::USER_AGENT, USER_AGENT)
+      .form(&[("client_id", self.client_id.as_str()), ("scope", scope.as_str())])
+      .send()
+      .await
+      .with_context(|| format!("POST {url} failed"))?;
+
+    let status = response.status();
+    let body = response.text().await.context("Failed to read response body")?;
+    if !status.is_success() {
+      bail!("Failed to start device login: HTTP {status} - {body}");
+    }
+    if let Ok(TokenResponse::Error {
+      error,
+      error_description,
+      ..
+    }) = serde_json::from_str::<TokenResponse>(&body)
+    {
+      bail!("Failed to start device login: {}", error_description.unwrap_or(error));
+    }
+    serde_json::from_str(&body).context("Failed to parse device code response")
+  }
+
+  /// Poll the token endpoint until the user authorizes the device, denies
+  /// it, or the code expires
+  #[instrument(skip(self, code), level = "debug")]
+  pub async fn poll_for_token(&self, code: &DeviceCode) -> Result<OAuthToken> {
+    let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+    let mut interval = code.interval;
+
+    loop {
+      tokio::time::sleep(Duration::from_secs(interval)).await;
+
+      match self
+        .request_token(&[
+          ("device_code", code.device_code.as_str()),
+          ("grant_type", DEVICE_CODE_GRANT_TYPE),
+        ])
+        .await?
+      {
+        TokenResponse::Token(token) => {
+          info!("Device authorized");
+          return Ok(token);
+        }
+        TokenResponse::Error {
+          error,
+          error_description,
+          interval: new_interval,
+        } => match error.as_str() {
+          "authorization_pending" => debug!("Waiting for the user to authorize the device"),
+          "slow_down" => {
+            interval = new_interval.unwrap_or(interval + SLOW_DOWN_INCREMENT);
+            debug!(interval, "Polling too fast, slowing down");
+          }
+          "expired_token" => bail!("The device code expired before it was authorized. Please try again."),
+          "access_denied" => bail!("Authorization was denied."),
+          _ => bail!("Device login failed: {}", error_description.unwrap_or(error)),
+        },
+      }
+
+      if Instant::now() >= deadline {
+        bail!("The device code expired before it was authorized. Please try again.");
+      }
+    }
+  }
+
+  /// Exchange a refresh token for a new access token
+  #[instrument(skip(self, refresh_token), level = "debug")]
+  pub async fn refresh(&self, refresh_token: &str) -> Result<OAuthToken> {
+    match self
+      .request_token(&[("refresh_token", refresh_token), ("grant_type", "refresh_token")])
+      .await?
+    {
+      TokenResponse::Token(token) => Ok(token),
+      TokenResponse::Error {
+        error,
+        error_description,
+        ..
+      } => bail!("Failed to refresh GitHub token: {}", error_description.unwrap_or(error)),
+    }
+  }
+
+  async fn request_token(&self, params: &[(&str, &str)]) -> Result<TokenResponse> {
+    let url = format!("{}/login/oauth/access_token", self.oauth_url);
+    let mut form = vec![("client_id", self.client_id.as_str())];
+    form.extend_from_slice(params);
+
+    let response = self
+      .client
+      .post(&url)
+      .header(header::ACCEPT, "application/json")
+      .header(header::USER_AGENT, USER_AGENT)
+      .form(&form)
+      .send()
+      .await
+      .with_context(|| format!("POST {url} failed"))?;
+
+    let status = response.status();
+    let body = response.text().await.context("Failed to read response body")?;
+    if !status.is_success() {
+      bail!("OAuth token request failed: HTTP {status} - {body}");
+    }
+    serde_json::from_str(&body).context("Failed to parse OAuth token response")
+  }
+}
+
+/// Resolve the OAuth client ID for `host`: an explicit value wins, then
+/// `TWIG_GITHUB_CLIENT_ID`, then `oauth_client_id` in `github.toml`
+pub fn resolve_client_id(explicit: Option<&str>, config: &GitHubConfig, host: &str) -> Option<String> {
+  explicit
+    .map(str::to_string)
+    .or_else(|| std::env::var(ENV_GITHUB_CLIENT_ID).ok())
+    .or_else(|| config.oauth_client_id(host).map(str::to_string))
+    .filter(|client_id| !client_id.trim().is_empty())
+}
+
+/// Parse a comma-separated scope list such as the `X-OAuth-Scopes` header
+pub fn parse_scopes(scopes: &str) -> Vec<String> {
+  scopes
+    .split([',', ' '])
+    .map(str::trim)
+    .filter(|scope| !scope.is_empty())
+    .map(str::to_string)
+    .collect()
+}
+
+/// Returns the scopes in `required` that none of the `granted` scopes cover
+pub fn missing_scopes(granted: &[String], required: &[&str]) -> Vec<String> {
+  required
+    .iter()
+    .filter(|required| !granted.iter().any(|granted| scope_covers(granted, required)))
+    .map(|required| required.to_string())
+    .collect()
+}
+
+/// Whether `granted` includes `required`, following GitHub's scope hierarchy
+/// (`repo` covers `public_repo`, `user` covers `read:user`, `admin:org`
+/// covers `write:org` and `read:org`, ...)
+fn scope_covers(granted: &str, required: &str) -> bool {
+  if granted == required || required.starts_with(&format!("{granted}:")) {
+    return true;
+  }
+  match granted {
+    "repo" => required == "public_repo" || required.starts_with("repo_"),
+    "user" => required == "read:user" || required.starts_with("user:"),
+    _ => {
+      if let Some(resource) = granted.strip_prefix("admin:") {
+        required == format!("write:{resource}") || required == format!("read:{resource}")
+      } else if let Some(resource) = granted.strip_prefix("write:") {
+        required == format!("read:{resource}")
+      } else {
+        false
+      }
+    }
+  }
+}
+
+/// Key under which the refresh token for `host` is stored in the credential
+/// backend
+pub fn refresh_token_key(host: &str) -> String {
+  format!("{host}/oauth-refresh")
+}
+
+/// Expiry metadata for tokens obtained through the device flow
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthState {
+  #[serde(default)]
+  pub hosts: BTreeMap<String, OAuthHostState>,
+}
+
+/// Expiry metadata for a single host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthHostState {
+  /// Client ID the token was issued to, needed to refresh it
+  pub client_id: String,
+  /// Unix timestamp at which the access token expires
+  pub expires_at: Option<u64>,
+  /// Unix timestamp at which the refresh token expires
+  pub refresh_token_expires_at: Option<u64>,
+}
+
+impl OAuthHostState {
+  /// Build the state for `token`, issued at `now`
+  pub fn from_token(client_id: &str, token: &OAuthToken, now: u64) -> Self {
+    Self {
+      client_id: client_id.to_string(),
+      expires_at: token.expires_in.map(|secs| now + secs),
+      refresh_token_expires_at: token.refresh_token_expires_in.map(|secs| now + secs),
+    }
+  }
+
+  /// Whether the access token expires within the refresh margin
+  pub fn needs_refresh(&self, now: u64) -> bool {
+    self
+      .expires_at
+      .is_some_and(|expires_at| now + REFRESH_MARGIN_SECS >= expires_at)
+  }
+
+  /// Whether the refresh token can no longer be used
+  pub fn refresh_expired(&self, now: u64) -> bool {
+    self
+      .refresh_token_expires_at
+      .is_some_and(|expires_at| now >= expires_at)
+  }
+}
+
+impl OAuthState {
+  /// Load the state from `path`, returning an empty state if it does not exist
+  pub fn load(path: &Path) -> Result<Self> {
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+  }
+
+  /// Write the state to `path`
+  pub fn save(&self, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let content = serde_json::to_string_pretty(self).context("Failed to serialize OAuth state")?;
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+  }
+}
+
+/// Path of the OAuth state file in the twig data directory
+pub fn oauth_state_path(config_dirs: &ConfigDirs) -> PathBuf {
+  config_dirs.data_dir().join(OAUTH_STATE_FILE)
+}
+
+fn unix_now() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Store a token obtained through the device flow for `host`.
+///
+/// The access token and any refresh token go to the first writable credential
+/// backend; expiry metadata goes to the OAuth state file. Returns the backend
+/// the token was stored in.
+pub fn store_oauth_token(
+  home: &Path,
+  host: &str,
+  username: &str,
+  client_id: &str,
+  token: &OAuthToken,
+) -> Result<CredentialBackendKind> {
+  let chain = CredentialChain::load(home)?;
+  let backend = chain.store(
+    host,
+    &Credentials {
+      username: username.to_string(),
+      password: token.access_token.clone(),
+    },
+  )?;
+  if let Some(refresh_token) = &token.refresh_token {
+    chain.store(
+      &refresh_token_key(host),
+      &Credentials {
+        username: username.to_string(),
+        password: refresh_token.clone(),
+      },
+    )?;
+  }
+
+  let state_path = oauth_state_path(&ConfigDirs::new()?);
+  let mut state = OAuthState::load(&state_path)?;
+  if token.expires_in.is_some() {
+    state.hosts.insert(
+      host.to_string(),
+      OAuthHostState::from_token(client_id, token, unix_now()),
+    );
+  } else {
+    state.hosts.remove(host);
+  }
+  state.save(&state_path)?;
+  Ok(backend)
+}
+
+/// Refresh the token in `found` if it was obtained through the device flow
+/// and is about to expire, returning the credentials to use.
+///
+/// Tokens from backends twig does not write to (environment variables, the
+/// GitHub CLI) are returned unchanged.
+pub fn refresh_if_expiring(home: &Path, host: &str, found: CredentialMatch) -> Result<Credentials> {
+  let config_dirs = ConfigDirs::new()?;
+  let state_path = oauth_state_path(&config_dirs);
+  let mut state = OAuthState::load(&state_path)?;
+  let now = unix_now();
+  let Some(host_state) = state.hosts.get(host).filter(|s| s.needs_refresh(now)).cloned() else {
+    return Ok(found.credentials);
+  };
+
+  let chain = CredentialChain::load(home)?;
+  if chain.store_backend() != Some(found.backend) {
+    return Ok(found.credentials);
+  }
+  if host_state.refresh_expired(now) {
+    bail!("The GitHub token for {host} has expired. Run 'twig creds setup' to log in again.");
+  }
+  let refresh_token = chain
+    .find(CredentialService::GitHubRefreshToken, &refresh_token_key(host))?
+    .with_context(|| format!("No refresh token stored for {host}. Run 'twig creds setup' to log in again."))?
+    .credentials
+    .password;
+
+  info!(host, "Refreshing expiring GitHub token");
+  let flow = DeviceFlow::for_host(&config_dirs.load_github_config()?, host, &host_state.client_id);
+  let token = block_on_new_runtime(flow.refresh(&refresh_token))?;
+
+  let credentials = Credentials {
+    username: found.credentials.username,
+    password: token.access_token.clone(),
+  };
+  chain.store(host, &credentials)?;
+  if let Some(refresh_token) = &token.refresh_token {
+    chain.store(
+      &refresh_token_key(host),
+      &Credentials {
+        username: credentials.username.clone(),
+        password: refresh_token.clone(),
+      },
+    )?;
+  }
+  state.hosts.insert(
+    host.to_string(),
+    OAuthHostState::from_token(&host_state.client_id, &token, now),
+  );
+  state.save(&state_path)?;
+  Ok(credentials)
+}
+
+/// Run `future` to completion on a dedicated runtime. A separate thread is
+/// used because callers may already be running inside a tokio runtime.
+fn block_on_new_runtime<T: Send>(future: impl Future<Output = Result<T>> + Send) -> Result<T> {
+  std::thread::scope(|scope| {
+    scope
+      .spawn(|| {
+        tokio::runtime::Builder::new_current_thread()
+          .enable_all()
+          .build()
+          .context("Failed to create async runtime")?
+          .block_on(future)
+      })
+      .join()
+      .map_err(|_| anyhow::anyhow!("Token refresh thread panicked"))?
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::matchers::{body_string_contains, method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+
+  fn device_code() -> DeviceCode {
+    DeviceCode {
+      device_code: "dev-123".to_string(),
+      user_code: "ABCD-1234".to_string(),
+      verification_uri: "https://github.com/login/device".to_string(),
+      expires_in: 60,
+      interval: 0,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_request_code() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+      .and(path("/login/device/code"))
+      .and(body_string_contains("client_id=Iv1.test"))
+      .and(body_string_contains("scope=repo+read%3Auser"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "device_code": "dev-123",
+        "user_code": "ABCD-1234",
+        "verification_uri": "https://github.com/login/device",
+        "expires_in": 900,
+        "interval": 5
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let flow = DeviceFlow::new(mock_server.uri(), "Iv1.test");
+    let code = flow.request_code(REQUIRED_SCOPES).await?;
+    assert_eq!(code.user_code, "ABCD-1234");
+    assert_eq!(code.interval, 5);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_poll_until_authorized() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+      .and(path("/login/oauth/access_token"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"error": "authorization_pending"})))
+      .up_to_n_times(2)
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("POST"))
+      .and(path("/login/oauth/access_token"))
+      .and(body_string_contains("device_code=dev-123"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "access_token": "ghu_abc",
+        "token_type": "bearer",
+        "scope": "",
+        "expires_in": 28800,
+        "refresh_token": "ghr_def",
+        "refresh_token_expires_in": 15897600
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let flow = DeviceFlow::new(mock_server.uri(), "Iv1.test");
+    let token = flow.poll_for_token(&device_code()).await?;
+    assert_eq!(token.access_token, "ghu_abc");
+    assert_eq!(token.refresh_token.as_deref(), Some("ghr_def"));
+
+    let state = OAuthHostState::from_token("Iv1.test", &token, 1_000);
+    assert_eq!(state.expires_at, Some(29_800));
+    assert!(!state.needs_refresh(1_000));
+    assert!(state.needs_refresh(29_600));
+    assert!(!state.refresh_expired(29_600));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_poll_access_denied() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+      .and(path("/login/oauth/access_token"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"error": "access_denied"})))
+      .mount(&mock_server)
+      .await;
+
+    let flow = DeviceFlow::new(mock_server.uri(), "Iv1.test");
+    let error = flow.poll_for_token(&device_code()).await.unwrap_err();
+    assert!(error.to_string().contains("denied"));
+    Ok(())
+  }
+
+  // Multi-threaded so the mock server keeps running while a worker blocks
+  #[tokio::test(flavor = "multi_thread")]
+  async fn test_refresh() -> Result<()> {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+      .and(path("/login/oauth/access_token"))
+      .and(body_string_contains("grant_type=refresh_token"))
+      .and(body_string_contains("refresh_token=ghr_old"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "access_token": "ghu_new",
+        "expires_in": 28800,
+        "refresh_token": "ghr_new"
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let flow = DeviceFlow::new(mock_server.uri(), "Iv1.test");
+    let token = flow.refresh("ghr_old").await?;
+    assert_eq!(token.access_token, "ghu_new");
+    assert_eq!(token.refresh_token.as_deref(), Some("ghr_new"));
+
+    // Refreshing from inside a runtime must not deadlock or panic
+    let token = block_on_new_runtime(flow.refresh("ghr_old"))?;
+    assert_eq!(token.access_token, "ghu_new");
+    Ok(())
+  }
+
+  #[test]
+  fn test_missing_scopes() {
+    let granted = parse_scopes("repo, user, admin:org");
+    assert!(missing_scopes(&granted, REQUIRED_SCOPES).is_empty());
+    assert!(missing_scopes(&granted, &["public_repo", "read:org", "user:email"]).is_empty());
+
+    let granted = parse_scopes("public_repo");
+    assert_eq!(missing_scopes(&granted, REQUIRED_SCOPES), vec!["repo", "read:user"]);
+  }
+}
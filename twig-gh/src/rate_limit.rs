@@ -0,0 +1,205 @@
+//! # Rate Limit Handling
+//!
+//! Parsing of GitHub's `X-RateLimit-*` response headers and detection of
+//! secondary rate limits, which GitHub asks clients to back off from before
+//! retrying.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::Serialize;
+
+/// Rate limit state reported by the most recent GitHub API response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RateLimit {
+  /// Maximum number of requests allowed in the current window
+  pub limit: u32,
+  /// Requests left in the current window
+  pub remaining: u32,
+  /// Requests made in the current window
+  pub used: u32,
+  /// Unix timestamp (seconds) at which the window resets
+  pub reset: u64,
+  /// Rate limit bucket, e.g. "core" or "graphql"
+  pub resource: Option<String>,
+}
+
+impl RateLimit {
+  /// Parse rate limit headers, returning `None` if they are absent
+  pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+    Some(Self {
+      limit: header_value(headers, "x-ratelimit-limit")?,
+      remaining: header_value(headers, "x-ratelimit-remaining")?,
+      used: header_value(headers, "x-ratelimit-used").unwrap_or_default(),
+      reset: header_value(headers, "x-ratelimit-reset")?,
+      resource: headers
+        .get("x-ratelimit-resource")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string),
+    })
+  }
+
+  /// Whether the primary rate limit has been used up
+  pub fn is_exhausted(&self) -> bool {
+    self.remaining == 0
+  }
+
+  /// Time left until the window resets, measured from now
+  pub fn time_until_reset(&self) -> Duration {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    Duration::from_secs(self.reset.saturating_sub(now))
+  }
+}
+
+/// How the client retries requests rejected by a secondary rate limit
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+  /// Retries attempted before the rate limit error is returned
+  pub max_retries: u32,
+  /// Initial wait when GitHub does not send `Retry-After`; doubled per retry
+  pub default_delay: Duration,
+  /// Upper bound on any single wait
+  pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    // GitHub asks clients to wait at least a minute when no Retry-After is given.
+    Self {
+      max_retries: 3,
+      default_delay: Duration::from_secs(60),
+      max_delay: Duration::from_secs(300),
+    }
+  }
+}
+
+impl RetryPolicy {
+  /// Delay before retrying a response that hit a secondary rate limit
+  ///
+  /// Returns `None` when the response is not a secondary rate limit or the
+  /// retry budget is spent.
+  pub(crate) fn secondary_limit_delay(
+    &self,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+    attempt: u32,
+  ) -> Option<Duration> {
+    if attempt >= self.max_retries || !is_secondary_rate_limit(status, headers, body) {
+      return None;
+    }
+
+    let delay = match header_value::<u64>(headers, RETRY_AFTER.as_str()) {
+      Some(seconds) => Duration::from_secs(seconds),
+      None => self.default_delay.saturating_mul(2u32.saturating_pow(attempt)),
+    };
+    Some(delay.min(self.max_delay))
+  }
+}
+
+/// Whether a response was rejected by the primary (hourly) rate limit
+pub(crate) fn is_primary_rate_limit(status: StatusCode, headers: &HeaderMap) -> bool {
+  is_rate_limit_status(status) && RateLimit::from_headers(headers).is_some_and(|r| r.is_exhausted())
+}
+
+fn is_secondary_rate_limit(status: StatusCode, headers: &HeaderMap, body: &str) -> bool {
+  if !is_rate_limit_status(status) || is_primary_rate_limit(status, headers) {
+    return false;
+  }
+  headers.contains_key(RETRY_AFTER) || body.to_ascii_lowercase().contains("secondary rate limit")
+}
+
+fn is_rate_limit_status(status: StatusCode) -> bool {
+  status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn header_value<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+  headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use reqwest::header::HeaderValue;
+
+  use super::*;
+
+  fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in pairs {
+      map.insert(*name, HeaderValue::from_static(value));
+    }
+    map
+  }
+
+  #[test]
+  fn test_parse_rate_limit_headers() {
+    let map = headers(&[
+      ("x-ratelimit-limit", "5000"),
+      ("x-ratelimit-remaining", "4990"),
+      ("x-ratelimit-used", "10"),
+      ("x-ratelimit-reset", "1700000000"),
+      ("x-ratelimit-resource", "core"),
+    ]);
+
+    let limit = RateLimit::from_headers(&map).expect("headers should parse");
+    assert_eq!(limit.limit, 5000);
+    assert_eq!(limit.remaining, 4990);
+    assert_eq!(limit.used, 10);
+    assert_eq!(limit.reset, 1_700_000_000);
+    assert_eq!(limit.resource.as_deref(), Some("core"));
+    assert!(!limit.is_exhausted());
+
+    assert!(RateLimit::from_headers(&HeaderMap::new()).is_none());
+  }
+
+  #[test]
+  fn test_secondary_limit_uses_retry_after() {
+    let policy = RetryPolicy::default();
+    let map = headers(&[("retry-after", "7")]);
+
+    let delay = policy.secondary_limit_delay(StatusCode::FORBIDDEN, &map, "", 0);
+    assert_eq!(delay, Some(Duration::from_secs(7)));
+    assert_eq!(
+      policy.secondary_limit_delay(StatusCode::FORBIDDEN, &map, "", policy.max_retries),
+      None
+    );
+  }
+
+  #[test]
+  fn test_secondary_limit_backs_off_exponentially_without_retry_after() {
+    let policy = RetryPolicy::default();
+    let body = r#"{"message":"You have exceeded a secondary rate limit."}"#;
+
+    let first = policy.secondary_limit_delay(StatusCode::FORBIDDEN, &HeaderMap::new(), body, 0);
+    let second = policy.secondary_limit_delay(StatusCode::FORBIDDEN, &HeaderMap::new(), body, 1);
+    let third = policy.secondary_limit_delay(StatusCode::FORBIDDEN, &HeaderMap::new(), body, 2);
+    assert_eq!(first, Some(Duration::from_secs(60)));
+    assert_eq!(second, Some(Duration::from_secs(120)));
+    assert_eq!(third, Some(Duration::from_secs(240)));
+  }
+
+  #[test]
+  fn test_other_errors_are_not_retried() {
+    let policy = RetryPolicy::default();
+    let exhausted = headers(&[
+      ("x-ratelimit-limit", "5000"),
+      ("x-ratelimit-remaining", "0"),
+      ("x-ratelimit-reset", "1700000000"),
+    ]);
+
+    assert!(is_primary_rate_limit(StatusCode::FORBIDDEN, &exhausted));
+    assert_eq!(
+      policy.secondary_limit_delay(StatusCode::FORBIDDEN, &exhausted, "", 0),
+      None
+    );
+    assert_eq!(
+      policy.secondary_limit_delay(StatusCode::FORBIDDEN, &HeaderMap::new(), "Bad credentials", 0),
+      None
+    );
+    assert_eq!(
+      policy.secondary_limit_delay(StatusCode::NOT_FOUND, &HeaderMap::new(), "", 0),
+      None
+    );
+  }
+}
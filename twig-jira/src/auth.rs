@@ -9,8 +9,8 @@ use std::path::Path;
 use anyhow::{Context, Result};
 use tokio::runtime::Runtime;
 use twig_core::creds::Credentials;
+use twig_core::creds::chain::{CredentialChain, CredentialMatch, CredentialService};
 use twig_core::creds::netrc::normalize_host;
-use twig_core::creds::platform::{CredentialProvider, get_credential_provider};
 pub use twig_core::url::ENV_JIRA_HOST;
 use twig_core::url::resolve_jira_base_url;
 
@@ -27,30 +27,47 @@ pub fn check_jira_credentials(home: &Path, jira_host: &str) -> Result<bool> {
   Ok(get_jira_credentials(home, jira_host).is_ok())
 }
 
-/// Retrieve Jira credentials from the configured credential provider.
-pub fn get_jira_credentials(home: &Path, jira_host: &str) -> Result<Credentials> {
-  let provider = get_credential_provider(home);
+/// Look up Jira credentials through the configured credential backends,
+/// reporting which backend provided them.
+///
+/// Credentials for the Jira host itself take precedence over a shared
+/// `atlassian.net` entry, which takes precedence over the `.netrc` `default`
+/// entry.
+pub fn find_jira_credentials(home: &Path, jira_host: &str) -> Result<Option<CredentialMatch>> {
+  let chain = CredentialChain::load(home)?;
 
   let normalized_host = normalize_host(jira_host);
-  if let Some(creds) = provider.get_credentials(&normalized_host)? {
-    return Ok(creds);
+  if let Some(found) = chain.find(CredentialService::Jira, &normalized_host)? {
+    return Ok(Some(found));
+  }
+  if let Some(found) = chain.find(CredentialService::Jira, "atlassian.net")? {
+    return Ok(Some(found));
   }
-  if let Some(creds) = provider.get_credentials("atlassian.net")? {
-    return Ok(creds);
+  chain.find_default()
+}
+
+/// Retrieve Jira credentials from the configured credential backends.
+pub fn get_jira_credentials(home: &Path, jira_host: &str) -> Result<Credentials> {
+  if let Some(found) = find_jira_credentials(home, jira_host)? {
+    return Ok(found.credentials);
   }
 
+  let normalized_host = normalize_host(jira_host);
   #[cfg(unix)]
   let error_msg = format!(
-    "Jira credentials not found in .netrc file. Please add credentials for machine '{normalized_host}' or 'atlassian.net'."
+    "Jira credentials not found. Set TWIG_JIRA_USER and TWIG_JIRA_TOKEN or add credentials for machine \
+     '{normalized_host}' or 'atlassian.net' to your .netrc file."
   );
   #[cfg(windows)]
   let error_msg = format!(
-    "Jira credentials not found. Please run 'twig creds setup' to configure credentials for '{normalized_host}' or 'atlassian.net'."
+    "Jira credentials not found. Set TWIG_JIRA_USER and TWIG_JIRA_TOKEN or run 'twig creds setup' to configure \
+     credentials for '{normalized_host}' or 'atlassian.net'."
   );
   Err(anyhow::anyhow!(error_msg))
 }
 
-/// Creates an authenticated Jira client using credentials from .netrc.
+/// Creates an authenticated Jira client using the configured credential
+/// backends.
 pub fn create_jira_client_from_netrc(home: &Path, jira_host: &str) -> Result<JiraClient> {
   let credentials = get_jira_credentials(home, jira_host).context("Failed to get credentials")?;
 
@@ -99,6 +116,19 @@ machine atlassian.net
     assert_eq!(jira_creds.password, "net-token");
   }
 
+  #[test]
+  fn test_netrc_default_entry_is_the_last_resort() {
+    let guard = NetrcGuard::new(
+      "default login anonymous password guest\nmachine atlassian.net login net@example.com password net-token\n",
+    );
+    let jira_creds = get_jira_credentials(guard.home_dir(), "acme.atlassian.net").unwrap();
+    assert_eq!(jira_creds.username, "net@example.com");
+
+    let guard = NetrcGuard::new("default login anonymous password guest\n");
+    let jira_creds = get_jira_credentials(guard.home_dir(), "acme.atlassian.net").unwrap();
+    assert_eq!(jira_creds.username, "anonymous");
+  }
+
   #[test]
   fn test_get_jira_credentials_error_messages() {
     let guard = NetrcGuard::new("");
@@ -0,0 +1,296 @@
+//! # Lifecycle Transitions
+//!
+//! Applies the `[transitions]` rules from `jira.toml` when branches are
+//! created and pull requests are opened or merged, so issues follow the work
+//! without manual `twig jira transition` calls.
+
+use anyhow::Result;
+use tracing::{debug, instrument};
+use twig_core::jira_parser::{JiraLifecycleEvent, JiraTransitionRules};
+
+use crate::client::JiraClient;
+use crate::models::Transition;
+
+/// Result of applying a transition rule to an issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransitionOutcome {
+  /// The issue was moved using the named transition
+  Transitioned { transition: String },
+  /// Dry run: the issue would be moved using the named transition
+  WouldTransition { transition: String },
+  /// The issue already has the target status
+  AlreadyInStatus { status: String },
+  /// No available transition matches the target
+  NotAvailable { target: String, available: Vec<String> },
+}
+
+impl TransitionOutcome {
+  /// One-line description of the outcome for `issue_key`
+  pub fn describe(&self, issue_key: &str) -> String {
+    match self {
+      Self::Transitioned { transition } => format!("Moved {issue_key} via '{transition}'"),
+      Self::WouldTransition { transition } => format!("Would move {issue_key} via '{transition}' (dry run)"),
+      Self::AlreadyInStatus { status } => format!("{issue_key} is already '{status}'"),
+      Self::NotAvailable { target, available } => format!(
+        "No transition to '{target}' is available for {issue_key} (available: {})",
+        available.join(", ")
+      ),
+    }
+  }
+}
+
+/// Find the transition matching `target` by name, ID or destination status,
+/// ignoring case
+pub fn find_transition<'a>(transitions: &'a [Transition], target: &str) -> Option<&'a Transition> {
+  let target = target.trim();
+  transitions
+    .iter()
+    .find(|t| t.name.eq_ignore_ascii_case(target) || t.id == target)
+    .or_else(|| {
+      transitions
+        .iter()
+        .find(|t| t.to.as_ref().is_some_and(|to| to.name.eq_ignore_ascii_case(target)))
+    })
+}
+
+impl JiraClient {
+  /// Move an issue towards `target`, a transition name, ID or status name
+  ///
+  /// Issues already in the target status, or in the destination status of
+  /// the target transition, are left alone. With `dry_run` the
+  /// matching transition is reported but not applied.
+  #[instrument(skip(self), level = "debug")]
+  pub async fn transition_issue_to(&self, issue_key: &str, target: &str, dry_run: bool) -> Result<TransitionOutcome> {
+    let issue = self.get_issue(issue_key).await?;
+    if issue.fields.status.name.eq_ignore_ascii_case(target.trim()) {
+      return Ok(TransitionOutcome::AlreadyInStatus {
+        status: issue.fields.status.name,
+      });
+    }
+
+    let transitions = self.get_transitions(issue_key).await?;
+    let Some(transition) = find_transition(&transitions, target) else {
+      return Ok(TransitionOutcome::NotAvailable {
+        target: target.to_string(),
+        available: transitions.iter().map(|t| t.name.clone()).collect(),
+      });
+    };
+
+    // A target naming a transition rather than a status is only known to be
+    // reached once the transition is resolved
+    if let Some(to) = &transition.to
+      && to.name.eq_ignore_ascii_case(&issue.fields.status.name)
+    {
+      return Ok(TransitionOutcome::AlreadyInStatus {
+        status: issue.fields.status.name,
+      });
+    }
+
+    if dry_run {
+      return Ok(TransitionOutcome::WouldTransition {
+        transition: transition.name.clone(),
+      });
+    }
+
+    self.transition_issue(issue_key, &transition.id).await?;
+    Ok(TransitionOutcome::Transitioned {
+      transition: transition.name.clone(),
+    })
+  }
+
+  /// Apply the rule configured for `event` to an issue
+  ///
+  /// Returns `None` when no rule is configured for the event. The rules'
+  /// own `dry_run` setting is combined with the caller's.
+  #[instrument(skip(self, rules), level = "debug")]
+  pub async fn apply_lifecycle_rule(
+    &self,
+    rules: &JiraTransitionRules,
+    event: JiraLifecycleEvent,
+    issue_key: &str,
+    dry_run: bool,
+  ) -> Result<Option<TransitionOutcome>> {
+    let Some(target) = rules.target_for(event) else {
+      debug!("No transition rule for {event}");
+      return Ok(None);
+    };
+
+    self
+      .transition_issue_to(issue_key, target, dry_run || rules.dry_run)
+      .await
+      .map(Some)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::matchers::{body_json, method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use super::*;
+  use crate::models::{IssueStatus, JiraAuth};
+
+  fn transition(id: &str, name: &str, to: &str) -> Transition {
+    Transition {
+      id: id.to_string(),
+      name: name.to_string(),
+      to: Some(IssueStatus {
+        id: None,
+        name: to.to_string(),
+      }),
+    }
+  }
+
+  #[test]
+  fn test_find_transition() {
+    let transitions = vec![
+      transition("11", "Start work", "In Progress"),
+      transition("21", "Request review", "In Review"),
+    ];
+
+    assert_eq!(
+      find_transition(&transitions, "start WORK").map(|t| t.id.as_str()),
+      Some("11")
+    );
+    assert_eq!(find_transition(&transitions, "21").map(|t| t.id.as_str()), Some("21"));
+    assert_eq!(
+      find_transition(&transitions, "in review").map(|t| t.id.as_str()),
+      Some("21")
+    );
+    assert!(find_transition(&transitions, "Done").is_none());
+  }
+
+  async fn mock_issue(server: &MockServer, status: &str) {
+    Mock::given(method("GET"))
+      .and(path("/rest/api/2/issue/PROJ-1"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "id": "1",
+          "key": "PROJ-1",
+          "fields": { "summary": "Issue", "status": { "name": status } }
+      })))
+      .mount(server)
+      .await;
+    Mock::given(method("GET"))
+      .and(path("/rest/api/2/issue/PROJ-1/transitions"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "transitions": [
+              { "id": "21", "name": "Start work", "to": { "name": "In Progress" } },
+              { "id": "31", "name": "Done", "to": { "name": "Done" } }
+          ]
+      })))
+      .mount(server)
+      .await;
+  }
+
+  fn client(server: &MockServer) -> JiraClient {
+    JiraClient::new(
+      &server.uri(),
+      JiraAuth {
+        username: "test_user".to_string(),
+        api_token: "test_token".to_string(),
+      },
+    )
+  }
+
+  #[tokio::test]
+  async fn test_apply_lifecycle_rule_transitions_issue() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    mock_issue(&server, "To Do").await;
+    Mock::given(method("POST"))
+      .and(path("/rest/api/2/issue/PROJ-1/transitions"))
+      .and(body_json(serde_json::json!({ "transition": { "id": "21" } })))
+      .respond_with(ResponseTemplate::new(204))
+      .expect(1)
+      .mount(&server)
+      .await;
+
+    let rules = JiraTransitionRules {
+      on_branch_create: Some("In Progress".to_string()),
+      ..JiraTransitionRules::default()
+    };
+    let client = client(&server);
+
+    let outcome = client
+      .apply_lifecycle_rule(&rules, JiraLifecycleEvent::BranchCreated, "PROJ-1", false)
+      .await?;
+    assert_eq!(
+      outcome,
+      Some(TransitionOutcome::Transitioned {
+        transition: "Start work".to_string()
+      })
+    );
+
+    let outcome = client
+      .apply_lifecycle_rule(&rules, JiraLifecycleEvent::PrMerged, "PROJ-1", false)
+      .await?;
+    assert_eq!(outcome, None);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_apply_lifecycle_rule_dry_run_and_current_status() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    mock_issue(&server, "In Progress").await;
+    Mock::given(method("POST"))
+      .and(path("/rest/api/2/issue/PROJ-1/transitions"))
+      .respond_with(ResponseTemplate::new(204))
+      .expect(0)
+      .mount(&server)
+      .await;
+
+    let rules = JiraTransitionRules {
+      on_branch_create: Some("In Progress".to_string()),
+      on_pr_merge: Some("Done".to_string()),
+      dry_run: true,
+      ..JiraTransitionRules::default()
+    };
+    let client = client(&server);
+
+    let outcome = client
+      .apply_lifecycle_rule(&rules, JiraLifecycleEvent::BranchCreated, "PROJ-1", false)
+      .await?;
+    assert_eq!(
+      outcome,
+      Some(TransitionOutcome::AlreadyInStatus {
+        status: "In Progress".to_string()
+      })
+    );
+
+    let outcome = client
+      .apply_lifecycle_rule(&rules, JiraLifecycleEvent::PrMerged, "PROJ-1", false)
+      .await?;
+    assert_eq!(
+      outcome,
+      Some(TransitionOutcome::WouldTransition {
+        transition: "Done".to_string()
+      })
+    );
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_transition_name_target_skips_issue_in_destination_status() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    mock_issue(&server, "In Progress").await;
+    Mock::given(method("POST"))
+      .and(path("/rest/api/2/issue/PROJ-1/transitions"))
+      .respond_with(ResponseTemplate::new(204))
+      .expect(0)
+      .mount(&server)
+      .await;
+
+    let outcome = client(&server)
+      .transition_issue_to("PROJ-1", "Start work", false)
+      .await?;
+    assert_eq!(
+      outcome,
+      TransitionOutcome::AlreadyInStatus {
+        status: "In Progress".to_string()
+      }
+    );
+
+    Ok(())
+  }
+}
@@ -60,6 +60,36 @@ impl JiraClient {
 
     Ok(success)
   }
+
+  /// Send an authenticated request to `path` below the Jira base URL and
+  /// return the status and body without interpreting them.
+  ///
+  /// Backs the plugin host API. Absolute URLs are rejected so credentials
+  /// are only ever sent to the configured host.
+  #[instrument(skip(self, body), level = "debug")]
+  pub async fn send_raw(&self, method: &str, path: &str, body: Option<&serde_json::Value>) -> Result<(u16, String)> {
+    if !path.starts_with('/') {
+      return Err(anyhow::anyhow!("API path '{path}' must start with '/'"));
+    }
+    let method = reqwest::Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+      .with_context(|| format!("Invalid HTTP method '{method}'"))?;
+    let url = format!("{}{path}", self.base_url.trim_end_matches('/'));
+
+    let mut request = self
+      .client
+      .request(method.clone(), &url)
+      .header(header::ACCEPT, "application/json")
+      .header(header::USER_AGENT, USER_AGENT)
+      .basic_auth(&self.auth.username, Some(&self.auth.api_token));
+    if let Some(body) = body {
+      request = request.json(body);
+    }
+
+    let response = request.send().await.context(format!("{method} {url} failed"))?;
+    let status = response.status().as_u16();
+    let body = response.text().await.context("Failed to read response body")?;
+    Ok((status, body))
+  }
 }
 
 /// Create a Jira client from credentials
@@ -120,7 +150,7 @@ mod tests {
     // Make the request - if auth is wrong, this will fail
     let response = client
       .client
-      .get(&format!("{}/rest/api/2/myself", client.base_url))
+      .get(format!("{}/rest/api/2/myself", client.base_url))
       .basic_auth("test_user", Some("test_token"))
       .send()
       .await?;
@@ -10,7 +10,7 @@ use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::client::JiraClient;
 use crate::consts::USER_AGENT;
-use crate::models::Issue;
+use crate::models::{CreatedIssue, Issue, IssueDetails, JiraUser, NewIssue};
 
 /// Represents a Jira comment
 #[derive(Debug, Deserialize)]
@@ -30,6 +30,61 @@ pub struct User {
   pub email_address: Option<String>,
 }
 
+/// Fields requested for the detailed issue view
+const ISSUE_DETAIL_FIELDS: &str = "summary,status,assignee,issuetype,description,parent,subtasks,issuelinks,comment";
+
+/// Largest page size accepted by the Jira search endpoint
+const MAX_SEARCH_PAGE_SIZE: usize = 100;
+
+/// One page of JQL search results
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuePage {
+  #[serde(default)]
+  pub issues: Vec<Issue>,
+  /// Total number of matches, when the endpoint reports it
+  #[serde(default)]
+  pub total: Option<u32>,
+  /// Token for fetching the next page
+  #[serde(default)]
+  pub next_page_token: Option<String>,
+  #[serde(default)]
+  pub is_last: bool,
+}
+
+/// Build a JQL query from the common list filters
+///
+/// An assignee of `me` maps to `currentUser()`. Results are ordered by the
+/// most recently updated issue.
+pub fn build_jql(project: Option<&str>, status: Option<&str>, assignee: Option<&str>) -> String {
+  let mut jql_parts = Vec::new();
+
+  // Add project filter
+  if let Some(project_key) = project {
+    jql_parts.push(format!("project = {project_key}"));
+  }
+
+  // Add status filter
+  if let Some(status_name) = status {
+    jql_parts.push(format!("status = \"{status_name}\""));
+  }
+
+  // Add assignee filter
+  if let Some(assignee_name) = assignee {
+    if assignee_name == "me" {
+      jql_parts.push("assignee = currentUser()".to_string());
+    } else {
+      jql_parts.push(format!("assignee = \"{assignee_name}\""));
+    }
+  }
+
+  if jql_parts.is_empty() {
+    "order by updated DESC".to_string()
+  } else {
+    format!("{} order by updated DESC", jql_parts.join(" AND "))
+  }
+}
+
 impl JiraClient {
   /// Get a Jira issue by key
   #[instrument(skip(self), level = "debug")]
@@ -78,6 +133,60 @@ impl JiraClient {
     }
   }
 
+  /// Get an issue with its description, comments, subtasks, links and parent
+  ///
+  /// Uses API v3 so rich text arrives as Atlassian Document Format, and falls
+  /// back to API v2 wiki markup on servers without v3 (Jira Server/Data
+  /// Center).
+  #[instrument(skip(self), level = "debug")]
+  pub async fn get_issue_details(&self, issue_key: &str) -> Result<IssueDetails> {
+    match self.fetch_issue_details("3", issue_key).await? {
+      Some(issue) => Ok(issue),
+      None => {
+        debug!("API v3 returned 404 for {}, retrying with API v2", issue_key);
+        self
+          .fetch_issue_details("2", issue_key)
+          .await?
+          .ok_or_else(|| anyhow::anyhow!("Issue {issue_key} not found"))
+      }
+    }
+  }
+
+  /// Fetch detailed issue fields from one API version, returning `None` on 404
+  async fn fetch_issue_details(&self, api_version: &str, issue_key: &str) -> Result<Option<IssueDetails>> {
+    let url = format!(
+      "{}/rest/api/{api_version}/issue/{issue_key}?fields={ISSUE_DETAIL_FIELDS}",
+      self.base_url
+    );
+    trace!("Jira API URL: {}", url);
+
+    let response = self
+      .client
+      .get(&url)
+      .header(header::USER_AGENT, USER_AGENT)
+      .basic_auth(&self.auth.username, Some(&self.auth.api_token))
+      .send()
+      .await
+      .context(format!("GET {url} failed"))?;
+
+    match response.status() {
+      StatusCode::OK => Ok(Some(
+        response
+          .json::<IssueDetails>()
+          .await
+          .context("Failed to parse Jira issue")?,
+      )),
+      StatusCode::NOT_FOUND => Ok(None),
+      StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow::anyhow!(
+        "Authentication failed. Please check your Jira credentials."
+      )),
+      status => {
+        let error_text = response.text().await.unwrap_or_default();
+        Err(anyhow::anyhow!("Unexpected error: HTTP {status} - {error_text}"))
+      }
+    }
+  }
+
   /// List Jira issues with filtering options
   #[instrument(skip(self), level = "debug")]
   pub async fn list_issues(
@@ -87,39 +196,53 @@ impl JiraClient {
     assignee: Option<&str>,
     pagination_options: Option<(u32, u32)>, // (max_results, start_at)
   ) -> Result<Vec<Issue>> {
-    let mut jql_parts = Vec::new();
+    let jql = build_jql(project, status, assignee);
 
-    // Add project filter
-    if let Some(project_key) = project {
-      jql_parts.push(format!("project = {project_key}"));
-    }
+    // Set up pagination
+    let (max_results, start_at) = pagination_options.unwrap_or((50, 0));
 
-    // Add status filter
-    if let Some(status_name) = status {
-      jql_parts.push(format!("status = \"{status_name}\""));
-    }
+    let page = self.search_issues_page(&jql, max_results, start_at, None).await?;
+    Ok(page.issues)
+  }
 
-    // Add assignee filter
-    if let Some(assignee_name) = assignee {
-      if assignee_name == "me" {
-        jql_parts.push("assignee = currentUser()".to_string());
-      } else {
-        jql_parts.push(format!("assignee = \"{assignee_name}\""));
+  /// Search issues with a raw JQL query
+  ///
+  /// Follows result pages until `limit` issues have been collected or the
+  /// last page is reached.
+  #[instrument(skip(self), level = "debug")]
+  pub async fn search_issues(&self, jql: &str, limit: usize) -> Result<Vec<Issue>> {
+    let mut issues = Vec::new();
+    let mut page_token: Option<String> = None;
+
+    while issues.len() < limit {
+      let page_size = (limit - issues.len()).min(MAX_SEARCH_PAGE_SIZE) as u32;
+      let page = self
+        .search_issues_page(jql, page_size, issues.len() as u32, page_token.as_deref())
+        .await?;
+      let fetched = page.issues.len();
+      issues.extend(page.issues);
+
+      match page.next_page_token {
+        Some(token) if fetched > 0 && !page.is_last => page_token = Some(token),
+        _ => break,
       }
     }
 
-    // Build JQL query
-    let jql = if jql_parts.is_empty() {
-      "order by updated DESC".to_string()
-    } else {
-      format!("{} order by updated DESC", jql_parts.join(" AND "))
-    };
+    issues.truncate(limit);
+    Ok(issues)
+  }
 
+  /// Fetch a single page of JQL search results
+  #[instrument(skip(self), level = "debug")]
+  pub async fn search_issues_page(
+    &self,
+    jql: &str,
+    max_results: u32,
+    start_at: u32,
+    next_page_token: Option<&str>,
+  ) -> Result<IssuePage> {
     info!("JQL query: {}", jql);
 
-    // Set up pagination
-    let (max_results, start_at) = pagination_options.unwrap_or((50, 0));
-
     #[derive(Serialize)]
     #[serde(rename_all = "camelCase")]
     struct SearchJqlRequest<'a> {
@@ -127,16 +250,19 @@ impl JiraClient {
       start_at: u32,
       max_results: u32,
       fields: Vec<&'a str>,
+      #[serde(skip_serializing_if = "Option::is_none")]
+      next_page_token: Option<&'a str>,
     }
 
     let url = format!("{}/rest/api/3/search/jql", self.base_url);
     trace!("Jira API URL: {}", url);
 
     let request_body = SearchJqlRequest {
-      jql: &jql,
+      jql,
       start_at,
       max_results,
       fields: vec!["*all"],
+      next_page_token,
     };
 
     // Send request
@@ -165,16 +291,9 @@ impl JiraClient {
 
     match status {
       StatusCode::OK => {
-        #[derive(Deserialize)]
-        struct SearchResponse {
-          issues: Vec<Issue>,
-          #[allow(dead_code)]
-          total: u32,
-        }
-
         #[derive(Deserialize)]
         struct MultiSearchResponse {
-          results: Vec<SearchResponse>,
+          results: Vec<IssuePage>,
         }
 
         let body = response
@@ -182,29 +301,21 @@ impl JiraClient {
           .await
           .context("Failed to read Jira search response body")?;
 
-        let parse_result: Result<(Vec<Issue>, u32), serde_json::Error> = serde_json::from_str::<SearchResponse>(&body)
-          .map(|search_response| (search_response.issues, search_response.total))
-          .or_else(|_| {
-            let multi_response: MultiSearchResponse = serde_json::from_str(&body)?;
-            let (issues, total) = multi_response
-              .results
-              .into_iter()
-              .next()
-              .map(|res| (res.issues, res.total))
-              .unwrap_or_default();
-            Ok((issues, total))
-          });
+        let parse_result = serde_json::from_str::<IssuePage>(&body).or_else(|_| {
+          let multi_response: MultiSearchResponse = serde_json::from_str(&body)?;
+          Ok::<_, serde_json::Error>(multi_response.results.into_iter().next().unwrap_or_default())
+        });
 
         match parse_result {
-          Ok((issues, total)) => {
-            info!("Successfully fetched {} Jira issues", issues.len());
-            trace!("Total issues reported by Jira: {}", total);
-            Ok(issues)
+          Ok(page) => {
+            info!("Successfully fetched {} Jira issues", page.issues.len());
+            trace!("Total issues reported by Jira: {:?}", page.total);
+            Ok(page)
           }
           Err(err) => {
             error!("Failed to parse Jira search response: {:?}", err);
             debug!("Raw response body: {}", body);
-            Err::<Vec<Issue>, _>(err).context("Failed to parse Jira search response")
+            Err::<IssuePage, _>(err).context("Failed to parse Jira search response")
           }
         }
       }
@@ -214,6 +325,10 @@ impl JiraClient {
           "Authentication failed. Please check your Jira credentials."
         ))
       }
+      StatusCode::BAD_REQUEST => {
+        let error_text = response.text().await.unwrap_or_default();
+        Err(anyhow::anyhow!("Invalid JQL: {}", describe_field_errors(&error_text)))
+      }
       _ => {
         let error_text = response.text().await.unwrap_or_default();
         warn!("Unexpected Jira API error: HTTP {} - {}", status, error_text);
@@ -284,4 +399,282 @@ impl JiraClient {
       }
     }
   }
+
+  /// Get the user the client is authenticated as
+  #[instrument(skip(self), level = "debug")]
+  pub async fn get_current_user(&self) -> Result<JiraUser> {
+    let url = format!("{}/rest/api/2/myself", self.base_url);
+    trace!("Jira API URL: {}", url);
+
+    let response = self
+      .client
+      .get(&url)
+      .header(header::USER_AGENT, USER_AGENT)
+      .basic_auth(&self.auth.username, Some(&self.auth.api_token))
+      .send()
+      .await
+      .context(format!("GET {url} failed"))?;
+
+    match response.status() {
+      StatusCode::OK => response.json::<JiraUser>().await.context("Failed to parse Jira user"),
+      StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(anyhow::anyhow!(
+        "Authentication failed. Please check your Jira credentials."
+      )),
+      status => {
+        let error_text = response.text().await.unwrap_or_default();
+        Err(anyhow::anyhow!("Unexpected error: HTTP {status} - {error_text}"))
+      }
+    }
+  }
+
+  /// Create a Jira issue
+  #[instrument(skip(self, issue), level = "debug")]
+  pub async fn create_issue(&self, issue: &NewIssue) -> Result<CreatedIssue> {
+    let url = format!("{}/rest/api/2/issue", self.base_url);
+    info!("Creating Jira issue in project {}", issue.project_key);
+    trace!("Jira API URL: {}", url);
+
+    let response = self
+      .client
+      .post(&url)
+      .header(header::USER_AGENT, USER_AGENT)
+      .basic_auth(&self.auth.username, Some(&self.auth.api_token))
+      .json(&serde_json::json!({ "fields": issue.to_fields() }))
+      .send()
+      .await
+      .context(format!("POST {url} failed"))?;
+
+    let status = response.status();
+    debug!("Jira API response status: {}", status);
+
+    match status {
+      StatusCode::CREATED | StatusCode::OK => {
+        let created = response
+          .json::<CreatedIssue>()
+          .await
+          .context("Failed to parse Jira create issue response")?;
+        info!("Created Jira issue {}", created.key);
+        Ok(created)
+      }
+      StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+        warn!("Authentication failed when accessing Jira API");
+        Err(anyhow::anyhow!(
+          "Authentication failed. Please check your Jira credentials."
+        ))
+      }
+      StatusCode::BAD_REQUEST => {
+        let error_text = response.text().await.unwrap_or_default();
+        Err(anyhow::anyhow!(
+          "Jira rejected the issue: {}",
+          describe_field_errors(&error_text)
+        ))
+      }
+      _ => {
+        let error_text = response.text().await.unwrap_or_default();
+        warn!("Unexpected Jira API error: HTTP {} - {}", status, error_text);
+        Err(anyhow::anyhow!("Unexpected error: HTTP {status} - {error_text}"))
+      }
+    }
+  }
+}
+
+/// Flatten a Jira error body (`errorMessages` plus per-field `errors`) into
+/// one line, falling back to the raw body
+fn describe_field_errors(body: &str) -> String {
+  #[derive(Deserialize)]
+  #[serde(rename_all = "camelCase")]
+  struct ErrorBody {
+    #[serde(default)]
+    error_messages: Vec<String>,
+    #[serde(default)]
+    errors: std::collections::BTreeMap<String, String>,
+  }
+
+  match serde_json::from_str::<ErrorBody>(body) {
+    Ok(parsed) if !parsed.error_messages.is_empty() || !parsed.errors.is_empty() => parsed
+      .error_messages
+      .into_iter()
+      .chain(
+        parsed
+          .errors
+          .into_iter()
+          .map(|(field, message)| format!("{field}: {message}")),
+      )
+      .collect::<Vec<_>>()
+      .join("; "),
+    _ => body.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use wiremock::matchers::{basic_auth, body_json, body_partial_json, method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  use super::build_jql;
+  use crate::client::JiraClient;
+  use crate::models::{JiraAuth, NewIssue, RichText};
+
+  fn new_issue() -> NewIssue {
+    NewIssue {
+      project_key: "PROJ".to_string(),
+      issue_type: "Task".to_string(),
+      summary: "Add retries".to_string(),
+      description: Some("Retry flaky uploads".to_string()),
+      ..NewIssue::default()
+    }
+  }
+
+  #[tokio::test]
+  async fn test_create_issue() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let auth = JiraAuth {
+      username: "test_user".to_string(),
+      api_token: "test_token".to_string(),
+    };
+    let client = JiraClient::new(&mock_server.uri(), auth);
+
+    Mock::given(method("POST"))
+      .and(path("/rest/api/2/issue"))
+      .and(basic_auth("test_user", "test_token"))
+      .and(body_json(serde_json::json!({
+          "fields": {
+              "project": { "key": "PROJ" },
+              "issuetype": { "name": "Task" },
+              "summary": "Add retries",
+              "description": "Retry flaky uploads"
+          }
+      })))
+      .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+          "id": "10042",
+          "key": "PROJ-42",
+          "self": "https://example.atlassian.net/rest/api/2/issue/10042"
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let created = client.create_issue(&new_issue()).await?;
+    assert_eq!(created.key, "PROJ-42");
+    assert_eq!(created.id, "10042");
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_build_jql() {
+    assert_eq!(build_jql(None, None, None), "order by updated DESC");
+    assert_eq!(
+      build_jql(Some("PROJ"), Some("In Progress"), Some("me")),
+      "project = PROJ AND status = \"In Progress\" AND assignee = currentUser() order by updated DESC"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_search_issues_follows_page_tokens() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let auth = JiraAuth {
+      username: "test_user".to_string(),
+      api_token: "test_token".to_string(),
+    };
+    let client = JiraClient::new(&mock_server.uri(), auth);
+
+    let issue = |key: &str| {
+      serde_json::json!({
+        "id": key,
+        "key": key,
+        "fields": { "summary": "Issue", "status": { "name": "To Do" } }
+      })
+    };
+
+    Mock::given(method("POST"))
+      .and(path("/rest/api/3/search/jql"))
+      .and(body_partial_json(serde_json::json!({ "nextPageToken": "page-2" })))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "issues": [issue("PROJ-3")],
+          "isLast": true
+      })))
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("POST"))
+      .and(path("/rest/api/3/search/jql"))
+      .and(body_partial_json(
+        serde_json::json!({ "jql": "sprint in openSprints()" }),
+      ))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "issues": [issue("PROJ-1"), issue("PROJ-2")],
+          "nextPageToken": "page-2",
+          "isLast": false
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let issues = client.search_issues("sprint in openSprints()", 10).await?;
+    let keys: Vec<_> = issues.iter().map(|i| i.key.as_str()).collect();
+    assert_eq!(keys, ["PROJ-1", "PROJ-2", "PROJ-3"]);
+
+    let issues = client.search_issues("sprint in openSprints()", 1).await?;
+    assert_eq!(issues.len(), 1);
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_create_issue_reports_field_errors() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let auth = JiraAuth {
+      username: "test_user".to_string(),
+      api_token: "test_token".to_string(),
+    };
+    let client = JiraClient::new(&mock_server.uri(), auth);
+
+    Mock::given(method("POST"))
+      .and(path("/rest/api/2/issue"))
+      .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+          "errorMessages": [],
+          "errors": { "issuetype": "Specify a valid issue type" }
+      })))
+      .mount(&mock_server)
+      .await;
+
+    let error = client.create_issue(&new_issue()).await.unwrap_err().to_string();
+    assert!(error.contains("issuetype: Specify a valid issue type"));
+
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_get_issue_details_falls_back_to_v2() -> anyhow::Result<()> {
+    let mock_server = MockServer::start().await;
+    let auth = JiraAuth {
+      username: "test_user".to_string(),
+      api_token: "test_token".to_string(),
+    };
+    let client = JiraClient::new(&mock_server.uri(), auth);
+
+    Mock::given(method("GET"))
+      .and(path("/rest/api/3/issue/PROJ-7"))
+      .respond_with(ResponseTemplate::new(404))
+      .mount(&mock_server)
+      .await;
+    Mock::given(method("GET"))
+      .and(path("/rest/api/2/issue/PROJ-7"))
+      .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+          "id": "10007",
+          "key": "PROJ-7",
+          "fields": {
+              "summary": "Server issue",
+              "status": { "name": "Open" },
+              "description": "h2. Wiki markup"
+          }
+      })))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let issue = client.get_issue_details("PROJ-7").await?;
+
+    assert_eq!(issue.key, "PROJ-7");
+    assert!(matches!(issue.fields.description, Some(RichText::Wiki(ref text)) if text == "h2. Wiki markup"));
+    Ok(())
+  }
 }
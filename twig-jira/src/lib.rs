@@ -5,18 +5,24 @@
 //! workflows.
 
 pub mod auth;
+pub mod automation;
 pub mod client;
 pub mod consts;
 pub mod endpoints;
 pub mod models;
+pub mod rich_text;
 
 // Re-export the client
 pub use auth::{
   ENV_JIRA_HOST, check_jira_credentials, create_jira_client_from_netrc, create_jira_runtime_and_client,
-  get_jira_credentials, get_jira_host,
+  find_jira_credentials, get_jira_credentials, get_jira_host,
 };
+pub use automation::{TransitionOutcome, find_transition};
 pub use client::{JiraClient, create_jira_client};
 // Re-export models
 pub use models::{
-  Issue, IssueFields, IssueStatus, JiraAuth, JiraUser, Transition, TransitionId, TransitionRequest, Transitions,
+  AdfMark, AdfNode, CreatedIssue, Issue, IssueComment, IssueComments, IssueDetailFields, IssueDetails, IssueFields,
+  IssueLink, IssueLinkType, IssueRef, IssueRefFields, IssueStatus, IssueType, JiraAuth, JiraUser, NewIssue, RichText,
+  Transition, TransitionId, TransitionRequest, Transitions,
 };
+pub use rich_text::RichTextRenderer;
@@ -41,6 +41,53 @@ pub struct JiraUser {
   pub display_name: String,
   #[serde(rename = "emailAddress", default)]
   pub email_address: Option<String>,
+  #[serde(rename = "accountId", default)]
+  pub account_id: Option<String>,
+}
+
+/// Fields for an issue to be created
+#[derive(Debug, Clone, Default)]
+pub struct NewIssue {
+  pub project_key: String,
+  pub issue_type: String,
+  pub summary: String,
+  pub description: Option<String>,
+  /// Account ID of the assignee
+  pub assignee: Option<String>,
+  pub labels: Vec<String>,
+  /// Key of the parent issue, e.g. an epic
+  pub parent: Option<String>,
+}
+
+impl NewIssue {
+  /// Build the `fields` object expected by the create issue endpoint
+  pub fn to_fields(&self) -> serde_json::Value {
+    let mut fields = serde_json::json!({
+      "project": { "key": self.project_key },
+      "issuetype": { "name": self.issue_type },
+      "summary": self.summary,
+    });
+    if let Some(description) = &self.description {
+      fields["description"] = serde_json::json!(description);
+    }
+    if let Some(assignee) = &self.assignee {
+      fields["assignee"] = serde_json::json!({ "accountId": assignee });
+    }
+    if !self.labels.is_empty() {
+      fields["labels"] = serde_json::json!(self.labels);
+    }
+    if let Some(parent) = &self.parent {
+      fields["parent"] = serde_json::json!({ "key": parent });
+    }
+    fields
+  }
+}
+
+/// Represents the response to a create issue request
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedIssue {
+  pub id: String,
+  pub key: String,
 }
 
 /// Represents a Jira issue status
@@ -55,6 +102,9 @@ pub struct IssueStatus {
 pub struct Transition {
   pub id: String,
   pub name: String,
+  /// Status the issue moves to
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub to: Option<IssueStatus>,
 }
 
 /// Represents a list of Jira transitions
@@ -75,6 +125,168 @@ pub struct TransitionId {
   pub id: String,
 }
 
+/// Rich text as returned by Jira
+///
+/// API v3 returns Atlassian Document Format (ADF) documents while API v2 and
+/// Jira Server return wiki markup strings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum RichText {
+  Wiki(String),
+  Adf(AdfNode),
+}
+
+/// A node in an Atlassian Document Format document
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AdfNode {
+  #[serde(rename = "type")]
+  pub node_type: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub text: Option<String>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub content: Vec<AdfNode>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub attrs: Option<serde_json::Value>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub marks: Vec<AdfMark>,
+}
+
+impl AdfNode {
+  /// Look up a string attribute, e.g. `href` or `language`
+  pub fn attr_str(&self, name: &str) -> Option<&str> {
+    self.attrs.as_ref()?.get(name)?.as_str()
+  }
+
+  /// Look up a numeric attribute, e.g. a heading `level`
+  pub fn attr_u64(&self, name: &str) -> Option<u64> {
+    self.attrs.as_ref()?.get(name)?.as_u64()
+  }
+}
+
+/// A formatting mark applied to an ADF text node
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AdfMark {
+  #[serde(rename = "type")]
+  pub mark_type: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub attrs: Option<serde_json::Value>,
+}
+
+impl AdfMark {
+  /// Look up a string attribute, e.g. a link `href`
+  pub fn attr_str(&self, name: &str) -> Option<&str> {
+    self.attrs.as_ref()?.get(name)?.as_str()
+  }
+}
+
+/// A Jira issue with the fields shown by `twig jira view`
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueDetails {
+  pub id: String,
+  pub key: String,
+  pub fields: IssueDetailFields,
+}
+
+/// Fields of a detailed issue
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueDetailFields {
+  pub summary: String,
+  #[serde(default)]
+  pub description: Option<RichText>,
+  pub status: IssueStatus,
+  #[serde(default)]
+  pub assignee: Option<JiraUser>,
+  #[serde(default)]
+  pub issuetype: Option<IssueType>,
+  /// Parent issue, e.g. the epic of a story or the story of a subtask
+  #[serde(default)]
+  pub parent: Option<IssueRef>,
+  #[serde(default)]
+  pub subtasks: Vec<IssueRef>,
+  #[serde(default)]
+  pub issuelinks: Vec<IssueLink>,
+  #[serde(default)]
+  pub comment: Option<IssueComments>,
+}
+
+/// Represents a Jira issue type
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueType {
+  pub name: String,
+}
+
+/// A reference to another issue with its summary fields
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueRef {
+  pub key: String,
+  #[serde(default)]
+  pub fields: Option<IssueRefFields>,
+}
+
+/// Summary fields embedded in an issue reference
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueRefFields {
+  #[serde(default)]
+  pub summary: Option<String>,
+  #[serde(default)]
+  pub status: Option<IssueStatus>,
+  #[serde(default)]
+  pub issuetype: Option<IssueType>,
+}
+
+/// A link between two issues
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueLink {
+  #[serde(rename = "type")]
+  pub link_type: IssueLinkType,
+  #[serde(rename = "inwardIssue", default)]
+  pub inward_issue: Option<IssueRef>,
+  #[serde(rename = "outwardIssue", default)]
+  pub outward_issue: Option<IssueRef>,
+}
+
+impl IssueLink {
+  /// The relationship as read from this issue, e.g. "blocks" or "is blocked
+  /// by", together with the linked issue
+  pub fn relation(&self) -> Option<(&str, &IssueRef)> {
+    match (&self.outward_issue, &self.inward_issue) {
+      (Some(issue), _) => Some((self.link_type.outward.as_deref().unwrap_or(&self.link_type.name), issue)),
+      (None, Some(issue)) => Some((self.link_type.inward.as_deref().unwrap_or(&self.link_type.name), issue)),
+      (None, None) => None,
+    }
+  }
+}
+
+/// Represents an issue link type
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueLinkType {
+  pub name: String,
+  #[serde(default)]
+  pub inward: Option<String>,
+  #[serde(default)]
+  pub outward: Option<String>,
+}
+
+/// Comments embedded in an issue
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueComments {
+  #[serde(default)]
+  pub comments: Vec<IssueComment>,
+  #[serde(default)]
+  pub total: Option<u32>,
+}
+
+/// A comment on an issue
+#[derive(Debug, Deserialize, Serialize)]
+pub struct IssueComment {
+  pub id: String,
+  #[serde(default)]
+  pub author: Option<JiraUser>,
+  pub body: RichText,
+  #[serde(default)]
+  pub created: String,
+}
+
 #[cfg(test)]
 mod tests {
   use serde_json::json;
@@ -115,6 +327,31 @@ mod tests {
     assert_eq!(issue.fields.status.name, "In Progress");
   }
 
+  #[test]
+  fn test_new_issue_fields() {
+    let issue = NewIssue {
+      project_key: "PROJ".to_string(),
+      issue_type: "Task".to_string(),
+      summary: "Add retries".to_string(),
+      description: None,
+      assignee: Some("abc123".to_string()),
+      labels: vec!["backend".to_string()],
+      parent: Some("PROJ-1".to_string()),
+    };
+
+    assert_eq!(
+      issue.to_fields(),
+      json!({
+        "project": { "key": "PROJ" },
+        "issuetype": { "name": "Task" },
+        "summary": "Add retries",
+        "assignee": { "accountId": "abc123" },
+        "labels": ["backend"],
+        "parent": { "key": "PROJ-1" },
+      })
+    );
+  }
+
   #[test]
   fn test_jira_transitions_deserialization() {
     let json = json!({
@@ -160,4 +397,42 @@ mod tests {
       })
     );
   }
+
+  #[test]
+  fn test_issue_details_deserialization() {
+    let json = json!({
+        "id": "10001",
+        "key": "TEST-1",
+        "fields": {
+            "summary": "Story",
+            "status": { "name": "In Progress" },
+            "description": {
+                "type": "doc",
+                "version": 1,
+                "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Hello" }] }]
+            },
+            "parent": { "key": "TEST-100", "fields": { "summary": "Epic", "issuetype": { "name": "Epic" } } },
+            "subtasks": [{ "key": "TEST-2", "fields": { "summary": "Sub", "status": { "name": "Done" } } }],
+            "issuelinks": [{
+                "type": { "name": "Blocks", "inward": "is blocked by", "outward": "blocks" },
+                "inwardIssue": { "key": "TEST-3" }
+            }],
+            "comment": {
+                "comments": [{ "id": "1", "body": "h1. Wiki", "created": "2024-01-01T00:00:00.000+0000" }],
+                "total": 1
+            }
+        }
+    });
+
+    let issue: IssueDetails = serde_json::from_value(json).unwrap();
+
+    assert!(matches!(issue.fields.description, Some(RichText::Adf(ref doc)) if doc.node_type == "doc"));
+    assert_eq!(issue.fields.parent.as_ref().unwrap().key, "TEST-100");
+    assert_eq!(issue.fields.subtasks.len(), 1);
+    let (relation, linked) = issue.fields.issuelinks[0].relation().unwrap();
+    assert_eq!(relation, "is blocked by");
+    assert_eq!(linked.key, "TEST-3");
+    let comments = issue.fields.comment.unwrap().comments;
+    assert!(matches!(comments[0].body, RichText::Wiki(ref text) if text == "h1. Wiki"));
+  }
 }
@@ -0,0 +1,597 @@
+//! # Rich Text Rendering
+//!
+//! Renders Jira rich text for the terminal. Jira Cloud's API v3 returns
+//! Atlassian Document Format (ADF) documents, while API v2 and Jira Server
+//! return wiki markup. Both are turned into plain lines with headings, lists,
+//! code blocks, quotes and tables laid out for a fixed-width font, and links
+//! emitted as OSC 8 hyperlinks.
+
+use std::sync::LazyLock;
+
+use chrono::DateTime;
+use owo_colors::OwoColorize;
+use regex::{Captures, Regex};
+use twig_core::output::ColorMode;
+use twig_core::text::{hyperlink, hyperlinks_disabled};
+
+use crate::models::{AdfMark, AdfNode, RichText};
+
+/// Inline wiki markup: monospace, mentions, links, bold and emphasis
+static WIKI_INLINE: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(concat!(
+    r"\{\{(?P<mono>.+?)\}\}",
+    r"|\[~(?P<mention>[^\]]+)\]",
+    r"|\[(?P<label>[^\[\]|]+)\|(?P<href>[^\[\]]+)\]",
+    r"|\[(?P<url>https?://[^\[\]\s]+)\]",
+    r"|\*(?P<strong>[^*\s](?:[^*]*[^*\s])?)\*",
+    r"|(?:^|\b)_(?P<em>[^_\s](?:[^_]*[^_\s])?)_",
+  ))
+  .expect("Failed to compile wiki inline regex")
+});
+
+/// Wiki list item prefix such as `*`, `##` or `-`
+static WIKI_LIST_ITEM: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"^\s*(?P<marker>[*#]+|-)\s+(?P<text>.*)$").expect("Failed to compile wiki list regex"));
+
+/// Wiki heading prefix such as `h2.`
+static WIKI_HEADING: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"^\s*h[1-6]\.\s+(?P<text>.*)$").expect("Failed to compile wiki heading regex"));
+
+/// Width of horizontal rules
+const RULE_WIDTH: usize = 40;
+
+/// Renders Jira rich text to terminal-friendly lines
+#[derive(Debug, Clone, Copy)]
+pub struct RichTextRenderer {
+  colors: ColorMode,
+}
+
+impl RichTextRenderer {
+  /// Create a renderer. With [`ColorMode::No`] the output contains no escape
+  /// sequences and links are written as `label (url)`.
+  pub fn new(colors: ColorMode) -> Self {
+    Self { colors }
+  }
+
+  /// Render ADF or wiki markup, whichever the server returned
+  pub fn render(&self, text: &RichText) -> String {
+    match text {
+      RichText::Adf(node) => self.render_adf(node),
+      RichText::Wiki(markup) => self.render_wiki(markup),
+    }
+  }
+
+  /// Render an ADF document
+  pub fn render_adf(&self, node: &AdfNode) -> String {
+    let lines = match node.node_type.as_str() {
+      "doc" => self.adf_blocks(&node.content, true),
+      _ => self.adf_block(node),
+    };
+    trim_blank_lines(lines).join("\n")
+  }
+
+  /// Render a wiki markup string
+  pub fn render_wiki(&self, markup: &str) -> String {
+    let mut lines = Vec::new();
+    let mut code_block: Option<&str> = None;
+    let mut quoted = false;
+    let mut table: Vec<(bool, Vec<String>)> = Vec::new();
+    let mut counters: Vec<usize> = Vec::new();
+
+    for raw in markup.lines() {
+      let line = raw.trim_end_matches('\r');
+
+      if let Some(closing) = code_block {
+        if line.trim_start().starts_with(closing) {
+          code_block = None;
+        } else {
+          lines.push(self.code_line(line));
+        }
+        continue;
+      }
+
+      let trimmed = line.trim();
+      if trimmed.starts_with('|') {
+        let header = trimmed.starts_with("||");
+        let separator = if header { "||" } else { "|" };
+        let cells = trimmed
+          .trim_start_matches(separator)
+          .trim_end_matches(separator)
+          .split(separator)
+          .map(|cell| self.wiki_inline(cell.trim()))
+          .collect();
+        table.push((header, cells));
+        continue;
+      }
+      if !table.is_empty() {
+        lines.extend(self.table(std::mem::take(&mut table)));
+      }
+
+      let prefix = if quoted { "│ " } else { "" };
+
+      if let Some(tag) = ["{code", "{noformat"].into_iter().find(|tag| trimmed.starts_with(tag)) {
+        let closing = if tag == "{code" { "{code}" } else { "{noformat}" };
+        let rest = trimmed.split_once('}').map(|(_, rest)| rest).unwrap_or_default();
+        match rest.split_once(closing) {
+          // Opening and closing tags on the same line
+          Some((inline, _)) => lines.push(self.code_line(inline)),
+          None => {
+            if !rest.is_empty() {
+              lines.push(self.code_line(rest));
+            }
+            code_block = Some(closing);
+          }
+        }
+        counters.clear();
+        continue;
+      }
+
+      if trimmed.starts_with("{quote}") || trimmed.starts_with("{panel") {
+        quoted = !quoted;
+        counters.clear();
+        continue;
+      }
+
+      if let Some(caps) = WIKI_HEADING.captures(line) {
+        lines.push(format!("{prefix}{}", self.strong(&self.wiki_inline(&caps["text"]))));
+        counters.clear();
+      } else if let Some(text) = trimmed.strip_prefix("bq. ") {
+        lines.push(format!("│ {}", self.wiki_inline(text)));
+        counters.clear();
+      } else if trimmed == "----" {
+        lines.push(format!("{prefix}{}", "─".repeat(RULE_WIDTH)));
+        counters.clear();
+      } else if let Some(caps) = WIKI_LIST_ITEM.captures(line) {
+        let marker = &caps["marker"];
+        let depth = marker.chars().count();
+        counters.resize(depth, 0);
+        let bullet = if marker.ends_with('#') {
+          counters[depth - 1] += 1;
+          format!("{}.", counters[depth - 1])
+        } else {
+          counters[depth - 1] = 0;
+          "•".to_string()
+        };
+        lines.push(format!(
+          "{prefix}{}{bullet} {}",
+          "  ".repeat(depth - 1),
+          self.wiki_inline(&caps["text"])
+        ));
+      } else {
+        counters.clear();
+        let text = self.wiki_inline(trimmed);
+        lines.push(if text.is_empty() {
+          String::new()
+        } else {
+          format!("{prefix}{text}")
+        });
+      }
+    }
+
+    if !table.is_empty() {
+      lines.extend(self.table(table));
+    }
+
+    trim_blank_lines(lines).join("\n")
+  }
+
+  fn styled(&self) -> bool {
+    self.colors != ColorMode::No
+  }
+
+  fn strong(&self, text: &str) -> String {
+    if self.styled() {
+      text.bold().to_string()
+    } else {
+      text.to_string()
+    }
+  }
+
+  fn code_line(&self, line: &str) -> String {
+    if self.styled() {
+      format!("    {}", line.dimmed())
+    } else {
+      format!("    {line}")
+    }
+  }
+
+  fn link(&self, label: &str, url: &str) -> String {
+    // A bare URL needs no "(url)" suffix when hyperlinks are unavailable
+    if label == url && (!self.styled() || hyperlinks_disabled()) {
+      return url.to_string();
+    }
+    hyperlink(label, url, self.colors).to_string()
+  }
+
+  /// Render a sequence of blocks, optionally separated by blank lines
+  fn adf_blocks(&self, nodes: &[AdfNode], spaced: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    for node in nodes {
+      let block = self.adf_block(node);
+      let is_list = matches!(node.node_type.as_str(), "bulletList" | "orderedList" | "taskList");
+      if spaced && !lines.is_empty() || !spaced && !lines.is_empty() && !is_list {
+        lines.push(String::new());
+      }
+      lines.extend(block);
+    }
+    lines
+  }
+
+  fn adf_block(&self, node: &AdfNode) -> Vec<String> {
+    match node.node_type.as_str() {
+      "paragraph" => self.adf_inline(&node.content).split('\n').map(str::to_string).collect(),
+      "heading" => {
+        let text = self.adf_inline(&node.content);
+        vec![self.strong(&text)]
+      }
+      "bulletList" => node
+        .content
+        .iter()
+        .flat_map(|item| self.adf_list_item(item, "• "))
+        .collect(),
+      "orderedList" => {
+        let start = node.attr_u64("order").unwrap_or(1);
+        node
+          .content
+          .iter()
+          .zip(start..)
+          .flat_map(|(item, number)| self.adf_list_item(item, &format!("{number}. ")))
+          .collect()
+      }
+      "taskList" => node
+        .content
+        .iter()
+        .flat_map(|item| {
+          let marker = if item.attr_str("state") == Some("DONE") {
+            "[x] "
+          } else {
+            "[ ] "
+          };
+          self.adf_list_item(item, marker)
+        })
+        .collect(),
+      "codeBlock" => {
+        let code: String = node.content.iter().filter_map(|child| child.text.as_deref()).collect();
+        code.lines().map(|line| self.code_line(line)).collect()
+      }
+      "blockquote" | "panel" => self
+        .adf_blocks(&node.content, true)
+        .into_iter()
+        .map(|line| format!("│ {line}").trim_end().to_string())
+        .collect(),
+      "expand" | "nestedExpand" => {
+        let mut lines = Vec::new();
+        if let Some(title) = node.attr_str("title").filter(|title| !title.is_empty()) {
+          lines.push(self.strong(title));
+        }
+        lines.extend(self.adf_blocks(&node.content, true));
+        lines
+      }
+      "rule" => vec!["─".repeat(RULE_WIDTH)],
+      "table" => {
+        let rows = node
+          .content
+          .iter()
+          .map(|row| {
+            let header = row.content.iter().all(|cell| cell.node_type == "tableHeader");
+            let cells = row
+              .content
+              .iter()
+              .map(|cell| self.adf_blocks(&cell.content, false).join(" "))
+              .collect();
+            (header, cells)
+          })
+          .collect();
+        self.table(rows)
+      }
+      "mediaSingle" | "mediaGroup" => node.content.iter().flat_map(|child| self.adf_block(child)).collect(),
+      "media" => {
+        let name = node.attr_str("alt").filter(|alt| !alt.is_empty());
+        vec![match name {
+          Some(name) => format!("[attachment: {name}]"),
+          None => "[attachment]".to_string(),
+        }]
+      }
+      "blockCard" | "embedCard" => node
+        .attr_str("url")
+        .map(|url| vec![self.link(url, url)])
+        .unwrap_or_default(),
+      _ if node.text.is_some() => vec![self.adf_inline(std::slice::from_ref(node))],
+      _ if node.content.iter().all(is_inline) => vec![self.adf_inline(&node.content)],
+      _ => self.adf_blocks(&node.content, true),
+    }
+  }
+
+  fn adf_list_item(&self, item: &AdfNode, marker: &str) -> Vec<String> {
+    let lines = if item.content.iter().all(is_inline) {
+      vec![self.adf_inline(&item.content)]
+    } else {
+      self.adf_blocks(&item.content, false)
+    };
+    let indent = " ".repeat(marker.chars().count());
+    lines
+      .into_iter()
+      .enumerate()
+      .map(|(index, line)| {
+        if index == 0 {
+          format!("{marker}{line}")
+        } else if line.is_empty() {
+          line
+        } else {
+          format!("{indent}{line}")
+        }
+      })
+      .collect()
+  }
+
+  fn adf_inline(&self, nodes: &[AdfNode]) -> String {
+    nodes.iter().map(|node| self.adf_inline_node(node)).collect()
+  }
+
+  fn adf_inline_node(&self, node: &AdfNode) -> String {
+    match node.node_type.as_str() {
+      "text" => self.adf_marked(node.text.as_deref().unwrap_or_default(), &node.marks),
+      "hardBreak" => "\n".to_string(),
+      "mention" => {
+        let name = node.attr_str("text").unwrap_or("someone");
+        if name.starts_with('@') {
+          name.to_string()
+        } else {
+          format!("@{name}")
+        }
+      }
+      "emoji" => node
+        .attr_str("text")
+        .or_else(|| node.attr_str("shortName"))
+        .unwrap_or_default()
+        .to_string(),
+      "inlineCard" => node.attr_str("url").map(|url| self.link(url, url)).unwrap_or_default(),
+      "status" => format!("[{}]", node.attr_str("text").unwrap_or_default().to_uppercase()),
+      "date" => node
+        .attr_str("timestamp")
+        .and_then(|timestamp| timestamp.parse::<i64>().ok())
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default(),
+      _ => self.adf_inline(&node.content),
+    }
+  }
+
+  fn adf_marked(&self, text: &str, marks: &[AdfMark]) -> String {
+    let mut rendered = text.to_string();
+    let mut href = None;
+    for mark in marks {
+      rendered = match mark.mark_type.as_str() {
+        "code" => format!("`{rendered}`"),
+        "strong" if self.styled() => rendered.bold().to_string(),
+        "em" if self.styled() => rendered.italic().to_string(),
+        "strike" if self.styled() => rendered.strikethrough().to_string(),
+        "underline" if self.styled() => rendered.underline().to_string(),
+        "link" => {
+          href = mark.attr_str("href");
+          rendered
+        }
+        _ => rendered,
+      };
+    }
+    match href {
+      Some(url) => self.link(&rendered, url),
+      None => rendered,
+    }
+  }
+
+  fn wiki_inline(&self, text: &str) -> String {
+    WIKI_INLINE
+      .replace_all(text, |caps: &Captures| {
+        if let Some(mono) = caps.name("mono") {
+          format!("`{}`", mono.as_str())
+        } else if let Some(mention) = caps.name("mention") {
+          format!("@{}", mention.as_str())
+        } else if let (Some(label), Some(href)) = (caps.name("label"), caps.name("href")) {
+          self.link(label.as_str(), href.as_str())
+        } else if let Some(url) = caps.name("url") {
+          self.link(url.as_str(), url.as_str())
+        } else if let Some(strong) = caps.name("strong") {
+          self.strong(strong.as_str())
+        } else if let Some(em) = caps.name("em") {
+          if self.styled() {
+            em.as_str().italic().to_string()
+          } else {
+            em.as_str().to_string()
+          }
+        } else {
+          caps[0].to_string()
+        }
+      })
+      .into_owned()
+  }
+
+  /// Lay out table rows in aligned columns, underlining a header row
+  fn table(&self, rows: Vec<(bool, Vec<String>)>) -> Vec<String> {
+    let columns = rows.iter().map(|(_, cells)| cells.len()).max().unwrap_or(0);
+    let mut widths = vec![0; columns];
+    for (_, cells) in &rows {
+      for (index, cell) in cells.iter().enumerate() {
+        widths[index] = widths[index].max(visible_width(cell));
+      }
+    }
+
+    let mut lines = Vec::new();
+    for (header, cells) in rows {
+      let padded: Vec<String> = (0..columns)
+        .map(|index| {
+          let cell = cells.get(index).map(String::as_str).unwrap_or_default();
+          let padding = " ".repeat(widths[index] - visible_width(cell));
+          if header {
+            format!("{}{padding}", self.strong(cell))
+          } else {
+            format!("{cell}{padding}")
+          }
+        })
+        .collect();
+      lines.push(padded.join(" │ ").trim_end().to_string());
+      if header {
+        let separator: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+        lines.push(separator.join("─┼─"));
+      }
+    }
+    lines
+  }
+}
+
+/// Whether an ADF node is inline content rather than a block
+fn is_inline(node: &AdfNode) -> bool {
+  matches!(
+    node.node_type.as_str(),
+    "text" | "hardBreak" | "mention" | "emoji" | "inlineCard" | "status" | "date"
+  )
+}
+
+/// Number of visible characters, ignoring ANSI styles and OSC 8 link targets
+fn visible_width(text: &str) -> usize {
+  let mut width = 0;
+  let mut chars = text.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != '\x1b' {
+      width += 1;
+      continue;
+    }
+    match chars.next() {
+      // CSI sequences end with a letter
+      Some('[') => {
+        for c in chars.by_ref() {
+          if c.is_ascii_alphabetic() {
+            break;
+          }
+        }
+      }
+      // OSC sequences end with BEL
+      Some(']') => {
+        for c in chars.by_ref() {
+          if c == '\x07' {
+            break;
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  width
+}
+
+fn trim_blank_lines(mut lines: Vec<String>) -> Vec<String> {
+  while lines.last().is_some_and(|line| line.trim().is_empty()) {
+    lines.pop();
+  }
+  let leading = lines.iter().take_while(|line| line.trim().is_empty()).count();
+  lines.drain(..leading);
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn plain() -> RichTextRenderer {
+    RichTextRenderer::new(ColorMode::No)
+  }
+
+  fn adf(value: serde_json::Value) -> AdfNode {
+    serde_json::from_value(value).unwrap()
+  }
+
+  #[test]
+  fn test_render_adf_blocks() {
+    let doc = adf(json!({
+        "type": "doc",
+        "version": 1,
+        "content": [
+            { "type": "heading", "attrs": { "level": 2 }, "content": [{ "type": "text", "text": "Steps" }] },
+            { "type": "orderedList", "content": [
+                { "type": "listItem", "content": [
+                    { "type": "paragraph", "content": [{ "type": "text", "text": "Run " },
+                        { "type": "text", "text": "twig sync", "marks": [{ "type": "code" }] }] },
+                    { "type": "bulletList", "content": [
+                        { "type": "listItem", "content": [
+                            { "type": "paragraph", "content": [{ "type": "text", "text": "nested" }] }
+                        ] }
+                    ] }
+                ] },
+                { "type": "listItem", "content": [
+                    { "type": "paragraph", "content": [
+                        { "type": "text", "text": "Ping " },
+                        { "type": "mention", "attrs": { "id": "abc", "text": "@Ada" } }
+                    ] }
+                ] }
+            ] },
+            { "type": "codeBlock", "attrs": { "language": "rust" }, "content": [
+                { "type": "text", "text": "fn main() {}\nmain();" }
+            ] },
+            { "type": "paragraph", "content": [
+                { "type": "text", "text": "docs", "marks": [{ "type": "link", "attrs": { "href": "https://example.com" } }] }
+            ] }
+        ]
+    }));
+
+    assert_eq!(
+      plain().render_adf(&doc),
+      "Steps\n\n1. Run `twig sync`\n   • nested\n2. Ping @Ada\n\n    fn main() {}\n    main();\n\ndocs \
+       (https://example.com)"
+    );
+  }
+
+  #[test]
+  fn test_render_adf_table_and_quote() {
+    let doc = adf(json!({
+        "type": "doc",
+        "content": [
+            { "type": "table", "content": [
+                { "type": "tableRow", "content": [
+                    { "type": "tableHeader", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Name" }] }] },
+                    { "type": "tableHeader", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "State" }] }] }
+                ] },
+                { "type": "tableRow", "content": [
+                    { "type": "tableCell", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "build" }] }] },
+                    { "type": "tableCell", "content": [{ "type": "paragraph", "content": [
+                        { "type": "status", "attrs": { "text": "done" } }
+                    ] }] }
+                ] }
+            ] },
+            { "type": "blockquote", "content": [
+                { "type": "paragraph", "content": [{ "type": "text", "text": "quoted" }] }
+            ] }
+        ]
+    }));
+
+    assert_eq!(
+      plain().render_adf(&doc),
+      "Name  │ State\n──────┼───────\nbuild │ [DONE]\n\n│ quoted"
+    );
+  }
+
+  #[test]
+  fn test_render_wiki_markup() {
+    let markup = "h1. Summary\nSee [the docs|https://example.com] and *ship* it, {{snake_case}} stays.\n\
+                  * first\n** nested\n# one\n# two\n{code:rust}\nlet x = 1;\n{code}\n||Key||Status||\n|ABC-1|Done|\n\
+                  ----\nThanks [~jdoe]";
+
+    assert_eq!(
+      plain().render_wiki(markup),
+      "Summary\nSee the docs (https://example.com) and ship it, `snake_case` stays.\n• first\n  • nested\n1. one\n2. \
+       two\n    let x = 1;\nKey   │ Status\n──────┼───────\nABC-1 │ Done\n────────────────────────────────────────\nThanks \
+       @jdoe"
+    );
+  }
+
+  #[test]
+  fn test_render_links_as_hyperlinks_when_colored() {
+    let rendered = RichTextRenderer::new(ColorMode::Yes).render(&RichText::Wiki("[https://example.com]".to_string()));
+    assert_eq!(
+      rendered,
+      "\x1b]8;;https://example.com\x07https://example.com\x1b]8;;\x07"
+    );
+    assert_eq!(visible_width(&rendered), "https://example.com".len());
+  }
+}
@@ -1,10 +1,12 @@
 //! Shared server context available to all tool handlers.
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use anyhow::Context;
 use twig_core::config::ConfigDirs;
-use twig_core::state::RepoState;
+use twig_core::state::{Registry, RepoState};
 use twig_gh::GitHubClient;
 use twig_jira::JiraClient;
 
@@ -13,11 +15,14 @@ use crate::types::ToolError;
 /// Shared context available to all tool handlers.
 pub struct ServerContext {
   pub config_dirs: ConfigDirs,
+  /// Default repository for tools called without a `repo` selector.
   pub repo_path: Option<PathBuf>,
   pub home_dir: PathBuf,
+  /// Whether tools may select any registered repository via their `repo` parameter.
+  pub multi_repo: bool,
 
-  /// Lazily initialized on first GitHub call.
-  github_client: tokio::sync::OnceCell<Option<GitHubClient>>,
+  /// Lazily initialized on first GitHub call, keyed by host.
+  github_clients: Mutex<HashMap<String, GitHubClient>>,
   /// Lazily initialized on first Jira call. Tuple of (client, host).
   jira_client: tokio::sync::OnceCell<Option<(JiraClient, String)>>,
 }
@@ -28,17 +33,57 @@ impl ServerContext {
       config_dirs,
       repo_path,
       home_dir,
-      github_client: tokio::sync::OnceCell::new(),
+      multi_repo: false,
+      github_clients: Mutex::new(HashMap::new()),
       jira_client: tokio::sync::OnceCell::new(),
     }
   }
 
+  /// Allow tools to target any repository in the twig registry.
+  pub fn with_multi_repo(mut self, multi_repo: bool) -> Self {
+    self.multi_repo = multi_repo;
+    self
+  }
+
   /// Returns the repo path or a structured `ToolError`.
   pub fn require_repo(&self) -> Result<&Path, ToolError> {
     self.repo_path.as_deref().ok_or_else(|| ToolError {
       code: "no_repo".into(),
       message: "twig-mcp was started outside a git repository".into(),
-      hint: Some("Run twig-mcp from within a git repository.".into()),
+      hint: Some(if self.multi_repo {
+        "Pass a `repo` parameter naming a registered repository (see `list_repositories`).".into()
+      } else {
+        "Run twig-mcp from within a git repository.".into()
+      }),
+    })
+  }
+
+  /// Resolve a tool's `repo` selector to a repository path.
+  ///
+  /// Without a selector this is the server's default repository. A selector
+  /// matches a registered repository by name or by path, and is only accepted
+  /// in multi-repo mode.
+  pub fn resolve_repo(&self, selector: Option<&str>) -> Result<PathBuf, ToolError> {
+    let Some(selector) = selector else {
+      return self.require_repo().map(Path::to_path_buf);
+    };
+    if !self.multi_repo {
+      return Err(ToolError {
+        code: "invalid_params".into(),
+        message: "The `repo` parameter is only available in multi-repo mode".into(),
+        hint: Some("Restart twig-mcp with `--multi-repo`, or omit `repo`.".into()),
+      });
+    }
+
+    let registry = Registry::load(&self.config_dirs).map_err(|e| ToolError {
+      code: "internal".into(),
+      message: format!("Failed to load registry: {e}"),
+      hint: None,
+    })?;
+    find_registered_repo(&registry, selector).ok_or_else(|| ToolError {
+      code: "not_found".into(),
+      message: format!("No registered repository matches '{selector}'"),
+      hint: Some("Use `list_repositories` to see registered repositories, or `twig git add <path>`.".into()),
     })
   }
 
@@ -48,9 +93,8 @@ impl ServerContext {
     RepoState::load(repo_path)
   }
 
-  /// Load repo state or return a structured `ToolError`.
-  pub fn require_repo_state(&self) -> Result<RepoState, ToolError> {
-    let repo_path = self.require_repo()?;
+  /// Load repo state for `repo_path` or return a structured `ToolError`.
+  pub fn require_repo_state(&self, repo_path: &Path) -> Result<RepoState, ToolError> {
     RepoState::load(repo_path).map_err(|e| ToolError {
       code: "no_twig_state".into(),
       message: format!("Failed to load twig state: {e}"),
@@ -58,18 +102,29 @@ impl ServerContext {
     })
   }
 
-  /// Lazily initialise and return the GitHub client.
-  pub async fn get_github_client(&self) -> Result<&GitHubClient, ToolError> {
-    let maybe_client = self
-      .github_client
-      .get_or_init(|| async { twig_gh::create_github_client_from_netrc(&self.home_dir).ok() })
-      .await;
+  /// Lazily initialise and return the GitHub client for `host`.
+  ///
+  /// Clients are created once per host so repositories on GitHub Enterprise
+  /// Server instances use their own credentials and API URL.
+  pub async fn get_github_client(&self, host: &str) -> Result<GitHubClient, ToolError> {
+    let mut clients = self.github_clients.lock().map_err(|_| ToolError {
+      code: "internal".into(),
+      message: "GitHub client cache is poisoned".into(),
+      hint: None,
+    })?;
+    if let Some(client) = clients.get(host) {
+      return Ok(client.clone());
+    }
 
-    maybe_client.as_ref().ok_or_else(|| ToolError {
+    let client = twig_gh::create_github_client_for_host(&self.home_dir, host).map_err(|e| ToolError {
       code: "credentials_missing".into(),
-      message: "GitHub credentials not found".into(),
-      hint: Some("Add credentials for github.com to `~/.netrc`. See `twig auth --help`.".into()),
-    })
+      message: format!("GitHub credentials not found for {host}: {e}"),
+      hint: Some(format!(
+        "Add credentials for {host} to `~/.netrc`. See `twig auth --help`."
+      )),
+    })?;
+    clients.insert(host.to_string(), client.clone());
+    Ok(client)
   }
 
   /// Lazily initialise and return the Jira client.
@@ -93,9 +148,8 @@ impl ServerContext {
       })
   }
 
-  /// Extract GitHub owner/repo from the git remote URL.
-  pub fn get_github_repo(&self) -> Result<twig_core::GitHubRepo, ToolError> {
-    let repo_path = self.require_repo()?;
+  /// Extract GitHub owner/repo from the git remote URL of `repo_path`.
+  pub fn get_github_repo(&self, repo_path: &Path) -> Result<twig_core::GitHubRepo, ToolError> {
     let repo = git2::Repository::open(repo_path).map_err(|e| ToolError {
       code: "no_repo".into(),
       message: format!("Failed to open git repository: {e}"),
@@ -118,3 +172,68 @@ impl ServerContext {
     })
   }
 }
+
+/// Find a registered repository by name, falling back to a path comparison.
+fn find_registered_repo(registry: &Registry, selector: &str) -> Option<PathBuf> {
+  let repos = registry.list();
+  if let Some(repo) = repos.iter().find(|r| r.name == selector) {
+    return Some(PathBuf::from(&repo.path));
+  }
+
+  let wanted = Path::new(selector);
+  let wanted = wanted.canonicalize().unwrap_or_else(|_| wanted.to_path_buf());
+  repos
+    .iter()
+    .map(|r| PathBuf::from(&r.path))
+    .find(|path| path.canonicalize().unwrap_or_else(|_| path.clone()) == wanted)
+}
+
+#[cfg(test)]
+mod tests {
+  use tempfile::TempDir;
+
+  use super::*;
+
+  fn context_with_registry(data_dir: &Path, repos: &[&Path]) -> ServerContext {
+    let config_dirs = ConfigDirs {
+      config_dir: data_dir.join("config"),
+      data_dir: data_dir.to_path_buf(),
+      cache_dir: None,
+    };
+    let mut registry = Registry::load(&config_dirs).unwrap();
+    for repo in repos {
+      registry.add(repo).unwrap();
+    }
+    registry.save(&config_dirs).unwrap();
+    ServerContext::new(config_dirs, None, PathBuf::from("/tmp"))
+  }
+
+  #[test]
+  fn repo_selector_requires_multi_repo_mode() {
+    let data = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    let context = context_with_registry(data.path(), &[repo.path()]);
+
+    let err = context.resolve_repo(Some("anything")).unwrap_err();
+    assert_eq!(err.code, "invalid_params");
+
+    let err = context.resolve_repo(None).unwrap_err();
+    assert_eq!(err.code, "no_repo");
+  }
+
+  #[test]
+  fn repo_selector_matches_registered_name_or_path() {
+    let data = TempDir::new().unwrap();
+    let repo = TempDir::new().unwrap();
+    let context = context_with_registry(data.path(), &[repo.path()]).with_multi_repo(true);
+    let expected = repo.path().canonicalize().unwrap();
+
+    let name = repo.path().file_name().unwrap().to_str().unwrap();
+    assert_eq!(context.resolve_repo(Some(name)).unwrap(), expected);
+    assert_eq!(
+      context.resolve_repo(Some(repo.path().to_str().unwrap())).unwrap(),
+      expected
+    );
+    assert_eq!(context.resolve_repo(Some("unknown")).unwrap_err().code, "not_found");
+  }
+}
@@ -0,0 +1,149 @@
+//! Streamable HTTP transport for twig-mcp.
+//!
+//! Serves the MCP streamable HTTP protocol at `/mcp` so one long-running
+//! server can handle several editor sessions. Every request must carry an
+//! `Authorization: Bearer <token>` header; each MCP session gets its own
+//! `TwigMcpServer` instance.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode, header};
+use hyper_util::rt::TokioIo;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
+use tokio::net::TcpListener;
+use tower_service::Service;
+
+use crate::server::TwigMcpServer;
+
+/// Path the MCP endpoint is served under.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Serve MCP over HTTP on `addr` until Ctrl-C is received.
+///
+/// `new_server` is called once per MCP session.
+pub async fn serve(
+  addr: SocketAddr,
+  token: String,
+  new_server: impl Fn() -> TwigMcpServer + Send + Sync + 'static,
+) -> Result<()> {
+  let config = StreamableHttpServerConfig::default();
+  let cancellation = config.cancellation_token.clone();
+  let mcp = StreamableHttpService::new(
+    move || Ok(new_server()),
+    Arc::new(LocalSessionManager::default()),
+    config,
+  );
+
+  let listener = TcpListener::bind(addr)
+    .await
+    .with_context(|| format!("Failed to bind {addr}"))?;
+  tracing::info!("Serving MCP over HTTP at http://{addr}{MCP_PATH}");
+
+  let token = Arc::new(token);
+  loop {
+    let (stream, peer) = tokio::select! {
+      accepted = listener.accept() => accepted.context("Failed to accept connection")?,
+      _ = tokio::signal::ctrl_c() => break,
+    };
+    tracing::debug!("Accepted HTTP connection from {peer}");
+
+    let mcp = mcp.clone();
+    let token = Arc::clone(&token);
+    let service = service_fn(move |request: Request<Incoming>| {
+      let mut mcp = mcp.clone();
+      let token = Arc::clone(&token);
+      async move {
+        if request.uri().path() != MCP_PATH {
+          return Ok::<_, Infallible>(plain_response(StatusCode::NOT_FOUND, "Not found"));
+        }
+        if !is_authorized(&request, &token) {
+          let mut response = plain_response(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token");
+          response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+          return Ok(response);
+        }
+        mcp.call(request).await
+      }
+    });
+
+    tokio::spawn(async move {
+      if let Err(e) = http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), service)
+        .await
+      {
+        tracing::debug!("HTTP connection from {peer} ended with error: {e}");
+      }
+    });
+  }
+
+  cancellation.cancel();
+  Ok(())
+}
+
+/// Generate a random bearer token for servers started without `--token`.
+pub fn generate_token() -> String {
+  uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Check the request's `Authorization: Bearer` header against `token`.
+fn is_authorized<B>(request: &Request<B>, token: &str) -> bool {
+  request
+    .headers()
+    .get(header::AUTHORIZATION)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("Bearer "))
+    .is_some_and(|presented| constant_time_eq(presented.trim().as_bytes(), token.as_bytes()))
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  if a.len() != b.len() {
+    return false;
+  }
+  a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn plain_response(status: StatusCode, message: &'static str) -> Response<BoxBody<Bytes, Infallible>> {
+  let mut response = Response::new(Full::new(Bytes::from_static(message.as_bytes())).boxed());
+  *response.status_mut() = status;
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn request_with_auth(value: Option<&str>) -> Request<()> {
+    let mut builder = Request::builder().uri(MCP_PATH);
+    if let Some(value) = value {
+      builder = builder.header(header::AUTHORIZATION, value);
+    }
+    builder.body(()).unwrap()
+  }
+
+  #[test]
+  fn bearer_token_is_required() {
+    assert!(is_authorized(&request_with_auth(Some("Bearer s3cret")), "s3cret"));
+    assert!(!is_authorized(&request_with_auth(Some("Bearer wrong!")), "s3cret"));
+    assert!(!is_authorized(&request_with_auth(Some("Basic s3cret")), "s3cret"));
+    assert!(!is_authorized(&request_with_auth(None), "s3cret"));
+  }
+
+  #[test]
+  fn generated_tokens_are_unique() {
+    let token = generate_token();
+    assert_eq!(token.len(), 32);
+    assert_ne!(token, generate_token());
+  }
+}
@@ -1,19 +1,25 @@
 //! twig-mcp: MCP server exposing twig branch metadata, Jira issues, and GitHub PRs.
 
 mod context;
+mod http;
+mod mutations;
+mod resources;
 mod server;
 mod tools;
 mod types;
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use anyhow::{Context, Result};
-use clap::{ArgAction, Parser};
+use anyhow::{Context, Result, bail};
+use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
 use directories::BaseDirs;
 use rmcp::ServiceExt;
 use tracing_subscriber::EnvFilter;
 use twig_core::config::ConfigDirs;
 use twig_core::git::detection::detect_repository;
+use twig_core::plugin::manifest::{PluginManifest, print_manifest_if_requested};
 
 use crate::context::ServerContext;
 use crate::server::{ToolGroup, TwigMcpServer};
@@ -37,14 +43,65 @@ struct Cli {
   #[arg(long = "repo", value_name = "PATH")]
   repo: Option<PathBuf>,
 
+  /// Let tools target any twig-registered repository through their `repo`
+  /// parameter (by name or path)
+  #[arg(long = "multi-repo")]
+  multi_repo: bool,
+
+  /// Transport to serve MCP over
+  #[arg(long = "transport", value_enum, default_value_t = Transport::Stdio)]
+  transport: Transport,
+
+  /// Address to listen on with `--transport http`. Must be a loopback
+  /// address unless `--allow-remote` is given.
+  #[arg(long = "bind", value_name = "ADDR", default_value = "127.0.0.1:8787")]
+  bind: SocketAddr,
+
+  /// Allow `--bind` to listen on a non-loopback address, exposing the server
+  /// to other machines
+  #[arg(long = "allow-remote")]
+  allow_remote: bool,
+
+  /// Bearer token HTTP clients must present. A random token is generated and
+  /// printed when omitted.
+  #[arg(long = "token", value_name = "TOKEN", env = "TWIG_MCP_TOKEN", hide_env_values = true)]
+  token: Option<String>,
+
   /// Disable a group of tools. Can be specified multiple times.
   #[arg(long = "disable", value_name = "GROUP")]
   disable: Vec<ToolGroup>,
+
+  /// Expose tools that modify branches and twig state (create branches, edit
+  /// dependencies, link Jira issues, cascade rebases, fixup commits)
+  #[arg(long = "allow-writes")]
+  allow_writes: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Transport {
+  /// JSON-RPC over stdin/stdout for a single client
+  Stdio,
+  /// MCP streamable HTTP on a local port, shared by several clients
+  Http,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+  // Answer the manifest handshake from the twig CLI
+  if print_manifest_if_requested(|| PluginManifest {
+    completions: false,
+    ..PluginManifest::from_command(&Cli::command())
+  })? {
+    return Ok(());
+  }
+
   let cli = Cli::parse();
+  if cli.transport == Transport::Http && !cli.bind.ip().is_loopback() && !cli.allow_remote {
+    bail!(
+      "Refusing to listen on non-loopback address {}; pass --allow-remote to expose twig-mcp to other machines",
+      cli.bind
+    );
+  }
 
   // Tracing to stderr — stdout is reserved for MCP JSON-RPC protocol.
   let level = match cli.verbose {
@@ -66,12 +123,32 @@ async fn main() -> Result<()> {
     .home_dir()
     .to_path_buf();
 
-  let context = ServerContext::new(config_dirs, repo_path, home_dir);
-  let server = TwigMcpServer::new(context, &cli.disable);
+  let multi_repo = cli.multi_repo;
+  let disabled = cli.disable;
+  let allow_writes = cli.allow_writes;
+  // One lock for every session, so HTTP clients can't run write tools concurrently
+  let write_lock = Arc::new(tokio::sync::Mutex::new(()));
+  let new_server = move || {
+    let context =
+      ServerContext::new(config_dirs.clone(), repo_path.clone(), home_dir.clone()).with_multi_repo(multi_repo);
+    TwigMcpServer::new(context, &disabled, allow_writes).with_write_lock(write_lock.clone())
+  };
 
-  // Start MCP server on stdio
-  let service = server.serve(rmcp::transport::io::stdio()).await?;
-  service.waiting().await?;
+  match cli.transport {
+    Transport::Stdio => {
+      let service = new_server().serve(rmcp::transport::io::stdio()).await?;
+      service.waiting().await?;
+    }
+    Transport::Http => {
+      let token = cli.token.unwrap_or_else(|| {
+        let token = http::generate_token();
+        eprintln!("Generated bearer token: {token}");
+        token
+      });
+      eprintln!("twig-mcp listening on http://{}{}", cli.bind, http::MCP_PATH);
+      http::serve(cli.bind, token, new_server).await?;
+    }
+  }
 
   Ok(())
 }
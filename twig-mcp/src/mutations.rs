@@ -0,0 +1,630 @@
+//! Repository mutations backing the write tools.
+//!
+//! Every operation validates its inputs and applies its changes to an
+//! in-memory `RepoState` first, so a dry run exercises the same checks (cycle
+//! detection, branch existence, ...) as a real run and only skips the final
+//! save or git invocation.
+
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use git2::{BranchType, Repository, StatusOptions};
+use twig_core::JiraTicketParser;
+use twig_core::git::find_checkout_path_for_branch;
+use twig_core::state::{BranchMetadata, RepoState};
+
+use crate::types::{CascadeResponse, CascadeStep, FixupCommitResponse, ToolError, WriteResponse};
+
+/// Create `name` from `parent`, record the dependency and optionally link a Jira issue.
+pub fn create_branch(
+  repo_path: &Path,
+  mut state: RepoState,
+  parser: &JiraTicketParser,
+  name: &str,
+  parent: Option<&str>,
+  jira_issue: Option<&str>,
+  dry_run: bool,
+) -> Result<WriteResponse, ToolError> {
+  let repo = open_repo(repo_path)?;
+
+  if !git2::Branch::name_is_valid(name).unwrap_or(false) {
+    return Err(tool_error(
+      "invalid_params",
+      format!("'{name}' is not a valid branch name"),
+      None,
+    ));
+  }
+  if repo.find_branch(name, BranchType::Local).is_ok() {
+    return Err(tool_error(
+      "already_exists",
+      format!("Branch '{name}' already exists"),
+      Some("Use `add_dependency` to attach an existing branch to a parent.".into()),
+    ));
+  }
+
+  let parent = match parent {
+    Some(p) => p.to_string(),
+    None => current_branch(&repo)?,
+  };
+  let parent_commit = require_local_branch(&repo, &parent)?
+    .get()
+    .peel_to_commit()
+    .map_err(|e| tool_error("internal", format!("Failed to resolve '{parent}': {e}"), None))?;
+
+  let mut changes = vec![format!(
+    "Create branch '{name}' at {} (from '{parent}')",
+    short_id(parent_commit.id())
+  )];
+
+  state
+    .add_dependency(name.to_string(), parent.clone())
+    .map_err(|e| tool_error("conflict", e.to_string(), None))?;
+  changes.push(format!("Add dependency '{name}' -> '{parent}'"));
+
+  if let Some(issue) = jira_issue {
+    let key = parse_issue_key(parser, issue)?;
+    changes.push(format!("Link '{name}' to {key}"));
+    state.add_branch_issue(BranchMetadata {
+      branch: name.to_string(),
+      jira_issue: Some(key),
+      github_pr: None,
+      created_at: chrono::Utc::now().to_rfc3339(),
+    });
+  }
+
+  if !dry_run {
+    repo.branch(name, &parent_commit, false).map_err(|e| {
+      tool_error(
+        "git_error",
+        format!("Failed to create branch '{name}': {}", e.message()),
+        None,
+      )
+    })?;
+    save_state(&state, repo_path)?;
+  }
+
+  Ok(WriteResponse { dry_run, changes })
+}
+
+/// Record that `child` depends on `parent`.
+pub fn add_dependency(
+  repo_path: &Path,
+  mut state: RepoState,
+  child: &str,
+  parent: &str,
+  dry_run: bool,
+) -> Result<WriteResponse, ToolError> {
+  let repo = open_repo(repo_path)?;
+  require_local_branch(&repo, child)?;
+  require_local_branch(&repo, parent)?;
+
+  if state.get_dependency_parents(child).contains(&parent) {
+    return Err(tool_error(
+      "already_exists",
+      format!("Dependency '{child}' -> '{parent}' already exists"),
+      None,
+    ));
+  }
+  state
+    .add_dependency(child.to_string(), parent.to_string())
+    .map_err(|e| tool_error("conflict", e.to_string(), None))?;
+
+  if !dry_run {
+    save_state(&state, repo_path)?;
+  }
+
+  Ok(WriteResponse {
+    dry_run,
+    changes: vec![format!("Add dependency '{child}' -> '{parent}'")],
+  })
+}
+
+/// Remove the recorded dependency of `child` on `parent`.
+pub fn remove_dependency(
+  repo_path: &Path,
+  mut state: RepoState,
+  child: &str,
+  parent: &str,
+  dry_run: bool,
+) -> Result<WriteResponse, ToolError> {
+  if !state.remove_dependency(child, parent) {
+    return Err(tool_error(
+      "not_found",
+      format!("No dependency '{child}' -> '{parent}' found in twig state"),
+      Some("Use `get_branch_stack` to see a branch's parents.".into()),
+    ));
+  }
+
+  if !dry_run {
+    save_state(&state, repo_path)?;
+  }
+
+  Ok(WriteResponse {
+    dry_run,
+    changes: vec![format!("Remove dependency '{child}' -> '{parent}'")],
+  })
+}
+
+/// Link `branch` (default: the current branch) to a Jira issue.
+pub fn link_jira_issue(
+  repo_path: &Path,
+  mut state: RepoState,
+  parser: &JiraTicketParser,
+  branch: Option<&str>,
+  issue_key: &str,
+  dry_run: bool,
+) -> Result<WriteResponse, ToolError> {
+  let repo = open_repo(repo_path)?;
+  let branch = match branch {
+    Some(b) => b.to_string(),
+    None => current_branch(&repo)?,
+  };
+  require_local_branch(&repo, &branch)?;
+  let key = parse_issue_key(parser, issue_key)?;
+
+  let existing = state.get_branch_metadata(&branch).cloned();
+  let change = match existing.as_ref().and_then(|m| m.jira_issue.as_deref()) {
+    Some(old) if old == key => format!("'{branch}' is already linked to {key}"),
+    Some(old) => format!("Link '{branch}' to {key} (replacing {old})"),
+    None => format!("Link '{branch}' to {key}"),
+  };
+
+  state.add_branch_issue(BranchMetadata {
+    branch: branch.clone(),
+    jira_issue: Some(key),
+    github_pr: existing.as_ref().and_then(|m| m.github_pr),
+    created_at: existing
+      .map(|m| m.created_at)
+      .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+  });
+
+  if !dry_run {
+    save_state(&state, repo_path)?;
+  }
+
+  Ok(WriteResponse {
+    dry_run,
+    changes: vec![change],
+  })
+}
+
+/// Rebase every descendant of `branch` onto its parents, parents first.
+///
+/// Branches checked out in a linked worktree are rebased in place there; all
+/// others are rebased in the main checkout, which is restored to its original
+/// branch afterwards. A conflicting rebase is aborted and reported as a
+/// `rebase_conflict` error listing the branches that were already rebased.
+pub fn cascade_rebase(
+  repo_path: &Path,
+  state: &RepoState,
+  branch: Option<&str>,
+  max_depth: Option<u32>,
+  dry_run: bool,
+) -> Result<CascadeResponse, ToolError> {
+  let repo = open_repo(repo_path)?;
+  let branch = match branch {
+    Some(b) => b.to_string(),
+    None => current_branch(&repo)?,
+  };
+  require_local_branch(&repo, &branch)?;
+
+  let main_dir = canonical(repo_path);
+  let mut steps = Vec::new();
+  let mut work_dirs = Vec::new();
+  // Branches that will move during the cascade; their children need rebasing too.
+  let mut moving = HashSet::new();
+  for descendant in descendants(state, &branch, max_depth) {
+    let worktree = find_checkout_path_for_branch(&repo, &descendant)
+      .ok()
+      .flatten()
+      .map(|p| canonical(&p))
+      .filter(|p| *p != main_dir);
+
+    for parent in state.get_dependency_parents(&descendant) {
+      let status = match (branch_tip(&repo, &descendant), branch_tip(&repo, parent)) {
+        (Some(_), Some(_)) if moving.contains(parent) => "needs_rebase",
+        (Some(tip), Some(onto)) if repo.merge_base(tip, onto).ok() == Some(onto) => "up_to_date",
+        (Some(_), Some(_)) => "needs_rebase",
+        _ => "missing",
+      };
+      if status == "needs_rebase" {
+        moving.insert(descendant.clone());
+        work_dirs.push(worktree.clone().unwrap_or_else(|| main_dir.clone()));
+      }
+      steps.push(CascadeStep {
+        branch: descendant.clone(),
+        onto: parent.to_string(),
+        worktree: worktree.as_ref().map(|p| p.display().to_string()),
+        status: status.into(),
+      });
+    }
+  }
+
+  if dry_run {
+    return Ok(CascadeResponse { dry_run, branch, steps });
+  }
+
+  for dir in work_dirs.iter().collect::<HashSet<_>>() {
+    if has_tracked_changes(dir)? {
+      return Err(tool_error(
+        "dirty_worktree",
+        format!("{} has uncommitted changes", dir.display()),
+        Some("Commit or stash the changes before applying a cascade.".into()),
+      ));
+    }
+  }
+
+  let original_head = repo.head().ok().and_then(|h| {
+    if h.is_branch() {
+      h.shorthand().map(str::to_string)
+    } else {
+      None
+    }
+  });
+  let mut main_checkout_moved = false;
+  let mut rebased = Vec::new();
+  let mut outcome = Ok(());
+
+  for step in steps.iter_mut().filter(|s| s.status == "needs_rebase") {
+    let (work_dir, args) = match &step.worktree {
+      Some(path) => (PathBuf::from(path), vec!["rebase", step.onto.as_str()]),
+      None => {
+        main_checkout_moved = true;
+        (
+          main_dir.clone(),
+          vec!["rebase", step.onto.as_str(), step.branch.as_str()],
+        )
+      }
+    };
+
+    let output = run_git(&work_dir, &args)?;
+    if output.status.success() {
+      step.status = "rebased".into();
+      rebased.push(step.branch.clone());
+      continue;
+    }
+
+    let _ = run_git(&work_dir, &["rebase", "--abort"]);
+    let done = if rebased.is_empty() {
+      "none".to_string()
+    } else {
+      rebased.join(", ")
+    };
+    outcome = Err(tool_error(
+      "rebase_conflict",
+      format!(
+        "Rebasing '{}' onto '{}' stopped on conflicts and was aborted. Already rebased: {done}",
+        step.branch, step.onto
+      ),
+      Some("Run `twig cascade` in a terminal to resolve the conflicts interactively.".into()),
+    ));
+    break;
+  }
+
+  if main_checkout_moved && let Some(original) = original_head {
+    let _ = run_git(&main_dir, &["checkout", &original]);
+  }
+
+  outcome.map(|()| CascadeResponse { dry_run, branch, steps })
+}
+
+/// Commit the staged changes as a `fixup!` of `target`.
+pub fn create_fixup_commit(repo_path: &Path, target: &str, dry_run: bool) -> Result<FixupCommitResponse, ToolError> {
+  let repo = open_repo(repo_path)?;
+  let target_commit = repo
+    .revparse_single(target)
+    .and_then(|o| o.peel_to_commit())
+    .map_err(|e| tool_error("not_found", format!("Could not resolve commit '{target}': {e}"), None))?;
+  let head = repo
+    .head()
+    .and_then(|h| h.peel_to_commit())
+    .map_err(|e| tool_error("internal", format!("Failed to resolve HEAD: {e}"), None))?;
+
+  let on_branch =
+    head.id() == target_commit.id() || repo.graph_descendant_of(head.id(), target_commit.id()).unwrap_or(false);
+  if !on_branch {
+    return Err(tool_error(
+      "invalid_params",
+      format!("Commit {} is not an ancestor of HEAD", short_id(target_commit.id())),
+      Some("Fixup commits must target a commit on the current branch.".into()),
+    ));
+  }
+
+  let staged = repo
+    .index()
+    .and_then(|index| repo.diff_tree_to_index(Some(&head.tree()?), Some(&index), None))
+    .map(|diff| diff.deltas().len())
+    .map_err(|e| tool_error("internal", format!("Failed to inspect staged changes: {e}"), None))?;
+  if staged == 0 {
+    return Err(tool_error(
+      "invalid_params",
+      "No staged changes to commit",
+      Some("Stage the changes for the fixup commit first.".into()),
+    ));
+  }
+
+  let target_hash = target_commit.id().to_string();
+  let mut response = FixupCommitResponse {
+    dry_run,
+    target: target_hash.clone(),
+    target_summary: target_commit.summary().unwrap_or_default().to_string(),
+    commit: None,
+  };
+  if dry_run {
+    return Ok(response);
+  }
+
+  let output = run_git(repo_path, &["commit", "--fixup", &target_hash])?;
+  if !output.status.success() {
+    return Err(tool_error(
+      "git_error",
+      format!("git commit --fixup failed: {}", git_output_details(&output)),
+      None,
+    ));
+  }
+  response.commit = repo.head().ok().and_then(|h| h.target()).map(|id| id.to_string());
+
+  Ok(response)
+}
+
+/// Collect descendants of `branch` breadth-first, so parents precede children.
+fn descendants(state: &RepoState, branch: &str, max_depth: Option<u32>) -> Vec<String> {
+  let mut result = Vec::new();
+  let mut visited = HashSet::from([branch.to_string()]);
+  let mut queue = VecDeque::from([(branch.to_string(), 0u32)]);
+
+  while let Some((current, depth)) = queue.pop_front() {
+    if max_depth.is_some_and(|max| depth >= max) {
+      continue;
+    }
+    for child in state.get_dependency_children(&current) {
+      if visited.insert(child.to_string()) {
+        result.push(child.to_string());
+        queue.push_back((child.to_string(), depth + 1));
+      }
+    }
+  }
+
+  result
+}
+
+fn open_repo(repo_path: &Path) -> Result<Repository, ToolError> {
+  Repository::open(repo_path).map_err(|e| tool_error("no_repo", format!("Failed to open git repository: {e}"), None))
+}
+
+fn current_branch(repo: &Repository) -> Result<String, ToolError> {
+  repo
+    .head()
+    .ok()
+    .filter(|h| h.is_branch())
+    .and_then(|h| h.shorthand().map(str::to_string))
+    .ok_or_else(|| {
+      tool_error(
+        "invalid_params",
+        "Not on any branch (detached HEAD)",
+        Some("Pass an explicit branch name.".into()),
+      )
+    })
+}
+
+fn require_local_branch<'r>(repo: &'r Repository, name: &str) -> Result<git2::Branch<'r>, ToolError> {
+  repo.find_branch(name, BranchType::Local).map_err(|_| {
+    tool_error(
+      "not_found",
+      format!("Local branch '{name}' does not exist"),
+      Some("Use `list_branches` to see tracked branches.".into()),
+    )
+  })
+}
+
+fn branch_tip(repo: &Repository, name: &str) -> Option<git2::Oid> {
+  repo.find_branch(name, BranchType::Local).ok()?.get().target()
+}
+
+fn parse_issue_key(parser: &JiraTicketParser, input: &str) -> Result<String, ToolError> {
+  parser.parse(input).map_err(|e| {
+    tool_error(
+      "invalid_params",
+      format!("Invalid Jira issue key '{input}': {e}"),
+      Some("Use the PROJ-123 format.".into()),
+    )
+  })
+}
+
+fn save_state(state: &RepoState, repo_path: &Path) -> Result<(), ToolError> {
+  state
+    .save(repo_path)
+    .map_err(|e| tool_error("internal", format!("Failed to save twig state: {e}"), None))
+}
+
+/// Whether tracked files in `path` have staged or unstaged modifications.
+fn has_tracked_changes(path: &Path) -> Result<bool, ToolError> {
+  let repo = open_repo(path)?;
+  let mut options = StatusOptions::new();
+  options.include_untracked(false).include_ignored(false);
+  repo.statuses(Some(&mut options)).map(|s| !s.is_empty()).map_err(|e| {
+    tool_error(
+      "internal",
+      format!("Failed to read status of {}: {e}", path.display()),
+      None,
+    )
+  })
+}
+
+fn run_git(work_dir: &Path, args: &[&str]) -> Result<Output, ToolError> {
+  Command::new("git")
+    .args(args)
+    .current_dir(work_dir)
+    .output()
+    .map_err(|e| tool_error("internal", format!("Failed to run git {}: {e}", args.join(" ")), None))
+}
+
+fn git_output_details(output: &Output) -> String {
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  let details = if stderr.trim().is_empty() {
+    String::from_utf8_lossy(&output.stdout)
+  } else {
+    stderr
+  };
+  details.trim().to_string()
+}
+
+fn canonical(path: &Path) -> PathBuf {
+  path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn short_id(id: git2::Oid) -> String {
+  id.to_string().chars().take(7).collect()
+}
+
+fn tool_error(code: &str, message: impl Into<String>, hint: Option<String>) -> ToolError {
+  ToolError {
+    code: code.into(),
+    message: message.into(),
+    hint,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use twig_core::JiraParsingConfig;
+  use twig_test_utils::git::{GitRepoTestGuard, create_commit, ensure_main_branch};
+
+  use super::*;
+
+  fn parser() -> JiraTicketParser {
+    JiraTicketParser::new(JiraParsingConfig::default())
+  }
+
+  #[test]
+  fn create_branch_dry_run_leaves_repo_untouched() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "a.txt", "a", "initial").unwrap();
+    ensure_main_branch(&guard.repo).unwrap();
+
+    let response = create_branch(
+      guard.path(),
+      RepoState::default(),
+      &parser(),
+      "feature/x",
+      Some("main"),
+      Some("PROJ-1"),
+      true,
+    )
+    .unwrap();
+
+    assert!(response.dry_run);
+    assert_eq!(response.changes.len(), 3);
+    assert!(guard.repo.find_branch("feature/x", BranchType::Local).is_err());
+  }
+
+  #[test]
+  fn create_branch_rejects_missing_parent_and_bad_issue_key() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "a.txt", "a", "initial").unwrap();
+    ensure_main_branch(&guard.repo).unwrap();
+
+    let err = create_branch(
+      guard.path(),
+      RepoState::default(),
+      &parser(),
+      "x",
+      Some("nope"),
+      None,
+      true,
+    )
+    .unwrap_err();
+    assert_eq!(err.code, "not_found");
+
+    let err = create_branch(
+      guard.path(),
+      RepoState::default(),
+      &parser(),
+      "x",
+      Some("main"),
+      Some("not a key"),
+      true,
+    )
+    .unwrap_err();
+    assert_eq!(err.code, "invalid_params");
+  }
+
+  #[test]
+  fn add_dependency_reports_cycles_as_conflicts() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "a.txt", "a", "initial").unwrap();
+    ensure_main_branch(&guard.repo).unwrap();
+    let head = guard.repo.head().unwrap().peel_to_commit().unwrap();
+    guard.repo.branch("feature", &head, false).unwrap();
+
+    let mut state = RepoState::default();
+    state.add_dependency("feature".into(), "main".into()).unwrap();
+
+    let err = add_dependency(guard.path(), state, "main", "feature", true).unwrap_err();
+    assert_eq!(err.code, "conflict");
+  }
+
+  #[test]
+  fn cascade_dry_run_plans_descendants_parents_first() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "a.txt", "a", "initial").unwrap();
+    ensure_main_branch(&guard.repo).unwrap();
+    let base = guard.repo.head().unwrap().peel_to_commit().unwrap();
+    guard.repo.branch("feature", &base, false).unwrap();
+    guard.repo.branch("sub", &base, false).unwrap();
+    create_commit(&guard.repo, "b.txt", "b", "main moves on").unwrap();
+
+    let mut state = RepoState::default();
+    state.add_dependency("feature".into(), "main".into()).unwrap();
+    state.add_dependency("sub".into(), "feature".into()).unwrap();
+
+    let response = cascade_rebase(guard.path(), &state, Some("main"), None, true).unwrap();
+    let plan: Vec<_> = response
+      .steps
+      .iter()
+      .map(|s| (s.branch.as_str(), s.onto.as_str(), s.status.as_str()))
+      .collect();
+    assert_eq!(
+      plan,
+      vec![("feature", "main", "needs_rebase"), ("sub", "feature", "needs_rebase")]
+    );
+
+    let response = cascade_rebase(guard.path(), &state, Some("main"), Some(1), true).unwrap();
+    assert_eq!(response.steps.len(), 1);
+  }
+
+  #[test]
+  fn cascade_apply_rebases_stack_and_restores_checkout() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "a.txt", "a", "initial").unwrap();
+    ensure_main_branch(&guard.repo).unwrap();
+    let base = guard.repo.head().unwrap().peel_to_commit().unwrap();
+    guard.repo.branch("feature", &base, false).unwrap();
+    guard.repo.branch("sub", &base, false).unwrap();
+    create_commit(&guard.repo, "b.txt", "b", "main moves on").unwrap();
+
+    let mut state = RepoState::default();
+    state.add_dependency("feature".into(), "main".into()).unwrap();
+    state.add_dependency("sub".into(), "feature".into()).unwrap();
+
+    let response = cascade_rebase(guard.path(), &state, Some("main"), None, false).unwrap();
+    assert!(response.steps.iter().all(|s| s.status == "rebased"));
+
+    let main_tip = branch_tip(&guard.repo, "main").unwrap();
+    for branch in ["feature", "sub"] {
+      let tip = branch_tip(&guard.repo, branch).unwrap();
+      assert_eq!(guard.repo.merge_base(tip, main_tip).unwrap(), main_tip);
+    }
+    assert_eq!(guard.repo.head().unwrap().shorthand(), Some("main"));
+  }
+
+  #[test]
+  fn fixup_requires_staged_changes() {
+    let guard = GitRepoTestGuard::new();
+    create_commit(&guard.repo, "a.txt", "a", "initial").unwrap();
+
+    let err = create_fixup_commit(guard.path(), "HEAD", true).unwrap_err();
+    assert_eq!(err.code, "invalid_params");
+  }
+}
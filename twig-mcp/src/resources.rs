@@ -0,0 +1,459 @@
+//! MCP resources for twig branch state, plus change notifications.
+//!
+//! Resources use stable `twig://` URIs:
+//!
+//! - `twig://tree` — branch dependency tree(s) for the repository
+//! - `twig://current-branch` — current branch with its metadata
+//! - `twig://branch/<name>` — metadata for a single tracked branch
+//! - `twig://repositories` — the twig repository registry
+//!
+//! Clients that subscribe to a URI receive `notifications/resources/updated`
+//! when `.twig/state.json`, the repository's `HEAD` or the registry changes.
+//! Changes are detected by polling, which keeps the server free of
+//! platform-specific file watching.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rmcp::model::{
+  AnnotateAble, ErrorData as McpError, RawResource, RawResourceTemplate, ReadResourceResult, Resource,
+  ResourceContents, ResourceTemplate, ResourceUpdatedNotificationParam,
+};
+use rmcp::{Peer, RoleServer};
+use serde::Serialize;
+use twig_core::git::graph::BranchGraphBuilder;
+use twig_core::state::{Registry, RepoState};
+
+use crate::context::ServerContext;
+use crate::server::{build_tree_node, extract_branch_metadata, get_current_branch_name, render_tree_text};
+use crate::types::{BranchMetadataResponse, BranchTreeResponse, ListRepositoriesResponse, RepositoryInfo};
+
+pub const TREE_URI: &str = "twig://tree";
+pub const CURRENT_BRANCH_URI: &str = "twig://current-branch";
+pub const REPOSITORIES_URI: &str = "twig://repositories";
+pub const BRANCH_URI_PREFIX: &str = "twig://branch/";
+
+/// How often the watcher checks for state changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+const JSON_MIME: &str = "application/json";
+
+/// Resources available for the current repository.
+pub fn list_resources(context: &ServerContext) -> Vec<Resource> {
+  let mut resources = vec![resource(
+    REPOSITORIES_URI,
+    "repositories",
+    "All twig-registered repositories",
+  )];
+  if context.repo_path.is_none() {
+    return resources;
+  }
+
+  resources.push(resource(
+    TREE_URI,
+    "branch-tree",
+    "Branch dependency tree for the repository",
+  ));
+  resources.push(resource(
+    CURRENT_BRANCH_URI,
+    "current-branch",
+    "Current branch with its linked Jira issue, PR and parent",
+  ));
+
+  if let Ok(state) = context.load_repo_state() {
+    for name in tracked_branches(&state) {
+      resources.push(resource(
+        &branch_uri(&name),
+        &name,
+        &format!("Metadata for branch '{name}'"),
+      ));
+    }
+  }
+
+  resources
+}
+
+/// Templates for resources whose URIs are parameterised.
+pub fn list_resource_templates() -> Vec<ResourceTemplate> {
+  vec![
+    RawResourceTemplate {
+      uri_template: format!("{BRANCH_URI_PREFIX}{{name}}"),
+      name: "branch".into(),
+      title: None,
+      description: Some("Metadata (Jira issue, PR, parent) for a twig-tracked branch".into()),
+      mime_type: Some(JSON_MIME.into()),
+      icons: None,
+    }
+    .no_annotation(),
+  ]
+}
+
+/// Read the resource identified by `uri`.
+pub fn read_resource(context: &ServerContext, uri: &str) -> Result<ReadResourceResult, McpError> {
+  let json = match uri {
+    REPOSITORIES_URI => to_json(&read_repositories(context)?),
+    TREE_URI => to_json(&read_tree(context)?),
+    CURRENT_BRANCH_URI => to_json(&read_current_branch(context)?),
+    _ => match uri.strip_prefix(BRANCH_URI_PREFIX) {
+      Some(name) if !name.is_empty() => to_json(&read_branch(context, name)?),
+      _ => return Err(McpError::resource_not_found(format!("Unknown resource '{uri}'"), None)),
+    },
+  }?;
+
+  Ok(ReadResourceResult {
+    contents: vec![ResourceContents::TextResourceContents {
+      uri: uri.to_string(),
+      mime_type: Some(JSON_MIME.into()),
+      text: json,
+      meta: None,
+    }],
+  })
+}
+
+/// URI for a branch's metadata resource.
+pub fn branch_uri(branch: &str) -> String {
+  format!("{BRANCH_URI_PREFIX}{branch}")
+}
+
+fn resource(uri: &str, name: &str, description: &str) -> Resource {
+  let mut raw = RawResource::new(uri, name);
+  raw.description = Some(description.into());
+  raw.mime_type = Some(JSON_MIME.into());
+  raw.no_annotation()
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, McpError> {
+  serde_json::to_string(value).map_err(|e| McpError::internal_error(format!("Serialization failed: {e}"), None))
+}
+
+fn require_repo(context: &ServerContext) -> Result<&std::path::Path, McpError> {
+  context
+    .require_repo()
+    .map_err(|e| McpError::resource_not_found(e.message, None))
+}
+
+fn read_repositories(context: &ServerContext) -> Result<ListRepositoriesResponse, McpError> {
+  let registry = Registry::load(&context.config_dirs)
+    .map_err(|e| McpError::internal_error(format!("Failed to load registry: {e}"), None))?;
+  let repositories = registry
+    .list()
+    .iter()
+    .map(|r| RepositoryInfo {
+      name: r.name.clone(),
+      path: r.path.clone(),
+    })
+    .collect();
+  Ok(ListRepositoriesResponse { repositories })
+}
+
+/// One tree per root branch, ordered as the graph reports its root candidates.
+fn read_tree(context: &ServerContext) -> Result<Vec<BranchTreeResponse>, McpError> {
+  let repo_path = require_repo(context)?;
+  let repo = git2::Repository::open(repo_path)
+    .map_err(|e| McpError::internal_error(format!("Failed to open repository: {e}"), None))?;
+  let graph = BranchGraphBuilder::new()
+    .with_declared_dependencies(true)
+    .with_orphan_parenting(true)
+    .build(&repo)
+    .map_err(|e| McpError::internal_error(format!("Failed to build branch graph: {e}"), None))?;
+  let state = context.load_repo_state().unwrap_or_default();
+
+  Ok(
+    graph
+      .root_candidates()
+      .iter()
+      .map(|root| {
+        let node = build_tree_node(&graph, &state, root);
+        let mut tree_text = String::new();
+        render_tree_text(&node, &mut tree_text, "", true);
+        BranchTreeResponse {
+          root: root.as_str().to_string(),
+          tree_text,
+          branches: vec![node],
+        }
+      })
+      .collect(),
+  )
+}
+
+fn read_current_branch(context: &ServerContext) -> Result<BranchMetadataResponse, McpError> {
+  let repo_path = require_repo(context)?;
+  let branch = get_current_branch_name(repo_path)
+    .ok()
+    .flatten()
+    .ok_or_else(|| McpError::resource_not_found("Not on any branch (detached HEAD state)", None))?;
+  let state = context.load_repo_state().unwrap_or_default();
+  Ok(branch_metadata(&state, branch))
+}
+
+fn read_branch(context: &ServerContext, name: &str) -> Result<BranchMetadataResponse, McpError> {
+  let repo_path = require_repo(context)?;
+  let state = context
+    .require_repo_state(repo_path)
+    .map_err(|e| McpError::resource_not_found(e.message, None))?;
+  if !tracked_branches(&state).contains(name) {
+    return Err(McpError::resource_not_found(
+      format!("Branch '{name}' not found in twig state"),
+      None,
+    ));
+  }
+  Ok(branch_metadata(&state, name.to_string()))
+}
+
+/// Branches twig knows about: those with metadata or a recorded dependency.
+fn tracked_branches(state: &RepoState) -> BTreeSet<String> {
+  let mut names: BTreeSet<String> = state.branches.keys().cloned().collect();
+  for dependency in state.list_dependencies() {
+    names.insert(dependency.child.clone());
+    names.insert(dependency.parent.clone());
+  }
+  names
+}
+
+fn branch_metadata(state: &RepoState, branch: String) -> BranchMetadataResponse {
+  let (jira_issue, pr_number, parent_branch, created_at) = extract_branch_metadata(state, &branch);
+  BranchMetadataResponse {
+    branch,
+    jira_issue,
+    pr_number,
+    parent_branch,
+    created_at,
+  }
+}
+
+// ===========================================================================
+// Subscriptions and change detection
+// ===========================================================================
+
+/// Resource subscriptions for one client session.
+#[derive(Default)]
+pub struct Subscriptions {
+  inner: Mutex<SubscriptionState>,
+}
+
+#[derive(Default)]
+struct SubscriptionState {
+  uris: HashSet<String>,
+  watcher_started: bool,
+}
+
+impl Subscriptions {
+  /// Subscribe to `uri`, starting the change watcher on first use.
+  pub fn subscribe(self: &Arc<Self>, context: Arc<ServerContext>, peer: Peer<RoleServer>, uri: String) {
+    let start_watcher = {
+      let mut state = self.lock();
+      state.uris.insert(uri);
+      !std::mem::replace(&mut state.watcher_started, true)
+    };
+    if start_watcher {
+      tokio::spawn(watch(Arc::clone(self), context, peer));
+    }
+  }
+
+  pub fn unsubscribe(&self, uri: &str) {
+    self.lock().uris.remove(uri);
+  }
+
+  /// Subscribed URIs affected by `change`.
+  fn affected(&self, change: &Change) -> Vec<String> {
+    let state = self.lock();
+    let mut uris: Vec<String> = state.uris.iter().filter(|uri| change.affects(uri)).cloned().collect();
+    uris.sort();
+    uris
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, SubscriptionState> {
+    self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
+}
+
+/// Which watched files changed since the last poll.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Change {
+  state: bool,
+  head: bool,
+  registry: bool,
+}
+
+impl Change {
+  fn is_empty(&self) -> bool {
+    !(self.state || self.head || self.registry)
+  }
+
+  fn affects(&self, uri: &str) -> bool {
+    match uri {
+      TREE_URI => self.state,
+      CURRENT_BRANCH_URI => self.state || self.head,
+      REPOSITORIES_URI => self.registry,
+      _ => self.state && uri.starts_with(BRANCH_URI_PREFIX),
+    }
+  }
+}
+
+/// Contents of the watched files at one point in time.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Snapshot {
+  state: Option<Vec<u8>>,
+  head: Option<Vec<u8>>,
+  registry: Option<Vec<u8>>,
+}
+
+impl Snapshot {
+  fn capture(paths: &WatchedPaths) -> Self {
+    let read = |path: &Option<PathBuf>| path.as_ref().and_then(|p| std::fs::read(p).ok());
+    Self {
+      state: read(&paths.state),
+      head: read(&paths.head),
+      registry: read(&paths.registry),
+    }
+  }
+
+  fn diff(&self, newer: &Self) -> Change {
+    Change {
+      state: self.state != newer.state,
+      head: self.head != newer.head,
+      registry: self.registry != newer.registry,
+    }
+  }
+}
+
+struct WatchedPaths {
+  state: Option<PathBuf>,
+  head: Option<PathBuf>,
+  registry: Option<PathBuf>,
+}
+
+impl WatchedPaths {
+  fn for_context(context: &ServerContext) -> Self {
+    let repo_path = context.repo_path.as_deref();
+    Self {
+      state: repo_path.map(|p| context.config_dirs.repo_state_path(p)),
+      // `Repository::path` points at the worktree's own git dir, so this is the
+      // right HEAD for linked worktrees too.
+      head: repo_path.and_then(|p| git2::Repository::open(p).ok().map(|r| r.path().join("HEAD"))),
+      registry: Some(context.config_dirs.registry_path()),
+    }
+  }
+}
+
+/// Poll watched files and notify the peer about updated resources until the
+/// session closes.
+async fn watch(subscriptions: Arc<Subscriptions>, context: Arc<ServerContext>, peer: Peer<RoleServer>) {
+  let paths = WatchedPaths::for_context(&context);
+  let mut last = Snapshot::capture(&paths);
+  let mut interval = tokio::time::interval(WATCH_INTERVAL);
+
+  loop {
+    interval.tick().await;
+    if peer.is_transport_closed() {
+      break;
+    }
+
+    let current = Snapshot::capture(&paths);
+    let change = last.diff(&current);
+    last = current;
+    if change.is_empty() {
+      continue;
+    }
+
+    tracing::debug!("Resource change detected: {change:?}");
+    for uri in subscriptions.affected(&change) {
+      if let Err(e) = peer
+        .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+        .await
+      {
+        tracing::debug!("Stopping resource watcher: {e}");
+        return;
+      }
+    }
+    // Branches may have been added to or removed from state.
+    if change.state && peer.notify_resource_list_changed().await.is_err() {
+      return;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use twig_core::config::ConfigDirs;
+
+  use super::*;
+
+  fn test_context() -> ServerContext {
+    let config_dirs = ConfigDirs {
+      config_dir: PathBuf::from("/tmp/twig-mcp-test/config"),
+      data_dir: PathBuf::from("/tmp/twig-mcp-test/data"),
+      cache_dir: None,
+    };
+    ServerContext::new(config_dirs, None, PathBuf::from("/tmp"))
+  }
+
+  #[test]
+  fn snapshot_diff_reports_changed_files() {
+    let before = Snapshot {
+      state: Some(b"{}".to_vec()),
+      head: Some(b"ref: refs/heads/main\n".to_vec()),
+      registry: None,
+    };
+    let mut after = before.clone();
+    assert!(before.diff(&after).is_empty());
+
+    after.head = Some(b"ref: refs/heads/feature\n".to_vec());
+    assert_eq!(
+      before.diff(&after),
+      Change {
+        head: true,
+        ..Change::default()
+      }
+    );
+  }
+
+  #[test]
+  fn head_changes_only_affect_current_branch() {
+    let subscriptions = Subscriptions::default();
+    for uri in [
+      TREE_URI,
+      CURRENT_BRANCH_URI,
+      REPOSITORIES_URI,
+      "twig://branch/feature/x",
+    ] {
+      subscriptions.lock().uris.insert(uri.to_string());
+    }
+
+    let head = Change {
+      head: true,
+      ..Change::default()
+    };
+    assert_eq!(subscriptions.affected(&head), vec![CURRENT_BRANCH_URI.to_string()]);
+
+    let state = Change {
+      state: true,
+      ..Change::default()
+    };
+    assert_eq!(
+      subscriptions.affected(&state),
+      vec![
+        "twig://branch/feature/x".to_string(),
+        CURRENT_BRANCH_URI.to_string(),
+        TREE_URI.to_string()
+      ]
+    );
+  }
+
+  #[test]
+  fn unknown_uris_are_not_found() {
+    let context = test_context();
+    for uri in ["twig://nope", "twig://branch/", "file:///etc/passwd"] {
+      assert!(read_resource(&context, uri).is_err(), "{uri} should not resolve");
+    }
+  }
+
+  #[test]
+  fn repo_resources_require_a_repository() {
+    let context = test_context();
+    let uris: Vec<String> = list_resources(&context).into_iter().map(|r| r.raw.uri).collect();
+    assert_eq!(uris, vec![REPOSITORIES_URI.to_string()]);
+  }
+}
@@ -1,5 +1,6 @@
 //! MCP server implementation with all tool handlers.
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use rmcp::handler::server::router::prompt::PromptRouter;
@@ -11,30 +12,53 @@ use rmcp::{
   ErrorData as McpError, RoleServer, ServerHandler, prompt, prompt_handler, prompt_router, tool, tool_handler,
   tool_router,
 };
+use twig_core::JiraTicketParser;
 use twig_core::git::graph::{BranchGraph, BranchGraphBuilder, BranchName};
-use twig_core::state::{Registry, RepoState};
+use twig_core::state::{Registry, RepoState, RepoStateLock};
 
 use crate::context::ServerContext;
-use crate::tools::github::{GetPrStatusParams, GetPullRequestParams, ListPullRequestsParams};
+use crate::mutations;
+use crate::resources::{self, Subscriptions};
+use crate::tools::github::{GetPrCommentsParams, GetPrStatusParams, GetPullRequestParams, ListPullRequestsParams};
 use crate::tools::jira::{GetJiraIssueParams, ListJiraIssuesParams};
-use crate::tools::local::{BranchMetadataParams, BranchStackParams, BranchTreeParams};
+use crate::tools::local::{BranchMetadataParams, BranchStackParams, BranchTreeParams, RepoParams};
+use crate::tools::write::{
+  CascadeParams, CreateBranchParams, CreateFixupCommitParams, DependencyParams, LinkJiraIssueParams,
+};
 use crate::types::*;
 
 /// A group of tools that can be disabled via `--disable`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, clap::ValueEnum)]
 pub enum ToolGroup {
-  /// GitHub PR tools (get_pull_request, get_pr_status, list_pull_requests)
+  /// GitHub PR tools (get_pull_request, get_pr_status, get_pr_comments, list_pull_requests)
   Github,
   /// Jira issue tools (get_jira_issue, list_jira_issues)
   Jira,
   /// MCP prompts (stack-status, branch-context)
   Prompts,
+  /// MCP resources (twig://tree, twig://branch/<name>, ...) and change notifications
+  Resources,
+  /// Tools that modify branches and twig state. Only available with `--allow-writes`.
+  Write,
 }
 
 /// Tool names belonging to each group.
-const GITHUB_TOOLS: &[&str] = &["get_pull_request", "get_pr_status", "list_pull_requests"];
+const GITHUB_TOOLS: &[&str] = &[
+  "get_pull_request",
+  "get_pr_status",
+  "get_pr_comments",
+  "list_pull_requests",
+];
 const JIRA_TOOLS: &[&str] = &["get_jira_issue", "list_jira_issues"];
 const PROMPT_NAMES: &[&str] = &["stack-status", "branch-context"];
+const WRITE_TOOLS: &[&str] = &[
+  "create_branch",
+  "add_dependency",
+  "remove_dependency",
+  "link_jira_issue",
+  "cascade_rebase",
+  "create_fixup_commit",
+];
 
 impl ToolGroup {
   /// MCP tool names that belong to this group.
@@ -42,14 +66,15 @@ impl ToolGroup {
     match self {
       Self::Github => GITHUB_TOOLS,
       Self::Jira => JIRA_TOOLS,
-      Self::Prompts => &[],
+      Self::Prompts | Self::Resources => &[],
+      Self::Write => WRITE_TOOLS,
     }
   }
 
   /// MCP prompt names that belong to this group.
   pub fn prompt_names(&self) -> &'static [&'static str] {
     match self {
-      Self::Github | Self::Jira => &[],
+      Self::Github | Self::Jira | Self::Resources | Self::Write => &[],
       Self::Prompts => PROMPT_NAMES,
     }
   }
@@ -69,17 +94,31 @@ pub struct TwigMcpServer {
   prompt_router: PromptRouter<Self>,
   tools_enabled: bool,
   prompts_enabled: bool,
+  resources_enabled: bool,
+  writes_enabled: bool,
+  subscriptions: Arc<Subscriptions>,
+  /// Serializes write tools so concurrent requests can't interleave state saves or rebases. Shared
+  /// between sessions when serving over HTTP.
+  write_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
 #[tool_router]
 impl TwigMcpServer {
   /// Create a new server, removing tools/prompts for any disabled groups.
-  pub fn new(context: ServerContext, disabled: &[ToolGroup]) -> Self {
+  ///
+  /// Write tools are only registered when `allow_writes` is set and the
+  /// `write` group has not been disabled.
+  pub fn new(context: ServerContext, disabled: &[ToolGroup], allow_writes: bool) -> Self {
     let context = Arc::new(context);
 
     let mut tool_router = Self::tool_router();
     let mut prompt_router = Self::prompt_router();
 
+    let writes_enabled = allow_writes && !disabled.contains(&ToolGroup::Write);
+    if writes_enabled {
+      tool_router.merge(Self::write_tool_router());
+    }
+
     for group in disabled {
       for name in group.tool_names() {
         tool_router.remove_route(name);
@@ -91,6 +130,7 @@ impl TwigMcpServer {
 
     let tools_enabled = !tool_router.list_all().is_empty();
     let prompts_enabled = !prompt_router.list_all().is_empty();
+    let resources_enabled = !disabled.contains(&ToolGroup::Resources);
 
     Self {
       context,
@@ -98,9 +138,20 @@ impl TwigMcpServer {
       prompt_router,
       tools_enabled,
       prompts_enabled,
+      resources_enabled,
+      writes_enabled,
+      subscriptions: Arc::new(Subscriptions::default()),
+      write_lock: Arc::new(tokio::sync::Mutex::new(())),
     }
   }
 
+  /// Use `write_lock` to serialize write tools, so that servers for different
+  /// sessions don't run them concurrently.
+  pub fn with_write_lock(mut self, write_lock: Arc<tokio::sync::Mutex<()>>) -> Self {
+    self.write_lock = write_lock;
+    self
+  }
+
   // =========================================================================
   // Local state tools
   // =========================================================================
@@ -109,13 +160,13 @@ impl TwigMcpServer {
     description = "Get the current git branch name and its linked Jira issue and GitHub PR",
     annotations(read_only_hint = true, idempotent_hint = true)
   )]
-  async fn get_current_branch(&self) -> Result<CallToolResult, McpError> {
-    let repo_path = match self.context.require_repo() {
+  async fn get_current_branch(&self, params: Parameters<RepoParams>) -> Result<CallToolResult, McpError> {
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
       Ok(p) => p,
       Err(e) => return e.into_result(),
     };
 
-    let branch_name = match get_current_branch_name(repo_path) {
+    let branch_name = match get_current_branch_name(&repo_path) {
       Ok(Some(name)) => name,
       Ok(None) => {
         return Ok(
@@ -132,7 +183,7 @@ impl TwigMcpServer {
     };
 
     // Degrade gracefully if state is missing
-    let (jira_issue, pr_number, parent_branch, created_at) = match self.context.load_repo_state() {
+    let (jira_issue, pr_number, parent_branch, created_at) = match RepoState::load(&repo_path) {
       Ok(state) => extract_branch_metadata(&state, &branch_name),
       Err(_) => (None, None, None, None),
     };
@@ -154,11 +205,11 @@ impl TwigMcpServer {
     annotations(read_only_hint = true, idempotent_hint = true)
   )]
   async fn get_branch_metadata(&self, params: Parameters<BranchMetadataParams>) -> Result<CallToolResult, McpError> {
-    let _repo_path = match self.context.require_repo() {
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
       Ok(p) => p,
       Err(e) => return e.into_result(),
     };
-    let state = match self.context.require_repo_state() {
+    let state = match self.context.require_repo_state(&repo_path) {
       Ok(s) => s,
       Err(e) => return e.into_result(),
     };
@@ -194,12 +245,12 @@ impl TwigMcpServer {
     annotations(read_only_hint = true, idempotent_hint = true)
   )]
   async fn get_branch_tree(&self, params: Parameters<BranchTreeParams>) -> Result<CallToolResult, McpError> {
-    let repo_path = match self.context.require_repo() {
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
       Ok(p) => p,
       Err(e) => return e.into_result(),
     };
 
-    let repo = match git2::Repository::open(repo_path) {
+    let repo = match git2::Repository::open(&repo_path) {
       Ok(r) => r,
       Err(e) => {
         return Ok(
@@ -230,7 +281,7 @@ impl TwigMcpServer {
       );
     }
 
-    let state = self.context.load_repo_state().unwrap_or_default();
+    let state = RepoState::load(&repo_path).unwrap_or_default();
 
     // Determine root
     let root_name = if let Some(ref branch) = params.0.branch {
@@ -267,18 +318,18 @@ impl TwigMcpServer {
     annotations(read_only_hint = true, idempotent_hint = true)
   )]
   async fn get_branch_stack(&self, params: Parameters<BranchStackParams>) -> Result<CallToolResult, McpError> {
-    let repo_path = match self.context.require_repo() {
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
       Ok(p) => p,
       Err(e) => return e.into_result(),
     };
-    let state = match self.context.require_repo_state() {
+    let state = match self.context.require_repo_state(&repo_path) {
       Ok(s) => s,
       Err(e) => return e.into_result(),
     };
 
     let start_branch = match &params.0.branch {
       Some(b) => b.clone(),
-      None => match get_current_branch_name(repo_path) {
+      None => match get_current_branch_name(&repo_path) {
         Ok(Some(name)) => name,
         Ok(None) => {
           return Ok(
@@ -329,12 +380,12 @@ impl TwigMcpServer {
     description = "List all twig-tracked branches in the current repository",
     annotations(read_only_hint = true, idempotent_hint = true)
   )]
-  async fn list_branches(&self) -> Result<CallToolResult, McpError> {
-    let _repo_path = match self.context.require_repo() {
+  async fn list_branches(&self, params: Parameters<RepoParams>) -> Result<CallToolResult, McpError> {
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
       Ok(p) => p,
       Err(e) => return e.into_result(),
     };
-    let state = match self.context.require_repo_state() {
+    let state = match self.context.require_repo_state(&repo_path) {
       Ok(s) => s,
       Err(e) => return e.into_result(),
     };
@@ -388,12 +439,12 @@ impl TwigMcpServer {
     description = "Get active worktrees for the current repository",
     annotations(read_only_hint = true, idempotent_hint = true)
   )]
-  async fn get_worktrees(&self) -> Result<CallToolResult, McpError> {
-    let _repo_path = match self.context.require_repo() {
+  async fn get_worktrees(&self, params: Parameters<RepoParams>) -> Result<CallToolResult, McpError> {
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
       Ok(p) => p,
       Err(e) => return e.into_result(),
     };
-    let state = match self.context.require_repo_state() {
+    let state = match self.context.require_repo_state(&repo_path) {
       Ok(s) => s,
       Err(e) => return e.into_result(),
     };
@@ -420,16 +471,20 @@ impl TwigMcpServer {
     annotations(read_only_hint = true)
   )]
   async fn get_pull_request(&self, params: Parameters<GetPullRequestParams>) -> Result<CallToolResult, McpError> {
-    let gh = match self.context.get_github_client().await {
-      Ok(c) => c,
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
+      Ok(p) => p,
       Err(e) => return e.into_result(),
     };
-    let gh_repo = match self.context.get_github_repo() {
+    let gh_repo = match self.context.get_github_repo(&repo_path) {
       Ok(r) => r,
       Err(e) => return e.into_result(),
     };
+    let gh = match self.context.get_github_client(&gh_repo.host).await {
+      Ok(c) => c,
+      Err(e) => return e.into_result(),
+    };
 
-    let pr_number = match resolve_pr_number(&self.context, params.0.pr_number) {
+    let pr_number = match resolve_pr_number(&self.context, &repo_path, params.0.pr_number) {
       Ok(n) => n,
       Err(e) => return e.into_result(),
     };
@@ -448,16 +503,20 @@ impl TwigMcpServer {
     annotations(read_only_hint = true)
   )]
   async fn get_pr_status(&self, params: Parameters<GetPrStatusParams>) -> Result<CallToolResult, McpError> {
-    let gh = match self.context.get_github_client().await {
-      Ok(c) => c,
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
+      Ok(p) => p,
       Err(e) => return e.into_result(),
     };
-    let gh_repo = match self.context.get_github_repo() {
+    let gh_repo = match self.context.get_github_repo(&repo_path) {
       Ok(r) => r,
       Err(e) => return e.into_result(),
     };
+    let gh = match self.context.get_github_client(&gh_repo.host).await {
+      Ok(c) => c,
+      Err(e) => return e.into_result(),
+    };
 
-    let pr_number = match resolve_pr_number(&self.context, params.0.pr_number) {
+    let pr_number = match resolve_pr_number(&self.context, &repo_path, params.0.pr_number) {
       Ok(n) => n,
       Err(e) => return e.into_result(),
     };
@@ -501,19 +560,71 @@ impl TwigMcpServer {
     )
   }
 
+  #[tool(
+    description = "Get the review threads and conversation comments on a PR, grouped by file. By default only \
+                   unresolved threads are returned, i.e. what is left to address. Defaults to the current branch's PR.",
+    annotations(read_only_hint = true)
+  )]
+  async fn get_pr_comments(&self, params: Parameters<GetPrCommentsParams>) -> Result<CallToolResult, McpError> {
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
+      Ok(p) => p,
+      Err(e) => return e.into_result(),
+    };
+    let gh_repo = match self.context.get_github_repo(&repo_path) {
+      Ok(r) => r,
+      Err(e) => return e.into_result(),
+    };
+    let gh = match self.context.get_github_client(&gh_repo.host).await {
+      Ok(c) => c,
+      Err(e) => return e.into_result(),
+    };
+
+    let pr_number = match resolve_pr_number(&self.context, &repo_path, params.0.pr_number) {
+      Ok(n) => n,
+      Err(e) => return e.into_result(),
+    };
+
+    let (threads, conversation) = match tokio::try_join!(
+      gh.get_review_threads(&gh_repo.owner, &gh_repo.repo, pr_number),
+      gh.list_issue_comments(&gh_repo.owner, &gh_repo.repo, pr_number),
+    ) {
+      Ok(results) => results,
+      Err(e) => {
+        return Ok(
+          ToolResponse::<PrCommentsResponse>::err("network_error", format!("GitHub API error: {e}"), None)
+            .to_call_tool_result(),
+        );
+      }
+    };
+
+    Ok(
+      ToolResponse::ok(map_pr_comments(
+        pr_number,
+        &threads,
+        &conversation,
+        params.0.include_resolved,
+      ))
+      .to_call_tool_result(),
+    )
+  }
+
   #[tool(
     description = "List pull requests for the current repository. Defaults to open PRs.",
     annotations(read_only_hint = true)
   )]
   async fn list_pull_requests(&self, params: Parameters<ListPullRequestsParams>) -> Result<CallToolResult, McpError> {
-    let gh = match self.context.get_github_client().await {
-      Ok(c) => c,
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
+      Ok(p) => p,
       Err(e) => return e.into_result(),
     };
-    let gh_repo = match self.context.get_github_repo() {
+    let gh_repo = match self.context.get_github_repo(&repo_path) {
       Ok(r) => r,
       Err(e) => return e.into_result(),
     };
+    let gh = match self.context.get_github_client(&gh_repo.host).await {
+      Ok(c) => c,
+      Err(e) => return e.into_result(),
+    };
 
     let state = params.0.state.as_deref();
     match gh.list_pull_requests(&gh_repo.owner, &gh_repo.repo, state, None).await {
@@ -542,7 +653,7 @@ impl TwigMcpServer {
       Err(e) => return e.into_result(),
     };
 
-    let issue_key = match resolve_jira_key(&self.context, params.0.issue_key) {
+    let issue_key = match resolve_jira_key(&self.context, params.0.repo.as_deref(), params.0.issue_key.clone()) {
       Ok(k) => k,
       Err(e) => return e.into_result(),
     };
@@ -588,6 +699,153 @@ impl TwigMcpServer {
   }
 }
 
+// ===========================================================================
+// Write tools (registered only with --allow-writes)
+// ===========================================================================
+
+#[tool_router(router = write_tool_router)]
+impl TwigMcpServer {
+  #[tool(
+    description = "Create a branch from a parent branch (default: current branch), record the dependency, and \
+                   optionally link a Jira issue. Set dry_run to preview the changes.",
+    annotations(read_only_hint = false, destructive_hint = false, idempotent_hint = false)
+  )]
+  async fn create_branch(&self, params: Parameters<CreateBranchParams>) -> Result<CallToolResult, McpError> {
+    let _guard = self.write_lock.lock().await;
+    let (repo_path, _state_lock, state) = match self.require_repo_and_state(params.0.repo.as_deref()).await {
+      Ok(v) => v,
+      Err(e) => return e.into_result(),
+    };
+    let p = &params.0;
+    let result = mutations::create_branch(
+      &repo_path,
+      state,
+      &self.jira_parser(),
+      &p.name,
+      p.parent.as_deref(),
+      p.jira_issue.as_deref(),
+      p.dry_run,
+    );
+    Ok(into_tool_response(result).to_call_tool_result())
+  }
+
+  #[tool(
+    description = "Record that a child branch depends on a parent branch. Set dry_run to validate without saving.",
+    annotations(read_only_hint = false, destructive_hint = false, idempotent_hint = false)
+  )]
+  async fn add_dependency(&self, params: Parameters<DependencyParams>) -> Result<CallToolResult, McpError> {
+    let _guard = self.write_lock.lock().await;
+    let (repo_path, _state_lock, state) = match self.require_repo_and_state(params.0.repo.as_deref()).await {
+      Ok(v) => v,
+      Err(e) => return e.into_result(),
+    };
+    let p = &params.0;
+    let result = mutations::add_dependency(&repo_path, state, &p.child, &p.parent, p.dry_run);
+    Ok(into_tool_response(result).to_call_tool_result())
+  }
+
+  #[tool(
+    description = "Remove a recorded dependency between a child and parent branch. Set dry_run to validate \
+                   without saving.",
+    annotations(read_only_hint = false, destructive_hint = true, idempotent_hint = true)
+  )]
+  async fn remove_dependency(&self, params: Parameters<DependencyParams>) -> Result<CallToolResult, McpError> {
+    let _guard = self.write_lock.lock().await;
+    let (repo_path, _state_lock, state) = match self.require_repo_and_state(params.0.repo.as_deref()).await {
+      Ok(v) => v,
+      Err(e) => return e.into_result(),
+    };
+    let p = &params.0;
+    let result = mutations::remove_dependency(&repo_path, state, &p.child, &p.parent, p.dry_run);
+    Ok(into_tool_response(result).to_call_tool_result())
+  }
+
+  #[tool(
+    description = "Link a branch (default: current branch) to a Jira issue. Set dry_run to validate without saving.",
+    annotations(read_only_hint = false, destructive_hint = false, idempotent_hint = true)
+  )]
+  async fn link_jira_issue(&self, params: Parameters<LinkJiraIssueParams>) -> Result<CallToolResult, McpError> {
+    let _guard = self.write_lock.lock().await;
+    let (repo_path, _state_lock, state) = match self.require_repo_and_state(params.0.repo.as_deref()).await {
+      Ok(v) => v,
+      Err(e) => return e.into_result(),
+    };
+    let p = &params.0;
+    let result = mutations::link_jira_issue(
+      &repo_path,
+      state,
+      &self.jira_parser(),
+      p.branch.as_deref(),
+      &p.issue_key,
+      p.dry_run,
+    );
+    Ok(into_tool_response(result).to_call_tool_result())
+  }
+
+  #[tool(
+    description = "Rebase all descendants of a branch (default: current branch) onto their parents. Set dry_run \
+                   to get the cascade preview without rebasing. Conflicting rebases are aborted.",
+    annotations(read_only_hint = false, destructive_hint = true, idempotent_hint = true)
+  )]
+  async fn cascade_rebase(&self, params: Parameters<CascadeParams>) -> Result<CallToolResult, McpError> {
+    let _guard = self.write_lock.lock().await;
+    let (repo_path, _state_lock, state) = match self.require_repo_and_state(params.0.repo.as_deref()).await {
+      Ok(v) => v,
+      Err(e) => return e.into_result(),
+    };
+    let p = &params.0;
+    let result = mutations::cascade_rebase(&repo_path, &state, p.branch.as_deref(), p.max_depth, p.dry_run);
+    Ok(into_tool_response(result).to_call_tool_result())
+  }
+
+  #[tool(
+    description = "Commit the currently staged changes as a fixup! of a commit on the current branch. Set dry_run \
+                   to validate the target without committing.",
+    annotations(read_only_hint = false, destructive_hint = false, idempotent_hint = false)
+  )]
+  async fn create_fixup_commit(&self, params: Parameters<CreateFixupCommitParams>) -> Result<CallToolResult, McpError> {
+    let _guard = self.write_lock.lock().await;
+    let repo_path = match self.context.resolve_repo(params.0.repo.as_deref()) {
+      Ok(p) => p,
+      Err(e) => return e.into_result(),
+    };
+    let p = &params.0;
+    let result = mutations::create_fixup_commit(&repo_path, &p.target, p.dry_run);
+    Ok(into_tool_response(result).to_call_tool_result())
+  }
+}
+
+impl TwigMcpServer {
+  /// Resolve the repository and load its state under the state file lock,
+  /// which the caller holds until its changes are saved.
+  ///
+  /// Waiting for the lock blocks, so it happens on the blocking thread pool
+  /// rather than on the async executor.
+  async fn require_repo_and_state(
+    &self,
+    selector: Option<&str>,
+  ) -> Result<(PathBuf, RepoStateLock, RepoState), ToolError> {
+    let repo_path = self.context.resolve_repo(selector)?;
+    let lock_error = |message: String| ToolError {
+      code: "internal".into(),
+      message,
+      hint: None,
+    };
+    let lock_path = repo_path.clone();
+    let lock = tokio::task::spawn_blocking(move || RepoState::lock(&lock_path))
+      .await
+      .map_err(|e| lock_error(format!("Failed to wait for the twig state lock: {e}")))?
+      .map_err(|e| lock_error(format!("Failed to lock twig state: {e:#}")))?;
+    let state = self.context.require_repo_state(&repo_path)?;
+    Ok((repo_path, lock, state))
+  }
+
+  /// Jira key parser honouring the user's `jira.toml` parsing mode.
+  fn jira_parser(&self) -> JiraTicketParser {
+    JiraTicketParser::new(self.context.config_dirs.load_jira_config().unwrap_or_default())
+  }
+}
+
 // ===========================================================================
 // MCP Prompts
 // ===========================================================================
@@ -735,15 +993,81 @@ impl TwigMcpServer {
 #[prompt_handler]
 impl ServerHandler for TwigMcpServer {
   fn get_info(&self) -> ServerInfo {
+    let instructions = if self.writes_enabled {
+      "Twig MCP server. Provides access to branch metadata, Jira issues, and GitHub PRs for the \
+       current repository, plus write tools for managing branches, dependencies and cascades. \
+       Write tools accept dry_run to preview their changes."
+    } else {
+      "Twig MCP server. Provides read-only access to branch metadata, \
+       Jira issues, and GitHub PRs for the current repository."
+    };
     ServerInfo {
-      instructions: Some(
-        "Twig MCP server. Provides read-only access to branch metadata, \
-         Jira issues, and GitHub PRs for the current repository."
-          .into(),
-      ),
-      capabilities: build_capabilities(self.tools_enabled, self.prompts_enabled),
+      instructions: Some(instructions.into()),
+      capabilities: build_capabilities(self.tools_enabled, self.prompts_enabled, self.resources_enabled),
+      ..Default::default()
+    }
+  }
+
+  async fn list_resources(
+    &self,
+    _request: Option<PaginatedRequestParams>,
+    _context: RequestContext<RoleServer>,
+  ) -> Result<ListResourcesResult, McpError> {
+    if !self.resources_enabled {
+      return Ok(ListResourcesResult::default());
+    }
+    Ok(ListResourcesResult {
+      resources: resources::list_resources(&self.context),
       ..Default::default()
+    })
+  }
+
+  async fn list_resource_templates(
+    &self,
+    _request: Option<PaginatedRequestParams>,
+    _context: RequestContext<RoleServer>,
+  ) -> Result<ListResourceTemplatesResult, McpError> {
+    if !self.resources_enabled {
+      return Ok(ListResourceTemplatesResult::default());
+    }
+    Ok(ListResourceTemplatesResult {
+      resource_templates: resources::list_resource_templates(),
+      ..Default::default()
+    })
+  }
+
+  async fn read_resource(
+    &self,
+    request: ReadResourceRequestParams,
+    _context: RequestContext<RoleServer>,
+  ) -> Result<ReadResourceResult, McpError> {
+    if !self.resources_enabled {
+      return Err(McpError::method_not_found::<ReadResourceRequestMethod>());
     }
+    resources::read_resource(&self.context, &request.uri)
+  }
+
+  async fn subscribe(
+    &self,
+    request: SubscribeRequestParams,
+    context: RequestContext<RoleServer>,
+  ) -> Result<(), McpError> {
+    if !self.resources_enabled {
+      return Err(McpError::method_not_found::<SubscribeRequestMethod>());
+    }
+    self
+      .subscriptions
+      .subscribe(Arc::clone(&self.context), context.peer, request.uri);
+    Ok(())
+  }
+
+  async fn unsubscribe(
+    &self,
+    request: UnsubscribeRequestParams,
+    _context: RequestContext<RoleServer>,
+  ) -> Result<(), McpError> {
+    self.subscriptions.unsubscribe(&request.uri);
+    Ok(())
   }
 }
 
@@ -754,26 +1078,45 @@ impl ServerHandler for TwigMcpServer {
 /// Build `ServerCapabilities` with the appropriate flags.
 ///
 /// The builder uses a typestate pattern, so we can't conditionally call
-/// `enable_tools()` / `enable_prompts()` on the same binding. Match all
-/// four combinations instead.
-fn build_capabilities(tools: bool, prompts: bool) -> ServerCapabilities {
-  match (tools, prompts) {
-    (true, true) => ServerCapabilities::builder().enable_tools().enable_prompts().build(),
-    (true, false) => ServerCapabilities::builder().enable_tools().build(),
-    (false, true) => ServerCapabilities::builder().enable_prompts().build(),
-    (false, false) => ServerCapabilities::builder().build(),
+/// `enable_tools()` / `enable_prompts()` on the same binding. Enable
+/// everything and clear the disabled capabilities afterwards instead.
+fn build_capabilities(tools: bool, prompts: bool, resources: bool) -> ServerCapabilities {
+  let mut capabilities = ServerCapabilities::builder()
+    .enable_tools()
+    .enable_prompts()
+    .enable_resources()
+    .enable_resources_subscribe()
+    .enable_resources_list_changed()
+    .build();
+  if !tools {
+    capabilities.tools = None;
+  }
+  if !prompts {
+    capabilities.prompts = None;
+  }
+  if !resources {
+    capabilities.resources = None;
+  }
+  capabilities
+}
+
+/// Wrap a mutation result in the standard response envelope.
+fn into_tool_response<T: serde::Serialize>(result: Result<T, ToolError>) -> ToolResponse<T> {
+  match result {
+    Ok(data) => ToolResponse::ok(data),
+    Err(error) => ToolResponse::Error { error },
   }
 }
 
 /// Get the current branch name from a repository path.
-fn get_current_branch_name(repo_path: &std::path::Path) -> anyhow::Result<Option<String>> {
+pub(crate) fn get_current_branch_name(repo_path: &Path) -> anyhow::Result<Option<String>> {
   let repo = git2::Repository::open(repo_path)?;
   let head = repo.head()?;
   Ok(head.shorthand().map(|s| s.to_string()))
 }
 
 /// Extract metadata for a branch from state.
-fn extract_branch_metadata(
+pub(crate) fn extract_branch_metadata(
   state: &RepoState,
   branch_name: &str,
 ) -> (Option<String>, Option<u32>, Option<String>, Option<String>) {
@@ -789,12 +1132,11 @@ fn extract_branch_metadata(
 }
 
 /// Resolve a PR number from explicit params or current branch state.
-fn resolve_pr_number(context: &ServerContext, explicit: Option<u32>) -> Result<u32, ToolError> {
+fn resolve_pr_number(context: &ServerContext, repo_path: &Path, explicit: Option<u32>) -> Result<u32, ToolError> {
   if let Some(n) = explicit {
     return Ok(n);
   }
   // Try to get from current branch
-  let repo_path = context.require_repo()?;
   let branch = get_current_branch_name(repo_path)
     .ok()
     .flatten()
@@ -803,7 +1145,7 @@ fn resolve_pr_number(context: &ServerContext, explicit: Option<u32>) -> Result<u
       message: "No pr_number provided and could not detect current branch".into(),
       hint: Some("Provide an explicit pr_number parameter.".into()),
     })?;
-  let state = context.require_repo_state()?;
+  let state = context.require_repo_state(repo_path)?;
   state
     .branches
     .get(&branch)
@@ -816,12 +1158,19 @@ fn resolve_pr_number(context: &ServerContext, explicit: Option<u32>) -> Result<u
 }
 
 /// Resolve a Jira issue key from explicit params or current branch state.
-fn resolve_jira_key(context: &ServerContext, explicit: Option<String>) -> Result<String, ToolError> {
+///
+/// The repository is only resolved when no key is given, so explicit keys work
+/// without a default repository.
+fn resolve_jira_key(
+  context: &ServerContext,
+  repo: Option<&str>,
+  explicit: Option<String>,
+) -> Result<String, ToolError> {
   if let Some(k) = explicit {
     return Ok(k);
   }
-  let repo_path = context.require_repo()?;
-  let branch = get_current_branch_name(repo_path)
+  let repo_path = context.resolve_repo(repo)?;
+  let branch = get_current_branch_name(&repo_path)
     .ok()
     .flatten()
     .ok_or_else(|| ToolError {
@@ -829,7 +1178,7 @@ fn resolve_jira_key(context: &ServerContext, explicit: Option<String>) -> Result
       message: "No issue_key provided and could not detect current branch".into(),
       hint: Some("Provide an explicit issue_key parameter.".into()),
     })?;
-  let state = context.require_repo_state()?;
+  let state = context.require_repo_state(&repo_path)?;
   state
     .branches
     .get(&branch)
@@ -857,6 +1206,56 @@ fn map_pull_request(pr: &twig_gh::GitHubPullRequest) -> PullRequestResponse {
   }
 }
 
+/// Map review threads and conversation comments to our response type.
+fn map_pr_comments(
+  pr_number: u32,
+  threads: &[twig_gh::ReviewThread],
+  conversation: &[twig_gh::IssueComment],
+  include_resolved: bool,
+) -> PrCommentsResponse {
+  let shown = threads.iter().filter(|t| include_resolved || !t.is_resolved);
+  let files = twig_gh::group_threads_by_file(shown)
+    .into_iter()
+    .map(|(path, file_threads)| FileReviewThreads {
+      path: path.to_string(),
+      threads: file_threads
+        .into_iter()
+        .map(|t| ReviewThreadInfo {
+          line: t.line,
+          resolved: t.is_resolved,
+          outdated: t.is_outdated,
+          comments: t
+            .comments
+            .iter()
+            .map(|c| CommentInfo {
+              author: c.author.clone(),
+              body: c.body.clone(),
+              url: c.url.clone(),
+              created_at: c.created_at.clone(),
+            })
+            .collect(),
+        })
+        .collect(),
+    })
+    .collect();
+
+  PrCommentsResponse {
+    pr_number,
+    total_threads: threads.len(),
+    unresolved_threads: threads.iter().filter(|t| !t.is_resolved).count(),
+    files,
+    conversation: conversation
+      .iter()
+      .map(|c| CommentInfo {
+        author: c.user.login.clone(),
+        body: c.body.clone(),
+        url: c.html_url.clone(),
+        created_at: c.created_at.clone(),
+      })
+      .collect(),
+  }
+}
+
 /// Map a Jira issue to our response type.
 fn map_jira_issue(issue: &twig_jira::Issue) -> JiraIssueResponse {
   // Jira descriptions can be complex objects in API v3. Convert to string.
@@ -871,7 +1270,7 @@ fn map_jira_issue(issue: &twig_jira::Issue) -> JiraIssueResponse {
 }
 
 /// Recursively build a `BranchTreeNode` from a `BranchGraph`.
-fn build_tree_node(graph: &BranchGraph, state: &RepoState, name: &BranchName) -> BranchTreeNode {
+pub(crate) fn build_tree_node(graph: &BranchGraph, state: &RepoState, name: &BranchName) -> BranchTreeNode {
   let node = graph.get(name);
   let meta = state.branches.get(name.as_str());
 
@@ -893,6 +1292,52 @@ fn build_tree_node(graph: &BranchGraph, state: &RepoState, name: &BranchName) ->
   }
 }
 
+/// Render a tree node as indented text using box-drawing characters.
+pub(crate) fn render_tree_text(node: &BranchTreeNode, out: &mut String, prefix: &str, is_root: bool) {
+  if is_root {
+    out.push_str(&node.branch);
+    // Add metadata inline
+    let mut annotations = Vec::new();
+    if let Some(ref jira) = node.jira_issue {
+      annotations.push(jira.clone());
+    }
+    if let Some(pr) = node.pr_number {
+      annotations.push(format!("#{pr}"));
+    }
+    if !annotations.is_empty() {
+      out.push_str(&format!(" ({})", annotations.join(", ")));
+    }
+    out.push('\n');
+  }
+
+  let child_count = node.children.len();
+  for (i, child) in node.children.iter().enumerate() {
+    let is_last = i == child_count - 1;
+    let connector = if is_last { "└── " } else { "├── " };
+    let child_prefix = if is_last { "    " } else { "│   " };
+
+    out.push_str(prefix);
+    out.push_str(connector);
+    out.push_str(&child.branch);
+
+    // Add metadata inline
+    let mut annotations = Vec::new();
+    if let Some(ref jira) = child.jira_issue {
+      annotations.push(jira.clone());
+    }
+    if let Some(pr) = child.pr_number {
+      annotations.push(format!("#{pr}"));
+    }
+    if !annotations.is_empty() {
+      out.push_str(&format!(" ({})", annotations.join(", ")));
+    }
+    out.push('\n');
+
+    let new_prefix = format!("{prefix}{child_prefix}");
+    render_tree_text(child, out, &new_prefix, false);
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::path::PathBuf;
@@ -931,7 +1376,7 @@ mod tests {
 
   #[test]
   fn no_groups_disabled_exposes_all_tools() {
-    let server = TwigMcpServer::new(test_context(), &[]);
+    let server = TwigMcpServer::new(test_context(), &[], false);
     let tools = tool_names(&server);
     for name in GITHUB_TOOLS.iter().chain(JIRA_TOOLS.iter()) {
       assert!(tools.contains(&name.to_string()), "expected tool {name}");
@@ -946,7 +1391,7 @@ mod tests {
 
   #[test]
   fn disable_github_removes_github_tools_only() {
-    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Github]);
+    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Github], false);
     let tools = tool_names(&server);
     for name in GITHUB_TOOLS {
       assert!(!tools.contains(&name.to_string()), "tool {name} should be removed");
@@ -960,7 +1405,7 @@ mod tests {
 
   #[test]
   fn disable_jira_removes_jira_tools_only() {
-    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Jira]);
+    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Jira], false);
     let tools = tool_names(&server);
     for name in JIRA_TOOLS {
       assert!(!tools.contains(&name.to_string()), "tool {name} should be removed");
@@ -973,7 +1418,7 @@ mod tests {
 
   #[test]
   fn disable_prompts_removes_all_prompts() {
-    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Prompts]);
+    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Prompts], false);
     let prompts = prompt_names(&server);
     assert!(prompts.is_empty(), "all prompts should be removed");
     assert!(!server.prompts_enabled);
@@ -986,6 +1431,7 @@ mod tests {
     let server = TwigMcpServer::new(
       test_context(),
       &[ToolGroup::Github, ToolGroup::Jira, ToolGroup::Prompts],
+      false,
     );
     let tools = tool_names(&server);
     for name in GITHUB_TOOLS.iter().chain(JIRA_TOOLS.iter()) {
@@ -997,50 +1443,132 @@ mod tests {
     assert!(server.tools_enabled);
     assert!(!server.prompts_enabled);
   }
-}
 
-/// Render a tree node as indented text using box-drawing characters.
-fn render_tree_text(node: &BranchTreeNode, out: &mut String, prefix: &str, is_root: bool) {
-  if is_root {
-    out.push_str(&node.branch);
-    // Add metadata inline
-    let mut annotations = Vec::new();
-    if let Some(ref jira) = node.jira_issue {
-      annotations.push(jira.clone());
-    }
-    if let Some(pr) = node.pr_number {
-      annotations.push(format!("#{pr}"));
+  #[test]
+  fn write_tools_require_allow_writes() {
+    let server = TwigMcpServer::new(test_context(), &[], false);
+    let tools = tool_names(&server);
+    for name in WRITE_TOOLS {
+      assert!(
+        !tools.contains(&name.to_string()),
+        "write tool {name} should not be exposed"
+      );
     }
-    if !annotations.is_empty() {
-      out.push_str(&format!(" ({})", annotations.join(", ")));
+    assert!(!server.writes_enabled);
+
+    let server = TwigMcpServer::new(test_context(), &[], true);
+    let tools = tool_names(&server);
+    for name in WRITE_TOOLS {
+      assert!(tools.contains(&name.to_string()), "expected write tool {name}");
     }
-    out.push('\n');
+    assert!(server.writes_enabled);
   }
 
-  let child_count = node.children.len();
-  for (i, child) in node.children.iter().enumerate() {
-    let is_last = i == child_count - 1;
-    let connector = if is_last { "└── " } else { "├── " };
-    let child_prefix = if is_last { "    " } else { "│   " };
+  #[test]
+  fn sessions_share_the_write_lock() {
+    let write_lock = Arc::new(tokio::sync::Mutex::new(()));
+    let first = TwigMcpServer::new(test_context(), &[], true).with_write_lock(write_lock.clone());
+    let second = TwigMcpServer::new(test_context(), &[], true).with_write_lock(write_lock.clone());
+    assert!(Arc::ptr_eq(&first.write_lock, &second.write_lock));
+
+    let _guard = first.write_lock.try_lock().unwrap();
+    assert!(second.write_lock.try_lock().is_err());
+  }
 
-    out.push_str(prefix);
-    out.push_str(connector);
-    out.push_str(&child.branch);
+  #[tokio::test]
+  async fn write_tools_hold_the_state_lock() {
+    let repo_dir = tempfile::TempDir::new().unwrap();
+    let context = ServerContext::new(
+      test_context().config_dirs,
+      Some(repo_dir.path().to_path_buf()),
+      PathBuf::from("/tmp"),
+    );
+    let server = Arc::new(TwigMcpServer::new(context, &[], true));
 
-    // Add metadata inline
-    let mut annotations = Vec::new();
-    if let Some(ref jira) = child.jira_issue {
-      annotations.push(jira.clone());
-    }
-    if let Some(pr) = child.pr_number {
-      annotations.push(format!("#{pr}"));
-    }
-    if !annotations.is_empty() {
-      out.push_str(&format!(" ({})", annotations.join(", ")));
+    let held = RepoState::lock(repo_dir.path()).unwrap();
+    let waiter = {
+      let server = server.clone();
+      tokio::spawn(async move { server.require_repo_and_state(None).await.map(|_| ()) })
+    };
+
+    // Blocked while another writer holds the lock, without stalling this
+    // single-threaded runtime
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(!waiter.is_finished());
+    drop(held);
+    tokio::time::timeout(std::time::Duration::from_secs(5), waiter)
+      .await
+      .unwrap()
+      .unwrap()
+      .unwrap();
+  }
+
+  #[test]
+  fn disable_write_overrides_allow_writes() {
+    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Write], true);
+    let tools = tool_names(&server);
+    for name in WRITE_TOOLS {
+      assert!(
+        !tools.contains(&name.to_string()),
+        "write tool {name} should be removed"
+      );
     }
-    out.push('\n');
+    assert!(!server.writes_enabled);
+  }
 
-    let new_prefix = format!("{prefix}{child_prefix}");
-    render_tree_text(child, out, &new_prefix, false);
+  #[test]
+  fn resources_capability_follows_group() {
+    let server = TwigMcpServer::new(test_context(), &[], false);
+    let resources = server.get_info().capabilities.resources.expect("resources enabled");
+    assert_eq!(resources.subscribe, Some(true));
+    assert_eq!(resources.list_changed, Some(true));
+
+    let server = TwigMcpServer::new(test_context(), &[ToolGroup::Resources], false);
+    assert!(server.get_info().capabilities.resources.is_none());
+  }
+
+  #[test]
+  fn explicit_jira_key_needs_no_default_repo() {
+    let context = test_context().with_multi_repo(true);
+
+    let key = resolve_jira_key(&context, None, Some("PROJ-123".to_string())).unwrap();
+    assert_eq!(key, "PROJ-123");
+
+    let err = resolve_jira_key(&context, None, None).unwrap_err();
+    assert_eq!(err.code, "no_repo");
+  }
+
+  #[test]
+  fn pr_comments_hide_resolved_threads_by_default() {
+    let thread = |path: &str, line: u32, resolved: bool| twig_gh::ReviewThread {
+      id: format!("{path}:{line}"),
+      path: path.to_string(),
+      line: Some(line),
+      is_resolved: resolved,
+      is_outdated: false,
+      comments: vec![twig_gh::ReviewThreadComment {
+        author: "reviewer".to_string(),
+        body: "Please fix".to_string(),
+        url: "https://example.com".to_string(),
+        created_at: "2024-01-01T00:00:00Z".to_string(),
+      }],
+    };
+    let threads = vec![
+      thread("src/a.rs", 10, false),
+      thread("src/a.rs", 2, true),
+      thread("src/b.rs", 1, true),
+    ];
+
+    let response = map_pr_comments(7, &threads, &[], false);
+    assert_eq!(response.total_threads, 3);
+    assert_eq!(response.unresolved_threads, 1);
+    assert_eq!(response.files.len(), 1);
+    assert_eq!(response.files[0].path, "src/a.rs");
+    assert_eq!(response.files[0].threads[0].line, Some(10));
+
+    let response = map_pr_comments(7, &threads, &[], true);
+    let paths: Vec<&str> = response.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, vec!["src/a.rs", "src/b.rs"]);
+    assert_eq!(response.files[0].threads[0].line, Some(2));
   }
 }
@@ -7,16 +7,33 @@ use serde::Deserialize;
 pub struct GetPullRequestParams {
   /// PR number. Defaults to the current branch's PR if omitted.
   pub pr_number: Option<u32>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetPrStatusParams {
   /// PR number. Defaults to the current branch's PR if omitted.
   pub pr_number: Option<u32>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListPullRequestsParams {
   /// Filter by state: "open", "closed", or "all". Defaults to "open".
   pub state: Option<String>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetPrCommentsParams {
+  /// PR number. Defaults to the current branch's PR if omitted.
+  pub pr_number: Option<u32>,
+  /// Also return review threads that have already been resolved.
+  #[serde(default)]
+  pub include_resolved: bool,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
 }
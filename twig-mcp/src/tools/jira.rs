@@ -7,6 +7,8 @@ use serde::Deserialize;
 pub struct GetJiraIssueParams {
   /// Jira issue key (e.g. "PROJ-123"). Defaults to the current branch's issue if omitted.
   pub issue_key: Option<String>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -3,20 +3,32 @@
 use schemars::JsonSchema;
 use serde::Deserialize;
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RepoParams {
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct BranchMetadataParams {
   /// Branch name to look up.
   pub branch: String,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct BranchTreeParams {
   /// Optional branch to use as the tree root. Defaults to the default root.
   pub branch: Option<String>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct BranchStackParams {
   /// Branch to trace from. Defaults to current branch.
   pub branch: Option<String>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
 }
@@ -0,0 +1,69 @@
+//! Parameter structs for write tools (only registered with `--allow-writes`).
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateBranchParams {
+  /// Name of the branch to create.
+  pub name: String,
+  /// Parent branch to branch from. Defaults to the current branch.
+  pub parent: Option<String>,
+  /// Jira issue key to link to the new branch (e.g. "PROJ-123").
+  pub jira_issue: Option<String>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
+  /// Report what would change without modifying the repository.
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DependencyParams {
+  /// Child branch (the branch that depends on `parent`).
+  pub child: String,
+  /// Parent branch.
+  pub parent: String,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
+  /// Report what would change without modifying twig state.
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LinkJiraIssueParams {
+  /// Jira issue key (e.g. "PROJ-123").
+  pub issue_key: String,
+  /// Branch to link. Defaults to the current branch.
+  pub branch: Option<String>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
+  /// Report what would change without modifying twig state.
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CascadeParams {
+  /// Branch whose descendants should be rebased. Defaults to the current branch.
+  pub branch: Option<String>,
+  /// Maximum depth of descendants to rebase.
+  pub max_depth: Option<u32>,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
+  /// Only return the rebase plan (a cascade preview) without rebasing.
+  #[serde(default)]
+  pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateFixupCommitParams {
+  /// Commit (hash or revision) that the staged changes should fix up.
+  pub target: String,
+  /// Registered repository name or path (multi-repo mode). Defaults to the server's repository.
+  pub repo: Option<String>,
+  /// Validate the target and staged changes without committing.
+  #[serde(default)]
+  pub dry_run: bool,
+}
@@ -173,6 +173,41 @@ pub struct CheckRunInfo {
   pub conclusion: Option<String>,
 }
 
+/// Response for `get_pr_comments`.
+#[derive(Debug, Serialize)]
+pub struct PrCommentsResponse {
+  pub pr_number: u32,
+  pub total_threads: usize,
+  pub unresolved_threads: usize,
+  /// Review threads grouped by file, ordered by path and line.
+  pub files: Vec<FileReviewThreads>,
+  /// Top-level conversation comments, oldest first.
+  pub conversation: Vec<CommentInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReviewThreads {
+  pub path: String,
+  pub threads: Vec<ReviewThreadInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewThreadInfo {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub line: Option<u32>,
+  pub resolved: bool,
+  pub outdated: bool,
+  pub comments: Vec<CommentInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentInfo {
+  pub author: String,
+  pub body: String,
+  pub url: String,
+  pub created_at: String,
+}
+
 /// Response for `list_pull_requests`.
 #[derive(Debug, Serialize)]
 pub struct ListPullRequestsResponse {
@@ -200,3 +235,48 @@ pub struct JiraIssueResponse {
 pub struct ListJiraIssuesResponse {
   pub issues: Vec<JiraIssueResponse>,
 }
+
+// ---------------------------------------------------------------------------
+// Write responses
+// ---------------------------------------------------------------------------
+
+/// Response for write tools that change branches or twig state.
+#[derive(Debug, Serialize)]
+pub struct WriteResponse {
+  /// `true` when nothing was modified and `changes` describes what would happen.
+  pub dry_run: bool,
+  pub changes: Vec<String>,
+}
+
+/// Response for `cascade_rebase`.
+#[derive(Debug, Serialize)]
+pub struct CascadeResponse {
+  pub dry_run: bool,
+  /// Branch whose descendants were (or would be) rebased.
+  pub branch: String,
+  /// Ordered so that parents are rebased before their children.
+  pub steps: Vec<CascadeStep>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CascadeStep {
+  pub branch: String,
+  pub onto: String,
+  /// Worktree the branch is rebased in, when it is checked out outside the main repository.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub worktree: Option<String>,
+  /// One of "needs_rebase", "up_to_date", "rebased" or "missing".
+  pub status: String,
+}
+
+/// Response for `create_fixup_commit`.
+#[derive(Debug, Serialize)]
+pub struct FixupCommitResponse {
+  pub dry_run: bool,
+  /// Full hash of the commit being fixed up.
+  pub target: String,
+  pub target_summary: String,
+  /// Hash of the new fixup commit. Absent on dry runs.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub commit: Option<String>,
+}